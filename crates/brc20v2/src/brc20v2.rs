@@ -0,0 +1,1832 @@
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  mem,
+};
+
+use bitcoin::hashes::{Hash, sha256};
+use serde::{Deserialize, Serialize};
+
+use super::{
+  errors::Brc20Error,
+  identity::{IdentityCommitment, IdentityProof, IdentityVerifier, authorization_message},
+  inscription::{Amount, Brc20Inscription},
+  merkle::{self, InclusionProof, LeafData, PartialTree, SparseMerkleTree, Store, Tree},
+  zk::{LeafUpdate, ZkProofGenerator, ZkStatement, ZkWitness},
+  zk_proof::ZkProof,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TokenDefinition {
+  pub ticker: String,
+  pub max_supply: u128,
+  pub mint_limit: u128,
+  pub decimals: u8,
+  pub soulbound: bool,
+  #[serde(default)]
+  pub transfer_rules: TransferRules,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TransferRules {
+  /// Senders must pass the indexer's identity verifier.
+  pub require_identity: bool,
+}
+
+/// A vesting schedule expressed in blocks, so that every indexer computes the
+/// same unlocked amount at the same height.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VestingSchedule {
+  pub start_height: u32,
+  pub cliff_blocks: u32,
+  pub duration_blocks: u32,
+  #[serde(default)]
+  pub curve: VestingCurve,
+  pub total_locked: u128,
+}
+
+/// How the amount locked by a `VestingSchedule` unlocks between the end of
+/// the cliff and the end of the schedule.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VestingCurve {
+  /// Unlock a little every block.
+  #[default]
+  Linear,
+  /// Unlock in equal tranches every `interval` blocks.
+  Step { interval: u32 },
+}
+
+impl VestingSchedule {
+  pub fn validate(&self) -> Result<(), Brc20Error> {
+    if self.duration_blocks == 0 {
+      return Err(Brc20Error::VestingScheduleInvalid(
+        "duration_blocks must be positive".to_string(),
+      ));
+    }
+    if self.cliff_blocks > self.duration_blocks {
+      return Err(Brc20Error::VestingScheduleInvalid(
+        "cliff_blocks cannot exceed duration_blocks".to_string(),
+      ));
+    }
+    if self.curve == (VestingCurve::Step { interval: 0 }) {
+      return Err(Brc20Error::VestingScheduleInvalid(
+        "step interval must be positive".to_string(),
+      ));
+    }
+    Ok(())
+  }
+
+  pub fn unlocked_at(&self, height: u32) -> u128 {
+    if height <= self.start_height.saturating_add(self.cliff_blocks) {
+      return 0;
+    }
+
+    let elapsed = height - self.start_height;
+    if elapsed >= self.duration_blocks {
+      return self.total_locked;
+    }
+
+    let elapsed = elapsed - self.cliff_blocks;
+    let vesting_window = self.duration_blocks - self.cliff_blocks;
+
+    let (elapsed, window) = match self.curve {
+      VestingCurve::Linear => (elapsed, vesting_window),
+      VestingCurve::Step { interval } => (elapsed / interval, vesting_window.div_ceil(interval)),
+    };
+
+    self
+      .total_locked
+      .saturating_mul(elapsed.into())
+      .saturating_div(window.into())
+      .min(self.total_locked)
+  }
+
+  pub fn locked_at(&self, height: u32) -> u128 {
+    self.total_locked.saturating_sub(self.unlocked_at(height))
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct AccountState {
+  pub balance: u128,
+  pub locked_balance: u128,
+  pub vesting: Option<VestingSchedule>,
+  /// Nonce of the last identity proof the account sent tokens with.
+  #[serde(default)]
+  pub nonce: u64,
+}
+
+impl AccountState {
+  pub fn available_balance(&self, height: u32) -> u128 {
+    let locked = match &self.vesting {
+      Some(vesting) => vesting.locked_at(height),
+      None => self.locked_balance,
+    };
+    self.balance.saturating_sub(locked)
+  }
+
+  fn apply_vesting(&mut self, schedule: VestingSchedule) -> Result<(), Brc20Error> {
+    schedule.validate()?;
+    if let Some(existing) = &self.vesting {
+      if existing.start_height != schedule.start_height
+        || existing.cliff_blocks != schedule.cliff_blocks
+        || existing.duration_blocks != schedule.duration_blocks
+        || existing.curve != schedule.curve
+      {
+        return Err(Brc20Error::VestingScheduleInvalid(
+          "conflicting vesting schedule".to_string(),
+        ));
+      }
+      self.locked_balance = self.locked_balance.saturating_add(schedule.total_locked);
+      let mut merged = existing.clone();
+      merged.total_locked = merged.total_locked.saturating_add(schedule.total_locked);
+      self.vesting = Some(merged);
+    } else {
+      self.locked_balance = self.locked_balance.saturating_add(schedule.total_locked);
+      self.vesting = Some(schedule);
+    }
+    Ok(())
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(from = "TokenStateData")]
+pub struct TokenState {
+  pub definition: TokenDefinition,
+  pub total_supply: u128,
+  pub accounts: BTreeMap<IdentityCommitment, AccountState>,
+  /// May freeze accounts, change the soulbound flag, and hand the role on.
+  /// Initially the owner of the deploy inscription.
+  pub admin: Option<IdentityCommitment>,
+  /// Amounts that spenders may transfer from owners, keyed by
+  /// `(owner, spender)`.
+  pub allowances: BTreeMap<(IdentityCommitment, IdentityCommitment), u128>,
+  /// Accounts that may neither send nor receive.
+  pub frozen: BTreeSet<IdentityCommitment>,
+  #[serde(skip)]
+  tree: Tree,
+  /// Changes made by the operation being applied, most recent last.
+  #[serde(skip)]
+  changes: Vec<Change>,
+}
+
+/// A change made to a token, holding what it replaced, so that it can be
+/// undone.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+  Account {
+    identity: IdentityCommitment,
+    previous: Option<AccountState>,
+  },
+  Admin(Option<IdentityCommitment>),
+  Allowance {
+    owner: IdentityCommitment,
+    spender: IdentityCommitment,
+    previous: u128,
+  },
+  Frozen {
+    identity: IdentityCommitment,
+    previous: bool,
+  },
+  Soulbound(bool),
+  TotalSupply(u128),
+}
+
+#[derive(Deserialize)]
+struct TokenStateData {
+  definition: TokenDefinition,
+  total_supply: u128,
+  accounts: BTreeMap<IdentityCommitment, AccountState>,
+  #[serde(default)]
+  admin: Option<IdentityCommitment>,
+  #[serde(default)]
+  allowances: BTreeMap<(IdentityCommitment, IdentityCommitment), u128>,
+  #[serde(default)]
+  frozen: BTreeSet<IdentityCommitment>,
+}
+
+impl From<TokenStateData> for TokenState {
+  fn from(data: TokenStateData) -> Self {
+    Self {
+      admin: data.admin,
+      allowances: data.allowances,
+      frozen: data.frozen,
+      ..Self::with_accounts(data.definition, data.total_supply, data.accounts)
+    }
+  }
+}
+
+impl TokenState {
+  pub fn new(definition: TokenDefinition) -> Self {
+    Self::with_accounts(definition, 0, BTreeMap::new())
+  }
+
+  pub fn with_accounts(
+    definition: TokenDefinition,
+    total_supply: u128,
+    accounts: BTreeMap<IdentityCommitment, AccountState>,
+  ) -> Self {
+    let tree = SparseMerkleTree::from_leaves(
+      merkle::DEPTH,
+      accounts.iter().map(|(identity, account)| {
+        (
+          identity.commitment,
+          LeafData::from(account).hash(&identity.commitment),
+        )
+      }),
+    );
+
+    Self {
+      definition,
+      total_supply,
+      accounts,
+      admin: None,
+      allowances: BTreeMap::new(),
+      frozen: BTreeSet::new(),
+      tree: Tree::Full(tree),
+      changes: Vec::new(),
+    }
+  }
+
+  /// A token with none of its accounts, which must be added with
+  /// `load_account` before operations that change them are applied.
+  pub fn with_tree(definition: TokenDefinition, total_supply: u128, tree: PartialTree) -> Self {
+    Self {
+      tree: Tree::Partial(tree),
+      ..Self::with_accounts(definition, total_supply, BTreeMap::new())
+    }
+  }
+
+  /// Whether `identity`'s account, if it has one, is loaded.
+  pub fn is_loaded(&self, identity: &IdentityCommitment) -> bool {
+    self.tree.contains(&identity.commitment)
+  }
+
+  /// Add `identity`'s `account` to a token created with `with_tree`, loading
+  /// the path to its leaf from `store`.
+  pub fn load_account<S: Store>(
+    &mut self,
+    identity: &IdentityCommitment,
+    account: Option<AccountState>,
+    store: &S,
+  ) -> Result<(), S::Error> {
+    if let Tree::Partial(tree) = &mut self.tree {
+      tree.load(store, &identity.commitment)?;
+    }
+
+    if let Some(account) = account {
+      self.accounts.insert(identity.clone(), account);
+    }
+
+    Ok(())
+  }
+
+  pub fn tree(&self) -> &Tree {
+    &self.tree
+  }
+
+  /// Take the changes made by the last operation applied to the token.
+  pub fn take_changes(&mut self) -> Vec<Change> {
+    mem::take(&mut self.changes)
+  }
+
+  /// Undo the changes made by the operation being applied.
+  fn undo(&mut self) {
+    while let Some(change) = self.changes.pop() {
+      match change {
+        Change::Account { identity, previous } => {
+          self.tree.insert(
+            &identity.commitment,
+            previous
+              .as_ref()
+              .map(|account| LeafData::from(account).hash(&identity.commitment))
+              .unwrap_or(merkle::EMPTY_LEAF),
+          );
+
+          match previous {
+            Some(account) => self.accounts.insert(identity, account),
+            None => self.accounts.remove(&identity),
+          };
+        }
+        Change::Admin(previous) => self.admin = previous,
+        Change::Allowance {
+          owner,
+          spender,
+          previous,
+        } => {
+          if previous == 0 {
+            self.allowances.remove(&(owner, spender));
+          } else {
+            self.allowances.insert((owner, spender), previous);
+          }
+        }
+        Change::Frozen { identity, previous } => {
+          if previous {
+            self.frozen.insert(identity);
+          } else {
+            self.frozen.remove(&identity);
+          }
+        }
+        Change::Soulbound(previous) => self.definition.soulbound = previous,
+        Change::TotalSupply(previous) => self.total_supply = previous,
+      }
+    }
+  }
+
+  pub fn account(&self, identity: &IdentityCommitment) -> AccountState {
+    debug_assert!(
+      self.is_loaded(identity),
+      "account of {} is not loaded",
+      identity.id
+    );
+    self.accounts.get(identity).cloned().unwrap_or_default()
+  }
+
+  pub fn allowance(&self, owner: &IdentityCommitment, spender: &IdentityCommitment) -> u128 {
+    self
+      .allowances
+      .get(&(owner.clone(), spender.clone()))
+      .copied()
+      .unwrap_or_default()
+  }
+
+  fn set_allowance(
+    &mut self,
+    owner: &IdentityCommitment,
+    spender: &IdentityCommitment,
+    amount: u128,
+  ) {
+    self.changes.push(Change::Allowance {
+      owner: owner.clone(),
+      spender: spender.clone(),
+      previous: self.allowance(owner, spender),
+    });
+
+    if amount == 0 {
+      self.allowances.remove(&(owner.clone(), spender.clone()));
+    } else {
+      self
+        .allowances
+        .insert((owner.clone(), spender.clone()), amount);
+    }
+  }
+
+  fn set_admin(&mut self, admin: IdentityCommitment) {
+    self.changes.push(Change::Admin(self.admin.replace(admin)));
+  }
+
+  fn set_frozen(&mut self, identity: &IdentityCommitment, frozen: bool) {
+    let previous = if frozen {
+      !self.frozen.insert(identity.clone())
+    } else {
+      self.frozen.remove(identity)
+    };
+
+    self.changes.push(Change::Frozen {
+      identity: identity.clone(),
+      previous,
+    });
+  }
+
+  fn set_soulbound(&mut self, soulbound: bool) {
+    self.changes.push(Change::Soulbound(mem::replace(
+      &mut self.definition.soulbound,
+      soulbound,
+    )));
+  }
+
+  fn set_total_supply(&mut self, total_supply: u128) {
+    self.changes.push(Change::TotalSupply(mem::replace(
+      &mut self.total_supply,
+      total_supply,
+    )));
+  }
+
+  fn ensure_not_frozen(&self, identity: &IdentityCommitment) -> Result<(), Brc20Error> {
+    if self.frozen.contains(identity) {
+      return Err(Brc20Error::AccountFrozen {
+        ticker: self.definition.ticker.clone(),
+        identity: identity.id.clone(),
+      });
+    }
+    Ok(())
+  }
+
+  /// Check that `owner` is the token's admin.
+  fn authorize_admin(&self, owner: Option<&IdentityCommitment>) -> Result<(), Brc20Error> {
+    let admin = self.admin.as_ref().ok_or_else(|| {
+      Brc20Error::Unauthorized(format!("token {} has no admin", self.definition.ticker))
+    })?;
+
+    authorize(owner, admin)
+  }
+
+  /// Check that `from` authorized an `op` of `amount` to `to` with `proof`,
+  /// and return the nonce of the proof, which must be greater than the last
+  /// nonce `from` sent tokens with.
+  fn verify_sender(
+    &self,
+    identity_verifier: &dyn IdentityVerifier,
+    op: &str,
+    from: &IdentityCommitment,
+    to: &IdentityCommitment,
+    amount: u128,
+    proof: Option<&IdentityProof>,
+  ) -> Result<Option<u64>, Brc20Error> {
+    let nonce = proof.map(IdentityProof::nonce);
+
+    if let Some(nonce) = nonce {
+      let last = self.account(from).nonce;
+      if nonce <= last {
+        return Err(Brc20Error::IdentityVerificationFailed(format!(
+          "identity {}: nonce {nonce} is not greater than last nonce {last}",
+          from.id
+        )));
+      }
+    }
+
+    identity_verifier.verify(
+      from,
+      &authorization_message(
+        op,
+        &self.definition.ticker,
+        from,
+        to,
+        amount,
+        nonce.unwrap_or_default(),
+      ),
+      proof,
+    )?;
+
+    Ok(nonce)
+  }
+
+  /// Move `amount` from `from` to `to`, returning the debit and credit. The
+  /// sender's nonce is advanced to `nonce`, if any.
+  fn send(
+    &mut self,
+    from: &IdentityCommitment,
+    to: &IdentityCommitment,
+    amount: u128,
+    nonce: Option<u64>,
+    height: u32,
+  ) -> Result<Vec<LeafUpdate>, Brc20Error> {
+    self.ensure_not_frozen(from)?;
+    self.ensure_not_frozen(to)?;
+
+    let mut sender = self.account(from);
+    let available = sender.available_balance(height);
+    if available < amount {
+      return Err(Brc20Error::InsufficientBalance {
+        available,
+        required: amount,
+      });
+    }
+
+    sender.balance = sender.balance.saturating_sub(amount);
+    if let Some(nonce) = nonce {
+      sender.nonce = nonce;
+    }
+    let debit = self.write(from, sender, true);
+
+    let mut receiver = self.account(to);
+    receiver.balance = receiver.balance.saturating_add(amount);
+    let credit = self.write(to, receiver, false);
+
+    Ok(vec![debit, credit])
+  }
+
+  pub fn merkle_root(&self) -> [u8; 32] {
+    self.tree.root()
+  }
+
+  /// Hash committing to the token's supply and accounts.
+  pub fn state_hash(&self) -> [u8; 32] {
+    hash_state(self, self.merkle_root())
+  }
+
+  /// Prove that `identity`'s account is committed to by `merkle_root`, or,
+  /// if the token has no such account, that it is not.
+  pub fn inclusion_proof(&self, identity: &IdentityCommitment) -> InclusionProof {
+    InclusionProof {
+      commitment: identity.commitment,
+      leaf: self.accounts.get(identity).map(LeafData::from),
+      proof: self.tree.prove(&identity.commitment),
+    }
+  }
+
+  /// Store `account` for `identity`, updating its leaf in place, and return
+  /// the update as a proof witness.
+  fn write(
+    &mut self,
+    identity: &IdentityCommitment,
+    account: AccountState,
+    debit: bool,
+  ) -> LeafUpdate {
+    let old = self.accounts.get(identity).map(LeafData::from);
+    let new = LeafData::from(&account);
+    let proof = self.tree.prove(&identity.commitment);
+
+    self
+      .tree
+      .insert(&identity.commitment, new.hash(&identity.commitment));
+
+    self.changes.push(Change::Account {
+      identity: identity.clone(),
+      previous: self.accounts.insert(identity.clone(), account),
+    });
+
+    LeafUpdate {
+      commitment: identity.commitment,
+      debit,
+      old,
+      new,
+      proof,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Operation {
+  Deploy {
+    definition: TokenDefinition,
+  },
+  Mint {
+    ticker: String,
+    to: IdentityCommitment,
+    amount: u128,
+  },
+  MintVested {
+    ticker: String,
+    to: IdentityCommitment,
+    amount: u128,
+    vesting: VestingSchedule,
+  },
+  Transfer {
+    ticker: String,
+    from: IdentityCommitment,
+    to: IdentityCommitment,
+    amount: u128,
+    proof: Option<IdentityProof>,
+  },
+  SetSoulbound {
+    ticker: String,
+    soulbound: bool,
+  },
+  Burn {
+    ticker: String,
+    from: IdentityCommitment,
+    amount: u128,
+  },
+  TransferAdmin {
+    ticker: String,
+    to: IdentityCommitment,
+  },
+  /// Allow `spender` to transfer up to `amount` from the owner of the
+  /// inscription, replacing any previous allowance.
+  Approve {
+    ticker: String,
+    spender: IdentityCommitment,
+    amount: u128,
+  },
+  /// Transfer from `from` using the allowance it gave the owner of the
+  /// inscription.
+  TransferFrom {
+    ticker: String,
+    from: IdentityCommitment,
+    to: IdentityCommitment,
+    amount: u128,
+    proof: Option<IdentityProof>,
+  },
+  Freeze {
+    ticker: String,
+    account: IdentityCommitment,
+    frozen: bool,
+  },
+}
+
+impl Operation {
+  pub fn ticker(&self) -> String {
+    match self {
+      Self::Deploy { definition } => definition.ticker.clone(),
+      Self::Mint { ticker, .. }
+      | Self::MintVested { ticker, .. }
+      | Self::Transfer { ticker, .. }
+      | Self::SetSoulbound { ticker, .. }
+      | Self::Burn { ticker, .. }
+      | Self::TransferAdmin { ticker, .. }
+      | Self::Approve { ticker, .. }
+      | Self::TransferFrom { ticker, .. }
+      | Self::Freeze { ticker, .. } => ticker.clone(),
+    }
+  }
+
+  /// Accounts whose balances the operation changes if it is applied.
+  pub fn accounts(&self) -> Vec<&IdentityCommitment> {
+    match self {
+      Self::Deploy { .. }
+      | Self::SetSoulbound { .. }
+      | Self::TransferAdmin { .. }
+      | Self::Approve { .. }
+      | Self::Freeze { .. } => Vec::new(),
+      Self::Mint { to, .. } | Self::MintVested { to, .. } => vec![to],
+      Self::Burn { from, .. } => vec![from],
+      Self::Transfer { from, to, .. } | Self::TransferFrom { from, to, .. } => vec![from, to],
+    }
+  }
+
+  pub fn operation_name(&self) -> &'static str {
+    match self {
+      Self::Deploy { .. } => "deploy",
+      Self::Mint { .. } => "mint",
+      Self::MintVested { .. } => "mint_vested",
+      Self::Transfer { .. } => "transfer",
+      Self::SetSoulbound { .. } => "soulbound",
+      Self::Burn { .. } => "burn",
+      Self::TransferAdmin { .. } => "transfer_admin",
+      Self::Approve { .. } => "approve",
+      Self::TransferFrom { .. } => "transfer_from",
+      Self::Freeze { .. } => "freeze",
+    }
+  }
+
+  /// The body of the operation's inscription, with amounts in whole tokens
+  /// of a token with `decimals`.
+  pub fn payload_json(&self, decimals: u8) -> serde_json::Value {
+    let format = |amount: &u128| Amount::format(*amount, decimals);
+
+    match self {
+      Self::Deploy { definition } => {
+        let mut payload = serde_json::json!({
+          "max": Amount::format(definition.max_supply, definition.decimals),
+          "lim": Amount::format(definition.mint_limit, definition.decimals),
+          "dec": definition.decimals,
+          "soulbound": definition.soulbound,
+        });
+
+        if definition.transfer_rules != TransferRules::default() {
+          payload["rules"] = serde_json::json!(definition.transfer_rules);
+        }
+
+        payload
+      }
+      Self::Mint { to, amount, .. } => serde_json::json!({
+        "to": to.id,
+        "amt": format(amount),
+      }),
+      Self::MintVested {
+        to,
+        amount,
+        vesting,
+        ..
+      } => {
+        let mut payload = serde_json::json!({
+          "to": to.id,
+          "amt": format(amount),
+          "vesting": {
+            "start": vesting.start_height,
+            "cliff": vesting.cliff_blocks,
+            "duration": vesting.duration_blocks,
+          },
+        });
+
+        if let VestingCurve::Step { interval } = vesting.curve {
+          payload["vesting"]["step"] = interval.into();
+        }
+
+        payload
+      }
+      Self::Transfer {
+        from,
+        to,
+        amount,
+        proof,
+        ..
+      } => {
+        let mut payload = serde_json::json!({
+          "from": from.id,
+          "to": to.id,
+          "amt": format(amount),
+        });
+
+        if let Some(proof) = proof {
+          payload["proof"] = serde_json::json!(proof);
+        }
+
+        payload
+      }
+      Self::SetSoulbound { soulbound, .. } => serde_json::json!({
+        "soulbound": soulbound,
+      }),
+      Self::Burn { from, amount, .. } => serde_json::json!({
+        "from": from.id,
+        "amt": format(amount),
+      }),
+      Self::TransferAdmin { to, .. } => serde_json::json!({
+        "to": to.id,
+      }),
+      Self::Approve {
+        spender, amount, ..
+      } => serde_json::json!({
+        "spender": spender.id,
+        "amt": format(amount),
+      }),
+      Self::TransferFrom {
+        from,
+        to,
+        amount,
+        proof,
+        ..
+      } => {
+        let mut payload = serde_json::json!({
+          "from": from.id,
+          "to": to.id,
+          "amt": format(amount),
+        });
+
+        if let Some(proof) = proof {
+          payload["proof"] = serde_json::json!(proof);
+        }
+
+        payload
+      }
+      Self::Freeze {
+        account, frozen, ..
+      } => serde_json::json!({
+        "account": account.id,
+        "frozen": frozen,
+      }),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransitionReceipt {
+  pub ticker: String,
+  pub merkle_root: [u8; 32],
+  pub proof: ZkProof,
+  pub inscription: Brc20Inscription,
+  pub state_hash: [u8; 32],
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Brc20StateMachine {
+  pub tokens: BTreeMap<String, TokenState>,
+}
+
+impl Brc20StateMachine {
+  /// Apply `operation` at `height`. `owner` is the identity that revealed the
+  /// operation, if any, and is who deploys, burns, approves, spends
+  /// allowances, and exercises the admin role. If the operation fails, the
+  /// changes it made are undone.
+  pub fn apply_operation<G: ZkProofGenerator>(
+    &mut self,
+    operation: Operation,
+    proof_generator: &G,
+    identity_verifier: &dyn IdentityVerifier,
+    owner: Option<&IdentityCommitment>,
+    height: u32,
+  ) -> Result<TransitionReceipt, Brc20Error> {
+    let ticker = operation.ticker();
+
+    let existed = match self.tokens.get_mut(&ticker) {
+      Some(token) => {
+        token.changes.clear();
+        true
+      }
+      None => false,
+    };
+
+    let result = self.transition(operation, proof_generator, identity_verifier, owner, height);
+
+    if result.is_err() {
+      if existed {
+        if let Some(token) = self.tokens.get_mut(&ticker) {
+          token.undo();
+        }
+      } else {
+        self.tokens.remove(&ticker);
+      }
+    }
+
+    result
+  }
+
+  fn transition<G: ZkProofGenerator>(
+    &mut self,
+    operation: Operation,
+    proof_generator: &G,
+    identity_verifier: &dyn IdentityVerifier,
+    owner: Option<&IdentityCommitment>,
+    height: u32,
+  ) -> Result<TransitionReceipt, Brc20Error> {
+    let ticker = operation.ticker();
+
+    let previous_merkle_root = self
+      .tokens
+      .get(&ticker)
+      .map(TokenState::merkle_root)
+      .unwrap_or_else(merkle::empty_root);
+
+    let updates = match &operation {
+      Operation::Deploy { definition } => self.deploy(definition.clone(), owner)?,
+      Operation::Mint { to, amount, .. } => self.mint(&ticker, to, *amount, None, height)?,
+      Operation::MintVested {
+        to,
+        amount,
+        vesting,
+        ..
+      } => self.mint(&ticker, to, *amount, Some(vesting.clone()), height)?,
+      Operation::Transfer {
+        from,
+        to,
+        amount,
+        proof,
+        ..
+      } => self.transfer(
+        &ticker,
+        owner,
+        from,
+        to,
+        *amount,
+        proof.as_ref(),
+        identity_verifier,
+        height,
+      )?,
+      Operation::SetSoulbound { soulbound, .. } => {
+        self.set_soulbound(&ticker, owner, *soulbound)?
+      }
+      Operation::Burn { from, amount, .. } => self.burn(&ticker, owner, from, *amount, height)?,
+      Operation::TransferAdmin { to, .. } => self.transfer_admin(&ticker, owner, to)?,
+      Operation::Approve {
+        spender, amount, ..
+      } => self.approve(&ticker, owner, spender, *amount)?,
+      Operation::TransferFrom {
+        from,
+        to,
+        amount,
+        proof,
+        ..
+      } => self.transfer_from(
+        &ticker,
+        owner,
+        from,
+        to,
+        *amount,
+        proof.as_ref(),
+        identity_verifier,
+        height,
+      )?,
+      Operation::Freeze {
+        account, frozen, ..
+      } => self.freeze(&ticker, owner, account, *frozen)?,
+    };
+
+    let token_state = self
+      .tokens
+      .get(&ticker)
+      .ok_or_else(|| Brc20Error::TokenNotFound(ticker.clone()))?;
+    let merkle_root = token_state.merkle_root();
+    let (statement, witness) =
+      Self::zk_inputs(&operation, previous_merkle_root, merkle_root, updates);
+    let proof = proof_generator.generate(&statement, &witness)?;
+    if !proof_generator.verify(&statement, &proof) {
+      return Err(Brc20Error::ProofVerificationFailed(
+        "proof generator failed to verify".to_string(),
+      ));
+    }
+
+    let inscription =
+      Brc20Inscription::from_operation(&operation, token_state.definition.decimals, merkle_root);
+    let state_hash = hash_state(token_state, merkle_root);
+
+    Ok(TransitionReceipt {
+      ticker,
+      merkle_root,
+      proof,
+      inscription,
+      state_hash,
+    })
+  }
+
+  fn deploy(
+    &mut self,
+    definition: TokenDefinition,
+    owner: Option<&IdentityCommitment>,
+  ) -> Result<Vec<LeafUpdate>, Brc20Error> {
+    if self.tokens.contains_key(&definition.ticker) {
+      return Err(Brc20Error::TokenAlreadyExists(definition.ticker));
+    }
+    self.tokens.insert(
+      definition.ticker.clone(),
+      TokenState {
+        admin: owner.cloned(),
+        ..TokenState::new(definition)
+      },
+    );
+    Ok(Vec::new())
+  }
+
+  fn mint(
+    &mut self,
+    ticker: &str,
+    to: &IdentityCommitment,
+    amount: u128,
+    vesting: Option<VestingSchedule>,
+    height: u32,
+  ) -> Result<Vec<LeafUpdate>, Brc20Error> {
+    let token = self
+      .tokens
+      .get_mut(ticker)
+      .ok_or_else(|| Brc20Error::TokenNotFound(ticker.to_string()))?;
+
+    if amount > token.definition.mint_limit {
+      return Err(Brc20Error::MintLimitExceeded {
+        limit: token.definition.mint_limit,
+        requested: amount,
+      });
+    }
+
+    let attempted_total = token.total_supply.saturating_add(amount);
+    if attempted_total > token.definition.max_supply {
+      return Err(Brc20Error::MaxSupplyExceeded {
+        max_supply: token.definition.max_supply,
+        attempted_total,
+      });
+    }
+
+    token.ensure_not_frozen(to)?;
+
+    let mut account = token.account(to);
+    account.balance = account.balance.saturating_add(amount);
+    account.locked_balance = account
+      .vesting
+      .as_ref()
+      .map(|vesting| vesting.locked_at(height))
+      .unwrap_or(account.locked_balance);
+
+    if let Some(vesting) = vesting {
+      account.apply_vesting(VestingSchedule {
+        total_locked: amount,
+        ..vesting
+      })?;
+    }
+
+    token.set_total_supply(attempted_total);
+    Ok(vec![token.write(to, account, false)])
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  fn transfer(
+    &mut self,
+    ticker: &str,
+    owner: Option<&IdentityCommitment>,
+    from: &IdentityCommitment,
+    to: &IdentityCommitment,
+    amount: u128,
+    proof: Option<&IdentityProof>,
+    identity_verifier: &dyn IdentityVerifier,
+    height: u32,
+  ) -> Result<Vec<LeafUpdate>, Brc20Error> {
+    let token = self
+      .tokens
+      .get_mut(ticker)
+      .ok_or_else(|| Brc20Error::TokenNotFound(ticker.to_string()))?;
+
+    if token.definition.soulbound {
+      return Err(Brc20Error::SoulboundTransferDenied(ticker.to_string()));
+    }
+
+    authorize(owner, from)?;
+
+    let nonce = if token.definition.transfer_rules.require_identity {
+      token.verify_sender(identity_verifier, "transfer", from, to, amount, proof)?
+    } else {
+      None
+    };
+
+    token.send(from, to, amount, nonce, height)
+  }
+
+  fn set_soulbound(
+    &mut self,
+    ticker: &str,
+    owner: Option<&IdentityCommitment>,
+    soulbound: bool,
+  ) -> Result<Vec<LeafUpdate>, Brc20Error> {
+    let token = self.token_mut(ticker)?;
+    token.authorize_admin(owner)?;
+    token.set_soulbound(soulbound);
+    Ok(Vec::new())
+  }
+
+  fn burn(
+    &mut self,
+    ticker: &str,
+    owner: Option<&IdentityCommitment>,
+    from: &IdentityCommitment,
+    amount: u128,
+    height: u32,
+  ) -> Result<Vec<LeafUpdate>, Brc20Error> {
+    let token = self.token_mut(ticker)?;
+
+    authorize(owner, from)?;
+    token.ensure_not_frozen(from)?;
+
+    let mut account = token.account(from);
+    let available = account.available_balance(height);
+    if available < amount {
+      return Err(Brc20Error::InsufficientBalance {
+        available,
+        required: amount,
+      });
+    }
+
+    account.balance -= amount;
+    token.set_total_supply(token.total_supply.saturating_sub(amount));
+    Ok(vec![token.write(from, account, true)])
+  }
+
+  fn transfer_admin(
+    &mut self,
+    ticker: &str,
+    owner: Option<&IdentityCommitment>,
+    to: &IdentityCommitment,
+  ) -> Result<Vec<LeafUpdate>, Brc20Error> {
+    let token = self.token_mut(ticker)?;
+    token.authorize_admin(owner)?;
+    token.set_admin(to.clone());
+    Ok(Vec::new())
+  }
+
+  fn approve(
+    &mut self,
+    ticker: &str,
+    owner: Option<&IdentityCommitment>,
+    spender: &IdentityCommitment,
+    amount: u128,
+  ) -> Result<Vec<LeafUpdate>, Brc20Error> {
+    let token = self.token_mut(ticker)?;
+    let owner =
+      owner.ok_or_else(|| Brc20Error::Unauthorized("approval has no owner".to_string()))?;
+    token.set_allowance(owner, spender, amount);
+    Ok(Vec::new())
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  fn transfer_from(
+    &mut self,
+    ticker: &str,
+    owner: Option<&IdentityCommitment>,
+    from: &IdentityCommitment,
+    to: &IdentityCommitment,
+    amount: u128,
+    proof: Option<&IdentityProof>,
+    identity_verifier: &dyn IdentityVerifier,
+    height: u32,
+  ) -> Result<Vec<LeafUpdate>, Brc20Error> {
+    let token = self.token_mut(ticker)?;
+
+    let spender =
+      owner.ok_or_else(|| Brc20Error::Unauthorized("transfer_from has no spender".to_string()))?;
+
+    if token.definition.soulbound {
+      return Err(Brc20Error::SoulboundTransferDenied(ticker.to_string()));
+    }
+
+    let nonce = if token.definition.transfer_rules.require_identity {
+      token.verify_sender(identity_verifier, "transfer_from", from, to, amount, proof)?
+    } else {
+      None
+    };
+
+    let allowance = token.allowance(from, spender);
+    if allowance < amount {
+      return Err(Brc20Error::InsufficientAllowance {
+        available: allowance,
+        required: amount,
+      });
+    }
+
+    let updates = token.send(from, to, amount, nonce, height)?;
+    token.set_allowance(from, spender, allowance - amount);
+    Ok(updates)
+  }
+
+  fn freeze(
+    &mut self,
+    ticker: &str,
+    owner: Option<&IdentityCommitment>,
+    account: &IdentityCommitment,
+    frozen: bool,
+  ) -> Result<Vec<LeafUpdate>, Brc20Error> {
+    let token = self.token_mut(ticker)?;
+    token.authorize_admin(owner)?;
+    token.set_frozen(account, frozen);
+    Ok(Vec::new())
+  }
+
+  fn token_mut(&mut self, ticker: &str) -> Result<&mut TokenState, Brc20Error> {
+    self
+      .tokens
+      .get_mut(ticker)
+      .ok_or_else(|| Brc20Error::TokenNotFound(ticker.to_string()))
+  }
+
+  fn zk_inputs(
+    operation: &Operation,
+    previous_merkle_root: [u8; 32],
+    merkle_root: [u8; 32],
+    updates: Vec<LeafUpdate>,
+  ) -> (ZkStatement, ZkWitness) {
+    let (from, to, amount) = match operation {
+      Operation::Deploy { .. }
+      | Operation::SetSoulbound { .. }
+      | Operation::TransferAdmin { .. }
+      | Operation::Approve { .. }
+      | Operation::Freeze { .. } => (None, None, 0),
+      Operation::Mint { to, amount, .. } | Operation::MintVested { to, amount, .. } => {
+        (None, Some(to.commitment), *amount)
+      }
+      Operation::Burn { from, amount, .. } => (Some(from.commitment), None, *amount),
+      Operation::Transfer {
+        from, to, amount, ..
+      }
+      | Operation::TransferFrom {
+        from, to, amount, ..
+      } => (Some(from.commitment), Some(to.commitment), *amount),
+    };
+
+    let balance_before = |debit: bool| {
+      updates
+        .iter()
+        .find(|update| update.debit == debit)
+        .and_then(|update| update.old)
+        .map(|leaf| leaf.balance)
+        .unwrap_or_default()
+    };
+
+    let witness = ZkWitness {
+      from_balance: balance_before(true),
+      to_balance: balance_before(false),
+      updates,
+    };
+
+    (
+      ZkStatement {
+        operation: operation.operation_name().to_string(),
+        token: operation.ticker(),
+        from,
+        to,
+        amount,
+        previous_merkle_root,
+        merkle_root,
+      },
+      witness,
+    )
+  }
+}
+
+/// Check that the owner of an operation is `identity`.
+fn authorize(
+  owner: Option<&IdentityCommitment>,
+  identity: &IdentityCommitment,
+) -> Result<(), Brc20Error> {
+  match owner {
+    Some(owner) if owner.commitment == identity.commitment => Ok(()),
+    Some(owner) => Err(Brc20Error::Unauthorized(format!(
+      "{} may not act for {}",
+      owner.id, identity.id
+    ))),
+    None => Err(Brc20Error::Unauthorized(format!(
+      "operation has no owner, expected {}",
+      identity.id
+    ))),
+  }
+}
+
+fn hash_state(token: &TokenState, merkle_root: [u8; 32]) -> [u8; 32] {
+  let mut data = Vec::new();
+  data.extend_from_slice(token.definition.ticker.as_bytes());
+  data.extend_from_slice(&token.total_supply.to_be_bytes());
+  data.extend_from_slice(&token.definition.max_supply.to_be_bytes());
+  data.extend_from_slice(&merkle_root);
+  data.extend_from_slice(
+    &token
+      .admin
+      .as_ref()
+      .map_or([0; 32], |admin| admin.commitment),
+  );
+  data.extend_from_slice(&(token.frozen.len() as u64).to_be_bytes());
+  for identity in &token.frozen {
+    data.extend_from_slice(&identity.commitment);
+  }
+  data.extend_from_slice(&(token.allowances.len() as u64).to_be_bytes());
+  for ((owner, spender), amount) in &token.allowances {
+    data.extend_from_slice(&owner.commitment);
+    data.extend_from_slice(&spender.commitment);
+    data.extend_from_slice(&amount.to_be_bytes());
+  }
+  sha256::Hash::hash(&data).to_byte_array()
+}
+
+/// Key of the leaf committing to the state of `ticker` in the tree whose root
+/// is returned by [`state_root`].
+pub fn state_key(ticker: &str) -> [u8; 32] {
+  sha256::Hash::hash(ticker.as_bytes()).to_byte_array()
+}
+
+/// Root of a sparse Merkle tree of the state hash of each token, keyed by the
+/// SHA-256 of its ticker. A single root commits to every token, which is what
+/// relays submit to other chains.
+pub fn state_root<'a>(tokens: impl IntoIterator<Item = &'a TokenState>) -> [u8; 32] {
+  SparseMerkleTree::from_leaves(
+    merkle::DEPTH,
+    tokens
+      .into_iter()
+      .map(|token| (state_key(&token.definition.ticker), token.state_hash())),
+  )
+  .root()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    super::{identity::Bip322Verifier, zk::PlaceholderZk},
+    *,
+  };
+
+  struct Allow(&'static str);
+
+  impl IdentityVerifier for Allow {
+    fn verify(
+      &self,
+      identity: &IdentityCommitment,
+      _message: &str,
+      _proof: Option<&IdentityProof>,
+    ) -> Result<(), Brc20Error> {
+      if identity.id == self.0 {
+        Ok(())
+      } else {
+        Err(Brc20Error::IdentityVerificationFailed(format!(
+          "{} is not allowed",
+          identity.id
+        )))
+      }
+    }
+  }
+
+  fn state_machine(require_identity: bool) -> Brc20StateMachine {
+    let mut state_machine = Brc20StateMachine::default();
+
+    for operation in [
+      Operation::Deploy {
+        definition: TokenDefinition {
+          ticker: "ordi".into(),
+          max_supply: 1000,
+          mint_limit: 1000,
+          decimals: 0,
+          soulbound: false,
+          transfer_rules: TransferRules { require_identity },
+        },
+      },
+      Operation::Mint {
+        ticker: "ordi".into(),
+        to: IdentityCommitment::new("alice"),
+        amount: 100,
+      },
+      Operation::Mint {
+        ticker: "ordi".into(),
+        to: IdentityCommitment::new("bob"),
+        amount: 100,
+      },
+    ] {
+      state_machine
+        .apply_operation(
+          operation,
+          &PlaceholderZk,
+          &Allow("alice"),
+          Some(&IdentityCommitment::new("alice")),
+          0,
+        )
+        .unwrap();
+    }
+
+    state_machine
+  }
+
+  fn transfer(from: &str, to: &str) -> Operation {
+    Operation::Transfer {
+      ticker: "ordi".into(),
+      from: IdentityCommitment::new(from),
+      to: IdentityCommitment::new(to),
+      amount: 10,
+      proof: None,
+    }
+  }
+
+  #[test]
+  fn transfers_from_verified_identities_are_allowed() {
+    let mut state_machine = state_machine(true);
+
+    apply(&mut state_machine, "alice", transfer("alice", "carol")).unwrap();
+
+    assert_eq!(
+      state_machine.tokens["ordi"]
+        .account(&IdentityCommitment::new("carol"))
+        .balance,
+      10
+    );
+  }
+
+  #[test]
+  fn transfers_from_unverified_identities_are_rejected() {
+    assert_eq!(
+      apply(&mut state_machine(true), "bob", transfer("bob", "alice")).unwrap_err(),
+      Brc20Error::IdentityVerificationFailed("bob is not allowed".into()),
+    );
+  }
+
+  #[test]
+  fn identity_is_only_checked_if_required() {
+    let mut state_machine = state_machine(false);
+
+    assert_eq!(
+      state_machine
+        .apply_operation(
+          transfer("bob", "alice"),
+          &PlaceholderZk,
+          &Allow("alice"),
+          None,
+          0,
+        )
+        .unwrap_err(),
+      Brc20Error::Unauthorized("operation has no owner, expected bob".into()),
+    );
+
+    apply(&mut state_machine, "bob", transfer("bob", "alice")).unwrap();
+  }
+
+  #[test]
+  fn identity_proofs_authorize_a_single_transfer() {
+    const ADDRESS: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+    const WIF_PRIVATE_KEY: &str = "L3VFeEujGtevx9w18HD1fhRbCH67Az2dpCymeRE1SoPK6XQtaN2k";
+
+    let sender = IdentityCommitment::new(ADDRESS);
+    let carol = IdentityCommitment::new("carol");
+
+    let mut state_machine = state_machine(true);
+
+    apply(
+      &mut state_machine,
+      "alice",
+      Operation::Mint {
+        ticker: "ordi".into(),
+        to: sender.clone(),
+        amount: 100,
+      },
+    )
+    .unwrap();
+
+    let transfer = |amount, nonce| Operation::Transfer {
+      ticker: "ordi".into(),
+      from: sender.clone(),
+      to: carol.clone(),
+      amount,
+      proof: Some(IdentityProof::Bip322 {
+        signature: bip322::sign_simple_encoded(
+          ADDRESS,
+          &authorization_message("transfer", "ordi", &sender, &carol, 10, nonce),
+          WIF_PRIVATE_KEY,
+        )
+        .unwrap(),
+        nonce,
+      }),
+    };
+
+    let mut apply = |operation| {
+      state_machine.apply_operation(
+        operation,
+        &PlaceholderZk,
+        &Bip322Verifier {
+          network: bitcoin::Network::Bitcoin,
+        },
+        Some(&sender),
+        0,
+      )
+    };
+
+    apply(transfer(10, 1)).unwrap();
+
+    assert_eq!(
+      apply(transfer(10, 1)).unwrap_err(),
+      Brc20Error::IdentityVerificationFailed(format!(
+        "identity {ADDRESS}: nonce 1 is not greater than last nonce 1"
+      )),
+    );
+
+    assert!(matches!(
+      apply(transfer(20, 2)).unwrap_err(),
+      Brc20Error::IdentityVerificationFailed(message)
+        if message.contains("invalid BIP-322 signature"),
+    ));
+
+    apply(transfer(10, 2)).unwrap();
+
+    let token = &state_machine.tokens["ordi"];
+
+    assert_eq!(token.account(&carol).balance, 20);
+    assert_eq!(token.account(&sender).nonce, 2);
+  }
+
+  #[test]
+  fn transfers_must_be_made_by_sender() {
+    assert_eq!(
+      apply(&mut state_machine(false), "alice", transfer("bob", "alice")).unwrap_err(),
+      Brc20Error::Unauthorized("alice may not act for bob".into()),
+    );
+  }
+
+  fn apply(
+    state_machine: &mut Brc20StateMachine,
+    owner: &str,
+    operation: Operation,
+  ) -> Result<TransitionReceipt, Brc20Error> {
+    state_machine.apply_operation(
+      operation,
+      &PlaceholderZk,
+      &Allow("alice"),
+      Some(&IdentityCommitment::new(owner)),
+      0,
+    )
+  }
+
+  fn set_soulbound() -> Operation {
+    Operation::SetSoulbound {
+      ticker: "ordi".into(),
+      soulbound: true,
+    }
+  }
+
+  #[test]
+  fn deployer_is_admin() {
+    let mut state_machine = state_machine(false);
+
+    assert_eq!(
+      state_machine.tokens["ordi"].admin,
+      Some(IdentityCommitment::new("alice"))
+    );
+
+    assert_eq!(
+      apply(&mut state_machine, "bob", set_soulbound()).unwrap_err(),
+      Brc20Error::Unauthorized("bob may not act for alice".into()),
+    );
+
+    assert_eq!(
+      state_machine
+        .apply_operation(set_soulbound(), &PlaceholderZk, &Allow("alice"), None, 0)
+        .unwrap_err(),
+      Brc20Error::Unauthorized("operation has no owner, expected alice".into()),
+    );
+
+    apply(&mut state_machine, "alice", set_soulbound()).unwrap();
+
+    assert!(state_machine.tokens["ordi"].definition.soulbound);
+  }
+
+  #[test]
+  fn admin_role_can_be_transferred() {
+    let mut state_machine = state_machine(false);
+
+    let transfer_admin = |to: &str| Operation::TransferAdmin {
+      ticker: "ordi".into(),
+      to: IdentityCommitment::new(to),
+    };
+
+    assert_eq!(
+      apply(&mut state_machine, "bob", transfer_admin("bob")).unwrap_err(),
+      Brc20Error::Unauthorized("bob may not act for alice".into()),
+    );
+
+    apply(&mut state_machine, "alice", transfer_admin("bob")).unwrap();
+
+    assert_eq!(
+      apply(&mut state_machine, "alice", set_soulbound()).unwrap_err(),
+      Brc20Error::Unauthorized("alice may not act for bob".into()),
+    );
+
+    apply(&mut state_machine, "bob", set_soulbound()).unwrap();
+  }
+
+  #[test]
+  fn tokens_without_admin_cannot_be_administered() {
+    let mut state_machine = state_machine(false);
+
+    state_machine.tokens.get_mut("ordi").unwrap().admin = None;
+
+    assert_eq!(
+      apply(&mut state_machine, "alice", set_soulbound()).unwrap_err(),
+      Brc20Error::Unauthorized("token ordi has no admin".into()),
+    );
+  }
+
+  #[test]
+  fn owners_can_burn_their_balance() {
+    let mut state_machine = state_machine(false);
+
+    let burn = |amount| Operation::Burn {
+      ticker: "ordi".into(),
+      from: IdentityCommitment::new("alice"),
+      amount,
+    };
+
+    assert_eq!(
+      apply(&mut state_machine, "bob", burn(30)).unwrap_err(),
+      Brc20Error::Unauthorized("bob may not act for alice".into()),
+    );
+
+    apply(&mut state_machine, "alice", burn(30)).unwrap();
+
+    let token = &state_machine.tokens["ordi"];
+    assert_eq!(token.account(&IdentityCommitment::new("alice")).balance, 70);
+    assert_eq!(token.total_supply, 170);
+
+    assert_eq!(
+      apply(&mut state_machine, "alice", burn(71)).unwrap_err(),
+      Brc20Error::InsufficientBalance {
+        available: 70,
+        required: 71,
+      },
+    );
+  }
+
+  #[test]
+  fn allowances_can_be_spent_by_spender() {
+    let mut state_machine = state_machine(false);
+
+    let approve = |amount| Operation::Approve {
+      ticker: "ordi".into(),
+      spender: IdentityCommitment::new("carol"),
+      amount,
+    };
+
+    let transfer_from = Operation::TransferFrom {
+      ticker: "ordi".into(),
+      from: IdentityCommitment::new("alice"),
+      to: IdentityCommitment::new("bob"),
+      amount: 10,
+      proof: None,
+    };
+
+    assert_eq!(
+      apply(&mut state_machine, "carol", transfer_from.clone()).unwrap_err(),
+      Brc20Error::InsufficientAllowance {
+        available: 0,
+        required: 10,
+      },
+    );
+
+    apply(&mut state_machine, "alice", approve(15)).unwrap();
+
+    assert_eq!(
+      apply(&mut state_machine, "bob", transfer_from.clone()).unwrap_err(),
+      Brc20Error::InsufficientAllowance {
+        available: 0,
+        required: 10,
+      },
+    );
+
+    apply(&mut state_machine, "carol", transfer_from.clone()).unwrap();
+
+    let token = &state_machine.tokens["ordi"];
+    let alice = IdentityCommitment::new("alice");
+    let carol = IdentityCommitment::new("carol");
+    assert_eq!(token.account(&alice).balance, 90);
+    assert_eq!(token.account(&IdentityCommitment::new("bob")).balance, 110);
+    assert_eq!(token.allowance(&alice, &carol), 5);
+
+    assert_eq!(
+      apply(&mut state_machine, "carol", transfer_from).unwrap_err(),
+      Brc20Error::InsufficientAllowance {
+        available: 5,
+        required: 10,
+      },
+    );
+
+    apply(&mut state_machine, "alice", approve(0)).unwrap();
+
+    assert!(state_machine.tokens["ordi"].allowances.is_empty());
+  }
+
+  #[test]
+  fn frozen_accounts_cannot_send_or_receive() {
+    let mut state_machine = state_machine(false);
+
+    let freeze = |frozen| Operation::Freeze {
+      ticker: "ordi".into(),
+      account: IdentityCommitment::new("bob"),
+      frozen,
+    };
+
+    assert_eq!(
+      apply(&mut state_machine, "bob", freeze(true)).unwrap_err(),
+      Brc20Error::Unauthorized("bob may not act for alice".into()),
+    );
+
+    let unfrozen = state_machine.tokens["ordi"].state_hash();
+
+    apply(&mut state_machine, "alice", freeze(true)).unwrap();
+
+    assert_ne!(state_machine.tokens["ordi"].state_hash(), unfrozen);
+
+    let frozen = Brc20Error::AccountFrozen {
+      ticker: "ordi".into(),
+      identity: "bob".into(),
+    };
+
+    assert_eq!(
+      apply(&mut state_machine, "bob", transfer("bob", "alice")).unwrap_err(),
+      frozen,
+    );
+
+    assert_eq!(
+      apply(&mut state_machine, "alice", transfer("alice", "bob")).unwrap_err(),
+      frozen,
+    );
+
+    assert_eq!(
+      apply(
+        &mut state_machine,
+        "bob",
+        Operation::Burn {
+          ticker: "ordi".into(),
+          from: IdentityCommitment::new("bob"),
+          amount: 1,
+        }
+      )
+      .unwrap_err(),
+      frozen,
+    );
+
+    apply(&mut state_machine, "alice", freeze(false)).unwrap();
+
+    assert_eq!(state_machine.tokens["ordi"].state_hash(), unfrozen);
+
+    apply(&mut state_machine, "bob", transfer("bob", "alice")).unwrap();
+  }
+
+  fn schedule(curve: VestingCurve) -> VestingSchedule {
+    VestingSchedule {
+      start_height: 100,
+      cliff_blocks: 10,
+      duration_blocks: 110,
+      curve,
+      total_locked: 1000,
+    }
+  }
+
+  #[test]
+  fn linear_vesting_unlocks_every_block_after_cliff() {
+    let schedule = schedule(VestingCurve::Linear);
+
+    assert_eq!(schedule.unlocked_at(0), 0);
+    assert_eq!(schedule.unlocked_at(110), 0);
+    assert_eq!(schedule.unlocked_at(111), 10);
+    assert_eq!(schedule.unlocked_at(160), 500);
+    assert_eq!(schedule.unlocked_at(209), 990);
+    assert_eq!(schedule.unlocked_at(210), 1000);
+    assert_eq!(schedule.unlocked_at(u32::MAX), 1000);
+    assert_eq!(schedule.locked_at(160), 500);
+  }
+
+  #[test]
+  fn step_vesting_unlocks_in_tranches() {
+    let schedule = schedule(VestingCurve::Step { interval: 30 });
+
+    assert_eq!(schedule.unlocked_at(110), 0);
+    assert_eq!(schedule.unlocked_at(139), 0);
+    assert_eq!(schedule.unlocked_at(140), 250);
+    assert_eq!(schedule.unlocked_at(169), 250);
+    assert_eq!(schedule.unlocked_at(170), 500);
+    assert_eq!(schedule.unlocked_at(200), 750);
+    assert_eq!(schedule.unlocked_at(209), 750);
+    assert_eq!(schedule.unlocked_at(210), 1000);
+  }
+
+  #[test]
+  fn invalid_vesting_schedules_are_rejected() {
+    assert_eq!(
+      schedule(VestingCurve::Step { interval: 0 }).validate(),
+      Err(Brc20Error::VestingScheduleInvalid(
+        "step interval must be positive".into()
+      )),
+    );
+
+    assert_eq!(
+      VestingSchedule {
+        cliff_blocks: 111,
+        ..schedule(VestingCurve::Linear)
+      }
+      .validate(),
+      Err(Brc20Error::VestingScheduleInvalid(
+        "cliff_blocks cannot exceed duration_blocks".into()
+      )),
+    );
+
+    assert_eq!(
+      VestingSchedule {
+        cliff_blocks: 0,
+        duration_blocks: 0,
+        ..schedule(VestingCurve::Linear)
+      }
+      .validate(),
+      Err(Brc20Error::VestingScheduleInvalid(
+        "duration_blocks must be positive".into()
+      )),
+    );
+  }
+
+  #[test]
+  fn vested_balances_unlock_by_height() {
+    let replay = || {
+      let mut state_machine = state_machine(false);
+
+      state_machine
+        .apply_operation(
+          Operation::MintVested {
+            ticker: "ordi".into(),
+            to: IdentityCommitment::new("carol"),
+            amount: 100,
+            vesting: VestingSchedule {
+              total_locked: 100,
+              ..schedule(VestingCurve::Step { interval: 30 })
+            },
+          },
+          &PlaceholderZk,
+          &Allow("alice"),
+          None,
+          100,
+        )
+        .unwrap();
+
+      let transfer = |amount| Operation::Transfer {
+        ticker: "ordi".into(),
+        from: IdentityCommitment::new("carol"),
+        to: IdentityCommitment::new("alice"),
+        amount,
+        proof: None,
+      };
+
+      let carol = IdentityCommitment::new("carol");
+
+      assert_eq!(
+        state_machine
+          .apply_operation(
+            transfer(1),
+            &PlaceholderZk,
+            &Allow("alice"),
+            Some(&carol),
+            139
+          )
+          .unwrap_err(),
+        Brc20Error::InsufficientBalance {
+          available: 0,
+          required: 1,
+        },
+      );
+
+      state_machine
+        .apply_operation(
+          transfer(25),
+          &PlaceholderZk,
+          &Allow("alice"),
+          Some(&carol),
+          140,
+        )
+        .unwrap()
+        .state_hash
+    };
+
+    assert_eq!(replay(), replay());
+  }
+
+  #[test]
+  fn state_root_commits_to_every_token() {
+    let mut state_machine = state_machine(false);
+
+    let root = state_root(state_machine.tokens.values());
+
+    assert_ne!(root, state_root([]));
+
+    apply(&mut state_machine, "bob", transfer("bob", "alice")).unwrap();
+
+    assert_ne!(state_root(state_machine.tokens.values()), root);
+
+    let ordi = state_machine.tokens["ordi"].clone();
+
+    let mut pepe = ordi.clone();
+    pepe.definition.ticker = "pepe".into();
+
+    assert_eq!(state_root([&ordi, &pepe]), state_root([&pepe, &ordi]));
+  }
+}
@@ -1,25 +1,179 @@
-use thiserror::Error;
+use std::{error::Error, fmt};
 
-#[derive(Error, Debug)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Brc20Error {
-    #[error("identity verification failed")]
-    IdentityFailed,
+  TokenAlreadyExists(String),
+  TokenNotFound(String),
+  InvalidOperation(String),
+  InsufficientBalance {
+    available: u128,
+    required: u128,
+  },
+  MintLimitExceeded {
+    limit: u128,
+    requested: u128,
+  },
+  MaxSupplyExceeded {
+    max_supply: u128,
+    attempted_total: u128,
+  },
+  SoulboundTransferDenied(String),
+  IdentityVerificationFailed(String),
+  VestingScheduleInvalid(String),
+  ProofGenerationFailed(String),
+  ProofVerificationFailed(String),
+  Relay(String),
+  Unauthorized(String),
+  AccountFrozen {
+    ticker: String,
+    identity: String,
+  },
+  InsufficientAllowance {
+    available: u128,
+    required: u128,
+  },
+}
 
-    #[error("insufficient balance")]
-    InsufficientBalance,
+impl fmt::Display for Brc20Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::TokenAlreadyExists(ticker) => {
+        write!(f, "token {ticker} already exists")
+      }
+      Self::TokenNotFound(ticker) => {
+        write!(f, "token {ticker} not found")
+      }
+      Self::InvalidOperation(message) => f.write_str(message),
+      Self::InsufficientBalance {
+        available,
+        required,
+      } => {
+        write!(
+          f,
+          "insufficient balance: {available} available, {required} required"
+        )
+      }
+      Self::MintLimitExceeded { limit, requested } => {
+        write!(f, "mint limit {limit} exceeded by request {requested}")
+      }
+      Self::MaxSupplyExceeded {
+        max_supply,
+        attempted_total,
+      } => write!(
+        f,
+        "max supply {max_supply} exceeded by attempted total {attempted_total}"
+      ),
+      Self::SoulboundTransferDenied(ticker) => {
+        write!(f, "token {ticker} is soulbound and cannot be transferred")
+      }
+      Self::IdentityVerificationFailed(message) => {
+        write!(f, "identity verification failed: {message}")
+      }
+      Self::VestingScheduleInvalid(message) => {
+        write!(f, "invalid vesting schedule: {message}")
+      }
+      Self::ProofGenerationFailed(message) => {
+        write!(f, "proof generation failed: {message}")
+      }
+      Self::ProofVerificationFailed(message) => {
+        write!(f, "proof verification failed: {message}")
+      }
+      Self::Relay(message) => write!(f, "relay failed: {message}"),
+      Self::Unauthorized(message) => write!(f, "unauthorized: {message}"),
+      Self::AccountFrozen { ticker, identity } => {
+        write!(f, "account {identity} is frozen on token {ticker}")
+      }
+      Self::InsufficientAllowance {
+        available,
+        required,
+      } => write!(
+        f,
+        "insufficient allowance: {available} available, {required} required"
+      ),
+    }
+  }
+}
 
-    #[error("token is soulbound")]
-    Soulbound,
+impl Error for Brc20Error {}
 
-    #[error("vesting locked until block {0}")]
-    VestingLocked(u64),
+/// Why an inscription claiming to be a BRC-20 v2 operation was not applied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "reason")]
+pub enum Rejection {
+  /// The payload doesn't match the `{p, op, tick, body}` envelope.
+  InvalidPayload {
+    message: String,
+  },
+  /// The ticker isn't 3 to 16 lowercase ASCII letters and digits.
+  InvalidTicker {
+    ticker: String,
+  },
+  UnknownOperation {
+    op: String,
+  },
+  /// The body is missing a field, has an unexpected one, or a field has the
+  /// wrong type.
+  InvalidBody {
+    op: String,
+    message: String,
+  },
+  InvalidAmount {
+    field: String,
+    amount: String,
+  },
+  /// A decimal amount has more fractional digits than the token allows.
+  ExcessPrecision {
+    field: String,
+    amount: String,
+    decimals: u8,
+  },
+  InvalidDecimals {
+    decimals: u8,
+  },
+  /// The operation was well formed, but the state machine refused it.
+  Rule {
+    message: String,
+  },
+}
 
-    #[error("amount exceeds max per tx")]
-    MaxTransferExceeded,
+impl fmt::Display for Rejection {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::InvalidPayload { message } => write!(f, "invalid payload: {message}"),
+      Self::InvalidTicker { ticker } => write!(f, "invalid ticker `{ticker}`"),
+      Self::UnknownOperation { op } => write!(f, "unknown operation `{op}`"),
+      Self::InvalidBody { op, message } => write!(f, "invalid {op} body: {message}"),
+      Self::InvalidAmount { field, amount } => {
+        write!(f, "invalid amount `{amount}` in field `{field}`")
+      }
+      Self::ExcessPrecision {
+        field,
+        amount,
+        decimals,
+      } => write!(
+        f,
+        "amount `{amount}` in field `{field}` exceeds {decimals} decimal places"
+      ),
+      Self::InvalidDecimals { decimals } => {
+        write!(
+          f,
+          "decimals {decimals} exceeds maximum of {}",
+          super::inscription::MAX_DECIMALS
+        )
+      }
+      Self::Rule { message } => f.write_str(message),
+    }
+  }
+}
 
-    #[error("invalid zk proof")]
-    InvalidProof,
+impl Error for Rejection {}
 
-    #[error("relay error: {0}")]
-    Relay(String),
+impl From<Brc20Error> for Rejection {
+  fn from(error: Brc20Error) -> Self {
+    Self::Rule {
+      message: error.to_string(),
+    }
+  }
 }
@@ -1,22 +1,179 @@
-use sha2::{Digest, Sha256};
+use std::fmt;
 
-pub struct IdentityProof {
-    pub subject: String,
-    pub commitment: String,
+use bitcoin::hashes::{Hash, sha256};
+use serde::{Deserialize, Serialize};
+
+use super::errors::Brc20Error;
+
+pub mod attestation;
+pub mod bip322;
+
+pub use {
+  attestation::{AttestationList, AttestationVerifier, Attestations},
+  bip322::Bip322Verifier,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IdentityCommitment {
+  pub id: String,
+  pub commitment: [u8; 32],
+}
+
+impl IdentityCommitment {
+  pub fn new(id: impl Into<String>) -> Self {
+    let id = id.into();
+    let commitment = sha256::Hash::hash(id.as_bytes()).to_byte_array();
+    Self { id, commitment }
+  }
+
+  pub fn with_commitment(id: impl Into<String>, commitment: [u8; 32]) -> Self {
+    Self {
+      id: id.into(),
+      commitment,
+    }
+  }
+}
+
+/// Evidence attached to an operation that its sender controls an identity
+/// and authorized the operation.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityProof {
+  /// Base64-encoded BIP-322 simple signature over the
+  /// `authorization_message` of the operation with `nonce`, as output by
+  /// `ord wallet sign`.
+  Bip322 { signature: String, nonce: u64 },
+}
+
+impl IdentityProof {
+  pub fn nonce(&self) -> u64 {
+    match self {
+      Self::Bip322 { nonce, .. } => *nonce,
+    }
+  }
+}
+
+/// The message the sender of an `op` of `amount` base units of `ticker` from
+/// `from` to `to` signs to authorize it. Each proof of an account must have a
+/// greater `nonce` than the last, so that a signature authorizes a single
+/// operation.
+pub fn authorization_message(
+  op: &str,
+  ticker: &str,
+  from: &IdentityCommitment,
+  to: &IdentityCommitment,
+  amount: u128,
+  nonce: u64,
+) -> String {
+  format!(
+    "brc-20-v2 {op} {ticker} {amount} {} {} {nonce}",
+    hex::encode(from.commitment),
+    hex::encode(to.commitment),
+  )
+}
+
+pub trait IdentityVerifier {
+  /// Check that `identity` authorized the operation whose
+  /// `authorization_message` is `message`.
+  fn verify(
+    &self,
+    identity: &IdentityCommitment,
+    message: &str,
+    proof: Option<&IdentityProof>,
+  ) -> Result<(), Brc20Error>;
 }
 
-pub fn verify_identity(subject: &str, signature: &str) -> bool {
-    let mut hasher = Sha256::new();
-    hasher.update(subject.as_bytes());
-    let expected = hex::encode(hasher.finalize());
-    expected == signature
+/// Accepts an identity if any of its verifiers do. Rejects everything if
+/// empty.
+#[derive(Default)]
+pub struct IdentityVerifiers(pub Vec<Box<dyn IdentityVerifier>>);
+
+impl fmt::Debug for IdentityVerifiers {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "IdentityVerifiers({})", self.0.len())
+  }
 }
 
-pub fn generate_identity_commitment(subject: &str) -> IdentityProof {
-    let mut hasher = Sha256::new();
-    hasher.update(subject.as_bytes());
-    IdentityProof {
-        subject: subject.to_string(),
-        commitment: hex::encode(hasher.finalize()),
+impl IdentityVerifier for IdentityVerifiers {
+  fn verify(
+    &self,
+    identity: &IdentityCommitment,
+    message: &str,
+    proof: Option<&IdentityProof>,
+  ) -> Result<(), Brc20Error> {
+    if self.0.is_empty() {
+      return Err(Brc20Error::IdentityVerificationFailed(
+        "no identity verifier configured".into(),
+      ));
     }
+
+    let mut errors = Vec::new();
+
+    for verifier in &self.0 {
+      match verifier.verify(identity, message, proof) {
+        Ok(()) => return Ok(()),
+        Err(Brc20Error::IdentityVerificationFailed(message)) => errors.push(message),
+        Err(err) => errors.push(err.to_string()),
+      }
+    }
+
+    Err(Brc20Error::IdentityVerificationFailed(errors.join("; ")))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct Accept;
+
+  impl IdentityVerifier for Accept {
+    fn verify(
+      &self,
+      _: &IdentityCommitment,
+      _: &str,
+      _: Option<&IdentityProof>,
+    ) -> Result<(), Brc20Error> {
+      Ok(())
+    }
+  }
+
+  struct Reject(&'static str);
+
+  impl IdentityVerifier for Reject {
+    fn verify(
+      &self,
+      _: &IdentityCommitment,
+      _: &str,
+      _: Option<&IdentityProof>,
+    ) -> Result<(), Brc20Error> {
+      Err(Brc20Error::IdentityVerificationFailed(self.0.into()))
+    }
+  }
+
+  #[test]
+  fn empty_verifiers_reject() {
+    assert_eq!(
+      IdentityVerifiers::default().verify(&IdentityCommitment::new("alice"), "", None),
+      Err(Brc20Error::IdentityVerificationFailed(
+        "no identity verifier configured".into()
+      )),
+    );
+  }
+
+  #[test]
+  fn any_verifier_may_accept() {
+    let alice = IdentityCommitment::new("alice");
+
+    assert_eq!(
+      IdentityVerifiers(vec![Box::new(Reject("foo")), Box::new(Accept)]).verify(&alice, "", None),
+      Ok(()),
+    );
+
+    assert_eq!(
+      IdentityVerifiers(vec![Box::new(Reject("foo")), Box::new(Reject("bar"))])
+        .verify(&alice, "", None),
+      Err(Brc20Error::IdentityVerificationFailed("foo; bar".into())),
+    );
+  }
 }
@@ -0,0 +1,232 @@
+use std::collections::BTreeSet;
+
+use bitcoin::{
+  hashes::{Hash, HashEngine, sha256},
+  secp256k1::{Keypair, Message, SECP256K1, XOnlyPublicKey, schnorr::Signature},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{Brc20Error, IdentityCommitment, IdentityProof, IdentityVerifier};
+
+const TAG: &[u8] = b"brc-20-v2/attestation";
+
+/// A list of identity ids vouched for by an issuer.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AttestationList {
+  pub identities: Vec<String>,
+  /// Hex-encoded BIP-340 signature over `digest`.
+  pub signature: String,
+}
+
+impl AttestationList {
+  pub fn sign(identities: Vec<String>, keypair: &Keypair) -> Self {
+    let signature = SECP256K1.sign_schnorr_no_aux_rand(&Self::message(&identities), keypair);
+
+    Self {
+      identities,
+      signature: hex::encode(signature.serialize()),
+    }
+  }
+
+  /// SHA-256 of a domain tag followed by the commitment of each identity.
+  pub fn digest(identities: &[String]) -> [u8; 32] {
+    let mut engine = sha256::Hash::engine();
+
+    engine.input(TAG);
+
+    for id in identities {
+      engine.input(&IdentityCommitment::new(id.as_str()).commitment);
+    }
+
+    sha256::Hash::from_engine(engine).to_byte_array()
+  }
+
+  fn message(identities: &[String]) -> Message {
+    Message::from_digest(Self::digest(identities))
+  }
+
+  pub fn verify(&self, issuer: &XOnlyPublicKey) -> Result<(), Brc20Error> {
+    let invalid = |message: String| {
+      Brc20Error::IdentityVerificationFailed(format!("invalid attestation list: {message}"))
+    };
+
+    let signature = hex::decode(&self.signature)
+      .map_err(|err| invalid(err.to_string()))
+      .and_then(|bytes| Signature::from_slice(&bytes).map_err(|err| invalid(err.to_string())))?;
+
+    SECP256K1
+      .verify_schnorr(&signature, &Self::message(&self.identities), issuer)
+      .map_err(|err| invalid(err.to_string()))
+  }
+}
+
+/// Attestation lists signed by `issuer`, as stored in the file configured with
+/// `--brc20v2-attestations`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Attestations {
+  /// Hex-encoded x-only public key of the issuer.
+  pub issuer: String,
+  pub lists: Vec<AttestationList>,
+}
+
+/// Accepts identities that appear in an attestation list signed by the
+/// configured issuer. No per-operation proof is required.
+#[derive(Clone, Debug)]
+pub struct AttestationVerifier {
+  attested: BTreeSet<[u8; 32]>,
+}
+
+impl AttestationVerifier {
+  /// Fails if the issuer key is malformed or any list is not signed by it.
+  pub fn new(attestations: &Attestations) -> Result<Self, Brc20Error> {
+    let issuer = attestations
+      .issuer
+      .parse::<XOnlyPublicKey>()
+      .map_err(|err| {
+        Brc20Error::IdentityVerificationFailed(format!("invalid attestation issuer: {err}"))
+      })?;
+
+    let mut attested = BTreeSet::new();
+
+    for list in &attestations.lists {
+      list.verify(&issuer)?;
+
+      attested.extend(
+        list
+          .identities
+          .iter()
+          .map(|id| IdentityCommitment::new(id.as_str()).commitment),
+      );
+    }
+
+    Ok(Self { attested })
+  }
+
+  /// SHA-256 of a domain tag followed by each attested commitment in order,
+  /// which identifies the attested identities however they were listed.
+  pub fn digest(&self) -> [u8; 32] {
+    let mut engine = sha256::Hash::engine();
+
+    engine.input(TAG);
+
+    for commitment in &self.attested {
+      engine.input(commitment);
+    }
+
+    sha256::Hash::from_engine(engine).to_byte_array()
+  }
+}
+
+impl IdentityVerifier for AttestationVerifier {
+  fn verify(
+    &self,
+    identity: &IdentityCommitment,
+    _message: &str,
+    _proof: Option<&IdentityProof>,
+  ) -> Result<(), Brc20Error> {
+    if self.attested.contains(&identity.commitment) {
+      Ok(())
+    } else {
+      Err(Brc20Error::IdentityVerificationFailed(format!(
+        "identity {} is not attested",
+        identity.id
+      )))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, bitcoin::secp256k1::SecretKey};
+
+  fn keypair(byte: u8) -> Keypair {
+    Keypair::from_secret_key(SECP256K1, &SecretKey::from_slice(&[byte; 32]).unwrap())
+  }
+
+  fn attestations(issuer: &Keypair, signer: &Keypair) -> Attestations {
+    Attestations {
+      issuer: issuer.x_only_public_key().0.to_string(),
+      lists: vec![AttestationList::sign(
+        vec!["alice".into(), "bob".into()],
+        signer,
+      )],
+    }
+  }
+
+  #[test]
+  fn attested_identities_are_accepted() {
+    let issuer = keypair(1);
+
+    let verifier = AttestationVerifier::new(&attestations(&issuer, &issuer)).unwrap();
+
+    assert_eq!(
+      verifier.verify(&IdentityCommitment::new("alice"), "", None),
+      Ok(())
+    );
+    assert_eq!(
+      verifier.verify(&IdentityCommitment::new("bob"), "", None),
+      Ok(())
+    );
+    assert_eq!(
+      verifier.verify(&IdentityCommitment::new("carol"), "", None),
+      Err(Brc20Error::IdentityVerificationFailed(
+        "identity carol is not attested".into()
+      )),
+    );
+  }
+
+  #[test]
+  fn lists_signed_by_other_keys_are_rejected() {
+    assert!(matches!(
+      AttestationVerifier::new(&attestations(&keypair(1), &keypair(2))),
+      Err(Brc20Error::IdentityVerificationFailed(message))
+        if message.starts_with("invalid attestation list"),
+    ));
+  }
+
+  #[test]
+  fn tampered_lists_are_rejected() {
+    let issuer = keypair(1);
+
+    let mut attestations = attestations(&issuer, &issuer);
+
+    attestations.lists[0].identities.push("carol".into());
+
+    assert!(AttestationVerifier::new(&attestations).is_err());
+  }
+
+  #[test]
+  fn digest_commits_to_attested_identities() {
+    let issuer = keypair(1);
+
+    let digest = |lists: &[&[&str]]| {
+      AttestationVerifier::new(&Attestations {
+        issuer: issuer.x_only_public_key().0.to_string(),
+        lists: lists
+          .iter()
+          .map(|ids| AttestationList::sign(ids.iter().map(|id| id.to_string()).collect(), &issuer))
+          .collect(),
+      })
+      .unwrap()
+      .digest()
+    };
+
+    assert_eq!(
+      digest(&[&["alice", "bob"]]),
+      digest(&[&["bob"], &["alice"]])
+    );
+    assert_ne!(digest(&[&["alice", "bob"]]), digest(&[&["alice"]]));
+  }
+
+  #[test]
+  fn attestations_round_trip_through_json() {
+    let issuer = keypair(1);
+
+    let attestations = attestations(&issuer, &issuer);
+
+    assert_eq!(
+      serde_json::from_str::<Attestations>(&serde_json::to_string(&attestations).unwrap()).unwrap(),
+      attestations,
+    );
+  }
+}
@@ -0,0 +1,131 @@
+use {
+  base64::{Engine, engine::general_purpose},
+  bitcoin::{
+    Address, Network, Witness, address::NetworkUnchecked, consensus::Decodable, io::Cursor,
+  },
+};
+
+use super::{Brc20Error, IdentityCommitment, IdentityProof, IdentityVerifier};
+
+/// Accepts identities whose id is an address on `network` and whose proof is
+/// a BIP-322 signature by that address over the authorization message.
+#[derive(Clone, Copy, Debug)]
+pub struct Bip322Verifier {
+  pub network: Network,
+}
+
+impl IdentityVerifier for Bip322Verifier {
+  fn verify(
+    &self,
+    identity: &IdentityCommitment,
+    message: &str,
+    proof: Option<&IdentityProof>,
+  ) -> Result<(), Brc20Error> {
+    let failed = |message| {
+      Brc20Error::IdentityVerificationFailed(format!("identity {}: {message}", identity.id))
+    };
+
+    let Some(IdentityProof::Bip322 { signature, .. }) = proof else {
+      return Err(failed("missing BIP-322 signature".into()));
+    };
+
+    let address = identity
+      .id
+      .parse::<Address<NetworkUnchecked>>()
+      .map_err(|err| failed(format!("invalid address: {err}")))?
+      .require_network(self.network)
+      .map_err(|err| failed(format!("invalid address: {err}")))?;
+
+    verify_witness(&address, message.as_bytes(), signature)
+      .map_err(|err| failed(format!("invalid BIP-322 signature: {err}")))
+  }
+}
+
+/// Check the base64-encoded, consensus-serialized BIP-322 `witness` of
+/// `address` over `message`.
+fn verify_witness(
+  address: &Address,
+  message: &[u8],
+  witness: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+  let mut cursor = Cursor::new(general_purpose::STANDARD.decode(witness)?);
+  let witness = Witness::consensus_decode_from_finite_reader(&mut cursor)?;
+  bip322::verify_simple(address, message, witness)?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const ADDRESS: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+
+  // BIP-322 test vector signature by `ADDRESS` over "Hello World"
+  const HELLO_WORLD: &str = "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+
+  #[test]
+  fn proof_is_required() {
+    assert_eq!(
+      Bip322Verifier {
+        network: Network::Bitcoin,
+      }
+      .verify(&IdentityCommitment::new(ADDRESS), "Hello World", None),
+      Err(Brc20Error::IdentityVerificationFailed(format!(
+        "identity {ADDRESS}: missing BIP-322 signature"
+      ))),
+    );
+  }
+
+  #[test]
+  fn id_must_be_address_on_network() {
+    let proof = IdentityProof::Bip322 {
+      signature: String::new(),
+      nonce: 1,
+    };
+
+    assert!(matches!(
+      Bip322Verifier {
+        network: Network::Bitcoin,
+      }
+      .verify(&IdentityCommitment::new("alice"), "Hello World", Some(&proof)),
+      Err(Brc20Error::IdentityVerificationFailed(message))
+        if message.starts_with("identity alice: invalid address"),
+    ));
+
+    assert!(matches!(
+      Bip322Verifier {
+        network: Network::Regtest,
+      }
+      .verify(&IdentityCommitment::new(ADDRESS), "Hello World", Some(&proof)),
+      Err(Brc20Error::IdentityVerificationFailed(message))
+        if message.contains("invalid address"),
+    ));
+  }
+
+  #[test]
+  fn signature_must_be_over_message() {
+    let verifier = Bip322Verifier {
+      network: Network::Bitcoin,
+    };
+
+    let proof = IdentityProof::Bip322 {
+      signature: HELLO_WORLD.into(),
+      nonce: 1,
+    };
+
+    assert_eq!(
+      verifier.verify(
+        &IdentityCommitment::new(ADDRESS),
+        "Hello World",
+        Some(&proof)
+      ),
+      Ok(()),
+    );
+
+    assert!(matches!(
+      verifier.verify(&IdentityCommitment::new(ADDRESS), "Goodbye World", Some(&proof)),
+      Err(Brc20Error::IdentityVerificationFailed(message))
+        if message.contains("invalid BIP-322 signature"),
+    ));
+  }
+}
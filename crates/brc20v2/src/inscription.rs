@@ -1,32 +1,882 @@
-use serde::Serialize;
-
-use crate::zk::ZkProof;
-
-#[derive(Serialize)]
-pub struct Inscription<'a> {
-    pub protocol: &'static str,
-    pub token: &'a str,
-    pub action: &'a str,
-    pub state_hash: &'a str,
-    pub merkle_root: &'a str,
-    pub proof: Option<&'a ZkProof>,
-}
-
-impl<'a> Inscription<'a> {
-    pub fn new(
-        token: &'a str,
-        action: &'a str,
-        state_hash: &'a str,
-        merkle_root: &'a str,
-        proof: Option<&'a ZkProof>,
-    ) -> Self {
-        Self {
-            protocol: "brc20v2",
-            token,
-            action,
-            state_hash,
-            merkle_root,
-            proof,
+use std::fmt;
+
+use serde::{
+  Deserialize, Deserializer, Serialize,
+  de::{self, DeserializeOwned, Visitor},
+};
+
+use super::{
+  brc20v2::{Operation, TokenDefinition, TransferRules, VestingCurve, VestingSchedule},
+  errors::{Brc20Error, Rejection},
+  identity::{IdentityCommitment, IdentityProof},
+};
+
+pub const PROTOCOL: &str = "brc-20-v2";
+
+/// The most decimal places a token may be deployed with.
+pub const MAX_DECIMALS: u8 = 18;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Brc20Inscription {
+  pub content_type: String,
+  pub body: Vec<u8>,
+}
+
+/// A BRC-20 v2 payload whose envelope and ticker are valid, but whose body
+/// has not yet been checked.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Payload {
+  pub op: String,
+  pub tick: String,
+  body: serde_json::Value,
+  #[serde(default)]
+  root: Option<String>,
+}
+
+/// An amount, either an integer number of base units, or a decimal string of
+/// whole tokens which is scaled by the token's decimals.
+#[derive(Debug)]
+pub(crate) enum Amount {
+  Base(u128),
+  Decimal(String),
+}
+
+impl<'de> Deserialize<'de> for Amount {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct AmountVisitor;
+
+    impl Visitor<'_> for AmountVisitor {
+      type Value = Amount;
+
+      fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an unsigned integer or a decimal string")
+      }
+
+      fn visit_u64<E: de::Error>(self, n: u64) -> Result<Amount, E> {
+        Ok(Amount::Base(n.into()))
+      }
+
+      fn visit_u128<E: de::Error>(self, n: u128) -> Result<Amount, E> {
+        Ok(Amount::Base(n))
+      }
+
+      fn visit_str<E: de::Error>(self, s: &str) -> Result<Amount, E> {
+        Ok(Amount::Decimal(s.into()))
+      }
+    }
+
+    deserializer.deserialize_any(AmountVisitor)
+  }
+}
+
+impl Amount {
+  /// Format `amount` base units as a decimal string of whole tokens, which
+  /// unlike a JSON number can hold any `u128`.
+  pub(crate) fn format(amount: u128, decimals: u8) -> String {
+    let decimals = usize::from(decimals);
+
+    let digits = format!("{amount:0>width$}", width = decimals + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+      integer.into()
+    } else {
+      format!("{integer}.{fraction}")
+    }
+  }
+
+  fn resolve(self, field: &str, ticker: &str, decimals: Option<u8>) -> Result<u128, Rejection> {
+    let amount = match self {
+      Self::Base(amount) => return Ok(amount),
+      Self::Decimal(amount) => amount,
+    };
+
+    let decimals = decimals.ok_or_else(|| Brc20Error::TokenNotFound(ticker.into()))?;
+
+    let invalid = || Rejection::InvalidAmount {
+      field: field.into(),
+      amount: amount.clone(),
+    };
+
+    let (integer, fraction) = match amount.split_once('.') {
+      Some((_, "")) => return Err(invalid()),
+      Some((integer, fraction)) => (integer, fraction),
+      None => (amount.as_str(), ""),
+    };
+
+    if integer.is_empty()
+      || !integer.bytes().all(|b| b.is_ascii_digit())
+      || !fraction.bytes().all(|b| b.is_ascii_digit())
+    {
+      return Err(invalid());
+    }
+
+    if fraction.len() > usize::from(decimals) {
+      return Err(Rejection::ExcessPrecision {
+        field: field.into(),
+        amount,
+        decimals,
+      });
+    }
+
+    Self::scale(integer, fraction, decimals).ok_or_else(invalid)
+  }
+
+  fn scale(integer: &str, fraction: &str, decimals: u8) -> Option<u128> {
+    let integer = integer
+      .parse::<u128>()
+      .ok()?
+      .checked_mul(10u128.checked_pow(decimals.into())?)?;
+
+    if fraction.is_empty() {
+      return Some(integer);
+    }
+
+    integer.checked_add(
+      format!("{fraction:0<width$}", width = usize::from(decimals))
+        .parse::<u128>()
+        .ok()?,
+    )
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DeployBody {
+  max: Amount,
+  lim: Amount,
+  dec: u8,
+  #[serde(default)]
+  soulbound: bool,
+  #[serde(default)]
+  rules: TransferRules,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MintBody {
+  to: String,
+  amt: Amount,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VestingBody {
+  start: u32,
+  cliff: u32,
+  duration: u32,
+  #[serde(default)]
+  step: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MintVestedBody {
+  to: String,
+  amt: Amount,
+  vesting: VestingBody,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransferBody {
+  from: String,
+  to: String,
+  amt: Amount,
+  #[serde(default)]
+  proof: Option<IdentityProof>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SoulboundBody {
+  soulbound: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BurnBody {
+  from: String,
+  amt: Amount,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransferAdminBody {
+  to: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ApproveBody {
+  spender: String,
+  amt: Amount,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransferFromBody {
+  from: String,
+  to: String,
+  amt: Amount,
+  #[serde(default)]
+  proof: Option<IdentityProof>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FreezeBody {
+  account: String,
+  frozen: bool,
+}
+
+impl Brc20Inscription {
+  /// The inscription of `operation` on a token with `decimals`, which a
+  /// deploy takes from its own definition instead.
+  pub fn from_operation(operation: &Operation, decimals: u8, merkle_root: [u8; 32]) -> Self {
+    let payload = serde_json::json!({
+      "p": PROTOCOL,
+      "op": operation.operation_name(),
+      "tick": operation.ticker(),
+      "body": operation.payload_json(decimals),
+      "root": hex::encode(merkle_root),
+    });
+
+    Self {
+      content_type: "application/json".to_string(),
+      body: serde_json::to_vec(&payload).unwrap_or_default(),
+    }
+  }
+
+  /// Parse an inscription body. Returns `None` if it isn't a BRC-20 v2
+  /// payload at all, and a rejection if it claims to be one but is malformed.
+  pub fn parse(body: &[u8]) -> Option<Result<Payload, Rejection>> {
+    let serde_json::Value::Object(mut object) = serde_json::from_slice(body).ok()? else {
+      return None;
+    };
+
+    if object.remove("p")?.as_str()? != PROTOCOL {
+      return None;
+    }
+
+    Some(Self::parse_payload(object.into()))
+  }
+
+  fn parse_payload(value: serde_json::Value) -> Result<Payload, Rejection> {
+    let payload =
+      serde_json::from_value::<Payload>(value).map_err(|err| Rejection::InvalidPayload {
+        message: err.to_string(),
+      })?;
+
+    if let Some(root) = &payload.root
+      && !(root.len() == 64 && root.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')))
+    {
+      return Err(Rejection::InvalidPayload {
+        message: format!("root `{root}` is not 64 lowercase hex digits"),
+      });
+    }
+
+    if !(3..=16).contains(&payload.tick.len())
+      || !payload
+        .tick
+        .bytes()
+        .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+    {
+      return Err(Rejection::InvalidTicker {
+        ticker: payload.tick,
+      });
+    }
+
+    Ok(payload)
+  }
+
+  /// Decode an inscription body produced by `from_operation` for a token
+  /// with `decimals`. Returns `None` for anything that isn't a valid BRC-20 v2
+  /// payload.
+  pub fn parse_operation(body: &[u8], decimals: u8) -> Option<Operation> {
+    Self::parse(body)?.ok()?.into_operation(Some(decimals)).ok()
+  }
+}
+
+impl Payload {
+  /// Check the body against the schema for `op`, scaling decimal amounts by
+  /// `decimals`, the decimals of the token if it has been deployed.
+  pub fn into_operation(self, decimals: Option<u8>) -> Result<Operation, Rejection> {
+    let Self {
+      op,
+      tick,
+      body: value,
+      root: _,
+    } = self;
+
+    let amount = |amount: Amount, field: &str| amount.resolve(field, &tick, decimals);
+
+    let identity = |id: String, field: &str| {
+      if id.is_empty() {
+        Err(Rejection::InvalidBody {
+          op: op.clone(),
+          message: format!("`{field}` must not be empty"),
+        })
+      } else {
+        Ok(IdentityCommitment::new(id))
+      }
+    };
+
+    let operation = match op.as_str() {
+      "deploy" => {
+        let DeployBody {
+          max,
+          lim,
+          dec,
+          soulbound,
+          rules,
+        } = parse_body(&op, value)?;
+
+        if dec > MAX_DECIMALS {
+          return Err(Rejection::InvalidDecimals { decimals: dec });
+        }
+
+        Operation::Deploy {
+          definition: TokenDefinition {
+            ticker: tick.clone(),
+            max_supply: max.resolve("max", &tick, Some(dec))?,
+            mint_limit: lim.resolve("lim", &tick, Some(dec))?,
+            decimals: dec,
+            soulbound,
+            transfer_rules: rules,
+          },
+        }
+      }
+      "mint" => {
+        let MintBody { to, amt } = parse_body(&op, value)?;
+
+        Operation::Mint {
+          ticker: tick.clone(),
+          to: identity(to, "to")?,
+          amount: amount(amt, "amt")?,
+        }
+      }
+      "mint_vested" => {
+        let MintVestedBody { to, amt, vesting } = parse_body(&op, value)?;
+
+        let amt = amount(amt, "amt")?;
+
+        Operation::MintVested {
+          ticker: tick.clone(),
+          to: identity(to, "to")?,
+          amount: amt,
+          vesting: VestingSchedule {
+            start_height: vesting.start,
+            cliff_blocks: vesting.cliff,
+            duration_blocks: vesting.duration,
+            curve: vesting
+              .step
+              .map_or(VestingCurve::Linear, |interval| VestingCurve::Step {
+                interval,
+              }),
+            total_locked: amt,
+          },
+        }
+      }
+      "transfer" => {
+        let TransferBody {
+          from,
+          to,
+          amt,
+          proof,
+        } = parse_body(&op, value)?;
+
+        Operation::Transfer {
+          ticker: tick.clone(),
+          from: identity(from, "from")?,
+          to: identity(to, "to")?,
+          amount: amount(amt, "amt")?,
+          proof,
+        }
+      }
+      "soulbound" => {
+        let SoulboundBody { soulbound } = parse_body(&op, value)?;
+
+        Operation::SetSoulbound {
+          ticker: tick.clone(),
+          soulbound,
+        }
+      }
+      "burn" => {
+        let BurnBody { from, amt } = parse_body(&op, value)?;
+
+        Operation::Burn {
+          ticker: tick.clone(),
+          from: identity(from, "from")?,
+          amount: amount(amt, "amt")?,
+        }
+      }
+      "transfer_admin" => {
+        let TransferAdminBody { to } = parse_body(&op, value)?;
+
+        Operation::TransferAdmin {
+          ticker: tick.clone(),
+          to: identity(to, "to")?,
+        }
+      }
+      "approve" => {
+        let ApproveBody { spender, amt } = parse_body(&op, value)?;
+
+        Operation::Approve {
+          ticker: tick.clone(),
+          spender: identity(spender, "spender")?,
+          amount: amount(amt, "amt")?,
+        }
+      }
+      "transfer_from" => {
+        let TransferFromBody {
+          from,
+          to,
+          amt,
+          proof,
+        } = parse_body(&op, value)?;
+
+        Operation::TransferFrom {
+          ticker: tick.clone(),
+          from: identity(from, "from")?,
+          to: identity(to, "to")?,
+          amount: amount(amt, "amt")?,
+          proof,
         }
+      }
+      "freeze" => {
+        let FreezeBody { account, frozen } = parse_body(&op, value)?;
+
+        Operation::Freeze {
+          ticker: tick.clone(),
+          account: identity(account, "account")?,
+          frozen,
+        }
+      }
+      _ => return Err(Rejection::UnknownOperation { op: op.clone() }),
+    };
+
+    Ok(operation)
+  }
+}
+
+fn parse_body<T: DeserializeOwned>(op: &str, value: serde_json::Value) -> Result<T, Rejection> {
+  serde_json::from_value(value).map_err(|err| Rejection::InvalidBody {
+    op: op.into(),
+    message: err.to_string(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn operations_round_trip() {
+    let operations = [
+      Operation::Deploy {
+        definition: TokenDefinition {
+          ticker: "ordi".into(),
+          max_supply: 21_000_000,
+          mint_limit: 1000,
+          decimals: 8,
+          soulbound: false,
+          transfer_rules: TransferRules::default(),
+        },
+      },
+      Operation::Deploy {
+        definition: TokenDefinition {
+          ticker: "kyc".into(),
+          max_supply: 100,
+          mint_limit: 100,
+          decimals: 0,
+          soulbound: false,
+          transfer_rules: TransferRules {
+            require_identity: true,
+          },
+        },
+      },
+      Operation::Mint {
+        ticker: "ordi".into(),
+        to: IdentityCommitment::new("alice"),
+        amount: 1000,
+      },
+      Operation::MintVested {
+        ticker: "ordi".into(),
+        to: IdentityCommitment::new("alice"),
+        amount: 500,
+        vesting: VestingSchedule {
+          start_height: 1,
+          cliff_blocks: 2,
+          duration_blocks: 3,
+          curve: VestingCurve::Linear,
+          total_locked: 500,
+        },
+      },
+      Operation::MintVested {
+        ticker: "ordi".into(),
+        to: IdentityCommitment::new("bob"),
+        amount: 500,
+        vesting: VestingSchedule {
+          start_height: 100,
+          cliff_blocks: 10,
+          duration_blocks: 1000,
+          curve: VestingCurve::Step { interval: 144 },
+          total_locked: 500,
+        },
+      },
+      Operation::Transfer {
+        ticker: "ordi".into(),
+        from: IdentityCommitment::new("alice"),
+        to: IdentityCommitment::new("bob"),
+        amount: 10,
+        proof: None,
+      },
+      Operation::Transfer {
+        ticker: "kyc".into(),
+        from: IdentityCommitment::new("alice"),
+        to: IdentityCommitment::new("bob"),
+        amount: 10,
+        proof: Some(IdentityProof::Bip322 {
+          signature: "AkcwRAIg".into(),
+          nonce: 1,
+        }),
+      },
+      Operation::SetSoulbound {
+        ticker: "ordi".into(),
+        soulbound: true,
+      },
+      Operation::Burn {
+        ticker: "ordi".into(),
+        from: IdentityCommitment::new("alice"),
+        amount: 5,
+      },
+      Operation::TransferAdmin {
+        ticker: "ordi".into(),
+        to: IdentityCommitment::new("bob"),
+      },
+      Operation::Approve {
+        ticker: "ordi".into(),
+        spender: IdentityCommitment::new("bob"),
+        amount: 100,
+      },
+      Operation::TransferFrom {
+        ticker: "ordi".into(),
+        from: IdentityCommitment::new("alice"),
+        to: IdentityCommitment::new("carol"),
+        amount: 50,
+        proof: None,
+      },
+      Operation::Freeze {
+        ticker: "ordi".into(),
+        account: IdentityCommitment::new("carol"),
+        frozen: true,
+      },
+    ];
+
+    for operation in operations {
+      let inscription = Brc20Inscription::from_operation(&operation, 8, [0; 32]);
+      assert_eq!(
+        Brc20Inscription::parse_operation(&inscription.body, 8),
+        Some(operation),
+      );
     }
+  }
+
+  #[test]
+  fn other_protocols_are_ignored() {
+    assert_eq!(
+      Brc20Inscription::parse_operation(
+        br#"{"p":"brc-20","op":"mint","tick":"ordi","body":{"to":"alice","amt":1}}"#,
+        0,
+      ),
+      None,
+    );
+  }
+
+  #[test]
+  fn malformed_payloads_are_ignored() {
+    assert_eq!(Brc20Inscription::parse_operation(b"hello", 0), None);
+    assert_eq!(
+      Brc20Inscription::parse_operation(
+        br#"{"p":"brc-20-v2","op":"mint","tick":"ordi","body":{"to":"alice"}}"#,
+        0,
+      ),
+      None,
+    );
+    assert_eq!(
+      Brc20Inscription::parse_operation(
+        br#"{"p":"brc-20-v2","op":"melt","tick":"ordi","body":{}}"#,
+        0,
+      ),
+      None,
+    );
+  }
+
+  fn reject(body: &str, decimals: Option<u8>) -> Rejection {
+    Brc20Inscription::parse(body.as_bytes())
+      .unwrap()
+      .and_then(|payload| payload.into_operation(decimals))
+      .unwrap_err()
+  }
+
+  #[test]
+  fn decimal_amounts_are_scaled_by_token_decimals() {
+    let operation = |amt: &str, decimals| {
+      Brc20Inscription::parse(
+        format!(
+          r#"{{"p":"brc-20-v2","op":"mint","tick":"ordi","body":{{"to":"alice","amt":{amt}}}}}"#
+        )
+        .as_bytes(),
+      )
+      .unwrap()
+      .unwrap()
+      .into_operation(decimals)
+    };
+
+    let mint = |amount| Operation::Mint {
+      ticker: "ordi".into(),
+      to: IdentityCommitment::new("alice"),
+      amount,
+    };
+
+    assert_eq!(operation("5", None), Ok(mint(5)));
+    assert_eq!(operation(r#""5""#, Some(2)), Ok(mint(500)));
+    assert_eq!(operation(r#""1.5""#, Some(2)), Ok(mint(150)));
+    assert_eq!(operation(r#""1.25""#, Some(2)), Ok(mint(125)));
+    assert_eq!(operation(r#""7""#, Some(0)), Ok(mint(7)));
+
+    assert_eq!(
+      operation(r#""1.255""#, Some(2)),
+      Err(Rejection::ExcessPrecision {
+        field: "amt".into(),
+        amount: "1.255".into(),
+        decimals: 2,
+      }),
+    );
+
+    for amount in ["", ".5", "1.", "-1", "+1", "1e3", "1.2.3"] {
+      assert_eq!(
+        operation(&format!(r#""{amount}""#), Some(2)),
+        Err(Rejection::InvalidAmount {
+          field: "amt".into(),
+          amount: amount.into(),
+        }),
+      );
+    }
+
+    assert_eq!(
+      operation(r#""1""#, None),
+      Err(Rejection::Rule {
+        message: "token ordi not found".into(),
+      }),
+    );
+  }
+
+  #[test]
+  fn deploy_amounts_use_deploy_decimals() {
+    assert_eq!(
+      Brc20Inscription::parse(
+        br#"{"p":"brc-20-v2","op":"deploy","tick":"ordi","body":{"max":"21000","lim":"1.5","dec":2}}"#
+      )
+      .unwrap()
+      .unwrap()
+      .into_operation(None),
+      Ok(Operation::Deploy {
+        definition: TokenDefinition {
+          ticker: "ordi".into(),
+          max_supply: 2_100_000,
+          mint_limit: 150,
+          decimals: 2,
+          soulbound: false,
+          transfer_rules: TransferRules::default(),
+        },
+      }),
+    );
+
+    assert_eq!(
+      reject(
+        r#"{"p":"brc-20-v2","op":"deploy","tick":"ordi","body":{"max":1,"lim":1,"dec":19}}"#,
+        None,
+      ),
+      Rejection::InvalidDecimals { decimals: 19 },
+    );
+  }
+
+  #[test]
+  fn amounts_beyond_u64_round_trip_as_decimal_strings() {
+    let deploy = Operation::Deploy {
+      definition: TokenDefinition {
+        ticker: "ordi".into(),
+        max_supply: 21_000_000 * 10u128.pow(18),
+        mint_limit: 1000 * 10u128.pow(18),
+        decimals: 18,
+        soulbound: false,
+        transfer_rules: TransferRules::default(),
+      },
+    };
+
+    let inscription = Brc20Inscription::from_operation(&deploy, 18, [0; 32]);
+
+    assert_eq!(
+      serde_json::from_slice::<serde_json::Value>(&inscription.body).unwrap()["body"],
+      serde_json::json!({
+        "max": "21000000",
+        "lim": "1000",
+        "dec": 18,
+        "soulbound": false,
+      }),
+    );
+
+    assert_eq!(
+      Brc20Inscription::parse_operation(&inscription.body, 18),
+      Some(deploy),
+    );
+
+    let mint = Operation::Mint {
+      ticker: "ordi".into(),
+      to: IdentityCommitment::new("alice"),
+      amount: u128::MAX,
+    };
+
+    let inscription = Brc20Inscription::from_operation(&mint, 18, [0; 32]);
+
+    assert_eq!(
+      Brc20Inscription::parse_operation(&inscription.body, 18),
+      Some(mint),
+    );
+  }
+
+  #[test]
+  fn amounts_are_formatted_as_whole_tokens() {
+    assert_eq!(Amount::format(0, 0), "0");
+    assert_eq!(Amount::format(7, 0), "7");
+    assert_eq!(Amount::format(0, 2), "0");
+    assert_eq!(Amount::format(5, 2), "0.05");
+    assert_eq!(Amount::format(150, 2), "1.5");
+    assert_eq!(Amount::format(12_345, 2), "123.45");
+    assert_eq!(
+      Amount::format(u128::MAX, 18),
+      "340282366920938463463.374607431768211455",
+    );
+  }
+
+  #[test]
+  fn tickers_must_be_short_lowercase_alphanumerics() {
+    for ticker in ["ordi", "kyc", "abcdefghijklmnop", "l33t"] {
+      assert!(
+        Brc20Inscription::parse(
+          format!(r#"{{"p":"brc-20-v2","op":"mint","tick":"{ticker}","body":{{}}}}"#).as_bytes()
+        )
+        .unwrap()
+        .is_ok()
+      );
+    }
+
+    for ticker in ["", "ab", "abcdefghijklmnopq", "ORDI", "or di", "ördi"] {
+      assert_eq!(
+        Brc20Inscription::parse(
+          format!(r#"{{"p":"brc-20-v2","op":"mint","tick":"{ticker}","body":{{}}}}"#).as_bytes()
+        )
+        .unwrap()
+        .unwrap_err(),
+        Rejection::InvalidTicker {
+          ticker: ticker.into(),
+        },
+      );
+    }
+  }
+
+  #[test]
+  fn rejections_describe_malformed_payloads() {
+    assert!(matches!(
+      reject(r#"{"p":"brc-20-v2","op":"mint","body":{}}"#, None),
+      Rejection::InvalidPayload { .. },
+    ));
+
+    assert!(matches!(
+      reject(
+        r#"{"p":"brc-20-v2","op":"mint","tick":"ordi","body":{},"extra":1}"#,
+        None
+      ),
+      Rejection::InvalidPayload { .. },
+    ));
+
+    assert!(matches!(
+      reject(
+        r#"{"p":"brc-20-v2","op":"mint","tick":"ordi","body":{},"root":"00"}"#,
+        None
+      ),
+      Rejection::InvalidPayload { .. },
+    ));
+
+    assert_eq!(
+      reject(
+        r#"{"p":"brc-20-v2","op":"melt","tick":"ordi","body":{}}"#,
+        None
+      ),
+      Rejection::UnknownOperation { op: "melt".into() },
+    );
+
+    assert_eq!(
+      reject(
+        r#"{"p":"brc-20-v2","op":"mint","tick":"ordi","body":{"to":"alice"}}"#,
+        None
+      ),
+      Rejection::InvalidBody {
+        op: "mint".into(),
+        message: "missing field `amt`".into(),
+      },
+    );
+
+    assert!(matches!(
+      reject(
+        r#"{"p":"brc-20-v2","op":"mint","tick":"ordi","body":{"to":"alice","amt":1,"memo":"hi"}}"#,
+        None
+      ),
+      Rejection::InvalidBody { .. },
+    ));
+
+    assert!(matches!(
+      reject(
+        r#"{"p":"brc-20-v2","op":"mint","tick":"ordi","body":{"to":"alice","amt":-1}}"#,
+        None
+      ),
+      Rejection::InvalidBody { .. },
+    ));
+
+    assert_eq!(
+      reject(
+        r#"{"p":"brc-20-v2","op":"mint","tick":"ordi","body":{"to":"","amt":1}}"#,
+        None
+      ),
+      Rejection::InvalidBody {
+        op: "mint".into(),
+        message: "`to` must not be empty".into(),
+      },
+    );
+  }
+
+  #[test]
+  fn rejections_round_trip_through_json() {
+    let rejection = Rejection::ExcessPrecision {
+      field: "amt".into(),
+      amount: "1.255".into(),
+      decimals: 2,
+    };
+
+    let json = serde_json::to_string(&rejection).unwrap();
+
+    assert_eq!(
+      json,
+      r#"{"reason":"excess_precision","field":"amt","amount":"1.255","decimals":2}"#
+    );
+    assert_eq!(serde_json::from_str::<Rejection>(&json).unwrap(), rejection);
+    assert_eq!(
+      rejection.to_string(),
+      "amount `1.255` in field `amt` exceeds 2 decimal places"
+    );
+  }
 }
@@ -1,30 +1,840 @@
-use sha2::{Digest, Sha256};
+use std::{
+  collections::{BTreeMap, HashMap},
+  convert::Infallible,
+  sync::OnceLock,
+};
 
-pub fn merkle_root(leaves: &[String]) -> String {
-    if leaves.is_empty() {
-        return String::new();
+use bitcoin::hashes::{Hash, sha256};
+use serde::{Deserialize, Serialize};
+
+use super::brc20v2::AccountState;
+
+/// Depth of token state trees. Leaves are keyed by the full 256-bit
+/// `IdentityCommitment::commitment`.
+pub const DEPTH: usize = 256;
+
+/// Value of a leaf with no account.
+pub const EMPTY_LEAF: [u8; 32] = [0; 32];
+
+/// The part of an account that is committed to by its Merkle leaf.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LeafData {
+  pub balance: u128,
+  pub locked_balance: u128,
+  pub vesting: bool,
+}
+
+impl LeafData {
+  pub const ENCODED_LEN: usize = 16 + 16 + 1;
+
+  pub fn encode(&self) -> [u8; Self::ENCODED_LEN] {
+    let mut data = [0; Self::ENCODED_LEN];
+    data[..16].copy_from_slice(&self.balance.to_be_bytes());
+    data[16..32].copy_from_slice(&self.locked_balance.to_be_bytes());
+    data[32] = self.vesting.into();
+    data
+  }
+
+  pub fn hash(&self, commitment: &[u8; 32]) -> [u8; 32] {
+    let mut data = Vec::with_capacity(32 + Self::ENCODED_LEN);
+    data.extend_from_slice(commitment);
+    data.extend_from_slice(&self.encode());
+    sha256::Hash::hash(&data).to_byte_array()
+  }
+}
+
+impl From<&AccountState> for LeafData {
+  fn from(account: &AccountState) -> Self {
+    Self {
+      balance: account.balance,
+      locked_balance: account.locked_balance,
+      vesting: account.vesting.is_some(),
+    }
+  }
+}
+
+/// Authentication path through a sparse Merkle tree. Only siblings that
+/// differ from the empty subtree of their height are included, ordered from
+/// the leaf up.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MerkleProof {
+  pub depth: u16,
+  pub siblings: Vec<(u16, [u8; 32])>,
+}
+
+impl MerkleProof {
+  /// Root of the tree in which `key` holds `leaf`.
+  pub fn root(&self, key: &[u8; 32], leaf: [u8; 32]) -> [u8; 32] {
+    self
+      .expand()
+      .into_iter()
+      .enumerate()
+      .fold(leaf, |node, (height, sibling)| {
+        climb(key, usize::from(self.depth), height, node, sibling)
+      })
+  }
+
+  /// Every sibling from the leaf up, with empty subtrees filled in.
+  pub fn expand(&self) -> Vec<[u8; 32]> {
+    let mut siblings = (0..usize::from(self.depth))
+      .map(empty_subtree)
+      .collect::<Vec<[u8; 32]>>();
+
+    for (height, sibling) in &self.siblings {
+      if let Some(slot) = siblings.get_mut(usize::from(*height)) {
+        *slot = *sibling;
+      }
+    }
+
+    siblings
+  }
+}
+
+/// Proof that an account is, or is not, committed to by a token's Merkle
+/// root. `leaf` is `None` for a proof of non-membership.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InclusionProof {
+  pub commitment: [u8; 32],
+  pub leaf: Option<LeafData>,
+  pub proof: MerkleProof,
+}
+
+impl InclusionProof {
+  pub fn root(&self) -> [u8; 32] {
+    self.proof.root(
+      &self.commitment,
+      self
+        .leaf
+        .map(|leaf| leaf.hash(&self.commitment))
+        .unwrap_or(EMPTY_LEAF),
+    )
+  }
+
+  pub fn verify(&self, root: [u8; 32]) -> bool {
+    usize::from(self.proof.depth) == DEPTH && self.root() == root
+  }
+}
+
+/// Where the leaves of a sparse Merkle tree and the hashes of its branches,
+/// subtrees with two or more leaves, are kept.
+pub trait Store {
+  type Error;
+
+  /// Up to two leaves with keys in `start..=end`, in order.
+  fn leaves(
+    &self,
+    start: &[u8; 32],
+    end: &[u8; 32],
+  ) -> Result<Vec<([u8; 32], [u8; 32])>, Self::Error>;
+
+  /// Hash of the branch of `height` whose keys start with `prefix`.
+  fn branch(&self, height: u16, prefix: &[u8; 32]) -> Result<[u8; 32], Self::Error>;
+}
+
+/// Fixed-depth sparse Merkle tree.
+///
+/// Only leaves and the hashes of subtrees holding two or more leaves are
+/// stored. Subtrees with a single leaf are hashed on demand, so memory grows
+/// with the number of leaves rather than with the depth, and an update
+/// rehashes one path instead of the whole tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseMerkleTree {
+  branches: HashMap<(u16, [u8; 32]), [u8; 32]>,
+  depth: usize,
+  leaves: BTreeMap<[u8; 32], [u8; 32]>,
+}
+
+impl Default for SparseMerkleTree {
+  fn default() -> Self {
+    Self::with_depth(DEPTH)
+  }
+}
+
+impl SparseMerkleTree {
+  pub fn with_depth(depth: usize) -> Self {
+    assert!(depth <= DEPTH, "depth {depth} exceeds {DEPTH}");
+
+    Self {
+      branches: HashMap::new(),
+      depth,
+      leaves: BTreeMap::new(),
+    }
+  }
+
+  /// Build a tree from `(key, leaf)` pairs in one pass.
+  pub fn from_leaves(depth: usize, leaves: impl IntoIterator<Item = ([u8; 32], [u8; 32])>) -> Self {
+    let mut tree = Self::with_depth(depth);
+
+    tree.leaves = leaves
+      .into_iter()
+      .filter(|(_, leaf)| *leaf != EMPTY_LEAF)
+      .map(|(key, leaf)| (mask(&key, depth), leaf))
+      .collect();
+
+    let leaves = tree
+      .leaves
+      .iter()
+      .map(|(key, leaf)| (*key, *leaf))
+      .collect::<Vec<([u8; 32], [u8; 32])>>();
+
+    tree.build(depth, &leaves);
+
+    tree
+  }
+
+  pub fn depth(&self) -> usize {
+    self.depth
+  }
+
+  pub fn root(&self) -> [u8; 32] {
+    self.node(self.depth, &[0; 32])
+  }
+
+  pub fn get(&self, key: &[u8; 32]) -> [u8; 32] {
+    self
+      .leaves
+      .get(&mask(key, self.depth))
+      .copied()
+      .unwrap_or(EMPTY_LEAF)
+  }
+
+  pub fn prove(&self, key: &[u8; 32]) -> MerkleProof {
+    let key = mask(key, self.depth);
+
+    MerkleProof {
+      depth: self.depth.try_into().unwrap(),
+      siblings: (0..self.depth)
+        .filter_map(|height| {
+          let sibling = self.node(height, &flip(&key, self.depth - 1 - height));
+          (sibling != empty_subtree(height)).then(|| (height.try_into().unwrap(), sibling))
+        })
+        .collect(),
+    }
+  }
+
+  /// Set the leaf at `key`, rehashing the path from it to the root. Setting
+  /// `EMPTY_LEAF` removes the leaf.
+  pub fn insert(&mut self, key: &[u8; 32], leaf: [u8; 32]) {
+    let key = mask(key, self.depth);
+
+    if leaf == EMPTY_LEAF {
+      self.leaves.remove(&key);
+    } else {
+      self.leaves.insert(key, leaf);
     }
 
-    let mut nodes: Vec<String> = leaves
-        .iter()
-        .map(|l| {
-            let mut h = Sha256::new();
-            h.update(l.as_bytes());
-            hex::encode(h.finalize())
+    let mut node = leaf;
+
+    for height in 0..self.depth {
+      let sibling = self.node(height, &flip(&key, self.depth - 1 - height));
+
+      node = climb(&key, self.depth, height, node, sibling);
+
+      let bits = self.depth - height - 1;
+      let prefix = mask(&key, bits);
+
+      if self
+        .leaves
+        .range(prefix..=fill(&key, bits))
+        .nth(1)
+        .is_some()
+      {
+        self
+          .branches
+          .insert(((height + 1).try_into().unwrap(), prefix), node);
+      } else {
+        self
+          .branches
+          .remove(&((height + 1).try_into().unwrap(), prefix));
+      }
+    }
+  }
+
+  fn build(&mut self, height: usize, leaves: &[([u8; 32], [u8; 32])]) -> [u8; 32] {
+    match leaves {
+      [] => empty_subtree(height),
+      [(key, leaf)] => self.fold(key, *leaf, height),
+      _ => {
+        let index = self.depth - height;
+        let split = leaves.partition_point(|(key, _)| !bit(key, index));
+        let left = self.build(height - 1, &leaves[..split]);
+        let right = self.build(height - 1, &leaves[split..]);
+        let node = hash_pair(left, right);
+
+        self.branches.insert(
+          (height.try_into().unwrap(), mask(&leaves[0].0, index)),
+          node,
+        );
+
+        node
+      }
+    }
+  }
+
+  /// Hash of the subtree of `height` on the path to `key`.
+  fn node(&self, height: usize, key: &[u8; 32]) -> [u8; 32] {
+    let Ok(node) = node(self, self.depth, height, key);
+    node
+  }
+
+  fn fold(&self, key: &[u8; 32], leaf: [u8; 32], height: usize) -> [u8; 32] {
+    fold(self.depth, key, leaf, height)
+  }
+}
+
+impl Store for SparseMerkleTree {
+  type Error = Infallible;
+
+  fn leaves(
+    &self,
+    start: &[u8; 32],
+    end: &[u8; 32],
+  ) -> Result<Vec<([u8; 32], [u8; 32])>, Infallible> {
+    Ok(
+      self
+        .leaves
+        .range(*start..=*end)
+        .take(2)
+        .map(|(key, leaf)| (*key, *leaf))
+        .collect(),
+    )
+  }
+
+  fn branch(&self, height: u16, prefix: &[u8; 32]) -> Result<[u8; 32], Infallible> {
+    Ok(self.branches[&(height, *prefix)])
+  }
+}
+
+/// The nodes of a sparse Merkle tree on the paths to some of its leaves, and
+/// their siblings, which is enough to prove and update those leaves without
+/// loading the rest of the tree from its `Store`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartialTree {
+  depth: usize,
+  nodes: HashMap<(u16, [u8; 32]), [u8; 32]>,
+  root: [u8; 32],
+}
+
+impl PartialTree {
+  /// The tree in `store`, with no paths loaded.
+  pub fn new<S: Store>(store: &S, depth: usize) -> Result<Self, S::Error> {
+    Ok(Self {
+      depth,
+      nodes: HashMap::new(),
+      root: node(store, depth, depth, &[0; 32])?,
+    })
+  }
+
+  pub fn root(&self) -> [u8; 32] {
+    self.root
+  }
+
+  pub fn contains(&self, key: &[u8; 32]) -> bool {
+    self.nodes.contains_key(&(0, mask(key, self.depth)))
+  }
+
+  /// Load the path to `key` from `store`. Loaded nodes take precedence over
+  /// `store`, which doesn't have the updates made since they were loaded.
+  pub fn load<S: Store>(&mut self, store: &S, key: &[u8; 32]) -> Result<(), S::Error> {
+    let key = mask(key, self.depth);
+
+    if self.contains(&key) {
+      return Ok(());
+    }
+
+    let mut node = node(store, self.depth, 0, &key)?;
+
+    self.nodes.insert((0, key), node);
+
+    for height in 0..self.depth {
+      let sibling = flip(&key, self.depth - 1 - height);
+      let index = (
+        height.try_into().unwrap(),
+        mask(&sibling, self.depth - height),
+      );
+
+      let sibling = match self.nodes.get(&index) {
+        Some(sibling) => *sibling,
+        None => self::node(store, self.depth, height, &sibling)?,
+      };
+
+      self.nodes.insert(index, sibling);
+
+      node = climb(&key, self.depth, height, node, sibling);
+
+      self.nodes.insert(
+        (
+          (height + 1).try_into().unwrap(),
+          mask(&key, self.depth - height - 1),
+        ),
+        node,
+      );
+    }
+
+    debug_assert_eq!(node, self.root, "path to leaf does not lead to root");
+
+    Ok(())
+  }
+
+  pub fn prove(&self, key: &[u8; 32]) -> MerkleProof {
+    let key = self.loaded(key);
+
+    MerkleProof {
+      depth: self.depth.try_into().unwrap(),
+      siblings: (0..self.depth)
+        .filter_map(|height| {
+          let sibling = self.node(height, &flip(&key, self.depth - 1 - height));
+          (sibling != empty_subtree(height)).then(|| (height.try_into().unwrap(), sibling))
         })
-        .collect();
-
-    while nodes.len() > 1 {
-        let mut next = vec![];
-        for i in (0..nodes.len()).step_by(2) {
-            let left = &nodes[i];
-            let right = nodes.get(i + 1).unwrap_or(left);
-            let mut h = Sha256::new();
-            h.update(format!("{}{}", left, right));
-            next.push(hex::encode(h.finalize()));
+        .collect(),
+    }
+  }
+
+  /// Set the loaded leaf at `key`, rehashing the path from it to the root.
+  pub fn insert(&mut self, key: &[u8; 32], leaf: [u8; 32]) {
+    let key = self.loaded(key);
+
+    let mut node = leaf;
+
+    self.nodes.insert((0, key), node);
+
+    for height in 0..self.depth {
+      let sibling = self.node(height, &flip(&key, self.depth - 1 - height));
+
+      node = climb(&key, self.depth, height, node, sibling);
+
+      self.nodes.insert(
+        (
+          (height + 1).try_into().unwrap(),
+          mask(&key, self.depth - height - 1),
+        ),
+        node,
+      );
+    }
+
+    self.root = node;
+  }
+
+  /// Hash of the loaded subtree of `height` on the path to `key`.
+  fn node(&self, height: usize, key: &[u8; 32]) -> [u8; 32] {
+    self.nodes[&(height.try_into().unwrap(), mask(key, self.depth - height))]
+  }
+
+  fn loaded(&self, key: &[u8; 32]) -> [u8; 32] {
+    assert!(self.contains(key), "path to leaf is not loaded");
+    mask(key, self.depth)
+  }
+}
+
+/// A sparse Merkle tree, either whole, or only the paths to some of its
+/// leaves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Tree {
+  Full(SparseMerkleTree),
+  Partial(PartialTree),
+}
+
+impl Default for Tree {
+  fn default() -> Self {
+    Self::Full(SparseMerkleTree::default())
+  }
+}
+
+impl Tree {
+  pub fn root(&self) -> [u8; 32] {
+    match self {
+      Self::Full(tree) => tree.root(),
+      Self::Partial(tree) => tree.root(),
+    }
+  }
+
+  /// Whether the leaf at `key` can be proven and updated.
+  pub fn contains(&self, key: &[u8; 32]) -> bool {
+    match self {
+      Self::Full(_) => true,
+      Self::Partial(tree) => tree.contains(key),
+    }
+  }
+
+  pub fn prove(&self, key: &[u8; 32]) -> MerkleProof {
+    match self {
+      Self::Full(tree) => tree.prove(key),
+      Self::Partial(tree) => tree.prove(key),
+    }
+  }
+
+  pub fn insert(&mut self, key: &[u8; 32], leaf: [u8; 32]) {
+    match self {
+      Self::Full(tree) => tree.insert(key, leaf),
+      Self::Partial(tree) => tree.insert(key, leaf),
+    }
+  }
+
+  fn depth(&self) -> usize {
+    match self {
+      Self::Full(tree) => tree.depth,
+      Self::Partial(tree) => tree.depth,
+    }
+  }
+
+  fn node(&self, height: usize, key: &[u8; 32]) -> [u8; 32] {
+    match self {
+      Self::Full(tree) => tree.node(height, key),
+      Self::Partial(tree) => tree.node(height, key),
+    }
+  }
+
+  /// The loaded leaf at `key`.
+  pub fn leaf(&self, key: &[u8; 32]) -> [u8; 32] {
+    self.node(0, key)
+  }
+
+  /// The nodes on the paths from `keys` to the root, with their hashes if
+  /// they are branches in `store`, which must already hold the leaves at
+  /// `keys`, or `None` if they are not, for writing back to `store`.
+  pub fn branches<'a, S: Store>(
+    &self,
+    store: &S,
+    keys: impl IntoIterator<Item = &'a [u8; 32]>,
+  ) -> Result<BTreeMap<(u16, [u8; 32]), Option<[u8; 32]>>, S::Error> {
+    let depth = self.depth();
+
+    let mut branches = BTreeMap::new();
+
+    for key in keys {
+      let mut branch = false;
+
+      for height in 1..=depth {
+        let bits = depth - height;
+        let index = (u16::try_from(height).unwrap(), mask(key, bits));
+
+        if branches.contains_key(&index) {
+          break;
         }
-        nodes = next;
+
+        // every ancestor of a branch is a branch
+        branch = branch || store.leaves(&index.1, &fill(key, bits))?.len() > 1;
+
+        branches.insert(index, branch.then(|| self.node(height, key)));
+      }
+    }
+
+    Ok(branches)
+  }
+}
+
+/// Root of a tree with no leaves.
+pub fn empty_root() -> [u8; 32] {
+  empty_subtree(DEPTH)
+}
+
+/// Hash of the subtree of `height` on the path to `key` in the tree of
+/// `depth` in `store`.
+fn node<S: Store>(
+  store: &S,
+  depth: usize,
+  height: usize,
+  key: &[u8; 32],
+) -> Result<[u8; 32], S::Error> {
+  let bits = depth - height;
+  let prefix = mask(key, bits);
+
+  Ok(match store.leaves(&prefix, &fill(key, bits))?.as_slice() {
+    [] => empty_subtree(height),
+    [(key, leaf)] => fold(depth, key, *leaf, height),
+    _ => store.branch(height.try_into().unwrap(), &prefix)?,
+  })
+}
+
+/// Hash a lone leaf up to `height` past empty siblings.
+fn fold(depth: usize, key: &[u8; 32], leaf: [u8; 32], height: usize) -> [u8; 32] {
+  (0..height).fold(leaf, |node, height| {
+    climb(key, depth, height, node, empty_subtree(height))
+  })
+}
+
+/// Hash of an empty subtree of `height`.
+pub fn empty_subtree(height: usize) -> [u8; 32] {
+  static EMPTY: OnceLock<Vec<[u8; 32]>> = OnceLock::new();
+
+  EMPTY.get_or_init(|| {
+    let mut hashes = vec![EMPTY_LEAF];
+    for height in 0..DEPTH {
+      hashes.push(hash_pair(hashes[height], hashes[height]));
     }
+    hashes
+  })[height]
+}
+
+pub fn hash_pair(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+  let mut data = Vec::with_capacity(64);
+  data.extend_from_slice(&left);
+  data.extend_from_slice(&right);
+  sha256::Hash::hash(&data).to_byte_array()
+}
+
+/// Whether bit `index` of `key`, counting from the most significant bit, is
+/// set. A set bit means the path to `key` takes the right child at depth
+/// `index + 1`.
+pub fn bit(key: &[u8; 32], index: usize) -> bool {
+  key[index / 8] & (0x80 >> (index % 8)) != 0
+}
+
+/// Hash `node` of `height` with its sibling to get its parent.
+fn climb(
+  key: &[u8; 32],
+  depth: usize,
+  height: usize,
+  node: [u8; 32],
+  sibling: [u8; 32],
+) -> [u8; 32] {
+  if bit(key, depth - 1 - height) {
+    hash_pair(sibling, node)
+  } else {
+    hash_pair(node, sibling)
+  }
+}
+
+fn flip(key: &[u8; 32], index: usize) -> [u8; 32] {
+  let mut key = *key;
+  key[index / 8] ^= 0x80 >> (index % 8);
+  key
+}
+
+/// Keep the first `bits` bits of `key`, clearing the rest.
+fn mask(key: &[u8; 32], bits: usize) -> [u8; 32] {
+  let mut key = *key;
+  for index in bits..DEPTH {
+    key[index / 8] &= !(0x80 >> (index % 8));
+  }
+  key
+}
+
+/// Keep the first `bits` bits of `key`, setting the rest.
+fn fill(key: &[u8; 32], bits: usize) -> [u8; 32] {
+  let mut key = *key;
+  for index in bits..DEPTH {
+    key[index / 8] |= 0x80 >> (index % 8);
+  }
+  key
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn key(i: u8) -> [u8; 32] {
+    sha256::Hash::hash(&[i]).to_byte_array()
+  }
+
+  fn leaf(i: u8) -> [u8; 32] {
+    [i + 1; 32]
+  }
+
+  /// Reference root computed over the full tree without any caching.
+  fn naive_root(depth: usize, leaves: &BTreeMap<[u8; 32], [u8; 32]>) -> [u8; 32] {
+    fn subtree(depth: usize, height: usize, leaves: &[([u8; 32], [u8; 32])]) -> [u8; 32] {
+      if leaves.is_empty() {
+        return empty_subtree(height);
+      }
+
+      if height == 0 {
+        return leaves[0].1;
+      }
+
+      let index = depth - height;
+      let split = leaves.partition_point(|(key, _)| !bit(key, index));
+
+      hash_pair(
+        subtree(depth, height - 1, &leaves[..split]),
+        subtree(depth, height - 1, &leaves[split..]),
+      )
+    }
+
+    let leaves = leaves
+      .iter()
+      .map(|(key, leaf)| (mask(key, depth), *leaf))
+      .collect::<BTreeMap<[u8; 32], [u8; 32]>>()
+      .into_iter()
+      .collect::<Vec<([u8; 32], [u8; 32])>>();
+
+    subtree(depth, depth, &leaves)
+  }
+
+  #[test]
+  fn empty_tree() {
+    let tree = SparseMerkleTree::default();
+    assert_eq!(tree.root(), empty_root());
+    assert_eq!(tree.get(&key(0)), EMPTY_LEAF);
+    assert!(tree.prove(&key(0)).siblings.is_empty());
+  }
+
+  #[test]
+  fn incremental_updates_match_rebuild() {
+    for depth in [64, DEPTH] {
+      let mut tree = SparseMerkleTree::with_depth(depth);
+      let mut leaves = BTreeMap::new();
+
+      for i in 0..32 {
+        tree.insert(&key(i), leaf(i));
+        leaves.insert(key(i), leaf(i));
+        assert_eq!(tree.root(), naive_root(depth, &leaves));
+      }
+
+      for i in (0..32).step_by(3) {
+        tree.insert(&key(i), leaf(i + 100));
+        leaves.insert(key(i), leaf(i + 100));
+        assert_eq!(tree.root(), naive_root(depth, &leaves));
+      }
+
+      for i in (0..32).step_by(2) {
+        tree.insert(&key(i), EMPTY_LEAF);
+        leaves.remove(&key(i));
+        assert_eq!(tree.root(), naive_root(depth, &leaves));
+      }
+
+      assert_eq!(SparseMerkleTree::from_leaves(depth, leaves), tree);
+    }
+  }
+
+  #[test]
+  fn removing_every_leaf_restores_empty_root() {
+    let mut tree = SparseMerkleTree::default();
+
+    for i in 0..8 {
+      tree.insert(&key(i), leaf(i));
+    }
+
+    for i in 0..8 {
+      tree.insert(&key(i), EMPTY_LEAF);
+    }
+
+    assert_eq!(tree, SparseMerkleTree::default());
+  }
+
+  #[test]
+  fn proofs_authenticate_members_and_non_members() {
+    let tree = SparseMerkleTree::from_leaves(DEPTH, (0..16).map(|i| (key(i), leaf(i))));
+
+    for i in 0..16 {
+      let proof = tree.prove(&key(i));
+      assert_eq!(proof.root(&key(i), leaf(i)), tree.root());
+      assert_ne!(proof.root(&key(i), EMPTY_LEAF), tree.root());
+    }
+
+    let proof = tree.prove(&key(100));
+    assert_eq!(proof.root(&key(100), EMPTY_LEAF), tree.root());
+    assert_ne!(proof.root(&key(100), leaf(100)), tree.root());
+  }
+
+  #[test]
+  fn proofs_stay_valid_across_an_update_of_their_leaf() {
+    let mut tree = SparseMerkleTree::from_leaves(DEPTH, (0..4).map(|i| (key(i), leaf(i))));
+
+    let proof = tree.prove(&key(9));
+    assert_eq!(proof.root(&key(9), EMPTY_LEAF), tree.root());
+
+    tree.insert(&key(9), leaf(9));
+    assert_eq!(proof.root(&key(9), leaf(9)), tree.root());
+  }
+
+  #[test]
+  fn partial_tree_updates_match_full_tree() {
+    let store = SparseMerkleTree::from_leaves(DEPTH, (0..16).map(|i| (key(i), leaf(i))));
+
+    let mut full = store.clone();
+    let mut partial = PartialTree::new(&store, DEPTH).unwrap();
+    assert_eq!(partial.root(), store.root());
+
+    let updates = [
+      (3, leaf(103)),
+      (20, leaf(20)),
+      (7, EMPTY_LEAF),
+      (21, leaf(21)),
+    ];
+
+    for (i, leaf) in updates {
+      partial.load(&store, &key(i)).unwrap();
+      assert_eq!(partial.prove(&key(i)), full.prove(&key(i)));
+
+      partial.insert(&key(i), leaf);
+      full.insert(&key(i), leaf);
+      assert_eq!(partial.root(), full.root());
+    }
+
+    let keys = updates.map(|(i, _)| key(i));
+
+    let partial = Tree::Partial(partial);
+    let mut written = store;
+
+    for key in &keys {
+      match partial.leaf(key) {
+        EMPTY_LEAF => written.leaves.remove(key),
+        leaf => written.leaves.insert(*key, leaf),
+      };
+    }
+
+    for ((height, prefix), hash) in partial.branches(&written, &keys).unwrap() {
+      match hash {
+        Some(hash) => written.branches.insert((height, prefix), hash),
+        None => written.branches.remove(&(height, prefix)),
+      };
+    }
+
+    assert_eq!(written, full);
+  }
+
+  #[test]
+  fn inclusion_proof_verifies_against_root() {
+    let commitment = key(7);
+
+    let data = LeafData {
+      balance: 5,
+      locked_balance: 1,
+      vesting: true,
+    };
+
+    let mut tree = SparseMerkleTree::from_leaves(DEPTH, (0..4).map(|i| (key(i), leaf(i))));
+    tree.insert(&commitment, data.hash(&commitment));
+
+    let proof = InclusionProof {
+      commitment,
+      leaf: Some(data),
+      proof: tree.prove(&commitment),
+    };
+
+    assert!(proof.verify(tree.root()));
+
+    assert!(
+      !InclusionProof {
+        leaf: Some(LeafData { balance: 6, ..data }),
+        ..proof.clone()
+      }
+      .verify(tree.root())
+    );
+
+    assert!(
+      !InclusionProof {
+        leaf: None,
+        ..proof.clone()
+      }
+      .verify(tree.root())
+    );
+
+    assert!(
+      !InclusionProof {
+        commitment: key(8),
+        ..proof
+      }
+      .verify(tree.root())
+    );
+
+    let absent = InclusionProof {
+      commitment: key(8),
+      leaf: None,
+      proof: tree.prove(&key(8)),
+    };
 
-    nodes[0].clone()
+    assert!(absent.verify(tree.root()));
+  }
 }
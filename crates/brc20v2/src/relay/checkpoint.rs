@@ -0,0 +1,332 @@
+use std::{fs, io, path::Path};
+
+use bitcoin::BlockHash;
+use serde::{Deserialize, Serialize};
+
+use super::ethereum::{EthereumAddress, EthereumRelay, RelayReceipt, selector};
+use crate::errors::Brc20Error;
+
+/// A BRC-20 v2 state root to be relayed. Envelopes form a chain: each has the
+/// nonce after, and the state hash of, the previously relayed envelope, so the
+/// relay contract can reject replayed or skipped roots.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayEnvelope {
+  pub chain_id: u64,
+  pub contract: EthereumAddress,
+  pub nonce: u64,
+  pub prev_state_hash: [u8; 32],
+  pub state_hash: [u8; 32],
+  pub height: u32,
+  pub block_hash: BlockHash,
+}
+
+impl RelayEnvelope {
+  pub const RELAY_ROOT: &'static str = "relayRoot(uint64,bytes32,bytes32)";
+
+  /// The envelope following `previous`, or the first envelope if there is
+  /// none, whose previous state hash is all zeros.
+  pub fn next(
+    previous: Option<&Self>,
+    chain_id: u64,
+    contract: EthereumAddress,
+    height: u32,
+    block_hash: BlockHash,
+    state_hash: [u8; 32],
+  ) -> Self {
+    Self {
+      chain_id,
+      contract,
+      nonce: previous.map_or(0, |previous| previous.nonce + 1),
+      prev_state_hash: previous.map_or([0; 32], |previous| previous.state_hash),
+      state_hash,
+      height,
+      block_hash,
+    }
+  }
+
+  /// Check that this envelope directly follows `previous`.
+  pub fn follows(&self, previous: Option<&Self>) -> Result<(), Brc20Error> {
+    let (nonce, prev_state_hash) = match previous {
+      Some(previous) => {
+        if (self.chain_id, self.contract) != (previous.chain_id, previous.contract) {
+          return Err(Brc20Error::Relay(format!(
+            "envelope is for contract {} on chain {}, but previous envelope was for contract {} \
+             on chain {}",
+            self.contract, self.chain_id, previous.contract, previous.chain_id,
+          )));
+        }
+
+        (previous.nonce + 1, previous.state_hash)
+      }
+      None => (0, [0; 32]),
+    };
+
+    if self.nonce != nonce {
+      return Err(Brc20Error::Relay(format!(
+        "envelope nonce {} does not follow previous envelope, expected {nonce}",
+        self.nonce
+      )));
+    }
+
+    if self.prev_state_hash != prev_state_hash {
+      return Err(Brc20Error::Relay(format!(
+        "envelope previous state hash {} does not match previous envelope state hash {}",
+        hex::encode(self.prev_state_hash),
+        hex::encode(prev_state_hash),
+      )));
+    }
+
+    Ok(())
+  }
+
+  /// ABI-encoded call of `relayRoot(nonce, prevStateHash, stateHash)`.
+  pub fn calldata(&self) -> Vec<u8> {
+    let mut data = selector(Self::RELAY_ROOT).to_vec();
+    data.extend_from_slice(&[0; 24]);
+    data.extend_from_slice(&self.nonce.to_be_bytes());
+    data.extend_from_slice(&self.prev_state_hash);
+    data.extend_from_slice(&self.state_hash);
+    data
+  }
+}
+
+/// The last relayed envelope and its receipt, persisted so that relaying
+/// resumes where it left off.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayCheckpoint {
+  pub envelope: RelayEnvelope,
+  pub receipt: RelayReceipt,
+}
+
+impl RelayCheckpoint {
+  /// Submit `envelope`, refusing it unless it directly follows `checkpoint`.
+  pub fn relay(
+    checkpoint: Option<&Self>,
+    relay: &EthereumRelay,
+    envelope: RelayEnvelope,
+  ) -> Result<Self, Brc20Error> {
+    envelope.follows(checkpoint.map(|checkpoint| &checkpoint.envelope))?;
+
+    let receipt = relay.submit_envelope(&envelope)?;
+
+    Ok(Self { envelope, receipt })
+  }
+
+  /// Load the checkpoint at `path`, or `None` if nothing has been relayed.
+  pub fn load(path: &Path) -> Result<Option<Self>, Brc20Error> {
+    match fs::read(path) {
+      Ok(json) => serde_json::from_slice(&json)
+        .map(Some)
+        .map_err(|err| Brc20Error::Relay(format!("invalid checkpoint {}: {err}", path.display()))),
+      Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+      Err(err) => Err(Brc20Error::Relay(format!(
+        "failed to read checkpoint {}: {err}",
+        path.display()
+      ))),
+    }
+  }
+
+  /// Write the checkpoint to a temporary file and rename it over `path`, so
+  /// an interrupted save leaves the previous checkpoint intact.
+  pub fn save(&self, path: &Path) -> Result<(), Brc20Error> {
+    let failed = |err: io::Error| {
+      Brc20Error::Relay(format!(
+        "failed to write checkpoint {}: {err}",
+        path.display()
+      ))
+    };
+
+    let tmp = path.with_extension("tmp");
+
+    fs::write(&tmp, serde_json::to_vec_pretty(self).unwrap()).map_err(failed)?;
+
+    fs::rename(&tmp, path).map_err(failed)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, bitcoin::hashes::Hash, secp256k1::SecretKey};
+
+  fn envelope(previous: Option<&RelayEnvelope>, state_hash: u8) -> RelayEnvelope {
+    RelayEnvelope::next(
+      previous,
+      1337,
+      EthereumAddress([0xcc; 20]),
+      100,
+      BlockHash::all_zeros(),
+      [state_hash; 32],
+    )
+  }
+
+  #[test]
+  fn envelopes_chain() {
+    let first = envelope(None, 1);
+
+    assert_eq!(first.nonce, 0);
+    assert_eq!(first.prev_state_hash, [0; 32]);
+    assert_eq!(first.follows(None), Ok(()));
+
+    let second = envelope(Some(&first), 2);
+
+    assert_eq!(second.nonce, 1);
+    assert_eq!(second.prev_state_hash, [1; 32]);
+    assert_eq!(second.follows(Some(&first)), Ok(()));
+  }
+
+  #[test]
+  fn envelopes_with_wrong_nonce_are_refused() {
+    let first = envelope(None, 1);
+
+    assert_eq!(
+      envelope(None, 2).follows(Some(&first)),
+      Err(Brc20Error::Relay(
+        "envelope nonce 0 does not follow previous envelope, expected 1".into()
+      )),
+    );
+
+    let second = envelope(Some(&first), 2);
+
+    assert_eq!(
+      envelope(Some(&second), 3).follows(Some(&first)),
+      Err(Brc20Error::Relay(
+        "envelope nonce 2 does not follow previous envelope, expected 1".into()
+      )),
+    );
+
+    assert!(second.follows(None).is_err());
+  }
+
+  #[test]
+  fn envelopes_with_wrong_previous_hash_are_refused() {
+    let first = envelope(None, 1);
+
+    let mut second = envelope(Some(&first), 2);
+    second.prev_state_hash = [3; 32];
+
+    assert_eq!(
+      second.follows(Some(&first)),
+      Err(Brc20Error::Relay(format!(
+        "envelope previous state hash {} does not match previous envelope state hash {}",
+        hex::encode([3; 32]),
+        hex::encode([1; 32]),
+      ))),
+    );
+  }
+
+  #[test]
+  fn envelopes_for_other_contracts_are_refused() {
+    let first = envelope(None, 1);
+
+    let mut second = envelope(Some(&first), 2);
+    second.contract = EthereumAddress([0xdd; 20]);
+
+    assert!(matches!(
+      second.follows(Some(&first)),
+      Err(Brc20Error::Relay(message)) if message.starts_with("envelope is for contract 0xdddd"),
+    ));
+  }
+
+  #[test]
+  fn calldata() {
+    let first = envelope(None, 1);
+    let second = envelope(Some(&first), 2);
+
+    assert_eq!(
+      hex::encode(second.calldata()),
+      format!(
+        "{}{:064x}{}{}",
+        hex::encode(selector(RelayEnvelope::RELAY_ROOT)),
+        1,
+        hex::encode([1; 32]),
+        hex::encode([2; 32]),
+      ),
+    );
+  }
+
+  #[test]
+  fn relayed_envelopes_are_checkpointed() {
+    let ethereum = mockethereum::builder().chain_id(1337).build();
+
+    let relay = EthereumRelay::new(
+      ethereum.url(),
+      EthereumAddress([0xcc; 20]),
+      SecretKey::from_slice(&[0x46; 32]).unwrap(),
+    );
+
+    let first = RelayCheckpoint::relay(None, &relay, envelope(None, 1)).unwrap();
+
+    assert_eq!(first.receipt.root, [1; 32]);
+    assert_eq!(
+      ethereum.transaction(first.receipt.transaction_hash).data,
+      first.envelope.calldata(),
+    );
+
+    assert_eq!(
+      RelayCheckpoint::relay(Some(&first), &relay, envelope(None, 2)),
+      Err(Brc20Error::Relay(
+        "envelope nonce 0 does not follow previous envelope, expected 1".into()
+      )),
+    );
+
+    assert_eq!(ethereum.state().transactions.len(), 1);
+
+    let second =
+      RelayCheckpoint::relay(Some(&first), &relay, envelope(Some(&first.envelope), 2)).unwrap();
+
+    assert_eq!(second.envelope.nonce, 1);
+    assert_eq!(second.receipt.nonce, 1);
+  }
+
+  #[test]
+  fn envelopes_for_other_chains_are_not_submitted() {
+    let ethereum = mockethereum::builder().chain_id(1).build();
+
+    let relay = EthereumRelay::new(
+      ethereum.url(),
+      EthereumAddress([0xcc; 20]),
+      SecretKey::from_slice(&[0x46; 32]).unwrap(),
+    );
+
+    assert_eq!(
+      RelayCheckpoint::relay(None, &relay, envelope(None, 1)),
+      Err(Brc20Error::Relay(
+        "envelope is for chain 1337, but node is on chain 1".into()
+      )),
+    );
+
+    assert!(ethereum.state().transactions.is_empty());
+  }
+
+  #[test]
+  fn checkpoints_round_trip_through_file() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+
+    let path = tempdir.path().join("checkpoint.json");
+
+    assert_eq!(RelayCheckpoint::load(&path), Ok(None));
+
+    let checkpoint = RelayCheckpoint {
+      envelope: envelope(None, 1),
+      receipt: RelayReceipt {
+        root: [1; 32],
+        transaction_hash: [2; 32],
+        block_hash: [3; 32],
+        block_number: 4,
+        gas_used: 5,
+        nonce: 6,
+      },
+    };
+
+    checkpoint.save(&path).unwrap();
+
+    assert_eq!(RelayCheckpoint::load(&path), Ok(Some(checkpoint)));
+
+    fs::write(&path, "{").unwrap();
+
+    assert!(matches!(
+      RelayCheckpoint::load(&path),
+      Err(Brc20Error::Relay(message)) if message.starts_with("invalid checkpoint"),
+    ));
+  }
+}
@@ -1,28 +1,653 @@
-use web3::transports::Http;
-use web3::{types::TransactionRequest, Web3};
+use std::{
+  fmt::{self, Display, Formatter},
+  str::FromStr,
+  sync::Mutex,
+  thread,
+  time::{Duration, Instant},
+};
 
+use secp256k1::{Message, PublicKey, SECP256K1, SecretKey};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use sha3::{Digest, Keccak256};
+
+use super::{checkpoint::RelayEnvelope, rlp::Item};
 use crate::errors::Brc20Error;
 
-pub async fn relay_proof(
-    rpc: &str,
-    from: web3::types::Address,
-    contract: web3::types::Address,
-    data: Vec<u8>,
-) -> Result<web3::types::H256, Brc20Error> {
-    let transport = Http::new(rpc).map_err(|e| Brc20Error::Relay(e.to_string()))?;
-    let web3 = Web3::new(transport);
-
-    let tx = TransactionRequest {
-        from,
-        to: Some(contract),
-        gas: Some(300_000.into()),
-        data: Some(data.into()),
-        ..Default::default()
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+  Keccak256::digest(data).into()
+}
+
+/// The first four bytes of the hash of a contract function signature, which
+/// identify the function being called in calldata.
+pub fn selector(signature: &str) -> [u8; 4] {
+  keccak256(signature.as_bytes())[..4].try_into().unwrap()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr)]
+pub struct EthereumAddress(pub [u8; 20]);
+
+impl EthereumAddress {
+  pub fn from_secret_key(secret_key: &SecretKey) -> Self {
+    let public_key = PublicKey::from_secret_key(SECP256K1, secret_key).serialize_uncompressed();
+    Self(keccak256(&public_key[1..])[12..].try_into().unwrap())
+  }
+}
+
+impl Display for EthereumAddress {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "0x{}", hex::encode(self.0))
+  }
+}
+
+impl FromStr for EthereumAddress {
+  type Err = Brc20Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || Brc20Error::Relay(format!("invalid Ethereum address `{s}`"));
+
+    hex::decode(s.strip_prefix("0x").ok_or_else(invalid)?)
+      .ok()
+      .and_then(|bytes| bytes.try_into().ok())
+      .map(Self)
+      .ok_or_else(invalid)
+  }
+}
+
+/// An EIP-1559 fee market transaction with an empty access list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Eip1559Transaction {
+  pub chain_id: u64,
+  pub nonce: u64,
+  pub max_priority_fee_per_gas: u128,
+  pub max_fee_per_gas: u128,
+  pub gas_limit: u64,
+  pub to: EthereumAddress,
+  pub value: u128,
+  pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedTransaction {
+  pub hash: [u8; 32],
+  pub raw: Vec<u8>,
+}
+
+impl Eip1559Transaction {
+  pub const TYPE: u8 = 0x02;
+
+  fn fields(&self) -> Vec<Item<'_>> {
+    vec![
+      Item::Uint(self.chain_id.into()),
+      Item::Uint(self.nonce.into()),
+      Item::Uint(self.max_priority_fee_per_gas),
+      Item::Uint(self.max_fee_per_gas),
+      Item::Uint(self.gas_limit.into()),
+      Item::Bytes(&self.to.0),
+      Item::Uint(self.value),
+      Item::Bytes(&self.data),
+      Item::List(Vec::new()),
+    ]
+  }
+
+  fn envelope(fields: Vec<Item>) -> Vec<u8> {
+    let mut envelope = vec![Self::TYPE];
+    envelope.extend(Item::List(fields).encode());
+    envelope
+  }
+
+  pub fn signature_hash(&self) -> [u8; 32] {
+    keccak256(&Self::envelope(self.fields()))
+  }
+
+  pub fn sign(&self, secret_key: &SecretKey) -> SignedTransaction {
+    let (recovery_id, signature) = SECP256K1
+      .sign_ecdsa_recoverable(&Message::from_digest(self.signature_hash()), secret_key)
+      .serialize_compact();
+
+    let (r, s) = signature.split_at(32);
+
+    let mut fields = self.fields();
+    fields.push(Item::Uint(recovery_id.to_i32().try_into().unwrap()));
+    fields.push(Item::Bytes(strip_leading_zeros(r)));
+    fields.push(Item::Bytes(strip_leading_zeros(s)));
+
+    let raw = Self::envelope(fields);
+
+    SignedTransaction {
+      hash: keccak256(&raw),
+      raw,
+    }
+  }
+}
+
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+  &bytes[bytes.iter().take_while(|byte| **byte == 0).count()..]
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayReceipt {
+  pub root: [u8; 32],
+  pub transaction_hash: [u8; 32],
+  pub block_hash: [u8; 32],
+  pub block_number: u64,
+  pub gas_used: u64,
+  pub nonce: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Account {
+  chain_id: u64,
+  nonce: u64,
+}
+
+/// Submits BRC-20 v2 state roots to a contract on an Ethereum JSON-RPC
+/// endpoint. Transactions are signed locally, so the node never needs access
+/// to the relayer's key.
+#[derive(Debug)]
+pub struct EthereumRelay {
+  pub endpoint: String,
+  pub contract: EthereumAddress,
+  pub poll_interval: Duration,
+  pub timeout: Duration,
+  account: Mutex<Option<Account>>,
+  client: reqwest::blocking::Client,
+  from: EthereumAddress,
+  secret_key: SecretKey,
+}
+
+impl EthereumRelay {
+  pub const SUBMIT_ROOT: &'static str = "submitRoot(bytes32)";
+
+  pub fn new(
+    endpoint: impl Into<String>,
+    contract: EthereumAddress,
+    secret_key: SecretKey,
+  ) -> Self {
+    Self {
+      endpoint: endpoint.into(),
+      contract,
+      poll_interval: Duration::from_secs(1),
+      timeout: Duration::from_secs(300),
+      account: Mutex::new(None),
+      client: reqwest::blocking::Client::new(),
+      from: EthereumAddress::from_secret_key(&secret_key),
+      secret_key,
+    }
+  }
+
+  pub fn from(&self) -> EthereumAddress {
+    self.from
+  }
+
+  /// Chain ID reported by the node.
+  pub fn chain_id(&self) -> Result<u64, Brc20Error> {
+    Ok(self.account(&mut self.account.lock().unwrap())?.chain_id)
+  }
+
+  /// Call `submitRoot(bytes32)` on the contract and wait for the transaction
+  /// to be mined.
+  pub fn submit_root(&self, root: [u8; 32]) -> Result<RelayReceipt, Brc20Error> {
+    let mut data = selector(Self::SUBMIT_ROOT).to_vec();
+    data.extend_from_slice(&root);
+
+    self.submit(root, data)
+  }
+
+  /// Call `relayRoot(uint64,bytes32,bytes32)` on the contract with
+  /// `envelope` and wait for the transaction to be mined.
+  pub fn submit_envelope(&self, envelope: &RelayEnvelope) -> Result<RelayReceipt, Brc20Error> {
+    if envelope.contract != self.contract {
+      return Err(Brc20Error::Relay(format!(
+        "envelope is for contract {}, but relay submits to {}",
+        envelope.contract, self.contract
+      )));
+    }
+
+    let chain_id = self.chain_id()?;
+
+    if envelope.chain_id != chain_id {
+      return Err(Brc20Error::Relay(format!(
+        "envelope is for chain {}, but node is on chain {chain_id}",
+        envelope.chain_id
+      )));
+    }
+
+    self.submit(envelope.state_hash, envelope.calldata())
+  }
+
+  fn submit(&self, root: [u8; 32], data: Vec<u8>) -> Result<RelayReceipt, Brc20Error> {
+    let (transaction, nonce) = self.send(data)?;
+
+    let receipt = self.wait_for_receipt(transaction.hash)?;
+
+    Ok(RelayReceipt {
+      root,
+      transaction_hash: transaction.hash,
+      block_hash: receipt.block_hash,
+      block_number: receipt.block_number,
+      gas_used: receipt.gas_used,
+      nonce,
+    })
+  }
+
+  fn send(&self, data: Vec<u8>) -> Result<(SignedTransaction, u64), Brc20Error> {
+    let mut account = self.account.lock().unwrap();
+
+    let Account { chain_id, nonce } = self.account(&mut account)?;
+
+    let gas_limit = self.quantity(
+      "eth_estimateGas",
+      json!([{
+        "from": self.from,
+        "to": self.contract,
+        "data": format!("0x{}", hex::encode(&data)),
+      }]),
+    )?;
+
+    let base_fee = self
+      .call::<Option<Block>>("eth_getBlockByNumber", json!(["latest", false]))?
+      .ok_or_else(|| Brc20Error::Relay("node returned no latest block".into()))?
+      .base_fee_per_gas
+      .ok_or_else(|| Brc20Error::Relay("latest block has no base fee".into()))?;
+
+    let max_priority_fee_per_gas = self.quantity("eth_maxPriorityFeePerGas", json!([]))?;
+
+    let transaction = Eip1559Transaction {
+      chain_id,
+      nonce,
+      max_priority_fee_per_gas,
+      max_fee_per_gas: parse_quantity::<u128>(&base_fee)?
+        .saturating_mul(2)
+        .saturating_add(max_priority_fee_per_gas),
+      gas_limit,
+      to: self.contract,
+      value: 0,
+      data,
+    }
+    .sign(&self.secret_key);
+
+    let hash = match self.call::<String>(
+      "eth_sendRawTransaction",
+      json!([format!("0x{}", hex::encode(&transaction.raw))]),
+    ) {
+      Ok(hash) => parse_hash(&hash)?,
+      Err(err) => {
+        // the node may know about transactions we don't, so fetch the nonce
+        // again before the next submission
+        *account = None;
+        return Err(err);
+      }
     };
 
-    web3
-        .eth()
-        .send_transaction(tx)
-        .await
-        .map_err(|e| Brc20Error::Relay(e.to_string()))
+    if hash != transaction.hash {
+      return Err(Brc20Error::Relay(format!(
+        "node returned transaction hash 0x{} for transaction 0x{}",
+        hex::encode(hash),
+        hex::encode(transaction.hash),
+      )));
+    }
+
+    *account = Some(Account {
+      chain_id,
+      nonce: nonce + 1,
+    });
+
+    Ok((transaction, nonce))
+  }
+
+  fn account(&self, account: &mut Option<Account>) -> Result<Account, Brc20Error> {
+    if let Some(account) = account {
+      return Ok(*account);
+    }
+
+    let fetched = Account {
+      chain_id: self.quantity("eth_chainId", json!([]))?,
+      nonce: self.quantity("eth_getTransactionCount", json!([self.from, "pending"]))?,
+    };
+
+    *account = Some(fetched);
+
+    Ok(fetched)
+  }
+
+  fn wait_for_receipt(&self, hash: [u8; 32]) -> Result<Receipt, Brc20Error> {
+    let start = Instant::now();
+
+    loop {
+      if let Some(receipt) = self.call::<Option<RawReceipt>>(
+        "eth_getTransactionReceipt",
+        json!([format!("0x{}", hex::encode(hash))]),
+      )? {
+        let receipt = Receipt::try_from(receipt)?;
+
+        if !receipt.status {
+          return Err(Brc20Error::Relay(format!(
+            "transaction 0x{} reverted",
+            hex::encode(hash)
+          )));
+        }
+
+        return Ok(receipt);
+      }
+
+      if start.elapsed() >= self.timeout {
+        return Err(Brc20Error::Relay(format!(
+          "timed out waiting for receipt for transaction 0x{}",
+          hex::encode(hash)
+        )));
+      }
+
+      thread::sleep(self.poll_interval);
+    }
+  }
+
+  fn quantity<T: TryFrom<u128>>(&self, method: &str, params: Value) -> Result<T, Brc20Error> {
+    parse_quantity(&self.call::<String>(method, params)?)
+  }
+
+  fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, Brc20Error> {
+    let failed = |message: String| Brc20Error::Relay(format!("{method} failed: {message}"));
+
+    let response = self
+      .client
+      .post(&self.endpoint)
+      .json(&json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+      }))
+      .send()
+      .and_then(|response| response.error_for_status())
+      .map_err(|err| failed(err.to_string()))?
+      .json::<Response>()
+      .map_err(|err| failed(err.to_string()))?;
+
+    if let Some(error) = response.error {
+      return Err(failed(format!("{} (code {})", error.message, error.code)));
+    }
+
+    serde_json::from_value(response.result).map_err(|err| failed(err.to_string()))
+  }
+}
+
+#[derive(Deserialize)]
+struct Response {
+  #[serde(default)]
+  result: Value,
+  error: Option<ResponseError>,
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+  code: i64,
+  message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Block {
+  base_fee_per_gas: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawReceipt {
+  block_hash: String,
+  block_number: String,
+  gas_used: String,
+  status: String,
+}
+
+struct Receipt {
+  block_hash: [u8; 32],
+  block_number: u64,
+  gas_used: u64,
+  status: bool,
+}
+
+impl TryFrom<RawReceipt> for Receipt {
+  type Error = Brc20Error;
+
+  fn try_from(receipt: RawReceipt) -> Result<Self, Self::Error> {
+    Ok(Self {
+      block_hash: parse_hash(&receipt.block_hash)?,
+      block_number: parse_quantity(&receipt.block_number)?,
+      gas_used: parse_quantity(&receipt.gas_used)?,
+      status: parse_quantity::<u8>(&receipt.status)? == 1,
+    })
+  }
+}
+
+fn parse_quantity<T: TryFrom<u128>>(quantity: &str) -> Result<T, Brc20Error> {
+  quantity
+    .strip_prefix("0x")
+    .and_then(|digits| u128::from_str_radix(digits, 16).ok())
+    .and_then(|quantity| quantity.try_into().ok())
+    .ok_or_else(|| Brc20Error::Relay(format!("invalid quantity `{quantity}`")))
+}
+
+fn parse_hash(hash: &str) -> Result<[u8; 32], Brc20Error> {
+  hash
+    .strip_prefix("0x")
+    .and_then(|digits| hex::decode(digits).ok())
+    .and_then(|bytes| bytes.try_into().ok())
+    .ok_or_else(|| Brc20Error::Relay(format!("invalid hash `{hash}`")))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn secret_key() -> SecretKey {
+    SecretKey::from_slice(&[0x46; 32]).unwrap()
+  }
+
+  fn contract() -> EthereumAddress {
+    EthereumAddress([0xcc; 20])
+  }
+
+  fn relay(ethereum: &mockethereum::Handle) -> EthereumRelay {
+    EthereumRelay {
+      poll_interval: Duration::from_millis(10),
+      timeout: Duration::from_millis(500),
+      ..EthereumRelay::new(ethereum.url(), contract(), secret_key())
+    }
+  }
+
+  #[test]
+  fn address_from_secret_key() {
+    assert_eq!(
+      EthereumAddress::from_secret_key(&secret_key()).to_string(),
+      "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+    );
+  }
+
+  #[test]
+  fn address_round_trips_through_string() {
+    let address = "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f";
+
+    assert_eq!(
+      address.parse::<EthereumAddress>().unwrap().to_string(),
+      address
+    );
+
+    assert_eq!(
+      "9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f".parse::<EthereumAddress>(),
+      Err(Brc20Error::Relay(
+        "invalid Ethereum address `9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f`".into()
+      )),
+    );
+
+    assert!("0x9d8a".parse::<EthereumAddress>().is_err());
+  }
+
+  #[test]
+  fn submit_root_selector() {
+    assert_eq!(
+      hex::encode(selector("transfer(address,uint256)")),
+      "a9059cbb"
+    );
+  }
+
+  #[test]
+  fn submit_root() {
+    let ethereum = mockethereum::spawn();
+
+    let relay = relay(&ethereum);
+
+    let receipt = relay.submit_root([1; 32]).unwrap();
+
+    let transaction = ethereum.transaction(receipt.transaction_hash);
+
+    assert_eq!(transaction.from, relay.from().0);
+    assert_eq!(transaction.to, contract().0);
+    assert_eq!(transaction.chain_id, 1337);
+    assert_eq!(transaction.nonce, 0);
+    assert_eq!(transaction.value, 0);
+    assert_eq!(transaction.max_priority_fee_per_gas, 100_000_000);
+    assert_eq!(transaction.max_fee_per_gas, 2_100_000_000);
+
+    let mut data = selector(EthereumRelay::SUBMIT_ROOT).to_vec();
+    data.extend_from_slice(&[1; 32]);
+    assert_eq!(transaction.data, data);
+
+    let mined = ethereum.receipt(receipt.transaction_hash).unwrap();
+
+    assert_eq!(
+      receipt,
+      RelayReceipt {
+        root: [1; 32],
+        transaction_hash: transaction.hash,
+        block_hash: mined.block_hash,
+        block_number: 1,
+        gas_used: mined.gas_used,
+        nonce: 0,
+      }
+    );
+  }
+
+  #[test]
+  fn nonces_increment_across_submissions() {
+    let ethereum = mockethereum::spawn();
+
+    let relay = relay(&ethereum);
+
+    for nonce in 0..3 {
+      let receipt = relay.submit_root([nonce; 32]).unwrap();
+      assert_eq!(receipt.nonce, u64::from(nonce));
+      assert_eq!(receipt.block_number, u64::from(nonce) + 1);
+    }
+
+    assert_eq!(ethereum.state().nonce(&relay.from().0), 3);
+  }
+
+  #[test]
+  fn rejected_transactions_refresh_nonce() {
+    let ethereum = mockethereum::spawn();
+
+    let relay = relay(&ethereum);
+
+    relay.submit_root([0; 32]).unwrap();
+
+    ethereum.bump_nonce(relay.from().0);
+
+    assert!(matches!(
+      relay.submit_root([1; 32]),
+      Err(Brc20Error::Relay(message))
+        if message.starts_with("eth_sendRawTransaction failed: nonce too low"),
+    ));
+
+    assert_eq!(relay.submit_root([1; 32]).unwrap().nonce, 2);
+  }
+
+  #[test]
+  fn node_errors_are_reported() {
+    let ethereum = mockethereum::spawn();
+
+    let relay = relay(&ethereum);
+
+    ethereum.reject_next("insufficient funds for gas * price + value");
+
+    assert_eq!(
+      relay.submit_root([0; 32]),
+      Err(Brc20Error::Relay(
+        "eth_sendRawTransaction failed: insufficient funds for gas * price + value (code -32000)"
+          .into()
+      )),
+    );
+
+    assert!(ethereum.mempool().is_empty());
+  }
+
+  #[test]
+  fn wrong_chain_id_is_rejected() {
+    let ethereum = mockethereum::spawn();
+
+    let relay = relay(&ethereum);
+
+    let transaction = Eip1559Transaction {
+      chain_id: 1,
+      nonce: 0,
+      max_priority_fee_per_gas: 1,
+      max_fee_per_gas: 10_000_000_000,
+      gas_limit: 100_000,
+      to: contract(),
+      value: 0,
+      data: Vec::new(),
+    }
+    .sign(&secret_key());
+
+    assert_eq!(
+      relay.call::<String>(
+        "eth_sendRawTransaction",
+        json!([format!("0x{}", hex::encode(transaction.raw))]),
+      ),
+      Err(Brc20Error::Relay(
+        "eth_sendRawTransaction failed: invalid chain id 1, expected 1337 (code -32000)".into()
+      )),
+    );
+  }
+
+  #[test]
+  fn reverted_transactions_are_errors() {
+    let ethereum = mockethereum::spawn();
+
+    let relay = relay(&ethereum);
+
+    ethereum.revert(true);
+
+    assert!(matches!(
+      relay.submit_root([0; 32]),
+      Err(Brc20Error::Relay(message))
+        if message.starts_with("transaction 0x") && message.ends_with(" reverted"),
+    ));
+
+    ethereum.revert(false);
+
+    assert_eq!(relay.submit_root([0; 32]).unwrap().nonce, 1);
+  }
+
+  #[test]
+  fn missing_receipt_times_out() {
+    let ethereum = mockethereum::builder().automine(false).build();
+
+    let relay = relay(&ethereum);
+
+    assert!(matches!(
+      relay.submit_root([0; 32]),
+      Err(Brc20Error::Relay(message))
+        if message.starts_with("timed out waiting for receipt for transaction 0x"),
+    ));
+
+    assert_eq!(ethereum.mempool().len(), 1);
+
+    ethereum.mine();
+
+    ethereum.state().automine = true;
+
+    assert_eq!(relay.submit_root([1; 32]).unwrap().nonce, 1);
+  }
 }
@@ -1,2 +1,3 @@
+pub mod checkpoint;
 pub mod ethereum;
-pub mod types;
+mod rlp;
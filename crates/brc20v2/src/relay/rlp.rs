@@ -0,0 +1,101 @@
+//! Minimal RLP encoder, covering what is needed to sign Ethereum
+//! transactions.
+
+pub(crate) enum Item<'a> {
+  Bytes(&'a [u8]),
+  List(Vec<Item<'a>>),
+  Uint(u128),
+}
+
+impl Item<'_> {
+  pub(crate) fn encode(&self) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    self.encode_to(&mut buffer);
+    buffer
+  }
+
+  fn encode_to(&self, buffer: &mut Vec<u8>) {
+    match self {
+      Self::Bytes(bytes) => encode_bytes(bytes, buffer),
+      Self::List(items) => {
+        let mut payload = Vec::new();
+
+        for item in items {
+          item.encode_to(&mut payload);
+        }
+
+        encode_header(0xc0, payload.len(), buffer);
+        buffer.extend_from_slice(&payload);
+      }
+      Self::Uint(n) => {
+        let bytes = n.to_be_bytes();
+        let leading = bytes.iter().take_while(|byte| **byte == 0).count();
+        encode_bytes(&bytes[leading..], buffer);
+      }
+    }
+  }
+}
+
+fn encode_bytes(bytes: &[u8], buffer: &mut Vec<u8>) {
+  if let [byte] = bytes
+    && *byte < 0x80
+  {
+    buffer.push(*byte);
+  } else {
+    encode_header(0x80, bytes.len(), buffer);
+    buffer.extend_from_slice(bytes);
+  }
+}
+
+fn encode_header(offset: u8, len: usize, buffer: &mut Vec<u8>) {
+  if len <= 55 {
+    buffer.push(offset + u8::try_from(len).unwrap());
+  } else {
+    let bytes = len.to_be_bytes();
+    let leading = bytes.iter().take_while(|byte| **byte == 0).count();
+    buffer.push(offset + 55 + u8::try_from(bytes.len() - leading).unwrap());
+    buffer.extend_from_slice(&bytes[leading..]);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn encode() {
+    #[track_caller]
+    fn case(item: Item, expected: &str) {
+      assert_eq!(hex::encode(item.encode()), expected);
+    }
+
+    case(Item::Bytes(b"dog"), "83646f67");
+    case(Item::Bytes(b""), "80");
+    case(Item::Bytes(&[0x00]), "00");
+    case(Item::Bytes(&[0x7f]), "7f");
+    case(Item::Bytes(&[0x80]), "8180");
+    case(Item::Uint(0), "80");
+    case(Item::Uint(15), "0f");
+    case(Item::Uint(1024), "820400");
+    case(Item::List(Vec::new()), "c0");
+    case(
+      Item::List(vec![Item::Bytes(b"cat"), Item::Bytes(b"dog")]),
+      "c88363617483646f67",
+    );
+    case(
+      Item::List(vec![
+        Item::List(Vec::new()),
+        Item::List(vec![Item::List(Vec::new())]),
+        Item::List(vec![
+          Item::List(Vec::new()),
+          Item::List(vec![Item::List(Vec::new())]),
+        ]),
+      ]),
+      "c7c0c1c0c3c0c1c0",
+    );
+    case(
+      Item::Bytes(b"Lorem ipsum dolor sit amet, consectetur adipisicing elit"),
+      "b8384c6f72656d20697073756d20646f6c6f722073697420616d65742c20636f6e7365637465747572206164697069736963696e6720656c6974",
+    );
+  }
+}
@@ -1,5 +0,0 @@
-#[derive(Debug, Clone)]
-pub struct RelayPayload {
-    pub destination: String,
-    pub data: Vec<u8>,
-}
@@ -1,31 +1,272 @@
-use sha2::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
 
-use crate::errors::Brc20Error;
+use super::{
+  errors::Brc20Error,
+  merkle::{EMPTY_LEAF, LeafData, MerkleProof},
+  zk_proof::ZkProof,
+};
 
-#[derive(Debug, Clone)]
-pub struct ZkProof {
-    pub commitment: String,
+#[cfg(feature = "zk-groth16")]
+pub mod groth16;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ZkStatement {
+  pub operation: String,
+  pub token: String,
+  pub from: Option<[u8; 32]>,
+  pub to: Option<[u8; 32]>,
+  pub amount: u128,
+  pub previous_merkle_root: [u8; 32],
+  pub merkle_root: [u8; 32],
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ZkWitness {
+  pub from_balance: u128,
+  pub to_balance: u128,
+  /// Leaf updates taking `previous_merkle_root` to `merkle_root`, in the
+  /// order they were applied. Transfers debit `from` and then credit `to`,
+  /// mints credit `to`.
+  pub updates: Vec<LeafUpdate>,
+}
+
+/// A single leaf write. `old` is `None` if the account did not exist, in
+/// which case the leaf was empty. `proof` holds the siblings of the leaf,
+/// which are the same before and after the write.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LeafUpdate {
+  pub commitment: [u8; 32],
+  pub debit: bool,
+  pub old: Option<LeafData>,
+  pub new: LeafData,
+  pub proof: MerkleProof,
+}
+
+impl LeafUpdate {
+  pub fn old_root(&self) -> [u8; 32] {
+    self.proof.root(
+      &self.commitment,
+      self
+        .old
+        .map(|old| old.hash(&self.commitment))
+        .unwrap_or(EMPTY_LEAF),
+    )
+  }
+
+  pub fn new_root(&self) -> [u8; 32] {
+    self
+      .proof
+      .root(&self.commitment, self.new.hash(&self.commitment))
+  }
 }
 
-pub fn generate_proof(
-    from: &str,
-    to: &str,
-    amount: u64,
-    prev_hash: &str,
-    identity_verified: bool,
-) -> Result<ZkProof, Brc20Error> {
-    if !identity_verified {
-        return Err(Brc20Error::IdentityFailed);
+impl ZkStatement {
+  /// The `(commitment, debit)` pairs a proof of this statement must cover.
+  pub fn schedule(&self) -> Result<Vec<([u8; 32], bool)>, Brc20Error> {
+    let missing = |side| {
+      Brc20Error::ProofGenerationFailed(format!("{} statement is missing `{side}`", self.operation))
+    };
+
+    match self.operation.as_str() {
+      "deploy" | "soulbound" | "transfer_admin" | "approve" | "freeze" => Ok(Vec::new()),
+      "mint" | "mint_vested" => Ok(vec![(self.to.ok_or_else(|| missing("to"))?, false)]),
+      "burn" => Ok(vec![(self.from.ok_or_else(|| missing("from"))?, true)]),
+      "transfer" | "transfer_from" => Ok(vec![
+        (self.from.ok_or_else(|| missing("from"))?, true),
+        (self.to.ok_or_else(|| missing("to"))?, false),
+      ]),
+      operation => Err(Brc20Error::InvalidOperation(format!(
+        "no proof schedule for operation `{operation}`"
+      ))),
     }
+  }
+}
 
-    let mut h = Sha256::new();
-    h.update(format!("{}|{}|{}|{}", from, to, amount, prev_hash));
+pub trait ZkProofGenerator {
+  fn generate(&self, statement: &ZkStatement, witness: &ZkWitness) -> Result<ZkProof, Brc20Error>;
+  fn verify(&self, statement: &ZkStatement, proof: &ZkProof) -> bool;
+}
 
-    Ok(ZkProof {
-        commitment: hex::encode(h.finalize()),
-    })
+#[derive(Clone, Debug, Default)]
+pub struct PlaceholderZk;
+
+impl ZkProofGenerator for PlaceholderZk {
+  fn generate(&self, statement: &ZkStatement, witness: &ZkWitness) -> Result<ZkProof, Brc20Error> {
+    let statement_bytes = serde_json::to_vec(statement).unwrap_or_default();
+    let witness_bytes = serde_json::to_vec(witness).unwrap_or_default();
+    Ok(ZkProof::new(&statement_bytes, &witness_bytes))
+  }
+
+  fn verify(&self, statement: &ZkStatement, proof: &ZkProof) -> bool {
+    let statement_bytes = serde_json::to_vec(statement).unwrap_or_default();
+    proof.verify(&statement_bytes)
+  }
 }
 
-pub fn verify_proof(proof: &ZkProof) -> bool {
-    !proof.commitment.is_empty()
+#[cfg(test)]
+mod tests {
+  use super::{
+    super::{
+      Brc20StateMachine, IdentityCommitment, IdentityVerifiers, Operation, TokenDefinition,
+      VestingCurve, VestingSchedule,
+    },
+    *,
+  };
+
+  struct Chained;
+
+  impl ZkProofGenerator for Chained {
+    fn generate(
+      &self,
+      statement: &ZkStatement,
+      witness: &ZkWitness,
+    ) -> Result<ZkProof, Brc20Error> {
+      let mut root = statement.previous_merkle_root;
+
+      for update in &witness.updates {
+        assert_eq!(update.old_root(), root);
+        root = update.new_root();
+      }
+
+      assert_eq!(root, statement.merkle_root);
+
+      assert_eq!(
+        witness
+          .updates
+          .iter()
+          .map(|update| (update.commitment, update.debit))
+          .collect::<Vec<([u8; 32], bool)>>(),
+        statement.schedule().unwrap(),
+      );
+
+      PlaceholderZk.generate(statement, witness)
+    }
+
+    fn verify(&self, statement: &ZkStatement, proof: &ZkProof) -> bool {
+      PlaceholderZk.verify(statement, proof)
+    }
+  }
+
+  #[test]
+  fn witness_updates_chain_roots() {
+    let alice = IdentityCommitment::new("alice");
+    let bob = IdentityCommitment::new("bob");
+    let carol = IdentityCommitment::new("carol");
+
+    let operations = [
+      (
+        &alice,
+        Operation::Deploy {
+          definition: TokenDefinition {
+            ticker: "ordi".into(),
+            max_supply: 1000,
+            mint_limit: 1000,
+            decimals: 0,
+            soulbound: false,
+            transfer_rules: Default::default(),
+          },
+        },
+      ),
+      (
+        &alice,
+        Operation::Mint {
+          ticker: "ordi".into(),
+          to: alice.clone(),
+          amount: 100,
+        },
+      ),
+      (
+        &alice,
+        Operation::MintVested {
+          ticker: "ordi".into(),
+          to: bob.clone(),
+          amount: 50,
+          vesting: VestingSchedule {
+            start_height: 0,
+            cliff_blocks: 0,
+            duration_blocks: 10,
+            curve: VestingCurve::Linear,
+            total_locked: 50,
+          },
+        },
+      ),
+      (
+        &alice,
+        Operation::Transfer {
+          ticker: "ordi".into(),
+          from: alice.clone(),
+          to: bob.clone(),
+          amount: 30,
+          proof: None,
+        },
+      ),
+      (
+        &bob,
+        Operation::Transfer {
+          ticker: "ordi".into(),
+          from: bob.clone(),
+          to: bob.clone(),
+          amount: 10,
+          proof: None,
+        },
+      ),
+      (
+        &alice,
+        Operation::Transfer {
+          ticker: "ordi".into(),
+          from: alice.clone(),
+          to: carol.clone(),
+          amount: 20,
+          proof: None,
+        },
+      ),
+      (
+        &alice,
+        Operation::Approve {
+          ticker: "ordi".into(),
+          spender: bob.clone(),
+          amount: 10,
+        },
+      ),
+      (
+        &bob,
+        Operation::TransferFrom {
+          ticker: "ordi".into(),
+          from: alice.clone(),
+          to: carol.clone(),
+          amount: 10,
+          proof: None,
+        },
+      ),
+      (
+        &carol,
+        Operation::Burn {
+          ticker: "ordi".into(),
+          from: carol.clone(),
+          amount: 5,
+        },
+      ),
+      (
+        &alice,
+        Operation::SetSoulbound {
+          ticker: "ordi".into(),
+          soulbound: true,
+        },
+      ),
+    ];
+
+    let mut state_machine = Brc20StateMachine::default();
+
+    for (owner, operation) in operations {
+      state_machine
+        .apply_operation(
+          operation,
+          &Chained,
+          &IdentityVerifiers::default(),
+          Some(owner),
+          100,
+        )
+        .unwrap();
+    }
+  }
 }
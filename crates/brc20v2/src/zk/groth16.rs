@@ -0,0 +1,612 @@
+//! Groth16 proofs over BN254 for BRC-20 v2 balance transitions.
+//!
+//! Every balance-changing operation is proven as a sequence of single-leaf
+//! updates of the token's sparse Merkle tree. Each step proves, for a public
+//! `(old_root, new_root, commitment, amount, debit)`, that the prover knows
+//! the leaf at `commitment` and its siblings such that:
+//!
+//! - the old leaf, or the empty leaf if the account did not exist, hashes up
+//!   to `old_root`, and the new leaf hashes up to `new_root` with the same
+//!   siblings, along the path selected by the bits of `commitment`
+//! - a debit takes exactly `amount` from the old balance without
+//!   underflowing, so `from_balance >= amount`
+//! - a credit adds exactly `amount` to the old balance without overflowing,
+//!   and a missing account is credited from zero
+//!
+//! Hashing uses the same SHA-256 leaf and node encoding as
+//! `TokenState::merkle_root`, so proofs are checked against the roots
+//! committed to by inscriptions. Locked balance and the vesting flag are
+//! carried in the leaf but are not constrained by the circuit.
+//!
+//! Verification only needs the verifying key and runs on the CPU.
+
+use {
+  super::{
+    super::{
+      errors::Brc20Error,
+      merkle::{EMPTY_LEAF, LeafData},
+      zk_proof::{ZkProof, ZkProofStep},
+    },
+    LeafUpdate, ZkProofGenerator, ZkStatement, ZkWitness,
+  },
+  ark_bn254::{Bn254, Fr},
+  ark_crypto_primitives::crh::sha256::constraints::Sha256Gadget,
+  ark_ff::ToConstraintField,
+  ark_groth16::{Groth16, PreparedVerifyingKey, Proof, ProvingKey, VerifyingKey},
+  ark_r1cs_std::{fields::fp::FpVar, prelude::*, uint8::UInt8},
+  ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+  ark_serialize::{CanonicalDeserialize, CanonicalSerialize},
+  ark_snark::SNARK,
+  ark_std::rand::{CryptoRng, RngCore},
+};
+
+const PUBLIC_INPUT_LEN: usize = 32 + 32 + 32 + 16 + 1;
+
+pub struct Groth16Zk {
+  depth: usize,
+  proving_key: Option<ProvingKey<Bn254>>,
+  verifying_key: PreparedVerifyingKey<Bn254>,
+}
+
+impl Groth16Zk {
+  /// Run a circuit-specific setup for trees of `depth`, which must be
+  /// `merkle::DEPTH` to prove token transitions. The randomness used here is
+  /// toxic waste:
+  /// anyone who knows it can forge proofs, so production keys should come
+  /// from a ceremony and be loaded with `from_proving_key` or
+  /// `from_verifying_key`.
+  pub fn setup<R: RngCore + CryptoRng>(depth: usize, rng: &mut R) -> Result<Self, Brc20Error> {
+    let (proving_key, verifying_key) =
+      Groth16::<Bn254>::circuit_specific_setup(UpdateCircuit::blank(depth), rng)
+        .map_err(|err| Brc20Error::ProofGenerationFailed(format!("setup failed: {err}")))?;
+
+    Ok(Self {
+      depth,
+      proving_key: Some(proving_key),
+      verifying_key: Groth16::<Bn254>::process_vk(&verifying_key)
+        .map_err(|err| Brc20Error::ProofGenerationFailed(format!("setup failed: {err}")))?,
+    })
+  }
+
+  pub fn from_proving_key(depth: usize, bytes: &[u8]) -> Result<Self, Brc20Error> {
+    let proving_key = ProvingKey::<Bn254>::deserialize_compressed(bytes)
+      .map_err(|err| Brc20Error::ProofGenerationFailed(format!("invalid proving key: {err}")))?;
+
+    Ok(Self {
+      depth,
+      verifying_key: Groth16::<Bn254>::process_vk(&proving_key.vk)
+        .map_err(|err| Brc20Error::ProofGenerationFailed(format!("invalid proving key: {err}")))?,
+      proving_key: Some(proving_key),
+    })
+  }
+
+  /// Load a verifier that can check, but not generate, proofs.
+  pub fn from_verifying_key(depth: usize, bytes: &[u8]) -> Result<Self, Brc20Error> {
+    let verifying_key = VerifyingKey::<Bn254>::deserialize_compressed(bytes).map_err(|err| {
+      Brc20Error::ProofVerificationFailed(format!("invalid verifying key: {err}"))
+    })?;
+
+    Ok(Self {
+      depth,
+      proving_key: None,
+      verifying_key: Groth16::<Bn254>::process_vk(&verifying_key).map_err(|err| {
+        Brc20Error::ProofVerificationFailed(format!("invalid verifying key: {err}"))
+      })?,
+    })
+  }
+
+  pub fn depth(&self) -> usize {
+    self.depth
+  }
+
+  pub fn proving_key_bytes(&self) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    self
+      .proving_key
+      .as_ref()?
+      .serialize_compressed(&mut bytes)
+      .ok()?;
+    Some(bytes)
+  }
+
+  pub fn verifying_key_bytes(&self) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    self
+      .verifying_key
+      .vk
+      .serialize_compressed(&mut bytes)
+      .expect("serializing to a vec cannot fail");
+    bytes
+  }
+
+  fn verify_step(
+    &self,
+    old_root: [u8; 32],
+    (commitment, debit): ([u8; 32], bool),
+    amount: u128,
+    step: &ZkProofStep,
+  ) -> bool {
+    let Ok(proof) = Proof::<Bn254>::deserialize_compressed(step.proof.as_slice()) else {
+      return false;
+    };
+
+    let Some(inputs) = ToConstraintField::<Fr>::to_field_elements(
+      &public_input(old_root, step.root, commitment, amount, debit)[..],
+    ) else {
+      return false;
+    };
+
+    Groth16::<Bn254>::verify_with_processed_vk(&self.verifying_key, &inputs, &proof)
+      .unwrap_or_default()
+  }
+}
+
+impl ZkProofGenerator for Groth16Zk {
+  fn generate(&self, statement: &ZkStatement, witness: &ZkWitness) -> Result<ZkProof, Brc20Error> {
+    let proving_key = self
+      .proving_key
+      .as_ref()
+      .ok_or_else(|| Brc20Error::ProofGenerationFailed("no proving key loaded".into()))?;
+
+    let schedule = statement.schedule()?;
+
+    if witness.updates.len() != schedule.len() {
+      return Err(Brc20Error::ProofGenerationFailed(format!(
+        "{} statement needs {} leaf updates but witness has {}",
+        statement.operation,
+        schedule.len(),
+        witness.updates.len(),
+      )));
+    }
+
+    let mut root = statement.previous_merkle_root;
+    let mut steps = Vec::with_capacity(schedule.len());
+
+    for (update, (commitment, debit)) in witness.updates.iter().zip(schedule) {
+      if update.commitment != commitment || update.debit != debit {
+        return Err(Brc20Error::ProofGenerationFailed(
+          "witness updates do not match statement".into(),
+        ));
+      }
+
+      if update.old_root() != root {
+        return Err(Brc20Error::ProofGenerationFailed(
+          "witness does not open the previous root".into(),
+        ));
+      }
+
+      let new_root = update.new_root();
+
+      let circuit = UpdateCircuit::new(self.depth, root, new_root, statement.amount, update)?;
+
+      let proof = Groth16::<Bn254>::prove(
+        proving_key,
+        circuit,
+        &mut bitcoin::secp256k1::rand::thread_rng(),
+      )
+      .map_err(|err| Brc20Error::ProofGenerationFailed(err.to_string()))?;
+
+      let mut bytes = Vec::new();
+      proof
+        .serialize_compressed(&mut bytes)
+        .map_err(|err| Brc20Error::ProofGenerationFailed(err.to_string()))?;
+
+      steps.push(ZkProofStep {
+        root: new_root,
+        proof: bytes,
+      });
+
+      root = new_root;
+    }
+
+    if root != statement.merkle_root {
+      return Err(Brc20Error::ProofGenerationFailed(
+        "witness does not produce the statement root".into(),
+      ));
+    }
+
+    Ok(ZkProof::with_steps(
+      &serde_json::to_vec(statement).unwrap_or_default(),
+      steps,
+    ))
+  }
+
+  fn verify(&self, statement: &ZkStatement, proof: &ZkProof) -> bool {
+    if !proof.verify(&serde_json::to_vec(statement).unwrap_or_default()) {
+      return false;
+    }
+
+    let Ok(schedule) = statement.schedule() else {
+      return false;
+    };
+
+    if proof.steps.len() != schedule.len() {
+      return false;
+    }
+
+    let mut root = statement.previous_merkle_root;
+
+    for (step, expected) in proof.steps.iter().zip(schedule) {
+      if !self.verify_step(root, expected, statement.amount, step) {
+        return false;
+      }
+      root = step.root;
+    }
+
+    root == statement.merkle_root
+  }
+}
+
+fn public_input(
+  old_root: [u8; 32],
+  new_root: [u8; 32],
+  commitment: [u8; 32],
+  amount: u128,
+  debit: bool,
+) -> [u8; PUBLIC_INPUT_LEN] {
+  let mut input = [0; PUBLIC_INPUT_LEN];
+  input[..32].copy_from_slice(&old_root);
+  input[32..64].copy_from_slice(&new_root);
+  input[64..96].copy_from_slice(&commitment);
+  input[96..112].copy_from_slice(&amount.to_be_bytes());
+  input[112] = debit.into();
+  input
+}
+
+#[derive(Clone)]
+struct UpdateCircuit {
+  public: [u8; PUBLIC_INPUT_LEN],
+  old_exists: bool,
+  old: LeafData,
+  new: LeafData,
+  siblings: Vec<[u8; 32]>,
+}
+
+impl UpdateCircuit {
+  fn blank(depth: usize) -> Self {
+    Self {
+      public: [0; PUBLIC_INPUT_LEN],
+      old_exists: false,
+      old: LeafData::default(),
+      new: LeafData::default(),
+      siblings: vec![[0; 32]; depth],
+    }
+  }
+
+  fn new(
+    depth: usize,
+    old_root: [u8; 32],
+    new_root: [u8; 32],
+    amount: u128,
+    update: &LeafUpdate,
+  ) -> Result<Self, Brc20Error> {
+    if usize::from(update.proof.depth) != depth {
+      return Err(Brc20Error::ProofGenerationFailed(format!(
+        "proof of depth {} does not match circuit depth {depth}",
+        update.proof.depth,
+      )));
+    }
+
+    Ok(Self {
+      public: public_input(old_root, new_root, update.commitment, amount, update.debit),
+      old_exists: update.old.is_some(),
+      old: update.old.unwrap_or_default(),
+      new: update.new,
+      siblings: update.proof.expand(),
+    })
+  }
+}
+
+impl ConstraintSynthesizer<Fr> for UpdateCircuit {
+  fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+    let public = UInt8::new_input_vec(cs.clone(), &self.public)?;
+
+    let old_root = &public[..32];
+    let new_root = &public[32..64];
+    let commitment = &public[64..96];
+    let amount = &public[96..112];
+
+    let debit = public[112].to_bits_le()?;
+    for bit in &debit[1..] {
+      bit.enforce_equal(&Boolean::FALSE)?;
+    }
+    let debit = &debit[0];
+
+    let old_exists = Boolean::new_witness(cs.clone(), || Ok(self.old_exists))?;
+    let old = UInt8::new_witness_vec(cs.clone(), &self.old.encode())?;
+    let new = UInt8::new_witness_vec(cs.clone(), &self.new.encode())?;
+
+    // Balances and the amount are decomposed into 128 bits, so the field
+    // equations below hold only if they hold over the integers.
+    let old_balance = pack(&old[..16])?;
+    let new_balance = pack(&new[..16])?;
+    let amount = pack(amount)?;
+
+    debit
+      .select(
+        &(&old_balance - &new_balance),
+        &(&new_balance - &old_balance),
+      )?
+      .enforce_equal(&amount)?;
+
+    // A missing account has nothing to spend.
+    (&old_balance * &FpVar::from(old_exists.not())).enforce_equal(&FpVar::zero())?;
+
+    let mut old_node = hash_leaf(commitment, &old)?
+      .iter()
+      .zip(UInt8::constant_vec(&EMPTY_LEAF))
+      .map(|(leaf, empty)| old_exists.select(leaf, &empty))
+      .collect::<Result<Vec<UInt8<Fr>>, SynthesisError>>()?;
+
+    let mut new_node = hash_leaf(commitment, &new)?;
+
+    let depth = self.siblings.len();
+
+    for (height, sibling) in self.siblings.iter().enumerate() {
+      let sibling = UInt8::new_witness_vec(cs.clone(), sibling)?;
+
+      let index = depth - 1 - height;
+      let right = commitment[index / 8].to_bits_le()?[7 - index % 8].clone();
+
+      old_node = climb(&old_node, &sibling, &right)?;
+      new_node = climb(&new_node, &sibling, &right)?;
+    }
+
+    old_node[..].enforce_equal(old_root)?;
+    new_node[..].enforce_equal(new_root)?;
+
+    Ok(())
+  }
+}
+
+/// Interpret big-endian bytes as a field element.
+fn pack(bytes: &[UInt8<Fr>]) -> Result<FpVar<Fr>, SynthesisError> {
+  let mut value = FpVar::zero();
+  for byte in bytes {
+    for bit in byte.to_bits_le()?.into_iter().rev() {
+      value = value.double()? + FpVar::from(bit);
+    }
+  }
+  Ok(value)
+}
+
+fn hash_leaf(
+  commitment: &[UInt8<Fr>],
+  data: &[UInt8<Fr>],
+) -> Result<Vec<UInt8<Fr>>, SynthesisError> {
+  let preimage = commitment
+    .iter()
+    .chain(data)
+    .cloned()
+    .collect::<Vec<UInt8<Fr>>>();
+
+  Ok(Sha256Gadget::<Fr>::digest(&preimage)?.0)
+}
+
+/// Hash `node` with `sibling` to get its parent, with `node` on the right if
+/// `right` is set.
+fn climb(
+  node: &[UInt8<Fr>],
+  sibling: &[UInt8<Fr>],
+  right: &Boolean<Fr>,
+) -> Result<Vec<UInt8<Fr>>, SynthesisError> {
+  let mut preimage = Vec::with_capacity(64);
+
+  for (sibling, node) in sibling.iter().zip(node) {
+    preimage.push(right.select(sibling, node)?);
+  }
+
+  for (node, sibling) in node.iter().zip(sibling) {
+    preimage.push(right.select(node, sibling)?);
+  }
+
+  Ok(Sha256Gadget::<Fr>::digest(&preimage)?.0)
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::{
+      super::super::{IdentityCommitment, merkle::SparseMerkleTree},
+      *,
+    },
+    ark_relations::r1cs::ConstraintSystem,
+    ark_std::rand::{SeedableRng, rngs::StdRng},
+  };
+
+  // Small enough to set up in a test. Keys are the leading bits of each
+  // commitment, which differ for the identities used below.
+  const DEPTH: usize = 4;
+
+  fn commitment(id: &str) -> [u8; 32] {
+    IdentityCommitment::new(id).commitment
+  }
+
+  fn leaf(balance: u128) -> LeafData {
+    LeafData {
+      balance,
+      ..LeafData::default()
+    }
+  }
+
+  fn write(
+    tree: &mut SparseMerkleTree,
+    commitment: [u8; 32],
+    old: Option<LeafData>,
+    balance: u128,
+    debit: bool,
+  ) -> LeafUpdate {
+    let new = leaf(balance);
+    let proof = tree.prove(&commitment);
+    tree.insert(&commitment, new.hash(&commitment));
+    LeafUpdate {
+      commitment,
+      debit,
+      old,
+      new,
+      proof,
+    }
+  }
+
+  fn resign(statement: &ZkStatement, proof: &ZkProof) -> ZkProof {
+    ZkProof::with_steps(&serde_json::to_vec(statement).unwrap(), proof.steps.clone())
+  }
+
+  fn satisfied(circuit: UpdateCircuit) -> bool {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    cs.is_satisfied().unwrap()
+  }
+
+  #[test]
+  fn transfer_is_proven_and_verified() {
+    let zk = Groth16Zk::setup(DEPTH, &mut StdRng::seed_from_u64(0)).unwrap();
+
+    let alice = commitment("alice");
+    let bob = commitment("bob");
+    let carol = commitment("carol");
+
+    let mut tree = SparseMerkleTree::with_depth(DEPTH);
+    tree.insert(&alice, leaf(100).hash(&alice));
+    tree.insert(&carol, leaf(5).hash(&carol));
+
+    let previous_merkle_root = tree.root();
+
+    let debit = write(&mut tree, alice, Some(leaf(100)), 70, true);
+    let credit = write(&mut tree, bob, None, 30, false);
+
+    let statement = ZkStatement {
+      operation: "transfer".into(),
+      token: "ordi".into(),
+      from: Some(alice),
+      to: Some(bob),
+      amount: 30,
+      previous_merkle_root,
+      merkle_root: tree.root(),
+    };
+
+    let proof = zk
+      .generate(
+        &statement,
+        &ZkWitness {
+          from_balance: 100,
+          to_balance: 0,
+          updates: vec![debit, credit],
+        },
+      )
+      .unwrap();
+
+    assert_eq!(proof.steps.len(), 2);
+    assert!(zk.verify(&statement, &proof));
+
+    let verifier = Groth16Zk::from_verifying_key(DEPTH, &zk.verifying_key_bytes()).unwrap();
+    assert!(verifier.verify(&statement, &proof));
+
+    for tampered in [
+      ZkStatement {
+        amount: 31,
+        ..statement.clone()
+      },
+      ZkStatement {
+        to: Some(carol),
+        ..statement.clone()
+      },
+      ZkStatement {
+        from: Some(carol),
+        ..statement.clone()
+      },
+      ZkStatement {
+        merkle_root: previous_merkle_root,
+        ..statement.clone()
+      },
+    ] {
+      assert!(!verifier.verify(&tampered, &resign(&tampered, &proof)));
+    }
+  }
+
+  #[test]
+  fn verifier_cannot_prove() {
+    let zk = Groth16Zk::setup(DEPTH, &mut StdRng::seed_from_u64(0)).unwrap();
+    let zk = Groth16Zk::from_verifying_key(DEPTH, &zk.verifying_key_bytes()).unwrap();
+
+    let alice = commitment("alice");
+    let mut tree = SparseMerkleTree::with_depth(DEPTH);
+    let previous_merkle_root = tree.root();
+    let credit = write(&mut tree, alice, None, 10, false);
+
+    assert_eq!(
+      zk.generate(
+        &ZkStatement {
+          operation: "mint".into(),
+          token: "ordi".into(),
+          from: None,
+          to: Some(alice),
+          amount: 10,
+          previous_merkle_root,
+          merkle_root: tree.root(),
+        },
+        &ZkWitness {
+          from_balance: 0,
+          to_balance: 0,
+          updates: vec![credit],
+        },
+      ),
+      Err(Brc20Error::ProofGenerationFailed(
+        "no proving key loaded".into()
+      )),
+    );
+  }
+
+  #[test]
+  fn honest_updates_are_satisfiable() {
+    let alice = commitment("alice");
+    let bob = commitment("bob");
+
+    let mut tree = SparseMerkleTree::with_depth(DEPTH);
+    tree.insert(&alice, leaf(10).hash(&alice));
+
+    let root = tree.root();
+    let debit = write(&mut tree, alice, Some(leaf(10)), 0, true);
+    assert!(satisfied(
+      UpdateCircuit::new(DEPTH, root, tree.root(), 10, &debit).unwrap()
+    ));
+
+    let root = tree.root();
+    let credit = write(&mut tree, bob, None, 10, false);
+    assert!(satisfied(
+      UpdateCircuit::new(DEPTH, root, tree.root(), 10, &credit).unwrap()
+    ));
+  }
+
+  #[test]
+  fn overdraft_is_unsatisfiable() {
+    let alice = commitment("alice");
+
+    let mut tree = SparseMerkleTree::with_depth(DEPTH);
+    tree.insert(&alice, leaf(10).hash(&alice));
+
+    // An overdraft of 1 would need a new balance of -1, which wraps to
+    // u128::MAX in the witness.
+    let root = tree.root();
+    let debit = write(&mut tree, alice, Some(leaf(10)), u128::MAX, true);
+
+    assert!(!satisfied(
+      UpdateCircuit::new(DEPTH, root, tree.root(), 11, &debit).unwrap()
+    ));
+  }
+
+  #[test]
+  fn missing_account_has_no_balance() {
+    let bob = commitment("bob");
+
+    let mut tree = SparseMerkleTree::with_depth(DEPTH);
+
+    let root = tree.root();
+    let credit = write(&mut tree, bob, None, 60, false);
+
+    let mut circuit = UpdateCircuit::new(DEPTH, root, tree.root(), 10, &credit).unwrap();
+    circuit.old = leaf(50);
+
+    assert!(!satisfied(circuit));
+  }
+}
@@ -1,4 +0,0 @@
-#[allow(dead_code)]
-pub fn prepare_inscription_payload(payload: &str) -> Vec<u8> {
-    payload.as_bytes().to_vec()
-}
@@ -697,7 +697,7 @@ impl Api for Server {
 
     let tx_height = state.txid_to_block_height.get(&txid);
 
-    let confirmations = tx_height.map(|tx_height| current_height - tx_height);
+    let confirmations = tx_height.map(|tx_height| current_height - tx_height + 1);
 
     let blockhash = tx_height.map(|tx_height| state.hashes[usize::try_from(*tx_height).unwrap()]);
 
@@ -860,6 +860,20 @@ impl Api for Server {
     Ok(self.state().new_address(false))
   }
 
+  fn get_address_info(
+    &self,
+    address: Address<NetworkUnchecked>,
+  ) -> Result<Value, jsonrpc_core::Error> {
+    let checked = address.assume_checked_ref();
+
+    Ok(serde_json::json!({
+      "address": checked.to_string(),
+      "scriptPubKey": checked.script_pubkey().to_hex_string(),
+      "ismine": self.state().is_wallet_address(checked),
+      "labels": [],
+    }))
+  }
+
   fn list_transactions(
     &self,
     _label: Option<String>,
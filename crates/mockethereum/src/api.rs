@@ -0,0 +1,38 @@
+use super::*;
+
+#[doc(hidden)]
+#[jsonrpc_derive::rpc(server)]
+pub trait Api {
+  #[rpc(name = "eth_chainId")]
+  fn chain_id(&self) -> Result<String, jsonrpc_core::Error>;
+
+  #[rpc(name = "eth_blockNumber")]
+  fn block_number(&self) -> Result<String, jsonrpc_core::Error>;
+
+  #[rpc(name = "eth_getBlockByNumber")]
+  fn get_block_by_number(
+    &self,
+    block: String,
+    full: bool,
+  ) -> Result<Option<Value>, jsonrpc_core::Error>;
+
+  #[rpc(name = "eth_getTransactionCount")]
+  fn get_transaction_count(
+    &self,
+    address: String,
+    block: Option<String>,
+  ) -> Result<String, jsonrpc_core::Error>;
+
+  #[rpc(name = "eth_maxPriorityFeePerGas")]
+  fn max_priority_fee_per_gas(&self) -> Result<String, jsonrpc_core::Error>;
+
+  #[rpc(name = "eth_estimateGas")]
+  fn estimate_gas(&self, call: Value, block: Option<String>)
+  -> Result<String, jsonrpc_core::Error>;
+
+  #[rpc(name = "eth_sendRawTransaction")]
+  fn send_raw_transaction(&self, raw: String) -> Result<String, jsonrpc_core::Error>;
+
+  #[rpc(name = "eth_getTransactionReceipt")]
+  fn get_transaction_receipt(&self, hash: String) -> Result<Option<Value>, jsonrpc_core::Error>;
+}
@@ -0,0 +1,188 @@
+#![allow(mismatched_lifetime_syntaxes)]
+
+use {
+  api::Api,
+  jsonrpc_core::{IoHandler, Value},
+  jsonrpc_http_server::{CloseHandle, ServerBuilder},
+  rlp::Rlp,
+  secp256k1::{
+    Message, PublicKey, SECP256K1,
+    ecdsa::{RecoverableSignature, RecoveryId},
+  },
+  serde_json::json,
+  server::Server,
+  sha3::{Digest, Keccak256},
+  std::{
+    collections::BTreeMap,
+    mem,
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::Duration,
+  },
+};
+
+pub use state::{Receipt, State, Transaction};
+
+mod api;
+mod rlp;
+mod server;
+mod state;
+
+pub type Address = [u8; 20];
+
+pub type Hash = [u8; 32];
+
+fn keccak256(data: &[u8]) -> Hash {
+  Keccak256::digest(data).into()
+}
+
+fn quantity(n: impl Into<u128>) -> String {
+  format!("{:#x}", n.into())
+}
+
+fn hex(bytes: &[u8]) -> String {
+  format!("0x{}", hex::encode(bytes))
+}
+
+pub fn builder() -> Builder {
+  Builder {
+    automine: true,
+    base_fee: 1_000_000_000,
+    chain_id: 1337,
+    priority_fee: 100_000_000,
+  }
+}
+
+pub struct Builder {
+  automine: bool,
+  base_fee: u128,
+  chain_id: u64,
+  priority_fee: u128,
+}
+
+impl Builder {
+  pub fn automine(self, automine: bool) -> Self {
+    Self { automine, ..self }
+  }
+
+  pub fn base_fee(self, base_fee: u128) -> Self {
+    Self { base_fee, ..self }
+  }
+
+  pub fn chain_id(self, chain_id: u64) -> Self {
+    Self { chain_id, ..self }
+  }
+
+  pub fn priority_fee(self, priority_fee: u128) -> Self {
+    Self {
+      priority_fee,
+      ..self
+    }
+  }
+
+  pub fn build(self) -> Handle {
+    let state = Arc::new(Mutex::new(State::new(
+      self.automine,
+      self.base_fee,
+      self.chain_id,
+      self.priority_fee,
+    )));
+    let server = Server::new(state.clone());
+    let mut io = IoHandler::default();
+    io.extend_with(server.to_delegate());
+
+    let rpc_server = ServerBuilder::new(io)
+      .threads(1)
+      .start_http(&"127.0.0.1:0".parse().unwrap())
+      .unwrap();
+
+    let close_handle = rpc_server.close_handle();
+    let port = rpc_server.address().port();
+
+    thread::spawn(|| rpc_server.wait());
+
+    for i in 0.. {
+      match reqwest::blocking::get(format!("http://127.0.0.1:{port}/")) {
+        Ok(_) => break,
+        Err(err) => {
+          if i == 400 {
+            panic!("mock ethereum server failed to start: {err}");
+          }
+        }
+      }
+
+      thread::sleep(Duration::from_millis(25));
+    }
+
+    Handle {
+      close_handle: Some(close_handle),
+      port,
+      state,
+    }
+  }
+}
+
+pub fn spawn() -> Handle {
+  builder().build()
+}
+
+pub struct Handle {
+  close_handle: Option<CloseHandle>,
+  port: u16,
+  state: Arc<Mutex<State>>,
+}
+
+impl Handle {
+  pub fn url(&self) -> String {
+    format!("http://127.0.0.1:{}", self.port)
+  }
+
+  pub fn state(&self) -> MutexGuard<State> {
+    self.state.lock().unwrap()
+  }
+
+  pub fn mine(&self) -> Hash {
+    self.state().mine()
+  }
+
+  pub fn mempool(&self) -> Vec<Transaction> {
+    self.state().mempool.clone()
+  }
+
+  pub fn receipt(&self, hash: Hash) -> Option<Receipt> {
+    self.state().receipts.get(&hash).cloned()
+  }
+
+  #[track_caller]
+  pub fn transaction(&self, hash: Hash) -> Transaction {
+    self
+      .state()
+      .transactions
+      .get(&hash)
+      .expect("unknown transaction")
+      .clone()
+  }
+
+  /// Make every subsequently mined transaction revert.
+  pub fn revert(&self, revert: bool) {
+    self.state().revert = revert;
+  }
+
+  /// Reject the next raw transaction with `message`, as a node would if it
+  /// failed validation.
+  pub fn reject_next(&self, message: &str) {
+    self.state().reject = Some(message.into());
+  }
+
+  /// Advance the nonce of `address` as if it had sent a transaction through
+  /// another node.
+  pub fn bump_nonce(&self, address: Address) {
+    *self.state().nonces.entry(address).or_default() += 1;
+  }
+}
+
+impl Drop for Handle {
+  fn drop(&mut self) {
+    self.close_handle.take().unwrap().close();
+  }
+}
@@ -0,0 +1,141 @@
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Rlp {
+  Bytes(Vec<u8>),
+  List(Vec<Rlp>),
+}
+
+impl Rlp {
+  pub(crate) fn decode(data: &[u8]) -> Result<Self, String> {
+    let (item, rest) = Self::decode_item(data)?;
+
+    if !rest.is_empty() {
+      return Err("trailing bytes after RLP item".into());
+    }
+
+    Ok(item)
+  }
+
+  fn decode_item(data: &[u8]) -> Result<(Self, &[u8]), String> {
+    let (&prefix, data) = data.split_first().ok_or("unexpected end of RLP")?;
+
+    let (list, len, data) = match prefix {
+      0x00..=0x7f => return Ok((Self::Bytes(vec![prefix]), data)),
+      0x80..=0xb7 => (false, usize::from(prefix - 0x80), data),
+      0xb8..=0xbf => {
+        let (len, data) = Self::decode_len(usize::from(prefix - 0xb7), data)?;
+        (false, len, data)
+      }
+      0xc0..=0xf7 => (true, usize::from(prefix - 0xc0), data),
+      0xf8..=0xff => {
+        let (len, data) = Self::decode_len(usize::from(prefix - 0xf7), data)?;
+        (true, len, data)
+      }
+    };
+
+    if data.len() < len {
+      return Err("unexpected end of RLP".into());
+    }
+
+    let (mut payload, rest) = data.split_at(len);
+
+    if !list {
+      if len == 1 && payload[0] < 0x80 {
+        return Err("non-canonical single byte".into());
+      }
+
+      return Ok((Self::Bytes(payload.into()), rest));
+    }
+
+    let mut items = Vec::new();
+
+    while !payload.is_empty() {
+      let (item, remaining) = Self::decode_item(payload)?;
+      items.push(item);
+      payload = remaining;
+    }
+
+    Ok((Self::List(items), rest))
+  }
+
+  fn decode_len(len_of_len: usize, data: &[u8]) -> Result<(usize, &[u8]), String> {
+    if data.len() < len_of_len || len_of_len > mem::size_of::<usize>() {
+      return Err("invalid RLP length".into());
+    }
+
+    let (len, data) = data.split_at(len_of_len);
+
+    if len[0] == 0 {
+      return Err("non-canonical RLP length".into());
+    }
+
+    let len = len
+      .iter()
+      .fold(0, |acc, byte| (acc << 8) | usize::from(*byte));
+
+    if len <= 55 {
+      return Err("non-canonical RLP length".into());
+    }
+
+    Ok((len, data))
+  }
+
+  pub(crate) fn encode(&self) -> Vec<u8> {
+    fn header(offset: u8, len: usize, buffer: &mut Vec<u8>) {
+      if len <= 55 {
+        buffer.push(offset + u8::try_from(len).unwrap());
+      } else {
+        let bytes = len.to_be_bytes();
+        let leading = bytes.iter().take_while(|byte| **byte == 0).count();
+        buffer.push(offset + 55 + u8::try_from(bytes.len() - leading).unwrap());
+        buffer.extend_from_slice(&bytes[leading..]);
+      }
+    }
+
+    let mut buffer = Vec::new();
+
+    match self {
+      Self::Bytes(bytes) => {
+        if bytes.len() == 1 && bytes[0] < 0x80 {
+          buffer.push(bytes[0]);
+        } else {
+          header(0x80, bytes.len(), &mut buffer);
+          buffer.extend_from_slice(bytes);
+        }
+      }
+      Self::List(items) => {
+        let payload = items.iter().flat_map(Self::encode).collect::<Vec<u8>>();
+        header(0xc0, payload.len(), &mut buffer);
+        buffer.extend_from_slice(&payload);
+      }
+    }
+
+    buffer
+  }
+
+  pub(crate) fn bytes(&self) -> Result<&[u8], String> {
+    match self {
+      Self::Bytes(bytes) => Ok(bytes),
+      Self::List(_) => Err("expected RLP string, found list".into()),
+    }
+  }
+
+  pub(crate) fn uint(&self) -> Result<u128, String> {
+    let bytes = self.bytes()?;
+
+    if bytes.first() == Some(&0) {
+      return Err("integer has leading zeros".into());
+    }
+
+    if bytes.len() > 16 {
+      return Err("integer overflow".into());
+    }
+
+    Ok(
+      bytes
+        .iter()
+        .fold(0, |acc, byte| (acc << 8) | u128::from(*byte)),
+    )
+  }
+}
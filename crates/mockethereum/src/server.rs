@@ -0,0 +1,142 @@
+use super::*;
+
+pub(crate) struct Server {
+  pub(crate) state: Arc<Mutex<State>>,
+}
+
+impl Server {
+  pub(crate) fn new(state: Arc<Mutex<State>>) -> Self {
+    Self { state }
+  }
+
+  fn state(&self) -> MutexGuard<State> {
+    self.state.lock().unwrap()
+  }
+
+  fn error(message: impl Into<String>) -> jsonrpc_core::Error {
+    jsonrpc_core::Error {
+      code: jsonrpc_core::ErrorCode::ServerError(-32000),
+      message: message.into(),
+      data: None,
+    }
+  }
+
+  fn decode<const N: usize>(hex: &str) -> Result<[u8; N], jsonrpc_core::Error> {
+    hex
+      .strip_prefix("0x")
+      .and_then(|digits| hex::decode(digits).ok())
+      .and_then(|bytes| bytes.try_into().ok())
+      .ok_or_else(|| jsonrpc_core::Error::invalid_params(format!("invalid hex `{hex}`")))
+  }
+}
+
+impl Api for Server {
+  fn chain_id(&self) -> Result<String, jsonrpc_core::Error> {
+    Ok(quantity(self.state().chain_id))
+  }
+
+  fn block_number(&self) -> Result<String, jsonrpc_core::Error> {
+    Ok(quantity(self.state().height()))
+  }
+
+  fn get_block_by_number(
+    &self,
+    block: String,
+    full: bool,
+  ) -> Result<Option<Value>, jsonrpc_core::Error> {
+    if full {
+      return Err(jsonrpc_core::Error::invalid_params(
+        "full transaction objects are not supported",
+      ));
+    }
+
+    let state = self.state();
+
+    let number = match block.as_str() {
+      "latest" | "pending" => state.height(),
+      "earliest" => 0,
+      number => number
+        .strip_prefix("0x")
+        .and_then(|digits| u64::from_str_radix(digits, 16).ok())
+        .ok_or_else(|| jsonrpc_core::Error::invalid_params(format!("invalid block `{block}`")))?,
+    };
+
+    let Some((hash, transactions)) = usize::try_from(number)
+      .ok()
+      .and_then(|number| state.blocks.get(number))
+    else {
+      return Ok(None);
+    };
+
+    Ok(Some(json!({
+      "number": quantity(number),
+      "hash": hex(hash),
+      "baseFeePerGas": quantity(state.base_fee),
+      "transactions": transactions.iter().map(|hash| hex(hash)).collect::<Vec<String>>(),
+    })))
+  }
+
+  fn get_transaction_count(
+    &self,
+    address: String,
+    _block: Option<String>,
+  ) -> Result<String, jsonrpc_core::Error> {
+    Ok(quantity(self.state().nonce(&Self::decode(&address)?)))
+  }
+
+  fn max_priority_fee_per_gas(&self) -> Result<String, jsonrpc_core::Error> {
+    Ok(quantity(self.state().priority_fee))
+  }
+
+  fn estimate_gas(
+    &self,
+    call: Value,
+    _block: Option<String>,
+  ) -> Result<String, jsonrpc_core::Error> {
+    let data = match call.get("data").or_else(|| call.get("input")) {
+      Some(Value::String(data)) => data
+        .strip_prefix("0x")
+        .and_then(|digits| hex::decode(digits).ok())
+        .ok_or_else(|| jsonrpc_core::Error::invalid_params(format!("invalid data `{data}`")))?,
+      Some(_) => return Err(jsonrpc_core::Error::invalid_params("invalid data")),
+      None => Vec::new(),
+    };
+
+    Ok(quantity(State::gas(&data)))
+  }
+
+  fn send_raw_transaction(&self, raw: String) -> Result<String, jsonrpc_core::Error> {
+    let raw = raw
+      .strip_prefix("0x")
+      .and_then(|digits| hex::decode(digits).ok())
+      .ok_or_else(|| jsonrpc_core::Error::invalid_params("invalid raw transaction"))?;
+
+    self
+      .state()
+      .send_raw_transaction(&raw)
+      .map(|hash| hex(&hash))
+      .map_err(Self::error)
+  }
+
+  fn get_transaction_receipt(&self, hash: String) -> Result<Option<Value>, jsonrpc_core::Error> {
+    let hash = Self::decode::<32>(&hash)?;
+
+    let state = self.state();
+
+    let Some(receipt) = state.receipts.get(&hash) else {
+      return Ok(None);
+    };
+
+    let transaction = &state.transactions[&hash];
+
+    Ok(Some(json!({
+      "transactionHash": hex(&hash),
+      "blockHash": hex(&receipt.block_hash),
+      "blockNumber": quantity(receipt.block_number),
+      "from": hex(&transaction.from),
+      "to": hex(&transaction.to),
+      "gasUsed": quantity(receipt.gas_used),
+      "status": quantity(u8::from(receipt.status)),
+    })))
+  }
+}
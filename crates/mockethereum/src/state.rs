@@ -0,0 +1,261 @@
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transaction {
+  pub chain_id: u64,
+  pub data: Vec<u8>,
+  pub from: Address,
+  pub gas_limit: u64,
+  pub hash: Hash,
+  pub max_fee_per_gas: u128,
+  pub max_priority_fee_per_gas: u128,
+  pub nonce: u64,
+  pub to: Address,
+  pub value: u128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Receipt {
+  pub block_hash: Hash,
+  pub block_number: u64,
+  pub gas_used: u64,
+  pub status: bool,
+}
+
+pub struct State {
+  pub automine: bool,
+  pub base_fee: u128,
+  pub blocks: Vec<(Hash, Vec<Hash>)>,
+  pub chain_id: u64,
+  pub mempool: Vec<Transaction>,
+  pub nonces: BTreeMap<Address, u64>,
+  pub priority_fee: u128,
+  pub receipts: BTreeMap<Hash, Receipt>,
+  pub reject: Option<String>,
+  pub revert: bool,
+  pub transactions: BTreeMap<Hash, Transaction>,
+}
+
+impl State {
+  pub(crate) fn new(automine: bool, base_fee: u128, chain_id: u64, priority_fee: u128) -> Self {
+    let mut state = Self {
+      automine,
+      base_fee,
+      blocks: Vec::new(),
+      chain_id,
+      mempool: Vec::new(),
+      nonces: BTreeMap::new(),
+      priority_fee,
+      receipts: BTreeMap::new(),
+      reject: None,
+      revert: false,
+      transactions: BTreeMap::new(),
+    };
+
+    state.mine();
+
+    state
+  }
+
+  pub fn height(&self) -> u64 {
+    u64::try_from(self.blocks.len()).unwrap() - 1
+  }
+
+  pub fn nonce(&self, address: &Address) -> u64 {
+    self.nonces.get(address).copied().unwrap_or_default()
+  }
+
+  /// Intrinsic gas of a call with `data`, which is all the mock charges.
+  pub fn gas(data: &[u8]) -> u64 {
+    data
+      .iter()
+      .fold(21_000, |gas, byte| gas + if *byte == 0 { 4 } else { 16 })
+  }
+
+  pub fn mine(&mut self) -> Hash {
+    let number = u64::try_from(self.blocks.len()).unwrap();
+
+    let mut preimage = number.to_be_bytes().to_vec();
+
+    if let Some((parent, _)) = self.blocks.last() {
+      preimage.extend_from_slice(parent);
+    }
+
+    let block_hash = keccak256(&preimage);
+
+    let mut hashes = Vec::new();
+
+    for transaction in mem::take(&mut self.mempool) {
+      self.receipts.insert(
+        transaction.hash,
+        Receipt {
+          block_hash,
+          block_number: number,
+          gas_used: Self::gas(&transaction.data),
+          status: !self.revert,
+        },
+      );
+
+      hashes.push(transaction.hash);
+    }
+
+    self.blocks.push((block_hash, hashes));
+
+    block_hash
+  }
+
+  pub(crate) fn send_raw_transaction(&mut self, raw: &[u8]) -> Result<Hash, String> {
+    if let Some(message) = self.reject.take() {
+      return Err(message);
+    }
+
+    let transaction = self.decode(raw)?;
+
+    if self.transactions.contains_key(&transaction.hash) {
+      return Err("already known".into());
+    }
+
+    if transaction.chain_id != self.chain_id {
+      return Err(format!(
+        "invalid chain id {}, expected {}",
+        transaction.chain_id, self.chain_id
+      ));
+    }
+
+    let nonce = self.nonce(&transaction.from);
+
+    if transaction.nonce < nonce {
+      return Err(format!(
+        "nonce too low: next nonce {nonce}, tx nonce {}",
+        transaction.nonce
+      ));
+    }
+
+    if transaction.nonce > nonce {
+      return Err(format!(
+        "nonce too high: next nonce {nonce}, tx nonce {}",
+        transaction.nonce
+      ));
+    }
+
+    if transaction.max_fee_per_gas < self.base_fee {
+      return Err(format!(
+        "max fee per gas less than block base fee: maxFeePerGas: {}, baseFee: {}",
+        transaction.max_fee_per_gas, self.base_fee
+      ));
+    }
+
+    if transaction.gas_limit < Self::gas(&transaction.data) {
+      return Err("intrinsic gas too low".into());
+    }
+
+    let hash = transaction.hash;
+
+    self.nonces.insert(transaction.from, nonce + 1);
+    self.transactions.insert(hash, transaction.clone());
+    self.mempool.push(transaction);
+
+    if self.automine {
+      self.mine();
+    }
+
+    Ok(hash)
+  }
+
+  /// Decode a signed EIP-1559 transaction and recover its sender.
+  fn decode(&self, raw: &[u8]) -> Result<Transaction, String> {
+    let Some((0x02, payload)) = raw.split_first() else {
+      return Err("transaction type not supported".into());
+    };
+
+    let Rlp::List(fields) = Rlp::decode(payload)? else {
+      return Err("transaction payload is not an RLP list".into());
+    };
+
+    let [
+      chain_id,
+      nonce,
+      max_priority_fee_per_gas,
+      max_fee_per_gas,
+      gas_limit,
+      to,
+      value,
+      data,
+      access_list,
+      y_parity,
+      r,
+      s,
+    ] = fields.as_slice()
+    else {
+      return Err(format!(
+        "expected 12 transaction fields, found {}",
+        fields.len()
+      ));
+    };
+
+    let u64 = |field: &Rlp| {
+      field
+        .uint()
+        .and_then(|n| u64::try_from(n).map_err(|err| err.to_string()))
+    };
+
+    let address = |field: &Rlp| -> Result<Address, String> {
+      field
+        .bytes()?
+        .try_into()
+        .map_err(|_| "invalid address length".to_string())
+    };
+
+    let scalar = |field: &Rlp| -> Result<[u8; 32], String> {
+      let bytes = field.bytes()?;
+
+      if bytes.len() > 32 {
+        return Err("signature scalar too long".into());
+      }
+
+      let mut scalar = [0; 32];
+      scalar[32 - bytes.len()..].copy_from_slice(bytes);
+      Ok(scalar)
+    };
+
+    if *access_list != Rlp::List(Vec::new()) {
+      return Err("access lists are not supported".into());
+    }
+
+    let mut signature = [0; 64];
+    signature[..32].copy_from_slice(&scalar(r)?);
+    signature[32..].copy_from_slice(&scalar(s)?);
+
+    let recovery_id = match y_parity.uint()? {
+      0 => RecoveryId::from_i32(0),
+      1 => RecoveryId::from_i32(1),
+      _ => return Err("invalid y parity".into()),
+    }
+    .map_err(|err| err.to_string())?;
+
+    let signature = RecoverableSignature::from_compact(&signature, recovery_id)
+      .map_err(|err| format!("invalid signature: {err}"))?;
+
+    let mut unsigned = vec![0x02];
+    unsigned.extend(Rlp::List(fields[..9].to_vec()).encode());
+
+    let public_key: PublicKey = SECP256K1
+      .recover_ecdsa(&Message::from_digest(keccak256(&unsigned)), &signature)
+      .map_err(|err| format!("invalid signature: {err}"))?;
+
+    Ok(Transaction {
+      chain_id: u64(chain_id)?,
+      data: data.bytes()?.into(),
+      from: keccak256(&public_key.serialize_uncompressed()[1..])[12..]
+        .try_into()
+        .unwrap(),
+      gas_limit: u64(gas_limit)?,
+      hash: keccak256(raw),
+      max_fee_per_gas: max_fee_per_gas.uint()?,
+      max_priority_fee_per_gas: max_priority_fee_per_gas.uint()?,
+      nonce: u64(nonce)?,
+      to: address(to)?,
+      value: value.uint()?,
+    })
+  }
+}
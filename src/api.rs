@@ -4,10 +4,11 @@ use {
 };
 
 pub use crate::{
+  index::address_history::AddressTransaction,
   subcommand::decode::RawOutput as Decode,
   templates::{
-    BlocksHtml as Blocks, RuneHtml as Rune, RunesHtml as Runes, StatusHtml as Status,
-    TransactionHtml as Transaction,
+    BlocksHtml as Blocks, Brc20v2AccountHtml as Brc20v2Account, Brc20v2TokenHtml as Brc20v2Token,
+    RuneHtml as Rune, RunesHtml as Runes, StatusHtml as Status, TransactionHtml as Transaction,
   },
 };
 
@@ -139,6 +140,12 @@ pub struct InscriptionRecursive {
   pub address: Option<String>,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct InscriptionHistory {
+  pub id: InscriptionId,
+  pub transfers: Vec<crate::index::InscriptionTransfer>,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct RelativeInscriptionRecursive {
   pub charms: Vec<Charm>,
@@ -152,6 +159,13 @@ pub struct RelativeInscriptionRecursive {
   pub timestamp: i64,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Events {
+  pub events: Vec<crate::index::event::EventRecord>,
+  pub more: bool,
+  pub page_index: u32,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Inscriptions {
   pub ids: Vec<InscriptionId>,
@@ -253,7 +267,117 @@ pub struct AddressInfo {
   pub runes_balances: Option<Vec<(SpacedRune, Decimal, Option<char>)>>,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct AddressTransactions {
+  pub transactions: Vec<AddressTransaction>,
+  pub more: bool,
+  pub page: usize,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Offers {
   pub offers: Vec<String>,
 }
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SearchResults {
+  pub inscriptions: Vec<InscriptionId>,
+  pub more: bool,
+  pub page: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Brc20v2Holder {
+  pub balance: u128,
+  #[serde(with = "SerHex::<Strict>")]
+  pub commitment: [u8; 32],
+  pub identity: String,
+  pub locked_balance: u128,
+}
+
+impl Brc20v2Holder {
+  pub fn new(
+    identity: &crate::brc20v2::IdentityCommitment,
+    account: &crate::brc20v2::AccountState,
+  ) -> Self {
+    Self {
+      balance: account.balance,
+      commitment: identity.commitment,
+      identity: identity.id.clone(),
+      locked_balance: account.locked_balance,
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Brc20v2Holders {
+  pub holders: Vec<Brc20v2Holder>,
+  pub more: bool,
+  pub page: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Brc20v2Tickers {
+  pub tickers: Vec<String>,
+  pub more: bool,
+  pub page: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Brc20v2InclusionProof {
+  #[serde(with = "SerHex::<Strict>")]
+  pub commitment: [u8; 32],
+  pub identity: String,
+  pub leaf: Option<crate::brc20v2::merkle::LeafData>,
+  #[serde(with = "SerHex::<Strict>")]
+  pub root: [u8; 32],
+  pub siblings: Vec<Brc20v2Sibling>,
+  pub ticker: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Brc20v2Sibling {
+  #[serde(with = "SerHex::<Strict>")]
+  pub hash: [u8; 32],
+  pub height: u16,
+}
+
+impl Brc20v2InclusionProof {
+  pub fn new(
+    ticker: String,
+    identity: String,
+    proof: crate::brc20v2::merkle::InclusionProof,
+    root: [u8; 32],
+  ) -> Self {
+    Self {
+      commitment: proof.commitment,
+      identity,
+      leaf: proof.leaf,
+      root,
+      siblings: proof
+        .proof
+        .siblings
+        .into_iter()
+        .map(|(height, hash)| Brc20v2Sibling { hash, height })
+        .collect(),
+      ticker,
+    }
+  }
+
+  pub fn proof(&self) -> crate::brc20v2::merkle::InclusionProof {
+    use crate::brc20v2::merkle::{DEPTH, InclusionProof, MerkleProof};
+
+    InclusionProof {
+      commitment: self.commitment,
+      leaf: self.leaf,
+      proof: MerkleProof {
+        depth: DEPTH.try_into().unwrap(),
+        siblings: self
+          .siblings
+          .iter()
+          .map(|sibling| (sibling.height, sibling.hash))
+          .collect(),
+      },
+    }
+  }
+}
@@ -1,101 +1,119 @@
-use brc20v2::cross_chain;
-use brc20v2::{BRC20v2, Metadata};
-use serde::Deserialize;
-use serde_json::to_string_pretty;
-use std::collections::HashMap;
-use std::fs;
-use std::io::Write;
-use std::process::Command;
-use tokio::runtime::Runtime;
+use {
+  anyhow::{Context, Result, ensure},
+  ord::brc20v2::{
+    Brc20StateMachine, IdentityCommitment, IdentityVerifiers, Operation, PlaceholderZk,
+    TokenDefinition,
+    relay::ethereum::{EthereumAddress, EthereumRelay},
+    state_root,
+  },
+  secp256k1::SecretKey,
+  serde::Deserialize,
+  std::{env, fs, process::Command},
+};
 
 #[derive(Deserialize)]
 struct Config {
-    fee_per_byte: u64,
-    rpc_url: String,
-    eth_rpc: String,
-    eth_contract: String,
-    eth_private_key: String,
+  fee_per_byte: u64,
+  rpc_url: String,
+  eth_rpc: String,
+  eth_contract: EthereumAddress,
+  eth_private_key: String,
 }
 
-impl Config {
-    fn load(path: &str) -> Self {
-        let contents = fs::read_to_string(path).expect("Failed to read config file");
-        serde_json::from_str(&contents).expect("Failed to parse config file")
-    }
-}
+/// Deploy, mint, and transfer a token, inscribe each operation with
+/// `ord wallet inscribe`, and relay the resulting state root to Ethereum.
+fn main() -> Result<()> {
+  let path = env::args().nth(1).unwrap_or_else(|| "config.json".into());
+
+  let config: Config = serde_json::from_str(
+    &fs::read_to_string(&path).with_context(|| format!("failed to read config `{path}`"))?,
+  )
+  .with_context(|| format!("failed to parse config `{path}`"))?;
 
-fn main() {
-    let config = Config::load("config.json");
+  let secret_key = config
+    .eth_private_key
+    .trim_start_matches("0x")
+    .parse::<SecretKey>()
+    .context("invalid Ethereum private key")?;
 
-    let mut token = BRC20v2::new("MYTOKEN");
+  let alice = IdentityCommitment::new("alice");
+  let bob = IdentityCommitment::new("bob");
 
-    let metadata = Metadata {
-        name: "MyToken".to_string(),
-        symbol: "MTK".to_string(),
+  let operations = [
+    Operation::Deploy {
+      definition: TokenDefinition {
+        ticker: "mytk".into(),
+        max_supply: 21_000_000,
+        mint_limit: 1000,
         decimals: 8,
         soulbound: false,
-        transfer_rules: HashMap::from([("max_per_tx".to_string(), 1000u64)]),
-        vesting: HashMap::from([("bc1qalice...".to_string(), 820000u64)]),
-    };
-
-    token.set_metadata(metadata);
-
-    token.mint("bc1qalice...", 1000);
-    token.mint("bc1qbob...", 500);
-    let mint_inscription = token.generate_inscription("mint", None);
-    let mut file = fs::File::create("mint_inscription.json").expect("Failed to create mint file");
-    file.write_all(
-        to_string_pretty(&mint_inscription)
-            .expect("Failed to serialize mint inscription")
-            .as_bytes(),
-    )
-    .expect("Failed to write mint inscription");
-
-    let proof = token.transfer("bc1qalice...", "bc1qbob...", 200, 820001, true);
-    let transfer_inscription = token.generate_inscription("transfer", Some(proof));
-    let mut file2 =
-        fs::File::create("transfer_inscription.json").expect("Failed to create transfer file");
-    file2
-        .write_all(
-            to_string_pretty(&transfer_inscription)
-                .expect("Failed to serialize transfer inscription")
-                .as_bytes(),
-        )
-        .expect("Failed to write transfer inscription");
-
-    Command::new("ord")
-        .args([
-            "inscribe",
-            "mint_inscription.json",
-            "--fee-rate",
-            &config.fee_per_byte.to_string(),
-            "--rpc-url",
-            &config.rpc_url,
-        ])
-        .output()
-        .expect("Failed to broadcast mint");
-
-    Command::new("ord")
-        .args([
-            "inscribe",
-            "transfer_inscription.json",
-            "--fee-rate",
-            &config.fee_per_byte.to_string(),
-            "--rpc-url",
-            &config.rpc_url,
-        ])
-        .output()
-        .expect("Failed to broadcast transfer");
-
-    let rt = Runtime::new().expect("Failed to build tokio runtime");
-    let relay_tx = rt
-        .block_on(cross_chain::relay_to_ethereum(
-            &serde_json::to_string(&transfer_inscription)
-                .expect("Failed to serialize relay payload"),
-            &config.eth_rpc,
-            &config.eth_contract,
-            &config.eth_private_key,
-        ))
-        .expect("Failed to relay to Ethereum");
-    println!("Relay tx hash: {relay_tx:?}");
+        transfer_rules: Default::default(),
+      },
+    },
+    Operation::Mint {
+      ticker: "mytk".into(),
+      to: alice.clone(),
+      amount: 1000,
+    },
+    Operation::Mint {
+      ticker: "mytk".into(),
+      to: bob.clone(),
+      amount: 500,
+    },
+    Operation::Transfer {
+      ticker: "mytk".into(),
+      from: alice.clone(),
+      to: bob,
+      amount: 200,
+      proof: None,
+    },
+  ];
+
+  let mut machine = Brc20StateMachine::default();
+
+  for (i, operation) in operations.into_iter().enumerate() {
+    let name = operation.operation_name();
+
+    let receipt = machine.apply_operation(
+      operation,
+      &PlaceholderZk,
+      &IdentityVerifiers::default(),
+      Some(&alice),
+      0,
+    )?;
+
+    let file = format!("{i}-{name}_inscription.json");
+
+    fs::write(&file, &receipt.inscription.body)?;
+
+    let output = Command::new("ord")
+      .args([
+        "--bitcoin-rpc-url",
+        &config.rpc_url,
+        "wallet",
+        "inscribe",
+        "--fee-rate",
+        &config.fee_per_byte.to_string(),
+        "--file",
+        &file,
+      ])
+      .output()
+      .context("failed to run `ord wallet inscribe`")?;
+
+    ensure!(
+      output.status.success(),
+      "failed to inscribe {file}: {}",
+      String::from_utf8_lossy(&output.stderr),
+    );
+
+    println!("{name}: {}", String::from_utf8_lossy(&output.stdout).trim());
+  }
+
+  let relay = EthereumRelay::new(config.eth_rpc, config.eth_contract, secret_key);
+
+  let receipt = relay.submit_root(state_root(machine.tokens.values()))?;
+
+  println!("Relay tx hash: 0x{}", hex::encode(receipt.transaction_hash));
+
+  Ok(())
 }
@@ -1,12 +0,0 @@
-pub mod brc20v2;
-pub mod errors;
-pub mod identity;
-pub mod inscription;
-pub mod relay;
-pub mod zk;
-pub mod zk_proof;
-
-pub use brc20v2::{
-  AccountState, Brc20StateMachine, Operation, TokenDefinition, TokenState, TransitionReceipt,
-  VestingSchedule,
-};
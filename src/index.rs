@@ -1,5 +1,26 @@
-super::*,
+use {
+  self::{
+    address_history::AddressActivity,
+    entry::{
+      AddressActivityValue, Brc20v2AccountValue, Brc20v2RejectionValue, Brc20v2TokenEntryValue,
+      Entry, EventRecordValue, HeaderValue, InscriptionEntry, InscriptionEntryValue,
+      InscriptionIdValue, InscriptionTransferValue, OutPointValue, RuneEntryValue, RuneIdValue,
+      SatPointValue, SatRange, TxidValue,
+    },
+    event::Event,
+    lot::Lot,
+    reorg::Reorg,
+    updater::Updater,
+    utxo_entry::{ParsedUtxoEntry, UtxoEntry, UtxoEntryBuf},
+  },
+  super::*,
   crate::{
+    brc20v2::{
+      AccountState, IdentityCommitment, Rejection, TokenDefinition, TokenState, TransferRules,
+      VestingCurve, VestingSchedule,
+      merkle::{self, InclusionProof, LeafData, PartialTree},
+    },
+    index::event::EventRecord,
     runes::MintError,
     subcommand::{find::FindRangeOutput, server::query},
     templates::StatusHtml,
@@ -7,18 +28,15 @@ super::*,
   bitcoin::block::Header,
   bitcoincore_rpc::{
     Client,
-    json::{
-      GetBlockHeaderResult, GetBlockStatsResult, GetRawTransactionResult,
-      GetRawTransactionResultVout, GetRawTransactionResultVoutScriptPubKey, GetTxOutResult,
-    },
+    json::{GetBlockHeaderResult, GetBlockStatsResult},
   },
   chrono::SubsecRound,
   indicatif::{ProgressBar, ProgressStyle},
   log::log_enabled,
   redb::{
     Database, DatabaseError, MultimapTable, MultimapTableDefinition, MultimapTableHandle,
-    ReadOnlyTable, ReadableDatabase, ReadableMultimapTable, ReadableTable, ReadableTableMetadata,
-    RepairSession, StorageError, Table, TableDefinition, TableHandle, TableStats, WriteTransaction,
+    ReadableDatabase, ReadableMultimapTable, ReadableTable, ReadableTableMetadata, RepairSession,
+    StorageError, Table, TableDefinition, TableHandle, TableStats, WriteTransaction,
   },
   std::{
     collections::HashMap,
@@ -27,25 +45,44 @@ super::*,
   },
 };
 
-pub use self::entry::RuneEntry;
+pub use self::entry::{Brc20v2TokenEntry, InscriptionTransfer, RuneEntry};
 
+pub mod address_history;
+pub mod backfill;
+mod block_files;
+pub(crate) mod dataset;
 pub(crate) mod entry;
 pub mod event;
 mod fetcher;
 mod lot;
+mod migration;
+pub(crate) mod offer;
 mod reorg;
 mod rtx;
+pub(crate) mod search;
 mod updater;
 mod utxo_entry;
 
 #[cfg(test)]
 pub(crate) mod testing;
 
-const SCHEMA_VERSION: u64 = 31;
+pub(crate) const SCHEMA_VERSION: u64 = 32;
 
+define_multimap_table! { EXPIRY_TO_OFFER_NUMBER, u32, u64 }
+define_multimap_table! { OUTPOINT_TO_OFFER_NUMBER, &OutPointValue, u64 }
 define_multimap_table! { SAT_TO_SEQUENCE_NUMBER, u64, u32 }
 define_multimap_table! { SCRIPT_PUBKEY_TO_OUTPOINT, &[u8], OutPointValue }
+define_multimap_table! { SEARCH_TERM_TO_SEQUENCE_NUMBER, &str, u32 }
 define_multimap_table! { SEQUENCE_NUMBER_TO_CHILDREN, u32, u32 }
+define_table! { BRC20V2_ACCOUNT_TO_STATE, (&str, &[u8; 32]), Brc20v2AccountValue }
+define_table! { BRC20V2_ALLOWANCE_TO_AMOUNT, (&str, &[u8; 32], &[u8; 32]), u128 }
+define_table! { BRC20V2_BRANCH_TO_HASH, (&str, u16, &[u8; 32]), &[u8; 32] }
+define_table! { BRC20V2_COMMITMENT_TO_IDENTITY, &[u8; 32], &str }
+define_table! { BRC20V2_FROZEN_ACCOUNTS, (&str, &[u8; 32]), () }
+define_table! { BRC20V2_INSCRIPTION_ID_TO_REJECTION, InscriptionIdValue, Brc20v2RejectionValue }
+define_table! { BRC20V2_LEAF_TO_HASH, (&str, &[u8; 32]), &[u8; 32] }
+define_table! { BRC20V2_TICKER_TO_TOKEN, &str, Brc20v2TokenEntryValue }
+define_table! { HEIGHT_AND_NUMBER_TO_EVENT, (u32, u32), EventRecordValue }
 define_table! { HEIGHT_TO_BLOCK_HEADER, u32, &HeaderValue }
 define_table! { HEIGHT_TO_LAST_SEQUENCE_NUMBER, u32, u32 }
 define_table! { HOME_INSCRIPTIONS, u32, InscriptionIdValue }
@@ -57,6 +94,8 @@ define_table! { OUTPOINT_TO_UTXO_ENTRY, &OutPointValue, &UtxoEntry }
 define_table! { RUNE_ID_TO_RUNE_ENTRY, RuneIdValue, RuneEntryValue }
 define_table! { RUNE_TO_RUNE_ID, u128, RuneIdValue }
 define_table! { SAT_TO_SATPOINT, u64, &SatPointValue }
+define_table! { SCRIPT_PUBKEY_HEIGHT_AND_TX_INDEX_TO_ACTIVITY, (&[u8], u32, u32), AddressActivityValue }
+define_table! { SEQUENCE_NUMBER_AND_INDEX_TO_TRANSFER, (u32, u32), InscriptionTransferValue }
 define_table! { SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY, u32, InscriptionEntryValue }
 define_table! { SEQUENCE_NUMBER_TO_RUNE_ID, u32, RuneIdValue }
 define_table! { SEQUENCE_NUMBER_TO_SATPOINT, u32, &SatPointValue }
@@ -84,4 +123,3694 @@ pub(crate) enum Statistic {
   SatRanges = 14,
   UnboundInscriptions = 16,
   LastSavepointHeight = 17,
+  IndexBrc20v2 = 18,
+  Brc20v2Attestations = 19,
+  IndexEvents = 20,
+  IndexTransferHistory = 21,
+  RunesBackfillHeight = 22,
+  TransactionsBackfillHeight = 23,
+  IndexAddressHistory = 24,
+  IndexSearch = 25,
+}
+
+impl Statistic {
+  fn key(self) -> u64 {
+    self.into()
+  }
+}
+
+impl From<Statistic> for u64 {
+  fn from(statistic: Statistic) -> Self {
+    statistic as u64
+  }
+}
+
+#[derive(Serialize)]
+pub struct Info {
+  blocks_indexed: u32,
+  branch_pages: u64,
+  fragmented_bytes: u64,
+  index_file_size: u64,
+  index_path: PathBuf,
+  leaf_pages: u64,
+  metadata_bytes: u64,
+  outputs_traversed: u64,
+  page_size: usize,
+  sat_ranges: u64,
+  stored_bytes: u64,
+  tables: BTreeMap<String, TableInfo>,
+  total_bytes: u64,
+  pub transactions: Vec<TransactionInfo>,
+  tree_height: u32,
+  utxos_indexed: u64,
+}
+
+#[derive(Serialize)]
+pub(crate) struct TableInfo {
+  branch_pages: u64,
+  fragmented_bytes: u64,
+  entries: u64,
+  leaf_pages: u64,
+  metadata_bytes: u64,
+  proportion: f64,
+  stored_bytes: u64,
+  total_bytes: u64,
+  tree_height: u32,
+}
+
+impl From<TableStats> for TableInfo {
+  fn from(stats: TableStats) -> Self {
+    Self {
+      branch_pages: stats.branch_pages(),
+      fragmented_bytes: stats.fragmented_bytes(),
+      entries: 0,
+      leaf_pages: stats.leaf_pages(),
+      metadata_bytes: stats.metadata_bytes(),
+      proportion: 0.0,
+      stored_bytes: stats.stored_bytes(),
+      total_bytes: stats.stored_bytes() + stats.metadata_bytes() + stats.fragmented_bytes(),
+      tree_height: stats.tree_height(),
+    }
+  }
+}
+
+#[derive(Serialize)]
+pub struct TransactionInfo {
+  pub starting_block_count: u32,
+  pub starting_timestamp: u128,
+}
+
+pub(crate) trait BitcoinCoreRpcResultExt<T> {
+  fn into_option(self) -> Result<Option<T>>;
+}
+
+impl<T> BitcoinCoreRpcResultExt<T> for Result<T, bitcoincore_rpc::Error> {
+  fn into_option(self) -> Result<Option<T>> {
+    match self {
+      Ok(ok) => Ok(Some(ok)),
+      Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
+        bitcoincore_rpc::jsonrpc::error::RpcError { code: -8, .. },
+      ))) => Ok(None),
+      Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
+        bitcoincore_rpc::jsonrpc::error::RpcError { message, .. },
+      )))
+        if message.ends_with("not found") =>
+      {
+        Ok(None)
+      }
+      Err(err) => Err(err.into()),
+    }
+  }
+}
+
+pub struct Index {
+  pub(crate) client: Client,
+  database: Database,
+  durability: redb::Durability,
+  event_sender: Option<tokio::sync::mpsc::Sender<Event>>,
+  first_index_height: u32,
+  genesis_block_coinbase_transaction: Transaction,
+  genesis_block_coinbase_txid: Txid,
+  height_limit: Option<u32>,
+  index_address_history: bool,
+  index_addresses: bool,
+  index_brc20v2: bool,
+  index_events: bool,
+  index_inscriptions: bool,
+  index_runes: bool,
+  index_sats: bool,
+  index_search: bool,
+  index_transactions: bool,
+  index_transfer_history: bool,
+  path: PathBuf,
+  settings: Settings,
+  started: DateTime<Utc>,
+  unrecoverably_reorged: AtomicBool,
+}
+
+impl Index {
+  pub fn open(settings: &Settings) -> Result<Self> {
+    Index::open_with_event_sender(settings, None)
+  }
+
+  pub fn open_with_event_sender(
+    settings: &Settings,
+    event_sender: Option<tokio::sync::mpsc::Sender<Event>>,
+  ) -> Result<Self> {
+    ensure!(
+      !settings.index_brc20v2_raw() || settings.index_inscriptions_raw(),
+      "`--index-brc20v2` requires inscriptions to be indexed, remove `--no-index-inscriptions`",
+    );
+
+    let client = settings.bitcoin_rpc_client(None)?;
+
+    let path = settings.index().to_owned();
+
+    let data_dir = path.parent().unwrap();
+
+    fs::create_dir_all(data_dir).snafu_context(error::Io { path: data_dir })?;
+
+    let index_cache_size = settings.index_cache_size();
+
+    log::info!("Setting index cache size to {index_cache_size} bytes");
+
+    let durability = if cfg!(test) {
+      redb::Durability::None
+    } else {
+      redb::Durability::Immediate
+    };
+
+    let index_path = path.clone();
+    let once = Once::new();
+    let progress_bar = Mutex::new(None);
+    let integration_test = settings.integration_test();
+
+    let repair_callback = move |progress: &mut RepairSession| {
+      once.call_once(|| {
+        println!(
+          "Index file `{}` needs recovery. This can take a long time, especially for the --index-sats index.",
+          index_path.display()
+        )
+      });
+
+      if !(cfg!(test) || log_enabled!(log::Level::Info) || integration_test) {
+        let mut guard = progress_bar.lock().unwrap();
+
+        let progress_bar = guard.get_or_insert_with(|| {
+          let progress_bar = ProgressBar::new(100);
+          progress_bar.set_style(
+            ProgressStyle::with_template("[repairing database] {wide_bar} {pos}/{len}").unwrap(),
+          );
+          progress_bar
+        });
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        progress_bar.set_position((progress.progress() * 100.0) as u64);
+      }
+    };
+
+    let database = match Database::builder()
+      .set_cache_size(index_cache_size)
+      .set_repair_callback(repair_callback)
+      .open(&path)
+    {
+      Ok(database) => {
+        {
+          let schema_version = database
+            .begin_read()?
+            .open_table(STATISTIC_TO_COUNT)?
+            .get(&Statistic::Schema.key())?
+            .map(|x| x.value())
+            .unwrap_or(0);
+
+          match schema_version.cmp(&SCHEMA_VERSION) {
+            cmp::Ordering::Less => bail!(
+              "index at `{}` appears to have been built with an older version of ord, run `ord index migrate` to migrate it: index schema {schema_version}, ord schema {SCHEMA_VERSION}",
+              path.display()
+            ),
+            cmp::Ordering::Greater => bail!(
+              "index at `{}` appears to have been built with a newer, incompatible version of ord, consider updating ord: index schema {schema_version}, ord schema {SCHEMA_VERSION}",
+              path.display()
+            ),
+            cmp::Ordering::Equal => {}
+          }
+        }
+
+        database
+      }
+      Err(DatabaseError::Storage(StorageError::Io(error)))
+        if error.kind() == io::ErrorKind::NotFound =>
+      {
+        let database = Database::builder()
+          .set_cache_size(index_cache_size)
+          .create(&path)?;
+
+        let mut tx = database.begin_write()?;
+
+        tx.set_durability(durability)?;
+        tx.set_quick_repair(true);
+
+        tx.open_multimap_table(EXPIRY_TO_OFFER_NUMBER)?;
+        tx.open_multimap_table(OUTPOINT_TO_OFFER_NUMBER)?;
+        tx.open_multimap_table(SAT_TO_SEQUENCE_NUMBER)?;
+        tx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
+        tx.open_multimap_table(SEARCH_TERM_TO_SEQUENCE_NUMBER)?;
+        tx.open_multimap_table(SEQUENCE_NUMBER_TO_CHILDREN)?;
+        tx.open_table(BRC20V2_ACCOUNT_TO_STATE)?;
+        tx.open_table(BRC20V2_ALLOWANCE_TO_AMOUNT)?;
+        tx.open_table(BRC20V2_BRANCH_TO_HASH)?;
+        tx.open_table(BRC20V2_COMMITMENT_TO_IDENTITY)?;
+        tx.open_table(BRC20V2_FROZEN_ACCOUNTS)?;
+        tx.open_table(BRC20V2_INSCRIPTION_ID_TO_REJECTION)?;
+        tx.open_table(BRC20V2_LEAF_TO_HASH)?;
+        tx.open_table(BRC20V2_TICKER_TO_TOKEN)?;
+        tx.open_table(HEIGHT_AND_NUMBER_TO_EVENT)?;
+        tx.open_table(HEIGHT_TO_BLOCK_HEADER)?;
+        tx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
+        tx.open_table(HOME_INSCRIPTIONS)?;
+        tx.open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
+        tx.open_table(INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER)?;
+        tx.open_table(NUMBER_TO_OFFER)?;
+        tx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
+        tx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+        tx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+        tx.open_table(RUNE_TO_RUNE_ID)?;
+        tx.open_table(SAT_TO_SATPOINT)?;
+        tx.open_table(SCRIPT_PUBKEY_HEIGHT_AND_TX_INDEX_TO_ACTIVITY)?;
+        tx.open_table(SEQUENCE_NUMBER_AND_INDEX_TO_TRANSFER)?;
+        tx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+        tx.open_table(SEQUENCE_NUMBER_TO_RUNE_ID)?;
+        tx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
+        tx.open_table(TRANSACTION_ID_TO_RUNE)?;
+        tx.open_table(TRANSACTION_ID_TO_TRANSACTION)?;
+        tx.open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?;
+
+        {
+          let mut statistics = tx.open_table(STATISTIC_TO_COUNT)?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexAddressHistory,
+            u64::from(settings.index_address_history_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexAddresses,
+            u64::from(settings.index_addresses_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexBrc20v2,
+            u64::from(settings.index_brc20v2_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexEvents,
+            u64::from(settings.index_events_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexInscriptions,
+            u64::from(settings.index_inscriptions_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexRunes,
+            u64::from(settings.index_runes_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexSats,
+            u64::from(settings.index_sats_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexSearch,
+            u64::from(settings.index_search_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexTransactions,
+            u64::from(settings.index_transactions_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexTransferHistory,
+            u64::from(settings.index_transfer_history_raw()),
+          )?;
+
+          if settings.index_brc20v2_raw() {
+            Self::set_statistic(
+              &mut statistics,
+              Statistic::Brc20v2Attestations,
+              settings.brc20v2_attestations_fingerprint()?,
+            )?;
+          }
+
+          Self::set_statistic(&mut statistics, Statistic::Schema, SCHEMA_VERSION)?;
+        }
+
+        if settings.index_runes_raw() && settings.chain() == Chain::Mainnet {
+          Self::insert_genesis_rune(&tx)?;
+        }
+
+        tx.commit()?;
+
+        database
+      }
+      Err(error) => bail!("failed to open index: {error}"),
+    };
+
+    let index_address_history;
+    let index_addresses;
+    let index_brc20v2;
+    let index_events;
+    let index_inscriptions;
+    let index_runes;
+    let index_sats;
+    let index_search;
+    let index_transactions;
+    let index_transfer_history;
+
+    {
+      let tx = database.begin_read()?;
+      let statistics = tx.open_table(STATISTIC_TO_COUNT)?;
+      index_address_history = Self::is_statistic_set(&statistics, Statistic::IndexAddressHistory)?;
+      index_addresses = Self::is_statistic_set(&statistics, Statistic::IndexAddresses)?;
+      index_brc20v2 = Self::is_statistic_set(&statistics, Statistic::IndexBrc20v2)?;
+      index_events = Self::is_statistic_set(&statistics, Statistic::IndexEvents)?;
+      index_inscriptions = Self::is_statistic_set(&statistics, Statistic::IndexInscriptions)?;
+      index_runes = Self::is_statistic_set(&statistics, Statistic::IndexRunes)?;
+      index_sats = Self::is_statistic_set(&statistics, Statistic::IndexSats)?;
+      index_search = Self::is_statistic_set(&statistics, Statistic::IndexSearch)?;
+      index_transactions = Self::is_statistic_set(&statistics, Statistic::IndexTransactions)?;
+      index_transfer_history =
+        Self::is_statistic_set(&statistics, Statistic::IndexTransferHistory)?;
+
+      if index_brc20v2 {
+        ensure!(
+          statistics
+            .get(&Statistic::Brc20v2Attestations.key())?
+            .map(|fingerprint| fingerprint.value())
+            .unwrap_or_default()
+            == settings.brc20v2_attestations_fingerprint()?,
+          "index at `{}` was built with different BRC-20 v2 attestations, \
+          use the `--brc20v2-attestations` file it was built with or rebuild the index",
+          path.display(),
+        );
+      }
+    }
+
+    let genesis_block_coinbase_transaction =
+      settings.chain().genesis_block().coinbase().unwrap().clone();
+
+    let first_index_height = if index_sats || index_addresses {
+      0
+    } else if index_inscriptions {
+      settings.first_inscription_height()
+    } else if index_runes {
+      settings.first_rune_height()
+    } else {
+      u32::MAX
+    };
+
+    Ok(Self {
+      genesis_block_coinbase_txid: genesis_block_coinbase_transaction.compute_txid(),
+      client,
+      database,
+      durability,
+      event_sender,
+      first_index_height,
+      genesis_block_coinbase_transaction,
+      height_limit: settings.height_limit(),
+      index_address_history,
+      index_addresses,
+      index_brc20v2,
+      index_events,
+      index_inscriptions,
+      index_runes,
+      index_sats,
+      index_search,
+      index_transactions,
+      index_transfer_history,
+      path,
+      settings: settings.clone(),
+      started: Utc::now(),
+      unrecoverably_reorged: AtomicBool::new(false),
+    })
+  }
+
+  pub fn have_full_utxo_index(&self) -> bool {
+    self.first_index_height == 0
+  }
+
+  /// Unlike normal outpoints, which are added to index on creation and removed
+  /// when spent, the UTXO entry for special outpoints may be updated.
+  ///
+  /// The special outpoints are the null outpoint, which receives lost sats,
+  /// and the unbound outpoint, which receives unbound inscriptions.
+  pub fn is_special_outpoint(outpoint: OutPoint) -> bool {
+    outpoint == OutPoint::null() || outpoint == unbound_outpoint()
+  }
+
+  #[cfg(test)]
+  fn set_durability(&mut self, durability: redb::Durability) {
+    self.durability = durability;
+  }
+
+  #[cfg(test)]
+  pub(crate) fn chain(&self) -> Chain {
+    self.settings.chain()
+  }
+
+  pub fn contains_output(&self, output: &OutPoint) -> Result<bool> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(OUTPOINT_TO_UTXO_ENTRY)?
+        .get(&output.store())?
+        .is_some(),
+    )
+  }
+
+  pub fn has_address_index(&self) -> bool {
+    self.index_addresses
+  }
+
+  pub fn has_inscription_index(&self) -> bool {
+    self.index_inscriptions
+  }
+
+  pub fn has_rune_index(&self) -> bool {
+    self.index_runes
+  }
+
+  pub fn has_sat_index(&self) -> bool {
+    self.index_sats
+  }
+
+  pub fn status(&self, json_api: bool) -> Result<StatusHtml> {
+    let rtx = self.database.begin_read()?;
+
+    let statistic_to_count = rtx.open_table(STATISTIC_TO_COUNT)?;
+
+    let statistic = |statistic: Statistic| -> Result<u64> {
+      Ok(
+        statistic_to_count
+          .get(statistic.key())?
+          .map(|guard| guard.value())
+          .unwrap_or_default(),
+      )
+    };
+
+    let height = rtx
+      .open_table(HEIGHT_TO_BLOCK_HEADER)?
+      .range(0..)?
+      .next_back()
+      .transpose()?
+      .map(|(height, _header)| height.value());
+
+    let next_height = height.map(|height| height + 1).unwrap_or(0);
+
+    let blessed_inscriptions = statistic(Statistic::BlessedInscriptions)?;
+    let cursed_inscriptions = statistic(Statistic::CursedInscriptions)?;
+    let initial_sync_time = statistic(Statistic::InitialSyncTime)?;
+
+    Ok(StatusHtml {
+      address_index: self.has_address_index(),
+      blessed_inscriptions,
+      chain: self.settings.chain(),
+      cursed_inscriptions,
+      height,
+      initial_sync_time: Duration::from_micros(initial_sync_time),
+      inscription_index: self.has_inscription_index(),
+      inscriptions: blessed_inscriptions + cursed_inscriptions,
+      json_api,
+      lost_sats: statistic(Statistic::LostSats)?,
+      minimum_rune_for_next_block: Rune::minimum_at_height(
+        self.settings.chain().network(),
+        Height(next_height),
+      ),
+      rune_index: self.has_rune_index(),
+      runes: statistic(Statistic::Runes)?,
+      sat_index: self.has_sat_index(),
+      started: self.started,
+      transaction_index: self.index_transactions,
+      unrecoverably_reorged: self.unrecoverably_reorged.load(atomic::Ordering::Relaxed),
+      uptime: (Utc::now() - self.started).to_std()?,
+    })
+  }
+
+  pub fn info(&self) -> Result<Info> {
+    let stats = self.database.begin_write()?.stats()?;
+
+    let rtx = self.database.begin_read()?;
+
+    let mut tables: BTreeMap<String, TableInfo> = BTreeMap::new();
+
+    for handle in rtx.list_tables()? {
+      let name = handle.name().into();
+      let stats = rtx.open_untyped_table(handle)?.stats()?;
+      tables.insert(name, stats.into());
+    }
+
+    for handle in rtx.list_multimap_tables()? {
+      let name = handle.name().into();
+      let stats = rtx.open_untyped_multimap_table(handle)?.stats()?;
+      tables.insert(name, stats.into());
+    }
+
+    for table in rtx.list_tables()? {
+      assert!(tables.contains_key(table.name()));
+    }
+
+    for table in rtx.list_multimap_tables()? {
+      assert!(tables.contains_key(table.name()));
+    }
+
+    let total_bytes = tables
+      .values()
+      .map(|table_info| table_info.total_bytes)
+      .sum();
+
+    tables.values_mut().for_each(|table_info| {
+      table_info.proportion = table_info.total_bytes as f64 / total_bytes as f64
+    });
+
+    let info = {
+      let statistic_to_count = rtx.open_table(STATISTIC_TO_COUNT)?;
+      let sat_ranges = statistic_to_count
+        .get(&Statistic::SatRanges.key())?
+        .map(|x| x.value())
+        .unwrap_or(0);
+      let outputs_traversed = statistic_to_count
+        .get(&Statistic::OutputsTraversed.key())?
+        .map(|x| x.value())
+        .unwrap_or(0);
+      Info {
+        index_path: self.path.clone(),
+        blocks_indexed: rtx
+          .open_table(HEIGHT_TO_BLOCK_HEADER)?
+          .range(0..)?
+          .next_back()
+          .transpose()?
+          .map(|(height, _header)| height.value() + 1)
+          .unwrap_or(0),
+        branch_pages: stats.branch_pages(),
+        fragmented_bytes: stats.fragmented_bytes(),
+        index_file_size: fs::metadata(&self.path)?.len(),
+        leaf_pages: stats.leaf_pages(),
+        metadata_bytes: stats.metadata_bytes(),
+        sat_ranges,
+        outputs_traversed,
+        page_size: stats.page_size(),
+        stored_bytes: stats.stored_bytes(),
+        total_bytes,
+        tables,
+        transactions: rtx
+          .open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?
+          .range(0..)?
+          .flat_map(|result| {
+            result.map(
+              |(starting_block_count, starting_timestamp)| TransactionInfo {
+                starting_block_count: starting_block_count.value(),
+                starting_timestamp: starting_timestamp.value(),
+              },
+            )
+          })
+          .collect(),
+        tree_height: stats.tree_height(),
+        utxos_indexed: rtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?.len()?,
+      }
+    };
+
+    Ok(info)
+  }
+
+  pub fn update(&self) -> Result {
+    loop {
+      let wtx = self.begin_write()?;
+
+      let mut updater = Updater {
+        height: wtx
+          .open_table(HEIGHT_TO_BLOCK_HEADER)?
+          .range(0..)?
+          .next_back()
+          .transpose()?
+          .map(|(height, _header)| height.value() + 1)
+          .unwrap_or(0),
+        index: self,
+        outputs_cached: 0,
+        outputs_traversed: 0,
+        sat_ranges_since_flush: 0,
+      };
+
+      match updater.update_index(wtx) {
+        Ok(ok) => return Ok(ok),
+        Err(err) => {
+          log::info!("{err}");
+
+          match err.downcast_ref() {
+            Some(&reorg::Error::Recoverable { height, depth }) => {
+              Reorg::handle_reorg(self, height, depth)?;
+            }
+            Some(&reorg::Error::Unrecoverable) => {
+              self
+                .unrecoverably_reorged
+                .store(true, atomic::Ordering::Relaxed);
+              return Err(anyhow!(reorg::Error::Unrecoverable));
+            }
+            _ => return Err(err),
+          };
+        }
+      }
+    }
+  }
+
+  pub fn export(&self, filename: &String, include_addresses: bool) -> Result {
+    let mut writer = BufWriter::new(File::create(filename)?);
+    let rtx = self.database.begin_read()?;
+
+    let blocks_indexed = rtx
+      .open_table(HEIGHT_TO_BLOCK_HEADER)?
+      .range(0..)?
+      .next_back()
+      .transpose()?
+      .map(|(height, _header)| height.value() + 1)
+      .unwrap_or(0);
+
+    writeln!(writer, "# export at block height {blocks_indexed}")?;
+
+    log::info!("exporting database tables to {filename}");
+
+    let sequence_number_to_satpoint = rtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
+
+    for result in rtx
+      .open_table(INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER)?
+      .iter()?
+    {
+      let entry = result?;
+      let sequence_number = entry.1.value();
+      let entry = InscriptionEntry::load(
+        rtx
+          .open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?
+          .get(&sequence_number)?
+          .unwrap()
+          .value(),
+      );
+
+      let satpoint = SatPoint::load(
+        *sequence_number_to_satpoint
+          .get(sequence_number)?
+          .unwrap()
+          .value(),
+      );
+
+      write!(
+        writer,
+        "{}\t{}\t{}",
+        entry.inscription_number, entry.id, satpoint
+      )?;
+
+      if include_addresses {
+        let address = if satpoint.outpoint == unbound_outpoint() {
+          "unbound".to_string()
+        } else {
+          let output = self
+            .get_transaction(satpoint.outpoint.txid)?
+            .unwrap()
+            .output
+            .into_iter()
+            .nth(satpoint.outpoint.vout.try_into().unwrap())
+            .unwrap();
+          self
+            .settings
+            .chain()
+            .address_from_script(&output.script_pubkey)
+            .map(|address| address.to_string())
+            .unwrap_or_else(|e| e.to_string())
+        };
+        write!(writer, "\t{address}")?;
+      }
+      writeln!(writer)?;
+
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        break;
+      }
+    }
+    writer.flush()?;
+    Ok(())
+  }
+
+  fn begin_read(&self) -> Result<rtx::Rtx> {
+    Ok(rtx::Rtx(self.database.begin_read()?))
+  }
+
+  fn begin_write(&self) -> Result<WriteTransaction> {
+    let mut tx = self.database.begin_write()?;
+    tx.set_durability(self.durability)?;
+    tx.set_quick_repair(true);
+    Ok(tx)
+  }
+
+  /// Inserts the mainnet genesis rune, UNCOMMON•GOODS, which is etched by
+  /// the protocol rather than by a transaction, into a new rune index.
+  pub(crate) fn insert_genesis_rune(wtx: &WriteTransaction) -> Result {
+    let rune = Rune(2055900680524219742);
+
+    let id = RuneId { block: 1, tx: 0 };
+    let etching = Txid::all_zeros();
+
+    wtx
+      .open_table(RUNE_TO_RUNE_ID)?
+      .insert(rune.store(), id.store())?;
+
+    Self::set_statistic(
+      &mut wtx.open_table(STATISTIC_TO_COUNT)?,
+      Statistic::Runes,
+      1,
+    )?;
+
+    wtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?.insert(
+      id.store(),
+      RuneEntry {
+        block: id.block,
+        burned: 0,
+        divisibility: 0,
+        etching,
+        terms: Some(Terms {
+          amount: Some(1),
+          cap: Some(u128::MAX),
+          height: (
+            Some((SUBSIDY_HALVING_INTERVAL * 4).into()),
+            Some((SUBSIDY_HALVING_INTERVAL * 5).into()),
+          ),
+          offset: (None, None),
+        }),
+        mints: 0,
+        number: 0,
+        premine: 0,
+        spaced_rune: SpacedRune { rune, spacers: 128 },
+        symbol: Some('\u{29C9}'),
+        timestamp: 0,
+        turbo: true,
+      }
+      .store(),
+    )?;
+
+    wtx
+      .open_table(TRANSACTION_ID_TO_RUNE)?
+      .insert(&etching.store(), rune.store())?;
+
+    Ok(())
+  }
+
+  fn increment_statistic(wtx: &WriteTransaction, statistic: Statistic, n: u64) -> Result {
+    let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
+    let value = statistic_to_count
+      .get(&(statistic.key()))?
+      .map(|x| x.value())
+      .unwrap_or_default()
+      + n;
+    statistic_to_count.insert(&statistic.key(), &value)?;
+    Ok(())
+  }
+
+  pub(crate) fn set_statistic(
+    statistics: &mut Table<u64, u64>,
+    statistic: Statistic,
+    value: u64,
+  ) -> Result<()> {
+    statistics.insert(&statistic.key(), &value)?;
+    Ok(())
+  }
+
+  pub(crate) fn is_statistic_set(
+    statistics: &impl ReadableTable<u64, u64>,
+    statistic: Statistic,
+  ) -> Result<bool> {
+    Ok(
+      statistics
+        .get(&statistic.key())?
+        .map(|guard| guard.value())
+        .unwrap_or_default()
+        != 0,
+    )
+  }
+
+  #[cfg(test)]
+  pub(crate) fn statistic(&self, statistic: Statistic) -> u64 {
+    self
+      .database
+      .begin_read()
+      .unwrap()
+      .open_table(STATISTIC_TO_COUNT)
+      .unwrap()
+      .get(&statistic.key())
+      .unwrap()
+      .map(|x| x.value())
+      .unwrap_or_default()
+  }
+
+  pub fn block_count(&self) -> Result<u32> {
+    self.begin_read()?.block_count()
+  }
+
+  pub fn block_height(&self) -> Result<Option<Height>> {
+    self.begin_read()?.block_height()
+  }
+
+  pub fn block_hash(&self, height: Option<u32>) -> Result<Option<BlockHash>> {
+    self.begin_read()?.block_hash(height)
+  }
+
+  pub fn blocks(&self, take: usize) -> Result<Vec<(u32, BlockHash)>> {
+    let rtx = self.begin_read()?;
+
+    let block_count = rtx.block_count()?;
+
+    let height_to_block_header = rtx.0.open_table(HEIGHT_TO_BLOCK_HEADER)?;
+
+    let mut blocks = Vec::with_capacity(block_count.try_into().unwrap());
+
+    for next in height_to_block_header
+      .range(0..block_count)?
+      .rev()
+      .take(take)
+    {
+      let next = next?;
+      blocks.push((next.0.value(), Header::load(*next.1.value()).block_hash()));
+    }
+
+    Ok(blocks)
+  }
+
+  pub fn rare_sat_satpoints(&self) -> Result<Vec<(Sat, SatPoint)>> {
+    let rtx = self.database.begin_read()?;
+
+    let sat_to_satpoint = rtx.open_table(SAT_TO_SATPOINT)?;
+
+    let mut result = Vec::with_capacity(sat_to_satpoint.len()?.try_into().unwrap());
+
+    for range in sat_to_satpoint.range(0..)? {
+      let (sat, satpoint) = range?;
+      result.push((Sat(sat.value()), Entry::load(*satpoint.value())));
+    }
+
+    Ok(result)
+  }
+
+  pub fn rare_sat_satpoint(&self, sat: Sat) -> Result<Option<SatPoint>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(SAT_TO_SATPOINT)?
+        .get(&sat.n())?
+        .map(|satpoint| Entry::load(*satpoint.value())),
+    )
+  }
+
+  pub fn get_rune_by_id(&self, id: RuneId) -> Result<Option<Rune>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(RUNE_ID_TO_RUNE_ENTRY)?
+        .get(&id.store())?
+        .map(|entry| RuneEntry::load(entry.value()).spaced_rune.rune),
+    )
+  }
+
+  pub fn get_rune_by_number(&self, number: usize) -> Result<Option<Rune>> {
+    match self
+      .database
+      .begin_read()?
+      .open_table(RUNE_ID_TO_RUNE_ENTRY)?
+      .iter()?
+      .nth(number)
+    {
+      Some(result) => {
+        let rune_result =
+          result.map(|(_id, entry)| RuneEntry::load(entry.value()).spaced_rune.rune);
+        Ok(rune_result.ok())
+      }
+      None => Ok(None),
+    }
+  }
+
+  pub fn rune(&self, rune: Rune) -> Result<Option<(RuneId, RuneEntry, Option<InscriptionId>)>> {
+    let rtx = self.database.begin_read()?;
+
+    let Some(id) = rtx
+      .open_table(RUNE_TO_RUNE_ID)?
+      .get(rune.0)?
+      .map(|guard| guard.value())
+    else {
+      return Ok(None);
+    };
+
+    let entry = RuneEntry::load(
+      rtx
+        .open_table(RUNE_ID_TO_RUNE_ENTRY)?
+        .get(id)?
+        .unwrap()
+        .value(),
+    );
+
+    let parent = InscriptionId {
+      txid: entry.etching,
+      index: 0,
+    };
+
+    let parent = rtx
+      .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+      .get(&parent.store())?
+      .is_some()
+      .then_some(parent);
+
+    Ok(Some((RuneId::load(id), entry, parent)))
+  }
+
+  pub fn runes(&self) -> Result<Vec<(RuneId, RuneEntry)>> {
+    let mut entries = Vec::new();
+
+    for result in self
+      .database
+      .begin_read()?
+      .open_table(RUNE_ID_TO_RUNE_ENTRY)?
+      .iter()?
+    {
+      let (id, entry) = result?;
+      entries.push((RuneId::load(id.value()), RuneEntry::load(entry.value())));
+    }
+
+    Ok(entries)
+  }
+
+  pub fn runes_paginated(
+    &self,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<(RuneId, RuneEntry)>, bool)> {
+    let mut entries = Vec::new();
+
+    for result in self
+      .database
+      .begin_read()?
+      .open_table(RUNE_ID_TO_RUNE_ENTRY)?
+      .iter()?
+      .rev()
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+    {
+      let (id, entry) = result?;
+      entries.push((RuneId::load(id.value()), RuneEntry::load(entry.value())));
+    }
+
+    let more = entries.len() > page_size;
+
+    if more {
+      entries.pop();
+    }
+
+    Ok((entries, more))
+  }
+
+  pub fn encode_rune_balance(id: RuneId, balance: u128, buffer: &mut Vec<u8>) {
+    varint::encode_to_vec(id.block.into(), buffer);
+    varint::encode_to_vec(id.tx.into(), buffer);
+    varint::encode_to_vec(balance, buffer);
+  }
+
+  pub fn decode_rune_balance(buffer: &[u8]) -> Result<((RuneId, u128), usize)> {
+    let mut len = 0;
+    let (block, block_len) = varint::decode(&buffer[len..])?;
+    len += block_len;
+    let (tx, tx_len) = varint::decode(&buffer[len..])?;
+    len += tx_len;
+    let id = RuneId {
+      block: block.try_into()?,
+      tx: tx.try_into()?,
+    };
+    let (balance, balance_len) = varint::decode(&buffer[len..])?;
+    len += balance_len;
+    Ok(((id, balance), len))
+  }
+
+  pub fn get_rune_balances_for_output(
+    &self,
+    outpoint: OutPoint,
+  ) -> Result<Option<BTreeMap<SpacedRune, Pile>>> {
+    if !self.index_runes {
+      return Ok(None);
+    }
+
+    let rtx = self.database.begin_read()?;
+
+    let outpoint_to_balances = rtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
+
+    let id_to_rune_entries = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    let Some(balances) = outpoint_to_balances.get(&outpoint.store())? else {
+      return Ok(Some(BTreeMap::new()));
+    };
+
+    let balances_buffer = balances.value();
+
+    let mut balances = BTreeMap::new();
+    let mut i = 0;
+    while i < balances_buffer.len() {
+      let ((id, amount), length) = Index::decode_rune_balance(&balances_buffer[i..]).unwrap();
+      i += length;
+
+      let entry = RuneEntry::load(id_to_rune_entries.get(id.store())?.unwrap().value());
+
+      balances.insert(
+        entry.spaced_rune,
+        Pile {
+          amount,
+          divisibility: entry.divisibility,
+          symbol: entry.symbol,
+        },
+      );
+    }
+
+    Ok(Some(balances))
+  }
+
+  pub fn get_rune_balance_map(&self) -> Result<BTreeMap<SpacedRune, BTreeMap<OutPoint, Pile>>> {
+    let outpoint_balances = self.get_rune_balances()?;
+
+    let rtx = self.database.begin_read()?;
+
+    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    let mut rune_balances_by_id: BTreeMap<RuneId, BTreeMap<OutPoint, u128>> = BTreeMap::new();
+
+    for (outpoint, balances) in outpoint_balances {
+      for (rune_id, amount) in balances {
+        *rune_balances_by_id
+          .entry(rune_id)
+          .or_default()
+          .entry(outpoint)
+          .or_default() += amount;
+      }
+    }
+
+    let mut rune_balances = BTreeMap::new();
+
+    for (rune_id, balances) in rune_balances_by_id {
+      let RuneEntry {
+        divisibility,
+        spaced_rune,
+        symbol,
+        ..
+      } = RuneEntry::load(
+        rune_id_to_rune_entry
+          .get(&rune_id.store())?
+          .unwrap()
+          .value(),
+      );
+
+      rune_balances.insert(
+        spaced_rune,
+        balances
+          .into_iter()
+          .map(|(outpoint, amount)| {
+            (
+              outpoint,
+              Pile {
+                amount,
+                divisibility,
+                symbol,
+              },
+            )
+          })
+          .collect(),
+      );
+    }
+
+    Ok(rune_balances)
+  }
+
+  pub fn get_rune_balances(&self) -> Result<Vec<(OutPoint, Vec<(RuneId, u128)>)>> {
+    let mut result = Vec::new();
+
+    for entry in self
+      .database
+      .begin_read()?
+      .open_table(OUTPOINT_TO_RUNE_BALANCES)?
+      .iter()?
+    {
+      let (outpoint, balances_buffer) = entry?;
+      let outpoint = OutPoint::load(*outpoint.value());
+      let balances_buffer = balances_buffer.value();
+
+      let mut balances = Vec::new();
+      let mut i = 0;
+      while i < balances_buffer.len() {
+        let ((id, balance), length) = Index::decode_rune_balance(&balances_buffer[i..]).unwrap();
+        i += length;
+        balances.push((id, balance));
+      }
+
+      result.push((outpoint, balances));
+    }
+
+    Ok(result)
+  }
+
+  pub fn block_header(&self, hash: BlockHash) -> Result<Option<Header>> {
+    self.client.get_block_header(&hash).into_option()
+  }
+
+  pub fn block_header_info(&self, hash: BlockHash) -> Result<Option<GetBlockHeaderResult>> {
+    self.client.get_block_header_info(&hash).into_option()
+  }
+
+  pub fn block_stats(&self, height: u64) -> Result<Option<GetBlockStatsResult>> {
+    self.client.get_block_stats(height).into_option()
+  }
+
+  pub fn get_block_by_height(&self, height: u32) -> Result<Option<Block>> {
+    Ok(
+      self
+        .client
+        .get_block_hash(height.into())
+        .into_option()?
+        .map(|hash| self.client.get_block(&hash))
+        .transpose()?,
+    )
+  }
+
+  pub fn get_block_by_hash(&self, hash: BlockHash) -> Result<Option<Block>> {
+    self.client.get_block(&hash).into_option()
+  }
+
+  pub fn get_collections_paginated(
+    &self,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let mut collections = rtx
+      .open_multimap_table(SEQUENCE_NUMBER_TO_CHILDREN)?
+      .iter()?
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|result| {
+        result
+          .and_then(|(parent, _children)| {
+            sequence_number_to_inscription_entry
+              .get(parent.value())
+              .map(|entry| InscriptionEntry::load(entry.unwrap().value()).id)
+          })
+          .map_err(|err| err.into())
+      })
+      .collect::<Result<Vec<InscriptionId>>>()?;
+
+    let more = collections.len() > page_size;
+
+    if more {
+      collections.pop();
+    }
+
+    Ok((collections, more))
+  }
+
+  pub fn get_children_by_sequence_number_paginated(
+    &self,
+    sequence_number: u32,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_entry = rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let mut children = rtx
+      .open_multimap_table(SEQUENCE_NUMBER_TO_CHILDREN)?
+      .get(sequence_number)?
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|result| {
+        result
+          .and_then(|sequence_number| {
+            sequence_number_to_entry
+              .get(sequence_number.value())
+              .map(|entry| InscriptionEntry::load(entry.unwrap().value()).id)
+          })
+          .map_err(|err| err.into())
+      })
+      .collect::<Result<Vec<InscriptionId>>>()?;
+
+    let more = children.len() > page_size;
+
+    if more {
+      children.pop();
+    }
+
+    Ok((children, more))
+  }
+
+  pub fn get_parents_by_sequence_number_paginated(
+    &self,
+    parent_sequence_numbers: Vec<u32>,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_entry = rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let mut parents = parent_sequence_numbers
+      .iter()
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|sequence_number| {
+        sequence_number_to_entry
+          .get(sequence_number)
+          .map(|entry| InscriptionEntry::load(entry.unwrap().value()).id)
+          .map_err(|err| err.into())
+      })
+      .collect::<Result<Vec<InscriptionId>>>()?;
+
+    let more_parents = parents.len() > page_size;
+
+    if more_parents {
+      parents.pop();
+    }
+
+    Ok((parents, more_parents))
+  }
+
+  pub fn get_etching(&self, txid: Txid) -> Result<Option<SpacedRune>> {
+    let rtx = self.database.begin_read()?;
+
+    let transaction_id_to_rune = rtx.open_table(TRANSACTION_ID_TO_RUNE)?;
+    let Some(rune) = transaction_id_to_rune.get(&txid.store())? else {
+      return Ok(None);
+    };
+
+    let rune_to_rune_id = rtx.open_table(RUNE_TO_RUNE_ID)?;
+    let id = rune_to_rune_id.get(rune.value())?.unwrap();
+
+    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+    let entry = rune_id_to_rune_entry.get(&id.value())?.unwrap();
+
+    Ok(Some(RuneEntry::load(entry.value()).spaced_rune))
+  }
+
+  pub fn get_inscription_ids_by_sat(&self, sat: Sat) -> Result<Vec<InscriptionId>> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let ids = rtx
+      .open_multimap_table(SAT_TO_SEQUENCE_NUMBER)?
+      .get(&sat.n())?
+      .map(|result| {
+        result
+          .and_then(|sequence_number| {
+            let sequence_number = sequence_number.value();
+            sequence_number_to_inscription_entry
+              .get(sequence_number)
+              .map(|entry| InscriptionEntry::load(entry.unwrap().value()).id)
+          })
+          .map_err(|err| err.into())
+      })
+      .collect::<Result<Vec<InscriptionId>>>()?;
+
+    Ok(ids)
+  }
+
+  pub fn get_inscription_ids_by_sat_paginated(
+    &self,
+    sat: Sat,
+    page_size: u64,
+    page_index: u64,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let mut ids = rtx
+      .open_multimap_table(SAT_TO_SEQUENCE_NUMBER)?
+      .get(&sat.n())?
+      .skip(page_index.saturating_mul(page_size).try_into().unwrap())
+      .take(page_size.saturating_add(1).try_into().unwrap())
+      .map(|result| {
+        result
+          .and_then(|sequence_number| {
+            let sequence_number = sequence_number.value();
+            sequence_number_to_inscription_entry
+              .get(sequence_number)
+              .map(|entry| InscriptionEntry::load(entry.unwrap().value()).id)
+          })
+          .map_err(|err| err.into())
+      })
+      .collect::<Result<Vec<InscriptionId>>>()?;
+
+    let more = ids.len() > page_size.try_into().unwrap();
+
+    if more {
+      ids.pop();
+    }
+
+    Ok((ids, more))
+  }
+
+  pub fn get_inscription_id_by_sat_indexed(
+    &self,
+    sat: Sat,
+    inscription_index: isize,
+  ) -> Result<Option<InscriptionId>> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let sat_to_sequence_number = rtx.open_multimap_table(SAT_TO_SEQUENCE_NUMBER)?;
+
+    if inscription_index < 0 {
+      sat_to_sequence_number
+        .get(&sat.n())?
+        .nth_back((inscription_index + 1).unsigned_abs())
+    } else {
+      sat_to_sequence_number
+        .get(&sat.n())?
+        .nth(inscription_index.unsigned_abs())
+    }
+    .map(|result| {
+      result
+        .and_then(|sequence_number| {
+          let sequence_number = sequence_number.value();
+          sequence_number_to_inscription_entry
+            .get(sequence_number)
+            .map(|entry| InscriptionEntry::load(entry.unwrap().value()).id)
+        })
+        .map_err(|err| anyhow!(err.to_string()))
+    })
+    .transpose()
+  }
+
+  pub fn get_inscription_satpoint_by_id(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<SatPoint>> {
+    let rtx = self.database.begin_read()?;
+
+    let Some(sequence_number) = rtx
+      .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+      .get(&inscription_id.store())?
+      .map(|guard| guard.value())
+    else {
+      return Ok(None);
+    };
+
+    let satpoint = rtx
+      .open_table(SEQUENCE_NUMBER_TO_SATPOINT)?
+      .get(sequence_number)?
+      .map(|satpoint| Entry::load(*satpoint.value()));
+
+    Ok(satpoint)
+  }
+
+  pub fn get_inscription_by_id(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<Inscription>> {
+    if !self.inscription_exists(inscription_id)? {
+      return Ok(None);
+    }
+
+    Ok(self.get_transaction(inscription_id.txid)?.and_then(|tx| {
+      ParsedEnvelope::from_transaction(&tx)
+        .into_iter()
+        .nth(inscription_id.index as usize)
+        .map(|envelope| envelope.payload)
+    }))
+  }
+
+  pub fn inscription_count(&self, txid: Txid) -> Result<u32> {
+    let start = InscriptionId { index: 0, txid };
+
+    let end = InscriptionId {
+      index: u32::MAX,
+      txid,
+    };
+
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+        .range::<&InscriptionIdValue>(&start.store()..&end.store())?
+        .count()
+        .try_into()?,
+    )
+  }
+
+  pub fn inscription_exists(&self, inscription_id: InscriptionId) -> Result<bool> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+        .get(&inscription_id.store())?
+        .is_some(),
+    )
+  }
+
+  pub fn get_inscriptions_on_output_with_satpoints(
+    &self,
+    outpoint: OutPoint,
+  ) -> Result<Option<Vec<(SatPoint, InscriptionId)>>> {
+    if !self.index_inscriptions {
+      return Ok(None);
+    }
+
+    let rtx = self.database.begin_read()?;
+    let outpoint_to_utxo_entry = rtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    self.inscriptions_on_output(
+      &outpoint_to_utxo_entry,
+      &sequence_number_to_inscription_entry,
+      outpoint,
+    )
+  }
+
+  pub fn get_inscriptions_for_output(
+    &self,
+    outpoint: OutPoint,
+  ) -> Result<Option<Vec<InscriptionId>>> {
+    let Some(inscriptions) = self.get_inscriptions_on_output_with_satpoints(outpoint)? else {
+      return Ok(None);
+    };
+
+    Ok(Some(
+      inscriptions
+        .iter()
+        .map(|(_satpoint, inscription_id)| *inscription_id)
+        .collect(),
+    ))
+  }
+
+  pub fn get_inscriptions_for_outputs(
+    &self,
+    outpoints: &Vec<OutPoint>,
+  ) -> Result<Option<Vec<InscriptionId>>> {
+    let mut result = Vec::new();
+    for outpoint in outpoints {
+      let Some(inscriptions) = self.get_inscriptions_on_output_with_satpoints(*outpoint)? else {
+        return Ok(None);
+      };
+
+      result.extend(
+        inscriptions
+          .iter()
+          .map(|(_satpoint, inscription_id)| *inscription_id),
+      );
+    }
+
+    Ok(Some(result))
+  }
+
+  pub fn get_transaction(&self, txid: Txid) -> Result<Option<Transaction>> {
+    if txid == self.genesis_block_coinbase_txid {
+      return Ok(Some(self.genesis_block_coinbase_transaction.clone()));
+    }
+
+    if self.index_transactions
+      && let Some(transaction) = self
+        .database
+        .begin_read()?
+        .open_table(TRANSACTION_ID_TO_TRANSACTION)?
+        .get(&txid.store())?
+    {
+      return Ok(Some(consensus::encode::deserialize(transaction.value())?));
+    }
+
+    self.client.get_raw_transaction(&txid, None).into_option()
+  }
+
+  pub fn get_transaction_hex_recursive(&self, txid: Txid) -> Result<Option<String>> {
+    if txid == self.genesis_block_coinbase_txid {
+      return Ok(Some(consensus::encode::serialize_hex(
+        &self.genesis_block_coinbase_transaction,
+      )));
+    }
+
+    self
+      .client
+      .get_raw_transaction_hex(&txid, None)
+      .into_option()
+  }
+
+  pub fn find(&self, sat: Sat) -> Result<Option<SatPoint>> {
+    let sat = sat.0;
+    let rtx = self.begin_read()?;
+
+    if rtx.block_count()? <= Sat(sat).height().n() {
+      return Ok(None);
+    }
+
+    let outpoint_to_utxo_entry = rtx.0.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+
+    for entry in outpoint_to_utxo_entry.iter()? {
+      let (outpoint, utxo_entry) = entry?;
+      let sat_ranges = utxo_entry.value().parse(self).sat_ranges();
+
+      let mut offset = 0;
+      for chunk in sat_ranges.chunks_exact(11) {
+        let (start, end) = SatRange::load(chunk.try_into().unwrap());
+        if start <= sat && sat < end {
+          return Ok(Some(SatPoint {
+            outpoint: Entry::load(*outpoint.value()),
+            offset: offset + sat - start,
+          }));
+        }
+        offset += end - start;
+      }
+    }
+
+    Ok(None)
+  }
+
+  pub fn find_range(
+    &self,
+    range_start: Sat,
+    range_end: Sat,
+  ) -> Result<Option<Vec<FindRangeOutput>>> {
+    let range_start = range_start.0;
+    let range_end = range_end.0;
+    let rtx = self.begin_read()?;
+
+    if rtx.block_count()? < Sat(range_end - 1).height().n() + 1 {
+      return Ok(None);
+    }
+
+    let Some(mut remaining_sats) = range_end.checked_sub(range_start) else {
+      return Err(anyhow!("range end is before range start"));
+    };
+
+    let outpoint_to_utxo_entry = rtx.0.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+
+    let mut result = Vec::new();
+    for entry in outpoint_to_utxo_entry.iter()? {
+      let (outpoint, utxo_entry) = entry?;
+      let sat_ranges = utxo_entry.value().parse(self).sat_ranges();
+
+      let mut offset = 0;
+      for sat_range in sat_ranges.chunks_exact(11) {
+        let (start, end) = SatRange::load(sat_range.try_into().unwrap());
+
+        if end > range_start && start < range_end {
+          let overlap_start = start.max(range_start);
+          let overlap_end = end.min(range_end);
+
+          result.push(FindRangeOutput {
+            start: overlap_start,
+            size: overlap_end - overlap_start,
+            satpoint: SatPoint {
+              outpoint: Entry::load(*outpoint.value()),
+              offset: offset + overlap_start - start,
+            },
+          });
+
+          remaining_sats -= overlap_end - overlap_start;
+
+          if remaining_sats == 0 {
+            break;
+          }
+        }
+        offset += end - start;
+      }
+    }
+
+    Ok(Some(result))
+  }
+
+  pub fn list(&self, outpoint: OutPoint) -> Result<Option<Vec<(u64, u64)>>> {
+    if !self.index_sats {
+      return Ok(None);
+    }
+
+    let sat_ranges = self.list_inner(outpoint.store())?;
+
+    let Some(sat_ranges) = sat_ranges else {
+      return Ok(None);
+    };
+
+    Ok(Some(
+      sat_ranges
+        .chunks_exact(11)
+        .map(|chunk| SatRange::load(chunk.try_into().unwrap()))
+        .collect::<Vec<(u64, u64)>>(),
+    ))
+  }
+
+  fn list_inner(&self, outpoint: OutPointValue) -> Result<Option<Vec<u8>>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(OUTPOINT_TO_UTXO_ENTRY)?
+        .get(&outpoint)?
+        .map(|utxo_entry| utxo_entry.value().parse(self).sat_ranges().to_vec()),
+    )
+  }
+
+  pub fn is_output_spent(&self, outpoint: OutPoint) -> Result<bool> {
+    Ok(
+      outpoint != OutPoint::null()
+        && outpoint != self.settings.chain().genesis_coinbase_outpoint()
+        && if self.have_full_utxo_index() {
+          self
+            .database
+            .begin_read()?
+            .open_table(OUTPOINT_TO_UTXO_ENTRY)?
+            .get(&outpoint.store())?
+            .is_none()
+        } else {
+          self
+            .client
+            .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
+            .is_none()
+        },
+    )
+  }
+
+  pub fn is_output_in_active_chain(&self, outpoint: OutPoint) -> Result<bool> {
+    if outpoint == OutPoint::null() {
+      return Ok(true);
+    }
+
+    if outpoint == self.settings.chain().genesis_coinbase_outpoint() {
+      return Ok(true);
+    }
+
+    let Some(info) = self
+      .client
+      .get_raw_transaction_info(&outpoint.txid, None)
+      .into_option()?
+    else {
+      return Ok(false);
+    };
+
+    if info.blockhash.is_none() {
+      return Ok(false);
+    }
+
+    if outpoint.vout.into_usize() >= info.vout.len() {
+      return Ok(false);
+    }
+
+    Ok(true)
+  }
+
+  pub fn block_time(&self, height: Height) -> Result<Blocktime> {
+    let height = height.n();
+
+    let rtx = self.database.begin_read()?;
+
+    let height_to_block_header = rtx.open_table(HEIGHT_TO_BLOCK_HEADER)?;
+
+    if let Some(guard) = height_to_block_header.get(height)? {
+      return Ok(Blocktime::confirmed(Header::load(*guard.value()).time));
+    }
+
+    let current = height_to_block_header
+      .range(0..)?
+      .next_back()
+      .transpose()?
+      .map(|(height, _header)| height)
+      .map(|x| x.value())
+      .unwrap_or(0);
+
+    let expected_blocks = height
+      .checked_sub(current)
+      .with_context(|| format!("current {current} height is greater than sat height {height}"))?;
+
+    Ok(Blocktime::Expected(
+      if self.settings.chain() == Chain::Regtest {
+        DateTime::default()
+      } else {
+        Utc::now()
+      }
+      .round_subsecs(0)
+      .checked_add_signed(
+        chrono::Duration::try_seconds(10 * 60 * i64::from(expected_blocks))
+          .context("timestamp out of range")?,
+      )
+      .context("timestamp out of range")?,
+    ))
+  }
+
+  pub fn get_inscriptions_paginated(
+    &self,
+    page_size: u32,
+    page_index: u32,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let last = sequence_number_to_inscription_entry
+      .iter()?
+      .next_back()
+      .map(|result| result.map(|(number, _entry)| number.value()))
+      .transpose()?
+      .unwrap_or_default();
+
+    let start = last.saturating_sub(page_size.saturating_mul(page_index));
+
+    let end = start.saturating_sub(page_size);
+
+    let mut inscriptions = sequence_number_to_inscription_entry
+      .range(end..=start)?
+      .rev()
+      .map(|result| result.map(|(_number, entry)| InscriptionEntry::load(entry.value()).id))
+      .collect::<Result<Vec<InscriptionId>, StorageError>>()?;
+
+    let more = u32::try_from(inscriptions.len()).unwrap_or(u32::MAX) > page_size;
+
+    if more {
+      inscriptions.pop();
+    }
+
+    Ok((inscriptions, more))
+  }
+
+  pub fn get_inscriptions_in_block(&self, block_height: u32) -> Result<Vec<InscriptionId>> {
+    let rtx = self.database.begin_read()?;
+
+    let height_to_last_sequence_number = rtx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let Some(newest_sequence_number) = height_to_last_sequence_number
+      .get(&block_height)?
+      .map(|ag| ag.value())
+    else {
+      return Ok(Vec::new());
+    };
+
+    let oldest_sequence_number = height_to_last_sequence_number
+      .get(block_height.saturating_sub(1))?
+      .map(|ag| ag.value())
+      .unwrap_or(0);
+
+    (oldest_sequence_number..newest_sequence_number)
+      .map(|num| match sequence_number_to_inscription_entry.get(&num) {
+        Ok(Some(inscription_id)) => Ok(InscriptionEntry::load(inscription_id.value()).id),
+        Ok(None) => Err(anyhow!(
+          "could not find inscription for inscription number {num}"
+        )),
+        Err(err) => Err(anyhow!(err)),
+      })
+      .collect::<Result<Vec<InscriptionId>>>()
+  }
+
+  pub fn get_runes_in_block(&self, block_height: u64) -> Result<Vec<SpacedRune>> {
+    let rtx = self.database.begin_read()?;
+
+    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    let min_id = RuneId {
+      block: block_height,
+      tx: 0,
+    };
+
+    let max_id = RuneId {
+      block: block_height,
+      tx: u32::MAX,
+    };
+
+    let runes = rune_id_to_rune_entry
+      .range(min_id.store()..=max_id.store())?
+      .map(|result| result.map(|(_, entry)| RuneEntry::load(entry.value()).spaced_rune))
+      .collect::<Result<Vec<SpacedRune>, StorageError>>()?;
+
+    Ok(runes)
+  }
+
+  pub fn get_highest_paying_inscriptions_in_block(
+    &self,
+    block_height: u32,
+    n: usize,
+  ) -> Result<(Vec<InscriptionId>, usize)> {
+    let inscription_ids = self.get_inscriptions_in_block(block_height)?;
+
+    let mut inscription_to_fee: Vec<(InscriptionId, u64)> = Vec::new();
+    for id in &inscription_ids {
+      inscription_to_fee.push((
+        *id,
+        self
+          .get_inscription_entry(*id)?
+          .ok_or_else(|| anyhow!("could not get entry for inscription {id}"))?
+          .fee,
+      ));
+    }
+
+    inscription_to_fee.sort_by_key(|(_, fee)| *fee);
+
+    Ok((
+      inscription_to_fee
+        .iter()
+        .map(|(id, _)| *id)
+        .rev()
+        .take(n)
+        .collect(),
+      inscription_ids.len(),
+    ))
+  }
+
+  pub fn get_home_inscriptions(&self) -> Result<Vec<InscriptionId>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(HOME_INSCRIPTIONS)?
+        .iter()?
+        .rev()
+        .flat_map(|result| result.map(|(_number, id)| InscriptionId::load(id.value())))
+        .collect(),
+    )
+  }
+
+  pub fn get_feed_inscriptions(&self, n: usize) -> Result<Vec<(i32, InscriptionId)>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?
+        .iter()?
+        .rev()
+        .take(n)
+        .flat_map(|result| {
+          result.map(|(_number, entry)| {
+            let entry = InscriptionEntry::load(entry.value());
+            (entry.inscription_number, entry.id)
+          })
+        })
+        .collect(),
+    )
+  }
+
+  pub(crate) fn inscription_info(
+    &self,
+    query: query::Inscription,
+    child: Option<usize>,
+  ) -> Result<Option<(api::Inscription, Option<TxOut>, Inscription)>> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number = match query {
+      query::Inscription::Id(id) => rtx
+        .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+        .get(&id.store())?
+        .map(|guard| guard.value()),
+      query::Inscription::Number(inscription_number) => rtx
+        .open_table(INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER)?
+        .get(inscription_number)?
+        .map(|guard| guard.value()),
+      query::Inscription::Sat(sat) => rtx
+        .open_multimap_table(SAT_TO_SEQUENCE_NUMBER)?
+        .get(sat.n())?
+        .next()
+        .transpose()?
+        .map(|guard| guard.value()),
+    };
+
+    let Some(sequence_number) = sequence_number else {
+      return Ok(None);
+    };
+
+    let sequence_number = if let Some(child) = child {
+      let Some(child) = rtx
+        .open_multimap_table(SEQUENCE_NUMBER_TO_CHILDREN)?
+        .get(sequence_number)?
+        .nth(child)
+        .transpose()?
+        .map(|child| child.value())
+      else {
+        return Ok(None);
+      };
+
+      child
+    } else {
+      sequence_number
+    };
+
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let entry = InscriptionEntry::load(
+      sequence_number_to_inscription_entry
+        .get(&sequence_number)?
+        .unwrap()
+        .value(),
+    );
+
+    let Some(transaction) = self.get_transaction(entry.id.txid)? else {
+      return Ok(None);
+    };
+
+    let Some(inscription) = ParsedEnvelope::from_transaction(&transaction)
+      .into_iter()
+      .nth(entry.id.index as usize)
+      .map(|envelope| envelope.payload)
+    else {
+      return Ok(None);
+    };
+
+    let satpoint = SatPoint::load(
+      *rtx
+        .open_table(SEQUENCE_NUMBER_TO_SATPOINT)?
+        .get(sequence_number)?
+        .unwrap()
+        .value(),
+    );
+
+    let Some(output) =
+      (if satpoint.outpoint == unbound_outpoint() || satpoint.outpoint == OutPoint::null() {
+        Some(None)
+      } else {
+        let Some(transaction) = self.get_transaction(satpoint.outpoint.txid)? else {
+          return Ok(None);
+        };
+
+        transaction
+          .output
+          .into_iter()
+          .nth(satpoint.outpoint.vout.try_into().unwrap())
+          .map(Some)
+      })
+    else {
+      return Ok(None);
+    };
+
+    let previous = if let Some(n) = sequence_number.checked_sub(1) {
+      Some(
+        InscriptionEntry::load(
+          sequence_number_to_inscription_entry
+            .get(n)?
+            .unwrap()
+            .value(),
+        )
+        .id,
+      )
+    } else {
+      None
+    };
+
+    let next = sequence_number_to_inscription_entry
+      .get(sequence_number + 1)?
+      .map(|guard| InscriptionEntry::load(guard.value()).id);
+
+    let all_children = rtx
+      .open_multimap_table(SEQUENCE_NUMBER_TO_CHILDREN)?
+      .get(sequence_number)?;
+
+    let child_count = all_children.len();
+
+    let children = all_children
+      .take(4)
+      .map(|result| {
+        result
+          .and_then(|sequence_number| {
+            sequence_number_to_inscription_entry
+              .get(sequence_number.value())
+              .map(|entry| InscriptionEntry::load(entry.unwrap().value()).id)
+          })
+          .map_err(|err| err.into())
+      })
+      .collect::<Result<Vec<InscriptionId>>>()?;
+
+    let rune = if let Some(rune_id) = rtx
+      .open_table(SEQUENCE_NUMBER_TO_RUNE_ID)?
+      .get(sequence_number)?
+    {
+      let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+      let entry = rune_id_to_rune_entry.get(&rune_id.value())?.unwrap();
+      Some(RuneEntry::load(entry.value()).spaced_rune)
+    } else {
+      None
+    };
+
+    let parents = entry
+      .parents
+      .iter()
+      .take(4)
+      .map(|parent| {
+        Ok(
+          InscriptionEntry::load(
+            sequence_number_to_inscription_entry
+              .get(parent)?
+              .unwrap()
+              .value(),
+          )
+          .id,
+        )
+      })
+      .collect::<Result<Vec<InscriptionId>>>()?;
+
+    let mut charms = entry.charms;
+
+    if satpoint.outpoint == OutPoint::null() {
+      Charm::Lost.set(&mut charms);
+    }
+
+    let effective_mime_type = if let Some(delegate_id) = inscription.delegate() {
+      let delegate_result = self.get_inscription_by_id(delegate_id);
+      if let Ok(Some(delegate)) = delegate_result {
+        delegate.content_type().map(str::to_string)
+      } else {
+        inscription.content_type().map(str::to_string)
+      }
+    } else {
+      inscription.content_type().map(str::to_string)
+    };
+
+    Ok(Some((
+      api::Inscription {
+        address: output
+          .as_ref()
+          .and_then(|o| {
+            self
+              .settings
+              .chain()
+              .address_from_script(&o.script_pubkey)
+              .ok()
+          })
+          .map(|address| address.to_string()),
+        charms: Charm::charms(charms),
+        child_count,
+        children,
+        content_length: inscription.content_length(),
+        content_type: inscription.content_type().map(|s| s.to_string()),
+        effective_content_type: effective_mime_type,
+        fee: entry.fee,
+        height: entry.height,
+        id: entry.id,
+        metaprotocol: inscription.metaprotocol().map(|s| s.to_string()),
+        next,
+        number: entry.inscription_number,
+        parents,
+        previous,
+        properties: inscription.properties(),
+        rune,
+        sat: entry.sat,
+        satpoint,
+        timestamp: timestamp(entry.timestamp.into()).timestamp(),
+        value: output.as_ref().map(|o| o.value.to_sat()),
+      },
+      output,
+      inscription,
+    )))
+  }
+
+  pub fn get_inscription_entry(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<InscriptionEntry>> {
+    let rtx = self.database.begin_read()?;
+
+    let Some(sequence_number) = rtx
+      .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+      .get(&inscription_id.store())?
+      .map(|guard| guard.value())
+    else {
+      return Ok(None);
+    };
+
+    let entry = rtx
+      .open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?
+      .get(sequence_number)?
+      .map(|value| InscriptionEntry::load(value.value()));
+
+    Ok(entry)
+  }
+
+  fn inscriptions_on_output<'a: 'tx, 'tx>(
+    &self,
+    outpoint_to_utxo_entry: &'a impl ReadableTable<&'static OutPointValue, &'static UtxoEntry>,
+    sequence_number_to_inscription_entry: &'a impl ReadableTable<u32, InscriptionEntryValue>,
+    outpoint: OutPoint,
+  ) -> Result<Option<Vec<(SatPoint, InscriptionId)>>> {
+    if !self.index_inscriptions {
+      return Ok(None);
+    }
+
+    let Some(utxo_entry) = outpoint_to_utxo_entry.get(&outpoint.store())? else {
+      return Ok(Some(Vec::new()));
+    };
+
+    let mut inscriptions = utxo_entry.value().parse(self).parse_inscriptions();
+
+    inscriptions.sort_by_key(|(sequence_number, _)| *sequence_number);
+
+    inscriptions
+      .into_iter()
+      .map(|(sequence_number, offset)| {
+        let entry = sequence_number_to_inscription_entry
+          .get(sequence_number)?
+          .unwrap();
+
+        let satpoint = SatPoint { outpoint, offset };
+
+        Ok((satpoint, InscriptionEntry::load(entry.value()).id))
+      })
+      .collect::<Result<_>>()
+      .map(Some)
+  }
+
+  pub fn get_address_info(&self, address: &Address) -> Result<Vec<OutPoint>> {
+    self
+      .database
+      .begin_read()?
+      .open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?
+      .get(address.script_pubkey().as_bytes())?
+      .map(|result| {
+        result
+          .map(|outpoint| OutPoint::load(outpoint.value()))
+          .map_err(|err| anyhow!(err))
+      })
+      .collect()
+  }
+
+  pub fn get_offers(&self) -> Result<Vec<Vec<u8>>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(NUMBER_TO_OFFER)?
+        .iter()?
+        .map(|result| result.map(|(_number, offer)| offer.value().to_vec()))
+        .collect::<Result<Vec<Vec<u8>>, StorageError>>()?,
+    )
+  }
+
+  pub(crate) fn get_aggregated_rune_balances_for_outputs(
+    &self,
+    outputs: &Vec<OutPoint>,
+  ) -> Result<Option<Vec<(SpacedRune, Decimal, Option<char>)>>> {
+    let mut runes = BTreeMap::new();
+
+    for output in outputs {
+      let Some(rune_balances) = self.get_rune_balances_for_output(*output)? else {
+        return Ok(None);
+      };
+
+      for (spaced_rune, pile) in rune_balances {
+        runes
+          .entry(spaced_rune)
+          .and_modify(|(decimal, _symbol): &mut (Decimal, Option<char>)| {
+            assert_eq!(decimal.scale, pile.divisibility);
+            decimal.value += pile.amount;
+          })
+          .or_insert((
+            Decimal {
+              value: pile.amount,
+              scale: pile.divisibility,
+            },
+            pile.symbol,
+          ));
+      }
+    }
+
+    Ok(Some(
+      runes
+        .into_iter()
+        .map(|(spaced_rune, (decimal, symbol))| (spaced_rune, decimal, symbol))
+        .collect(),
+    ))
+  }
+
+  pub(crate) fn get_sat_balances_for_outputs(&self, outputs: &Vec<OutPoint>) -> Result<u64> {
+    let outpoint_to_utxo_entry = self
+      .database
+      .begin_read()?
+      .open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+
+    let mut acc = 0;
+    for output in outputs {
+      if let Some(utxo_entry) = outpoint_to_utxo_entry.get(&output.store())? {
+        acc += utxo_entry.value().parse(self).total_value();
+      };
+    }
+
+    Ok(acc)
+  }
+
+  pub(crate) fn get_utxo_recursive(
+    &self,
+    outpoint: OutPoint,
+  ) -> Result<Option<api::UtxoRecursive>> {
+    let Some(utxo_entry) = self
+      .database
+      .begin_read()?
+      .open_table(OUTPOINT_TO_UTXO_ENTRY)?
+      .get(&outpoint.store())?
+    else {
+      return Ok(None);
+    };
+
+    Ok(Some(api::UtxoRecursive {
+      inscriptions: self.get_inscriptions_for_output(outpoint)?,
+      runes: self.get_rune_balances_for_output(outpoint)?,
+      sat_ranges: self.list(outpoint)?,
+      value: utxo_entry.value().parse(self).total_value(),
+    }))
+  }
+
+  pub(crate) fn get_output_info(&self, outpoint: OutPoint) -> Result<Option<(api::Output, TxOut)>> {
+    let sat_ranges = self.list(outpoint)?;
+
+    let confirmations;
+    let indexed;
+
+    let txout = if outpoint == OutPoint::null() || outpoint == unbound_outpoint() {
+      let mut value = 0;
+
+      if let Some(ranges) = &sat_ranges {
+        for (start, end) in ranges {
+          value += end - start;
+        }
+      }
+
+      confirmations = 0;
+      indexed = true;
+
+      TxOut {
+        value: Amount::from_sat(value),
+        script_pubkey: ScriptBuf::new(),
+      }
+    } else {
+      indexed = self.contains_output(&outpoint)?;
+
+      if outpoint.txid == self.genesis_block_coinbase_txid {
+        let Some(txout) = self
+          .genesis_block_coinbase_transaction
+          .output
+          .get(outpoint.vout.into_usize())
+        else {
+          return Ok(None);
+        };
+
+        confirmations = self.block_count()?;
+
+        txout.clone()
+      } else {
+        let Some(info) = self
+          .client
+          .get_raw_transaction_info(&outpoint.txid, None)
+          .into_option()?
+        else {
+          return Ok(None);
+        };
+
+        let Some(vout) = info.vout.into_iter().nth(outpoint.vout.into_usize()) else {
+          return Ok(None);
+        };
+
+        confirmations = info.confirmations.unwrap_or_default();
+
+        TxOut {
+          value: vout.value,
+          script_pubkey: vout.script_pub_key.hex.into(),
+        }
+      }
+    };
+
+    let inscriptions = self.get_inscriptions_for_output(outpoint)?;
+
+    let runes = self.get_rune_balances_for_output(outpoint)?;
+
+    let spent = self.is_output_spent(outpoint)?;
+
+    Ok(Some((
+      api::Output::new(
+        self.settings.chain(),
+        confirmations,
+        inscriptions,
+        outpoint,
+        txout.clone(),
+        indexed,
+        runes,
+        sat_ranges,
+        spent,
+      ),
+      txout,
+    )))
+  }
+}
+
+/// A BRC-20 v2 Merkle tree kept in the index: the tree of the accounts of
+/// the token whose ticker is `namespace`, or, if `namespace` is empty, which
+/// no ticker can be, the tree of the state hashes of every token.
+pub(crate) struct Brc20v2Tree<'a, B, L> {
+  pub(crate) branch_to_hash: &'a B,
+  pub(crate) leaf_to_hash: &'a L,
+  pub(crate) namespace: &'a str,
+}
+
+impl<B, L> merkle::Store for Brc20v2Tree<'_, B, L>
+where
+  B: ReadableTable<(&'static str, u16, &'static [u8; 32]), &'static [u8; 32]>,
+  L: ReadableTable<(&'static str, &'static [u8; 32]), &'static [u8; 32]>,
+{
+  type Error = StorageError;
+
+  fn leaves(
+    &self,
+    start: &[u8; 32],
+    end: &[u8; 32],
+  ) -> Result<Vec<([u8; 32], [u8; 32])>, StorageError> {
+    self
+      .leaf_to_hash
+      .range((self.namespace, start)..=(self.namespace, end))?
+      .take(2)
+      .map(|result| result.map(|(key, leaf)| (*key.value().1, *leaf.value())))
+      .collect()
+  }
+
+  fn branch(&self, height: u16, prefix: &[u8; 32]) -> Result<[u8; 32], StorageError> {
+    Ok(
+      *self
+        .branch_to_hash
+        .get((self.namespace, height, prefix))?
+        .unwrap()
+        .value(),
+    )
+  }
+}
+
+impl Index {
+  pub fn has_brc20v2_index(&self) -> bool {
+    self.index_inscriptions && self.index_brc20v2
+  }
+
+  pub fn get_brc20v2_token(&self, ticker: &str) -> Result<Option<TokenState>> {
+    let rtx = self.database.begin_read()?;
+
+    Self::load_brc20v2_token(
+      ticker,
+      &rtx.open_table(BRC20V2_TICKER_TO_TOKEN)?,
+      &rtx.open_table(BRC20V2_ACCOUNT_TO_STATE)?,
+      &rtx.open_table(BRC20V2_ALLOWANCE_TO_AMOUNT)?,
+      &rtx.open_table(BRC20V2_COMMITMENT_TO_IDENTITY)?,
+      &rtx.open_table(BRC20V2_FROZEN_ACCOUNTS)?,
+    )
+  }
+
+  pub fn get_brc20v2_account(
+    &self,
+    ticker: &str,
+    identity: &IdentityCommitment,
+  ) -> Result<Option<AccountState>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(BRC20V2_ACCOUNT_TO_STATE)?
+        .get((ticker, &identity.commitment))?
+        .map(|entry| AccountState::load(entry.value())),
+    )
+  }
+
+  /// Summary of token `ticker`, read from its entry and the root of its
+  /// stored tree without loading its accounts.
+  pub fn get_brc20v2_token_info(&self, ticker: &str) -> Result<Option<api::Brc20v2Token>> {
+    let rtx = self.database.begin_read()?;
+
+    let Some(entry) = rtx.open_table(BRC20V2_TICKER_TO_TOKEN)?.get(ticker)? else {
+      return Ok(None);
+    };
+
+    let entry = Brc20v2TokenEntry::load(entry.value());
+
+    let merkle_root = PartialTree::new(
+      &Brc20v2Tree {
+        branch_to_hash: &rtx.open_table(BRC20V2_BRANCH_TO_HASH)?,
+        leaf_to_hash: &rtx.open_table(BRC20V2_LEAF_TO_HASH)?,
+        namespace: ticker,
+      },
+      merkle::DEPTH,
+    )?
+    .root();
+
+    Ok(Some(api::Brc20v2Token {
+      definition: entry.definition(ticker.into()),
+      holders: entry.holders.try_into().unwrap(),
+      merkle_root,
+      total_supply: entry.total_supply,
+    }))
+  }
+
+  /// Accounts of token `ticker` with a balance, in order of commitment.
+  pub fn get_brc20v2_holders_paginated(
+    &self,
+    ticker: &str,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<api::Brc20v2Holder>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let commitment_to_identity = rtx.open_table(BRC20V2_COMMITMENT_TO_IDENTITY)?;
+
+    let mut holders = Vec::new();
+
+    for result in rtx
+      .open_table(BRC20V2_ACCOUNT_TO_STATE)?
+      .range((ticker, &[0; 32])..=(ticker, &[u8::MAX; 32]))?
+      .filter(|result| {
+        result
+          .as_ref()
+          .map_or(true, |(_, account)| account.value().0 > 0)
+      })
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+    {
+      let (key, account) = result?;
+      let (_ticker, commitment) = key.value();
+
+      let id = commitment_to_identity
+        .get(commitment)?
+        .map(|id| id.value().to_string())
+        .unwrap_or_default();
+
+      holders.push(api::Brc20v2Holder::new(
+        &IdentityCommitment::with_commitment(id, *commitment),
+        &AccountState::load(account.value()),
+      ));
+    }
+
+    let more = holders.len() > page_size;
+
+    if more {
+      holders.pop();
+    }
+
+    Ok((holders, more))
+  }
+
+  /// Prove that `identity`'s account in token `ticker` is, or is not,
+  /// committed to by the root of the token's stored tree, which is returned
+  /// along with the proof.
+  pub fn get_brc20v2_inclusion_proof(
+    &self,
+    ticker: &str,
+    identity: &IdentityCommitment,
+  ) -> Result<Option<(InclusionProof, [u8; 32])>> {
+    let rtx = self.database.begin_read()?;
+
+    if rtx
+      .open_table(BRC20V2_TICKER_TO_TOKEN)?
+      .get(ticker)?
+      .is_none()
+    {
+      return Ok(None);
+    }
+
+    let store = Brc20v2Tree {
+      branch_to_hash: &rtx.open_table(BRC20V2_BRANCH_TO_HASH)?,
+      leaf_to_hash: &rtx.open_table(BRC20V2_LEAF_TO_HASH)?,
+      namespace: ticker,
+    };
+
+    let mut tree = PartialTree::new(&store, merkle::DEPTH)?;
+
+    tree.load(&store, &identity.commitment)?;
+
+    let leaf = rtx
+      .open_table(BRC20V2_ACCOUNT_TO_STATE)?
+      .get((ticker, &identity.commitment))?
+      .map(|account| LeafData::from(&AccountState::load(account.value())));
+
+    Ok(Some((
+      InclusionProof {
+        commitment: identity.commitment,
+        leaf,
+        proof: tree.prove(&identity.commitment),
+      },
+      tree.root(),
+    )))
+  }
+
+  /// Why the BRC-20 v2 operation in inscription `id` was not applied, if it
+  /// was rejected.
+  pub fn get_brc20v2_rejection(&self, id: InscriptionId) -> Result<Option<Rejection>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(BRC20V2_INSCRIPTION_ID_TO_REJECTION)?
+        .get(&id.store())?
+        .map(|rejection| Rejection::load(rejection.value())),
+    )
+  }
+
+  pub fn get_brc20v2_tokens_paginated(
+    &self,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<(String, Brc20v2TokenEntry)>, bool)> {
+    let mut entries = self
+      .database
+      .begin_read()?
+      .open_table(BRC20V2_TICKER_TO_TOKEN)?
+      .iter()?
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|result| {
+        result.map(|(ticker, entry)| {
+          (
+            ticker.value().to_string(),
+            Brc20v2TokenEntry::load(entry.value()),
+          )
+        })
+      })
+      .collect::<Result<Vec<(String, Brc20v2TokenEntry)>, StorageError>>()?;
+
+    let more = entries.len() > page_size;
+
+    if more {
+      entries.pop();
+    }
+
+    Ok((entries, more))
+  }
+
+  pub fn get_brc20v2_balances(
+    &self,
+    identity: &IdentityCommitment,
+  ) -> Result<Vec<(TokenDefinition, AccountState)>> {
+    let rtx = self.database.begin_read()?;
+
+    let account_to_state = rtx.open_table(BRC20V2_ACCOUNT_TO_STATE)?;
+
+    let mut balances = Vec::new();
+
+    for result in rtx.open_table(BRC20V2_TICKER_TO_TOKEN)?.iter()? {
+      let (ticker, entry) = result?;
+      let ticker = ticker.value();
+
+      if let Some(account) = account_to_state.get((ticker, &identity.commitment))? {
+        balances.push((
+          Brc20v2TokenEntry::load(entry.value()).definition(ticker.into()),
+          AccountState::load(account.value()),
+        ));
+      }
+    }
+
+    Ok(balances)
+  }
+
+  /// The root committing to every BRC-20 v2 token, with the height and hash
+  /// of the block it was read at, or `None` if no blocks have been indexed.
+  pub fn get_brc20v2_state_root(&self) -> Result<Option<(u32, BlockHash, [u8; 32])>> {
+    let rtx = self.begin_read()?;
+
+    let (Some(height), Some(block_hash)) = (rtx.block_height()?, rtx.block_hash(None)?) else {
+      return Ok(None);
+    };
+
+    let root = PartialTree::new(
+      &Brc20v2Tree {
+        branch_to_hash: &rtx.0.open_table(BRC20V2_BRANCH_TO_HASH)?,
+        leaf_to_hash: &rtx.0.open_table(BRC20V2_LEAF_TO_HASH)?,
+        namespace: "",
+      },
+      merkle::DEPTH,
+    )?
+    .root();
+
+    Ok(Some((height.n(), block_hash, root)))
+  }
+
+  pub fn has_event_index(&self) -> bool {
+    self.index_events
+  }
+
+  /// Logged events of blocks `from_height` through `to_height`, in the order
+  /// they were emitted.
+  pub fn get_events(&self, from_height: u32, to_height: u32) -> Result<Vec<EventRecord>> {
+    if from_height > to_height {
+      return Ok(Vec::new());
+    }
+
+    self
+      .database
+      .begin_read()?
+      .open_table(HEIGHT_AND_NUMBER_TO_EVENT)?
+      .range((from_height, 0)..=(to_height, u32::MAX))?
+      .map(|result| {
+        let (_, record) = result?;
+        Ok(EventRecord::load(record.value()))
+      })
+      .collect()
+  }
+
+  /// Logged events of blocks from `from_height` on, and the block count of
+  /// the index they were read from, read in a single transaction so that the
+  /// events of every block below the block count are included.
+  pub(crate) fn get_events_since(&self, from_height: u32) -> Result<(Vec<EventRecord>, u32)> {
+    let rtx = self.begin_read()?;
+
+    let events = rtx
+      .0
+      .open_table(HEIGHT_AND_NUMBER_TO_EVENT)?
+      .range((from_height, 0)..)?
+      .map(|result| {
+        let (_, record) = result?;
+        Ok(EventRecord::load(record.value()))
+      })
+      .collect::<Result<Vec<EventRecord>>>()?;
+
+    Ok((events, rtx.block_count()?))
+  }
+
+  pub fn get_events_paginated(
+    &self,
+    height: u32,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<EventRecord>, bool)> {
+    let mut events = self
+      .database
+      .begin_read()?
+      .open_table(HEIGHT_AND_NUMBER_TO_EVENT)?
+      .range((height, 0)..=(height, u32::MAX))?
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|result| {
+        let (_, record) = result?;
+        Ok(EventRecord::load(record.value()))
+      })
+      .collect::<Result<Vec<EventRecord>>>()?;
+
+    let more = events.len() > page_size;
+
+    if more {
+      events.pop();
+    }
+
+    Ok((events, more))
+  }
+
+  pub fn has_transaction_index(&self) -> bool {
+    self.index_transactions
+  }
+
+  pub fn has_transfer_history_index(&self) -> bool {
+    self.index_transfer_history
+  }
+
+  /// Every location an inscription has been at, starting with the one it was
+  /// created at, or `None` if there is no such inscription.
+  pub fn get_inscription_transfers(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<Vec<InscriptionTransfer>>> {
+    let rtx = self.database.begin_read()?;
+
+    let Some(sequence_number) = rtx
+      .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+      .get(&inscription_id.store())?
+      .map(|sequence_number| sequence_number.value())
+    else {
+      return Ok(None);
+    };
+
+    Ok(Some(
+      rtx
+        .open_table(SEQUENCE_NUMBER_AND_INDEX_TO_TRANSFER)?
+        .range((sequence_number, 0)..=(sequence_number, u32::MAX))?
+        .map(|result| result.map(|(_, transfer)| InscriptionTransfer::load(transfer.value())))
+        .collect::<Result<Vec<InscriptionTransfer>, StorageError>>()?,
+    ))
+  }
+
+  pub(crate) fn load_brc20v2_token(
+    ticker: &str,
+    ticker_to_token: &impl ReadableTable<&'static str, Brc20v2TokenEntryValue>,
+    account_to_state: &impl ReadableTable<(&'static str, &'static [u8; 32]), Brc20v2AccountValue>,
+    allowance_to_amount: &impl ReadableTable<(&'static str, &'static [u8; 32], &'static [u8; 32]), u128>,
+    commitment_to_identity: &impl ReadableTable<&'static [u8; 32], &'static str>,
+    frozen_accounts: &impl ReadableTable<(&'static str, &'static [u8; 32]), ()>,
+  ) -> Result<Option<TokenState>> {
+    let Some(entry) = ticker_to_token.get(ticker)? else {
+      return Ok(None);
+    };
+
+    let identity = |commitment: &[u8; 32]| -> Result<IdentityCommitment> {
+      let id = commitment_to_identity
+        .get(commitment)?
+        .map(|id| id.value().to_string())
+        .unwrap_or_default();
+
+      Ok(IdentityCommitment::with_commitment(id, *commitment))
+    };
+
+    let mut accounts = BTreeMap::new();
+
+    for result in account_to_state.range((ticker, &[0; 32])..=(ticker, &[u8::MAX; 32]))? {
+      let (key, value) = result?;
+      let (_ticker, commitment) = key.value();
+
+      accounts.insert(identity(commitment)?, AccountState::load(value.value()));
+    }
+
+    let entry = Brc20v2TokenEntry::load(entry.value());
+
+    Self::load_brc20v2_roles(
+      TokenState::with_accounts(
+        entry.definition(ticker.into()),
+        entry.total_supply,
+        accounts,
+      ),
+      entry.admin,
+      allowance_to_amount,
+      commitment_to_identity,
+      frozen_accounts,
+    )
+    .map(Some)
+  }
+
+  /// Add the admin of `token`, and the allowances and freezes it has granted,
+  /// which are needed to apply operations to it.
+  pub(crate) fn load_brc20v2_roles(
+    mut token: TokenState,
+    admin: Option<[u8; 32]>,
+    allowance_to_amount: &impl ReadableTable<(&'static str, &'static [u8; 32], &'static [u8; 32]), u128>,
+    commitment_to_identity: &impl ReadableTable<&'static [u8; 32], &'static str>,
+    frozen_accounts: &impl ReadableTable<(&'static str, &'static [u8; 32]), ()>,
+  ) -> Result<TokenState> {
+    let ticker = token.definition.ticker.clone();
+    let ticker = ticker.as_str();
+
+    let identity = |commitment: &[u8; 32]| -> Result<IdentityCommitment> {
+      let id = commitment_to_identity
+        .get(commitment)?
+        .map(|id| id.value().to_string())
+        .unwrap_or_default();
+
+      Ok(IdentityCommitment::with_commitment(id, *commitment))
+    };
+
+    token.admin = admin.as_ref().map(identity).transpose()?;
+
+    for result in allowance_to_amount
+      .range((ticker, &[0; 32], &[0; 32])..=(ticker, &[u8::MAX; 32], &[u8::MAX; 32]))?
+    {
+      let (key, amount) = result?;
+      let (_ticker, owner, spender) = key.value();
+
+      token
+        .allowances
+        .insert((identity(owner)?, identity(spender)?), amount.value());
+    }
+
+    for result in frozen_accounts.range((ticker, &[0; 32])..=(ticker, &[u8::MAX; 32]))? {
+      let (key, _frozen) = result?;
+      let (_ticker, commitment) = key.value();
+
+      token.frozen.insert(identity(commitment)?);
+    }
+
+    Ok(token)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::{
+      brc20v2::{Brc20Inscription, Operation},
+      index::testing::Context,
+    },
+  };
+
+  fn brc20v2_witness(operation: Operation) -> Witness {
+    inscription(
+      "application/json",
+      Brc20Inscription::from_operation(&operation, 8, [0; 32]).body,
+    )
+    .to_witness()
+  }
+
+  fn deploy(ticker: &str) -> Operation {
+    Operation::Deploy {
+      definition: TokenDefinition {
+        ticker: ticker.into(),
+        max_supply: 21_000,
+        mint_limit: 1000,
+        decimals: 8,
+        soulbound: false,
+        transfer_rules: Default::default(),
+      },
+    }
+  }
+
+  fn mint(ticker: &str, to: &str, amount: u128) -> Operation {
+    Operation::Mint {
+      ticker: ticker.into(),
+      to: IdentityCommitment::new(to),
+      amount,
+    }
+  }
+
+  #[test]
+  fn brc20v2_operations_are_indexed() {
+    let context = Context::builder().arg("--index-brc20v2").build();
+
+    context.mine_blocks(3);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, brc20v2_witness(deploy("ordi")))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, brc20v2_witness(mint("ordi", "alice", 1000)))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 0, 0, brc20v2_witness(mint("ordi", "bob", 1001)))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let token = context.index.get_brc20v2_token("ordi").unwrap().unwrap();
+
+    assert_eq!(token.total_supply, 1000);
+    assert_eq!(token.definition.max_supply, 21_000);
+    assert_eq!(
+      token.accounts,
+      [(
+        IdentityCommitment::new("alice"),
+        AccountState {
+          balance: 1000,
+          ..default()
+        },
+      )]
+      .into_iter()
+      .collect(),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_brc20v2_account("ordi", &IdentityCommitment::new("alice"))
+        .unwrap()
+        .unwrap()
+        .balance,
+      1000,
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_brc20v2_account("ordi", &IdentityCommitment::new("bob"))
+        .unwrap(),
+      None,
+    );
+  }
+
+  #[test]
+  fn brc20v2_operations_are_owned_by_the_output_they_are_revealed_to() {
+    let context = Context::builder().arg("--index-brc20v2").build();
+
+    context.mine_blocks(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        Inscription {
+          content_type: Some("application/json".into()),
+          body: Some(Brc20Inscription::from_operation(&deploy("ordi"), 8, [0; 32]).body),
+          pointer: Some(Inscription::pointer_value(25 * COIN_VALUE)),
+          ..default()
+        }
+        .to_witness(),
+      )],
+      outputs: 2,
+      p2tr: true,
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let admin = Address::from_script(
+      &context.core.tx_by_id(txid).output[1].script_pubkey,
+      Network::Regtest,
+    )
+    .unwrap();
+
+    assert_eq!(
+      context
+        .index
+        .get_brc20v2_token("ordi")
+        .unwrap()
+        .unwrap()
+        .admin,
+      Some(IdentityCommitment::new(admin.to_string())),
+    );
+  }
+
+  #[test]
+  fn brc20v2_transfers_require_configured_identity() {
+    use crate::brc20v2::identity::{AttestationList, Attestations};
+
+    let keypair = secp256k1::Keypair::from_secret_key(
+      &Secp256k1::new(),
+      &secp256k1::SecretKey::from_slice(&[1; 32]).unwrap(),
+    );
+
+    let address = |n| {
+      Address::from_script(
+        &ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([n; 20])),
+        Network::Regtest,
+      )
+      .unwrap()
+    };
+
+    let alice = address(1);
+    let bob = address(2);
+
+    let tempdir = TempDir::new().unwrap();
+
+    let attestations = tempdir.path().join("attestations.json");
+
+    fs::write(
+      &attestations,
+      serde_json::to_string(&Attestations {
+        issuer: keypair.x_only_public_key().0.to_string(),
+        lists: vec![AttestationList::sign(vec![alice.to_string()], &keypair)],
+      })
+      .unwrap(),
+    )
+    .unwrap();
+
+    let context = Context::builder()
+      .arg("--index-brc20v2")
+      .arg(format!("--brc20v2-attestations={}", attestations.display()))
+      .tempdir(tempdir)
+      .build();
+
+    context.mine_blocks(6);
+
+    let transfer = |from: &Address| Operation::Transfer {
+      ticker: "ordi".into(),
+      from: IdentityCommitment::new(from.to_string()),
+      to: IdentityCommitment::new("carol"),
+      amount: 10,
+      proof: None,
+    };
+
+    let Operation::Deploy { mut definition } = deploy("ordi") else {
+      unreachable!()
+    };
+
+    definition.transfer_rules = TransferRules {
+      require_identity: true,
+    };
+
+    // transfers are revealed to their sender, who is their owner
+    let operations = [
+      (Operation::Deploy { definition }, None),
+      (mint("ordi", &alice.to_string(), 100), None),
+      (mint("ordi", &bob.to_string(), 100), None),
+      (transfer(&alice), Some(alice.clone())),
+      (transfer(&bob), Some(bob.clone())),
+    ];
+
+    for (i, (operation, recipient)) in operations.into_iter().enumerate() {
+      context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(i + 1, 0, 0, brc20v2_witness(operation))],
+        recipient,
+        ..default()
+      });
+
+      context.mine_blocks(1);
+    }
+
+    let token = context.index.get_brc20v2_token("ordi").unwrap().unwrap();
+
+    let balance = |address: &Address| {
+      token
+        .account(&IdentityCommitment::new(address.to_string()))
+        .balance
+    };
+
+    assert!(token.definition.transfer_rules.require_identity);
+    assert_eq!(balance(&alice), 90);
+    assert_eq!(balance(&bob), 100);
+    assert_eq!(token.account(&IdentityCommitment::new("carol")).balance, 10);
+  }
+
+  #[test]
+  fn brc20v2_tokens_are_paginated_by_ticker() {
+    let context = Context::builder().arg("--index-brc20v2").build();
+
+    context.mine_blocks(3);
+
+    for (i, ticker) in ["pepe", "ordi", "sats"].into_iter().enumerate() {
+      context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(i + 1, 0, 0, brc20v2_witness(deploy(ticker)))],
+        ..default()
+      });
+
+      context.mine_blocks(1);
+    }
+
+    let tickers = |page_index| {
+      let (entries, more) = context
+        .index
+        .get_brc20v2_tokens_paginated(2, page_index)
+        .unwrap();
+
+      (
+        entries
+          .into_iter()
+          .map(|(ticker, _)| ticker)
+          .collect::<Vec<String>>(),
+        more,
+      )
+    };
+
+    assert_eq!(tickers(0), (vec!["ordi".into(), "pepe".into()], true));
+    assert_eq!(tickers(1), (vec!["sats".into()], false));
+    assert_eq!(tickers(2), (Vec::new(), false));
+  }
+
+  #[test]
+  fn brc20v2_balances_span_tokens() {
+    let context = Context::builder().arg("--index-brc20v2").build();
+
+    context.mine_blocks(5);
+
+    for (i, operation) in [
+      deploy("ordi"),
+      deploy("pepe"),
+      mint("ordi", "alice", 10),
+      mint("pepe", "alice", 20),
+      mint("pepe", "bob", 30),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+      context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(i + 1, 0, 0, brc20v2_witness(operation))],
+        ..default()
+      });
+
+      context.mine_blocks(1);
+    }
+
+    assert_eq!(
+      context
+        .index
+        .get_brc20v2_balances(&IdentityCommitment::new("alice"))
+        .unwrap()
+        .into_iter()
+        .map(|(definition, account)| (definition.ticker, account.balance))
+        .collect::<Vec<(String, u128)>>(),
+      [("ordi".into(), 10), ("pepe".into(), 20)],
+    );
+
+    assert!(
+      context
+        .index
+        .get_brc20v2_balances(&IdentityCommitment::new("carol"))
+        .unwrap()
+        .is_empty()
+    );
+  }
+
+  #[test]
+  fn brc20v2_state_root_commits_to_indexed_tokens() {
+    let context = Context::builder().arg("--index-brc20v2").build();
+
+    context.mine_blocks(1);
+
+    let (height, block_hash, root) = context.index.get_brc20v2_state_root().unwrap().unwrap();
+
+    assert_eq!(height, 1);
+    assert_eq!(block_hash, context.index.block_hash(None).unwrap().unwrap());
+    assert_eq!(root, brc20v2::state_root([]));
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, brc20v2_witness(deploy("ordi")))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let (height, _, root) = context.index.get_brc20v2_state_root().unwrap().unwrap();
+
+    assert_eq!(height, 2);
+    assert_eq!(
+      root,
+      brc20v2::state_root([&context.index.get_brc20v2_token("ordi").unwrap().unwrap()]),
+    );
+  }
+
+  #[test]
+  fn brc20v2_token_reads_use_stored_trees() {
+    let context = Context::builder().arg("--index-brc20v2").build();
+
+    context.mine_blocks(3);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, brc20v2_witness(deploy("ordi")))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, brc20v2_witness(mint("ordi", "alice", 1000)))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 0, 0, brc20v2_witness(mint("ordi", "bob", 500)))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let token = context.index.get_brc20v2_token("ordi").unwrap().unwrap();
+
+    let info = context
+      .index
+      .get_brc20v2_token_info("ordi")
+      .unwrap()
+      .unwrap();
+
+    assert_eq!(info.definition, token.definition);
+    assert_eq!(info.holders, 2);
+    assert_eq!(info.merkle_root, token.merkle_root());
+    assert_eq!(info.total_supply, 1500);
+
+    for identity in ["alice", "bob", "carol"] {
+      let identity = IdentityCommitment::new(identity);
+
+      assert_eq!(
+        context
+          .index
+          .get_brc20v2_inclusion_proof("ordi", &identity)
+          .unwrap()
+          .unwrap(),
+        (token.inclusion_proof(&identity), token.merkle_root()),
+      );
+    }
+
+    let mut holders = token
+      .accounts
+      .iter()
+      .map(|(identity, account)| api::Brc20v2Holder::new(identity, account))
+      .collect::<Vec<api::Brc20v2Holder>>();
+
+    holders.sort_by_key(|holder| holder.commitment);
+
+    assert_eq!(
+      context
+        .index
+        .get_brc20v2_holders_paginated("ordi", 1, 0)
+        .unwrap(),
+      (vec![holders.remove(0)], true),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_brc20v2_holders_paginated("ordi", 1, 1)
+        .unwrap(),
+      (holders, false),
+    );
+
+    assert_eq!(context.index.get_brc20v2_token_info("sats").unwrap(), None);
+    assert_eq!(
+      context
+        .index
+        .get_brc20v2_inclusion_proof("sats", &IdentityCommitment::new("alice"))
+        .unwrap(),
+      None,
+    );
+  }
+
+  #[test]
+  fn brc20v2_admin_is_revealing_address() {
+    let context = Context::builder().arg("--index-brc20v2").build();
+
+    context.mine_blocks(3);
+
+    let admin = IdentityCommitment::new(
+      context
+        .index
+        .settings
+        .chain()
+        .address_from_script(&ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::all_zeros()))
+        .unwrap()
+        .to_string(),
+    );
+
+    let freeze = |frozen| Operation::Freeze {
+      ticker: "ordi".into(),
+      account: IdentityCommitment::new("alice"),
+      frozen,
+    };
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, brc20v2_witness(deploy("ordi")))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, brc20v2_witness(freeze(true)))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let token = context.index.get_brc20v2_token("ordi").unwrap().unwrap();
+
+    assert_eq!(token.admin, Some(admin.clone()));
+    assert!(token.frozen.contains(&IdentityCommitment::new("alice")));
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 0, 0, brc20v2_witness(freeze(false)))],
+      p2tr: true,
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let token = context.index.get_brc20v2_token("ordi").unwrap().unwrap();
+
+    assert!(token.frozen.contains(&IdentityCommitment::new("alice")));
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(4, 0, 0, brc20v2_witness(freeze(false)))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let token = context.index.get_brc20v2_token("ordi").unwrap().unwrap();
+
+    assert_eq!(token.admin, Some(admin));
+    assert!(token.frozen.is_empty());
+  }
+
+  #[test]
+  fn brc20v2_rejections_are_recorded_by_inscription() {
+    let context = Context::builder().arg("--index-brc20v2").build();
+
+    context.mine_blocks(3);
+
+    let raw = |body: &str| inscription("application/json", body).to_witness();
+
+    let invalid_ticker = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        raw(r#"{"p":"brc-20-v2","op":"deploy","tick":"ORDI","body":{"max":1,"lim":1,"dec":0}}"#),
+      )],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let undeployed = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, brc20v2_witness(mint("ordi", "alice", 10)))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let other_protocol = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        3,
+        0,
+        0,
+        raw(r#"{"p":"brc-20","op":"deploy","tick":"ordi"}"#),
+      )],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let rejection = |txid| {
+      context
+        .index
+        .get_brc20v2_rejection(InscriptionId { txid, index: 0 })
+        .unwrap()
+    };
+
+    assert_eq!(
+      rejection(invalid_ticker),
+      Some(Rejection::InvalidTicker {
+        ticker: "ORDI".into(),
+      }),
+    );
+
+    assert_eq!(
+      rejection(undeployed),
+      Some(Rejection::Rule {
+        message: "token ordi not found".into(),
+      }),
+    );
+
+    assert_eq!(rejection(other_protocol), None);
+  }
+
+  #[test]
+  fn brc20v2_operations_are_not_indexed_without_flag() {
+    let context = Context::builder().build();
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, brc20v2_witness(deploy("ordi")))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(context.index.get_brc20v2_token("ordi").unwrap(), None);
+  }
+
+  #[test]
+  fn brc20v2_index_requires_inscription_index() {
+    assert!(
+      Context::builder()
+        .arg("--index-brc20v2")
+        .build()
+        .index
+        .has_brc20v2_index()
+    );
+
+    let Err(err) = Context::builder()
+      .args(["--index-brc20v2", "--no-index-inscriptions"])
+      .try_build()
+    else {
+      panic!("index opened without inscriptions to index BRC-20 v2 operations from");
+    };
+
+    assert_eq!(
+      err.to_string(),
+      "`--index-brc20v2` requires inscriptions to be indexed, remove `--no-index-inscriptions`",
+    );
+  }
+
+  #[test]
+  fn brc20v2_index_flag_is_read_from_the_index() {
+    let Context { index, tempdir, .. } = Context::builder().build();
+
+    drop(index);
+
+    let context = Context::builder()
+      .arg("--index-brc20v2")
+      .tempdir(tempdir)
+      .build();
+
+    assert!(!context.index.has_brc20v2_index());
+  }
+
+  #[test]
+  fn brc20v2_index_requires_the_attestations_it_was_built_with() {
+    use crate::brc20v2::identity::{AttestationList, Attestations};
+
+    let keypair = secp256k1::Keypair::from_secret_key(
+      &Secp256k1::new(),
+      &secp256k1::SecretKey::from_slice(&[1; 32]).unwrap(),
+    );
+
+    let tempdir = TempDir::new().unwrap();
+
+    let attestations = tempdir.path().join("attestations.json");
+
+    fs::write(
+      &attestations,
+      serde_json::to_string(&Attestations {
+        issuer: keypair.x_only_public_key().0.to_string(),
+        lists: vec![AttestationList::sign(vec!["alice".into()], &keypair)],
+      })
+      .unwrap(),
+    )
+    .unwrap();
+
+    let Context { index, tempdir, .. } = Context::builder()
+      .arg("--index-brc20v2")
+      .arg(format!("--brc20v2-attestations={}", attestations.display()))
+      .tempdir(tempdir)
+      .build();
+
+    drop(index);
+
+    let Context { index, tempdir, .. } = Context::builder()
+      .arg("--index-brc20v2")
+      .arg(format!("--brc20v2-attestations={}", attestations.display()))
+      .tempdir(tempdir)
+      .build();
+
+    drop(index);
+
+    let Err(err) = Context::builder()
+      .arg("--index-brc20v2")
+      .tempdir(tempdir)
+      .try_build()
+    else {
+      panic!("index opened without the attestations it was built with");
+    };
+
+    assert!(
+      err
+        .to_string()
+        .contains("was built with different BRC-20 v2 attestations"),
+      "{err}",
+    );
+  }
+
+  #[test]
+  fn brc20v2_mint_before_deploy_is_ignored() {
+    let context = Context::builder().arg("--index-brc20v2").build();
+
+    context.mine_blocks(2);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, brc20v2_witness(mint("ordi", "alice", 10)))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, brc20v2_witness(deploy("ordi")))],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let token = context.index.get_brc20v2_token("ordi").unwrap().unwrap();
+
+    assert_eq!(token.total_supply, 0);
+    assert!(token.accounts.is_empty());
+  }
+
+  #[test]
+  fn event_index_flag_is_read_from_the_index() {
+    let Context { index, tempdir, .. } = Context::builder().build();
+
+    drop(index);
+
+    let context = Context::builder()
+      .arg("--index-events")
+      .tempdir(tempdir)
+      .build();
+
+    assert!(!context.index.has_event_index());
+  }
+
+  #[test]
+  fn events_are_logged_in_order() {
+    let context = Context::builder().arg("--index-events").build();
+
+    context.mine_blocks(1);
+
+    let first = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let second = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Witness::new())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let events = context.index.get_events(0, 3).unwrap();
+
+    assert_eq!(events.len(), 2);
+
+    assert_eq!(events[0].number, 0);
+    assert_eq!(events[0].tx_index, 1);
+    assert_eq!(events[0].event.block_height(), 2);
+    assert_eq!(
+      events[0].event.inscription_id(),
+      Some(InscriptionId {
+        txid: first,
+        index: 0,
+      }),
+    );
+
+    let Event::InscriptionTransferred { new_location, .. } = events[1].event else {
+      panic!("unexpected event: {:?}", events[1].event);
+    };
+
+    assert_eq!(events[1].number, 0);
+    assert_eq!(events[1].tx_index, 1);
+    assert_eq!(events[1].event.block_height(), 3);
+    assert_eq!(new_location.outpoint.txid, second);
+
+    assert_eq!(context.index.get_events(3, 3).unwrap(), &events[1..]);
+    assert!(context.index.get_events(4, 10).unwrap().is_empty());
+
+    assert_eq!(
+      context.index.get_events_paginated(2, 1, 0).unwrap(),
+      (events[..1].to_vec(), false),
+    );
+  }
+
+  #[test]
+  fn transfer_history_index_flag_is_read_from_the_index() {
+    let Context { index, tempdir, .. } = Context::builder().build();
+
+    drop(index);
+
+    let context = Context::builder()
+      .arg("--index-transfer-history")
+      .tempdir(tempdir)
+      .build();
+
+    assert!(!context.index.has_transfer_history_index());
+  }
+
+  #[test]
+  fn inscription_transfers_are_recorded() {
+    let context = Context::builder().arg("--index-transfer-history").build();
+
+    context.mine_blocks(1);
+
+    let create = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let transfer = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Witness::new())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let inscription_id = InscriptionId {
+      txid: create,
+      index: 0,
+    };
+
+    let created_at = SatPoint {
+      outpoint: OutPoint {
+        txid: create,
+        vout: 0,
+      },
+      offset: 0,
+    };
+
+    assert_eq!(
+      context
+        .index
+        .get_inscription_transfers(inscription_id)
+        .unwrap()
+        .unwrap(),
+      [
+        InscriptionTransfer {
+          height: 2,
+          new_satpoint: created_at,
+          old_satpoint: None,
+          txid: create,
+        },
+        InscriptionTransfer {
+          height: 3,
+          new_satpoint: SatPoint {
+            outpoint: OutPoint {
+              txid: transfer,
+              vout: 0,
+            },
+            offset: 0,
+          },
+          old_satpoint: Some(created_at),
+          txid: transfer,
+        },
+      ],
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_inscription_transfers(InscriptionId {
+          txid: transfer,
+          index: 0,
+        })
+        .unwrap(),
+      None,
+    );
+  }
+
+  #[test]
+  fn event_log_is_rolled_back_by_reorg() {
+    let mut context = Context::builder().arg("--index-events").build();
+
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(context.index.get_events(2, 2).unwrap().len(), 1);
+
+    context.core.invalidate_tip();
+
+    context.mine_blocks(2);
+
+    assert!(context.index.get_events(0, 3).unwrap().is_empty());
+  }
 }
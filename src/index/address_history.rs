@@ -0,0 +1,227 @@
+use super::*;
+
+/// What a transaction did with the outputs of a script pubkey, as stored in
+/// the address history. Inscriptions are stored by sequence number, which is
+/// smaller than their ID.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AddressActivity {
+  pub(crate) inscriptions_received: Vec<u32>,
+  pub(crate) inscriptions_sent: Vec<u32>,
+  pub(crate) received: u64,
+  pub(crate) runes_received: Vec<(RuneId, u128)>,
+  pub(crate) runes_sent: Vec<(RuneId, u128)>,
+  pub(crate) spent: u64,
+  pub(crate) txid: Txid,
+}
+
+impl AddressActivity {
+  pub(crate) fn new(txid: Txid) -> Self {
+    Self {
+      inscriptions_received: Vec::new(),
+      inscriptions_sent: Vec::new(),
+      received: 0,
+      runes_received: Vec::new(),
+      runes_sent: Vec::new(),
+      spent: 0,
+      txid,
+    }
+  }
+}
+
+/// A transaction that created or spent outputs of an address, with the
+/// amount, inscriptions and runes it received and sent.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct AddressTransaction {
+  pub height: u32,
+  pub inscriptions_received: Vec<InscriptionId>,
+  pub inscriptions_sent: Vec<InscriptionId>,
+  pub received: u64,
+  pub runes_received: Vec<(SpacedRune, Decimal, Option<char>)>,
+  pub runes_sent: Vec<(SpacedRune, Decimal, Option<char>)>,
+  pub spent: u64,
+  pub txid: Txid,
+}
+
+impl Index {
+  pub fn has_address_history_index(&self) -> bool {
+    self.index_addresses && self.index_address_history
+  }
+
+  /// Transactions that created or spent outputs of `script_pubkey`, most
+  /// recent first.
+  pub fn get_address_transactions_paginated(
+    &self,
+    script_pubkey: &Script,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<AddressTransaction>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let inscription_ids = |sequence_numbers: Vec<u32>| -> Result<Vec<InscriptionId>> {
+      sequence_numbers
+        .into_iter()
+        .map(|sequence_number| {
+          Ok(
+            InscriptionEntry::load(
+              sequence_number_to_inscription_entry
+                .get(sequence_number)?
+                .unwrap()
+                .value(),
+            )
+            .id,
+          )
+        })
+        .collect()
+    };
+
+    let runes =
+      |balances: Vec<(RuneId, u128)>| -> Result<Vec<(SpacedRune, Decimal, Option<char>)>> {
+        balances
+          .into_iter()
+          .map(|(id, amount)| {
+            let entry = RuneEntry::load(rune_id_to_rune_entry.get(id.store())?.unwrap().value());
+
+            Ok((
+              entry.spaced_rune,
+              Decimal {
+                value: amount,
+                scale: entry.divisibility,
+              },
+              entry.symbol,
+            ))
+          })
+          .collect()
+      };
+
+    let script_pubkey = script_pubkey.as_bytes();
+
+    let mut transactions = rtx
+      .open_table(SCRIPT_PUBKEY_HEIGHT_AND_TX_INDEX_TO_ACTIVITY)?
+      .range((script_pubkey, 0, 0)..=(script_pubkey, u32::MAX, u32::MAX))?
+      .rev()
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|result| {
+        let (key, activity) = result?;
+        let (_script_pubkey, height, _tx_index) = key.value();
+        let activity = AddressActivity::load(activity.value());
+
+        Ok(AddressTransaction {
+          height,
+          inscriptions_received: inscription_ids(activity.inscriptions_received)?,
+          inscriptions_sent: inscription_ids(activity.inscriptions_sent)?,
+          received: activity.received,
+          runes_received: runes(activity.runes_received)?,
+          runes_sent: runes(activity.runes_sent)?,
+          spent: activity.spent,
+          txid: activity.txid,
+        })
+      })
+      .collect::<Result<Vec<AddressTransaction>>>()?;
+
+    let more = transactions.len() > page_size;
+
+    if more {
+      transactions.pop();
+    }
+
+    Ok((transactions, more))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn address_history_index_flag_is_read_from_the_index() {
+    let testing::Context { index, tempdir, .. } =
+      testing::Context::builder().arg("--index-addresses").build();
+
+    drop(index);
+
+    let context = testing::Context::builder()
+      .args(["--index-addresses", "--index-address-history"])
+      .tempdir(tempdir)
+      .build();
+
+    assert!(!context.index.has_address_history_index());
+  }
+
+  #[test]
+  fn runes_sent_and_received_are_recorded() {
+    let context = testing::Context::builder()
+      .args([
+        "--index-addresses",
+        "--index-address-history",
+        "--index-runes",
+      ])
+      .build();
+
+    let (etch, id) = context.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: u128::MAX,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(99246114928149462)),
+          premine: Some(u128::MAX),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let script_pubkey = context.core.tx_by_id(etch).output[0].script_pubkey.clone();
+
+    let transfer = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let rune = (
+      SpacedRune {
+        rune: Rune(99246114928149462),
+        spacers: 0,
+      },
+      Decimal {
+        value: u128::MAX,
+        scale: 0,
+      },
+      None,
+    );
+
+    let (transactions, more) = context
+      .index
+      .get_address_transactions_paginated(&script_pubkey, 1, 0)
+      .unwrap();
+
+    assert!(more);
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].txid, transfer);
+    assert_eq!(transactions[0].runes_sent, [rune]);
+    assert_eq!(transactions[0].runes_received, [rune]);
+
+    let (transactions, _more) = context
+      .index
+      .get_address_transactions_paginated(&script_pubkey, 100, 0)
+      .unwrap();
+
+    let etching = transactions
+      .iter()
+      .find(|transaction| transaction.txid == etch)
+      .unwrap();
+
+    assert_eq!(etching.runes_received, [rune]);
+    assert!(etching.runes_sent.is_empty());
+  }
+}
@@ -0,0 +1,242 @@
+use {super::*, clap::ValueEnum};
+
+/// An optional index that can be enabled on an existing index with `ord index
+/// enable`, after which the updater backfills it by rescanning the blocks that
+/// were indexed without it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Feature {
+  Runes,
+  Transactions,
+}
+
+impl Display for Feature {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::Runes => "runes",
+        Self::Transactions => "transactions",
+      }
+    )
+  }
+}
+
+impl Feature {
+  pub(crate) const BACKFILLABLE: [Self; 2] = [Self::Runes, Self::Transactions];
+
+  fn statistic(self) -> Statistic {
+    match self {
+      Self::Runes => Statistic::IndexRunes,
+      Self::Transactions => Statistic::IndexTransactions,
+    }
+  }
+
+  /// The statistic holding the height of the next block to backfill, which is
+  /// removed once the backfill has caught up with the rest of the index.
+  pub(crate) fn backfill_statistic(self) -> Statistic {
+    match self {
+      Self::Runes => Statistic::RunesBackfillHeight,
+      Self::Transactions => Statistic::TransactionsBackfillHeight,
+    }
+  }
+}
+
+impl Index {
+  /// Enables `feature` on the existing index at `settings.index()`. Returns the
+  /// height the updater will start backfilling from, or `None` if there is
+  /// nothing to backfill.
+  pub(crate) fn enable(settings: &Settings, feature: Feature) -> Result<Option<u32>> {
+    let path = settings.index();
+
+    let database = Database::open(path)
+      .with_context(|| format!("failed to open index at `{}`", path.display()))?;
+
+    let wtx = database.begin_write()?;
+
+    let backfill_height = {
+      let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
+
+      let statistic = |statistic: Statistic| -> Result<u64> {
+        Ok(
+          statistic_to_count
+            .get(&statistic.key())?
+            .map(|count| count.value())
+            .unwrap_or(0),
+        )
+      };
+
+      let schema_version = statistic(Statistic::Schema)?;
+
+      ensure!(
+        schema_version == SCHEMA_VERSION,
+        "index schema {schema_version} does not match ord schema {SCHEMA_VERSION}, \
+        migrate the index with `ord index migrate` first",
+      );
+
+      ensure!(
+        statistic(feature.statistic())? == 0,
+        "{feature} are already indexed",
+      );
+
+      let backfill_height = match feature {
+        Feature::Runes => Some(settings.first_rune_height()),
+        Feature::Transactions => (statistic(Statistic::IndexInscriptions)? != 0)
+          .then(|| settings.first_inscription_height()),
+      };
+
+      statistic_to_count.insert(&feature.statistic().key(), &1)?;
+
+      if let Some(height) = backfill_height {
+        statistic_to_count.insert(&feature.backfill_statistic().key(), &u64::from(height))?;
+      }
+
+      backfill_height
+    };
+
+    if feature == Feature::Runes && settings.chain() == Chain::Mainnet {
+      Self::insert_genesis_rune(&wtx)?;
+    }
+
+    wtx.commit()?;
+
+    Ok(backfill_height)
+  }
+
+  /// Returns the features that are being backfilled, along with the height of
+  /// the next block to backfill.
+  pub fn backfilling(&self) -> Result<BTreeMap<Feature, u32>> {
+    let rtx = self.database.begin_read()?;
+
+    let statistic_to_count = rtx.open_table(STATISTIC_TO_COUNT)?;
+
+    let mut backfilling = BTreeMap::new();
+
+    for feature in Feature::BACKFILLABLE {
+      if let Some(height) = statistic_to_count.get(&feature.backfill_statistic().key())? {
+        backfilling.insert(feature, u32::try_from(height.value()).unwrap());
+      }
+    }
+
+    Ok(backfilling)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn runes_are_backfilled() {
+    let context = testing::Context::builder().build();
+
+    let (txid, id) = context.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: u128::MAX,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(99246114928149462)),
+          premine: Some(u128::MAX),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let settings = context.index.settings.clone();
+
+    drop(context.index);
+
+    assert_eq!(Index::enable(&settings, Feature::Runes).unwrap(), Some(0));
+
+    let index = Index::open(&settings).unwrap();
+
+    assert!(index.has_rune_index());
+
+    assert_eq!(
+      index.backfilling().unwrap(),
+      [(Feature::Runes, 0)].into_iter().collect(),
+    );
+
+    index.update().unwrap();
+
+    assert!(index.backfilling().unwrap().is_empty());
+
+    assert_eq!(
+      index
+        .runes()
+        .unwrap()
+        .into_iter()
+        .map(|(id, entry)| (id, entry.etching))
+        .collect::<Vec<(RuneId, Txid)>>(),
+      [(id, txid)],
+    );
+
+    assert_eq!(
+      index.get_rune_balances().unwrap(),
+      [(OutPoint { txid, vout: 0 }, vec![(id, u128::MAX)])],
+    );
+  }
+
+  #[test]
+  fn transactions_are_backfilled() {
+    let context = testing::Context::builder().build();
+
+    context.mine_blocks(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let settings = context.index.settings.clone();
+
+    drop(context.index);
+
+    assert_eq!(
+      Index::enable(&settings, Feature::Transactions).unwrap(),
+      Some(0),
+    );
+
+    let index = Index::open(&settings).unwrap();
+
+    index.update().unwrap();
+
+    assert!(index.backfilling().unwrap().is_empty());
+
+    assert!(
+      index
+        .database
+        .begin_read()
+        .unwrap()
+        .open_table(TRANSACTION_ID_TO_TRANSACTION)
+        .unwrap()
+        .get(&txid.store())
+        .unwrap()
+        .is_some()
+    );
+  }
+
+  #[test]
+  fn features_can_only_be_enabled_once() {
+    let context = testing::Context::builder().arg("--index-runes").build();
+
+    let settings = context.index.settings.clone();
+
+    drop(context.index);
+
+    assert_eq!(
+      Index::enable(&settings, Feature::Runes)
+        .unwrap_err()
+        .to_string(),
+      "runes are already indexed",
+    );
+  }
+}
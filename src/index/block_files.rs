@@ -0,0 +1,240 @@
+use {
+  super::*,
+  std::io::{Seek, SeekFrom},
+};
+
+#[derive(Copy, Clone)]
+struct Location {
+  file: u32,
+  offset: u64,
+  size: u32,
+}
+
+/// Reads blocks directly from Bitcoin Core's `blk*.dat` files, which is much
+/// faster than fetching them over RPC.
+///
+/// Blocks are stored in the order they were received, which is not chain
+/// order, and stale blocks are never removed, so the files are scanned once
+/// to find where each block is, and blocks are then looked up by hash.
+pub(crate) struct BlockFiles {
+  dir: PathBuf,
+  key: [u8; 8],
+  locations: HashMap<BlockHash, Location>,
+}
+
+impl BlockFiles {
+  pub(crate) fn open(dir: &Path, network: Network) -> Result<Self> {
+    let xor = dir.join("xor.dat");
+
+    // files written by Bitcoin Core 28.0 and later are obfuscated with the key
+    // in `xor.dat`, and earlier files are not
+    let key = match fs::read(&xor) {
+      Ok(key) => <[u8; 8]>::try_from(key.as_slice())
+        .map_err(|_| anyhow!("`{}` should contain 8 bytes", xor.display()))?,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => [0; 8],
+      Err(err) => {
+        return Err(err).with_context(|| format!("failed to read `{}`", xor.display()));
+      }
+    };
+
+    let mut block_files = Self {
+      dir: dir.into(),
+      key,
+      locations: HashMap::new(),
+    };
+
+    let magic = network.magic().to_bytes();
+
+    let mut file = 0;
+    while block_files.path(file).is_file() {
+      block_files
+        .scan(file, magic)
+        .with_context(|| format!("failed to scan `{}`", block_files.path(file).display()))?;
+      file += 1;
+    }
+
+    log::info!(
+      "Found {} blocks in {} block files in `{}`",
+      block_files.locations.len(),
+      file,
+      dir.display(),
+    );
+
+    Ok(block_files)
+  }
+
+  pub(crate) fn get(&self, hash: BlockHash) -> Result<Option<Block>> {
+    let Some(location) = self.locations.get(&hash) else {
+      return Ok(None);
+    };
+
+    let path = self.path(location.file);
+
+    let mut buffer = vec![0; usize::try_from(location.size).unwrap()];
+
+    self.read_at(&mut File::open(&path)?, location.offset, &mut buffer)?;
+
+    let block = consensus::deserialize::<Block>(&buffer)
+      .with_context(|| format!("failed to decode block {hash} in `{}`", path.display()))?;
+
+    ensure!(
+      block.block_hash() == hash,
+      "block {hash} in `{}` is corrupt",
+      path.display(),
+    );
+
+    Ok(Some(block))
+  }
+
+  fn path(&self, file: u32) -> PathBuf {
+    self.dir.join(format!("blk{file:05}.dat"))
+  }
+
+  fn scan(&mut self, file: u32, magic: [u8; 4]) -> Result {
+    let len = fs::metadata(self.path(file))?.len();
+    let mut reader = BufReader::new(File::open(self.path(file))?);
+
+    let mut offset = 0;
+
+    // each block is preceded by the network magic and its size, and files are
+    // preallocated with zeros, so the first record without the magic, or that
+    // runs past the end of the file, marks the end of the blocks
+    while offset + 88 <= len {
+      let mut record = [0; 88];
+      self.read_at(&mut reader, offset, &mut record)?;
+
+      if record[..4] != magic {
+        break;
+      }
+
+      let size = u32::from_le_bytes(record[4..8].try_into().unwrap());
+
+      if offset + 8 + u64::from(size) > len {
+        break;
+      }
+
+      let header = consensus::deserialize::<Header>(&record[8..])?;
+
+      self.locations.insert(
+        header.block_hash(),
+        Location {
+          file,
+          offset: offset + 8,
+          size,
+        },
+      );
+
+      offset += 8 + u64::from(size);
+    }
+
+    Ok(())
+  }
+
+  fn read_at(&self, reader: &mut (impl Read + Seek), offset: u64, buffer: &mut [u8]) -> Result {
+    reader.seek(SeekFrom::Start(offset))?;
+    reader.read_exact(buffer)?;
+
+    let start = usize::try_from(offset % 8).unwrap();
+
+    for (byte, key) in buffer.iter_mut().zip(self.key.iter().cycle().skip(start)) {
+      *byte ^= key;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, bitcoin::blockdata::constants::genesis_block};
+
+  fn write_blocks(dir: &Path, file: u32, key: [u8; 8], blocks: &[Block], padding: usize) {
+    let mut data = Vec::new();
+
+    for block in blocks {
+      let block = consensus::serialize(block);
+      data.extend_from_slice(&Network::Regtest.magic().to_bytes());
+      data.extend_from_slice(&u32::try_from(block.len()).unwrap().to_le_bytes());
+      data.extend_from_slice(&block);
+    }
+
+    data.resize(data.len() + padding, 0);
+
+    for (byte, key) in data.iter_mut().zip(key.iter().cycle()) {
+      *byte ^= key;
+    }
+
+    fs::write(dir.join(format!("blk{file:05}.dat")), data).unwrap();
+  }
+
+  fn block(prev_blockhash: BlockHash) -> Block {
+    let mut block = genesis_block(Network::Regtest);
+    block.header.prev_blockhash = prev_blockhash;
+    block.header.nonce += 1;
+    block
+  }
+
+  #[test]
+  fn blocks_are_read_from_obfuscated_files() {
+    let tempdir = TempDir::new().unwrap();
+
+    let key = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    fs::write(tempdir.path().join("xor.dat"), key).unwrap();
+
+    let genesis = genesis_block(Network::Regtest);
+    let first = block(genesis.block_hash());
+    let second = block(first.block_hash());
+
+    write_blocks(
+      tempdir.path(),
+      0,
+      key,
+      &[genesis.clone(), second.clone()],
+      0,
+    );
+    write_blocks(tempdir.path(), 1, key, std::slice::from_ref(&first), 100);
+
+    let block_files = BlockFiles::open(tempdir.path(), Network::Regtest).unwrap();
+
+    for block in [genesis, first, second] {
+      assert_eq!(block_files.get(block.block_hash()).unwrap(), Some(block));
+    }
+
+    assert_eq!(block_files.get(BlockHash::all_zeros()).unwrap(), None);
+  }
+
+  #[test]
+  fn files_without_xor_key_are_not_obfuscated() {
+    let tempdir = TempDir::new().unwrap();
+
+    let genesis = genesis_block(Network::Regtest);
+
+    write_blocks(tempdir.path(), 0, [0; 8], std::slice::from_ref(&genesis), 0);
+
+    assert_eq!(
+      BlockFiles::open(tempdir.path(), Network::Regtest)
+        .unwrap()
+        .get(genesis.block_hash())
+        .unwrap(),
+      Some(genesis),
+    );
+  }
+
+  #[test]
+  fn blocks_of_other_networks_are_ignored() {
+    let tempdir = TempDir::new().unwrap();
+
+    let genesis = genesis_block(Network::Regtest);
+
+    write_blocks(tempdir.path(), 0, [0; 8], std::slice::from_ref(&genesis), 0);
+
+    assert_eq!(
+      BlockFiles::open(tempdir.path(), Network::Bitcoin)
+        .unwrap()
+        .get(genesis.block_hash())
+        .unwrap(),
+      None,
+    );
+  }
+}
@@ -0,0 +1,329 @@
+use {super::*, clap::ValueEnum};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub(crate) enum Dataset {
+  AddressUtxos,
+  Inscriptions,
+  RuneBalances,
+  RuneEntries,
+  SatRanges,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ColumnType {
+  Boolean,
+  Integer,
+  Text,
+  Unsigned,
+}
+
+/// A value in a dataset row. Rune amounts don't fit in any integer type that
+/// export formats support, so they are exported as decimal text. Sats,
+/// values and other `u64`s may not fit in an `i64`, so they are exported as
+/// unsigned integers.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Field {
+  Boolean(bool),
+  Integer(i64),
+  Null,
+  Text(String),
+  Unsigned(u64),
+}
+
+impl From<bool> for Field {
+  fn from(value: bool) -> Self {
+    Self::Boolean(value)
+  }
+}
+
+impl From<i32> for Field {
+  fn from(value: i32) -> Self {
+    Self::Integer(value.into())
+  }
+}
+
+impl From<u8> for Field {
+  fn from(value: u8) -> Self {
+    Self::Integer(value.into())
+  }
+}
+
+impl From<u32> for Field {
+  fn from(value: u32) -> Self {
+    Self::Integer(value.into())
+  }
+}
+
+impl From<u64> for Field {
+  fn from(value: u64) -> Self {
+    Self::Unsigned(value)
+  }
+}
+
+impl From<u128> for Field {
+  fn from(value: u128) -> Self {
+    Self::Text(value.to_string())
+  }
+}
+
+impl From<String> for Field {
+  fn from(value: String) -> Self {
+    Self::Text(value)
+  }
+}
+
+impl<T: Into<Field>> From<Option<T>> for Field {
+  fn from(value: Option<T>) -> Self {
+    value.map(Into::into).unwrap_or(Self::Null)
+  }
+}
+
+impl Dataset {
+  pub(crate) fn name(self) -> &'static str {
+    match self {
+      Self::AddressUtxos => "address-utxos",
+      Self::Inscriptions => "inscriptions",
+      Self::RuneBalances => "rune-balances",
+      Self::RuneEntries => "rune-entries",
+      Self::SatRanges => "sat-ranges",
+    }
+  }
+
+  pub(crate) fn columns(self) -> &'static [(&'static str, ColumnType)] {
+    use ColumnType::*;
+
+    match self {
+      Self::AddressUtxos => &[
+        ("outpoint", Text),
+        ("address", Text),
+        ("script_pubkey", Text),
+        ("value", Unsigned),
+      ],
+      Self::Inscriptions => &[
+        ("sequence_number", Integer),
+        ("number", Integer),
+        ("id", Text),
+        ("height", Integer),
+        ("timestamp", Integer),
+        ("fee", Unsigned),
+        ("sat", Unsigned),
+        ("satpoint", Text),
+      ],
+      Self::RuneBalances => &[
+        ("outpoint", Text),
+        ("rune_id", Text),
+        ("rune", Text),
+        ("amount", Text),
+      ],
+      Self::RuneEntries => &[
+        ("id", Text),
+        ("rune", Text),
+        ("number", Unsigned),
+        ("block", Unsigned),
+        ("etching", Text),
+        ("divisibility", Integer),
+        ("symbol", Text),
+        ("premine", Text),
+        ("mints", Text),
+        ("burned", Text),
+        ("timestamp", Unsigned),
+        ("turbo", Boolean),
+        ("amount", Text),
+        ("cap", Text),
+        ("height_start", Unsigned),
+        ("height_end", Unsigned),
+        ("offset_start", Unsigned),
+        ("offset_end", Unsigned),
+      ],
+      Self::SatRanges => &[("outpoint", Text), ("start", Unsigned), ("end", Unsigned)],
+    }
+  }
+
+  fn flag(self) -> &'static str {
+    match self {
+      Self::AddressUtxos => "--index-addresses",
+      Self::Inscriptions => "--index-inscriptions",
+      Self::RuneBalances | Self::RuneEntries => "--index-runes",
+      Self::SatRanges => "--index-sats",
+    }
+  }
+
+  fn is_indexed(self, index: &Index) -> bool {
+    match self {
+      Self::AddressUtxos => index.index_addresses,
+      Self::Inscriptions => index.index_inscriptions,
+      Self::RuneBalances | Self::RuneEntries => index.index_runes,
+      Self::SatRanges => index.index_sats,
+    }
+  }
+}
+
+impl Index {
+  /// Reads `datasets` in a single read transaction, so that they are
+  /// consistent with each other, passing each row to `sink`. Returns the
+  /// height and hash of the last block the datasets reflect.
+  pub(crate) fn export_datasets(
+    &self,
+    datasets: &[Dataset],
+    mut sink: impl FnMut(Dataset, Vec<Field>) -> Result,
+  ) -> Result<(u32, BlockHash)> {
+    for dataset in datasets {
+      ensure!(
+        dataset.is_indexed(self),
+        "exporting {} requires index created with `{}` flag",
+        dataset.name(),
+        dataset.flag(),
+      );
+    }
+
+    let rtx = self.database.begin_read()?;
+
+    let (height, header) = rtx
+      .open_table(HEIGHT_TO_BLOCK_HEADER)?
+      .last()?
+      .map(|(height, header)| (height.value(), Header::load(*header.value())))
+      .ok_or_else(|| anyhow!("index has no blocks"))?;
+
+    for &dataset in datasets {
+      match dataset {
+        Dataset::AddressUtxos => {
+          for result in rtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?.iter()? {
+            let (outpoint, entry) = result?;
+            let entry = entry.value().parse(self);
+            let script_pubkey = Script::from_bytes(entry.script_pubkey());
+
+            sink(
+              dataset,
+              vec![
+                OutPoint::load(*outpoint.value()).to_string().into(),
+                Address::from_script(script_pubkey, self.settings.chain().network())
+                  .ok()
+                  .map(|address| address.to_string())
+                  .into(),
+                script_pubkey.to_hex_string().into(),
+                entry.total_value().into(),
+              ],
+            )?;
+          }
+        }
+        Dataset::Inscriptions => {
+          let sequence_number_to_satpoint = rtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
+
+          for result in rtx
+            .open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?
+            .iter()?
+          {
+            let (sequence_number, entry) = result?;
+            let entry = InscriptionEntry::load(entry.value());
+
+            let satpoint = sequence_number_to_satpoint
+              .get(sequence_number.value())?
+              .map(|satpoint| SatPoint::load(*satpoint.value()).to_string());
+
+            sink(
+              dataset,
+              vec![
+                entry.sequence_number.into(),
+                entry.inscription_number.into(),
+                entry.id.to_string().into(),
+                entry.height.into(),
+                entry.timestamp.into(),
+                entry.fee.into(),
+                entry.sat.map(|sat| sat.n()).into(),
+                satpoint.into(),
+              ],
+            )?;
+          }
+        }
+        Dataset::RuneBalances => {
+          let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+          let mut runes = HashMap::new();
+
+          for result in rtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?.iter()? {
+            let (outpoint, balances) = result?;
+            let outpoint = OutPoint::load(*outpoint.value());
+            let balances = balances.value();
+
+            let mut i = 0;
+            while i < balances.len() {
+              let ((id, balance), len) = Index::decode_rune_balance(&balances[i..])?;
+              i += len;
+
+              let rune = match runes.get(&id) {
+                Some(rune) => *rune,
+                None => {
+                  let rune = rune_id_to_rune_entry
+                    .get(id.store())?
+                    .map(|entry| RuneEntry::load(entry.value()).spaced_rune)
+                    .ok_or_else(|| anyhow!("rune {id} has balance but no entry"))?;
+                  runes.insert(id, rune);
+                  rune
+                }
+              };
+
+              sink(
+                dataset,
+                vec![
+                  outpoint.to_string().into(),
+                  id.to_string().into(),
+                  rune.to_string().into(),
+                  balance.into(),
+                ],
+              )?;
+            }
+          }
+        }
+        Dataset::RuneEntries => {
+          for result in rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?.iter()? {
+            let (id, entry) = result?;
+            let id = RuneId::load(id.value());
+            let entry = RuneEntry::load(entry.value());
+            let terms = entry.terms.unwrap_or_default();
+
+            sink(
+              dataset,
+              vec![
+                id.to_string().into(),
+                entry.spaced_rune.to_string().into(),
+                entry.number.into(),
+                entry.block.into(),
+                entry.etching.to_string().into(),
+                entry.divisibility.into(),
+                entry.symbol.map(|symbol| symbol.to_string()).into(),
+                entry.premine.into(),
+                entry.mints.into(),
+                entry.burned.into(),
+                entry.timestamp.into(),
+                entry.turbo.into(),
+                terms.amount.into(),
+                terms.cap.into(),
+                terms.height.0.into(),
+                terms.height.1.into(),
+                terms.offset.0.into(),
+                terms.offset.1.into(),
+              ],
+            )?;
+          }
+        }
+        Dataset::SatRanges => {
+          for result in rtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?.iter()? {
+            let (outpoint, entry) = result?;
+            let outpoint = OutPoint::load(*outpoint.value()).to_string();
+
+            for chunk in entry.value().parse(self).sat_ranges().chunks_exact(11) {
+              let (start, end) = SatRange::load(chunk.try_into().unwrap());
+
+              sink(
+                dataset,
+                vec![outpoint.clone().into(), start.into(), end.into()],
+              )?;
+            }
+          }
+        }
+      }
+    }
+
+    Ok((height, header.block_hash()))
+  }
+}
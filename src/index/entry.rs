@@ -357,6 +357,43 @@ impl Entry for InscriptionEntry {
   }
 }
 
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct InscriptionTransfer {
+  pub height: u32,
+  pub new_satpoint: SatPoint,
+  pub old_satpoint: Option<SatPoint>,
+  pub txid: Txid,
+}
+
+pub(super) type InscriptionTransferValue = (
+  u32,                   // height
+  SatPointValue,         // new satpoint
+  Option<SatPointValue>, // old satpoint
+  TxidValue,             // txid
+);
+
+impl Entry for InscriptionTransfer {
+  type Value = InscriptionTransferValue;
+
+  fn load((height, new_satpoint, old_satpoint, txid): Self::Value) -> Self {
+    Self {
+      height,
+      new_satpoint: SatPoint::load(new_satpoint),
+      old_satpoint: old_satpoint.map(SatPoint::load),
+      txid: Txid::load(txid),
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    (
+      self.height,
+      self.new_satpoint.store(),
+      self.old_satpoint.map(SatPoint::store),
+      self.txid.store(),
+    )
+  }
+}
+
 pub(crate) type InscriptionIdValue = (u128, u128, u32);
 
 impl Entry for InscriptionId {
@@ -488,6 +525,517 @@ impl Entry for Txid {
   }
 }
 
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct Brc20v2TokenEntry {
+  pub admin: Option<[u8; 32]>,
+  pub decimals: u8,
+  pub holders: u64,
+  pub max_supply: u128,
+  pub mint_limit: u128,
+  pub require_identity: bool,
+  pub soulbound: bool,
+  pub total_supply: u128,
+}
+
+impl Brc20v2TokenEntry {
+  /// The entry of `token`, which has `holders` accounts with a balance.
+  pub(crate) fn new(token: &TokenState, holders: u64) -> Self {
+    Self {
+      admin: token.admin.as_ref().map(|admin| admin.commitment),
+      decimals: token.definition.decimals,
+      holders,
+      max_supply: token.definition.max_supply,
+      mint_limit: token.definition.mint_limit,
+      require_identity: token.definition.transfer_rules.require_identity,
+      soulbound: token.definition.soulbound,
+      total_supply: token.total_supply,
+    }
+  }
+
+  pub fn definition(&self, ticker: String) -> TokenDefinition {
+    TokenDefinition {
+      ticker,
+      max_supply: self.max_supply,
+      mint_limit: self.mint_limit,
+      decimals: self.decimals,
+      soulbound: self.soulbound,
+      transfer_rules: TransferRules {
+        require_identity: self.require_identity,
+      },
+    }
+  }
+}
+
+pub(super) type Brc20v2TokenEntryValue = (
+  Option<[u8; 32]>, // admin
+  u8,               // decimals
+  u64,              // holders
+  u128,             // max supply
+  u128,             // mint limit
+  bool,             // require identity
+  bool,             // soulbound
+  u128,             // total supply
+);
+
+impl Entry for Brc20v2TokenEntry {
+  type Value = Brc20v2TokenEntryValue;
+
+  fn load(
+    (
+      admin,
+      decimals,
+      holders,
+      max_supply,
+      mint_limit,
+      require_identity,
+      soulbound,
+      total_supply,
+    ): Self::Value,
+  ) -> Self {
+    Self {
+      admin,
+      decimals,
+      holders,
+      max_supply,
+      mint_limit,
+      require_identity,
+      soulbound,
+      total_supply,
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    (
+      self.admin,
+      self.decimals,
+      self.holders,
+      self.max_supply,
+      self.mint_limit,
+      self.require_identity,
+      self.soulbound,
+      self.total_supply,
+    )
+  }
+}
+
+type VestingScheduleValue = (
+  u32,  // start height
+  u32,  // cliff blocks
+  u32,  // duration blocks
+  u32,  // step interval, or zero if linear
+  u128, // total locked
+);
+
+pub(super) type Brc20v2AccountValue = (
+  u128,                         // balance
+  u128,                         // locked balance
+  Option<VestingScheduleValue>, // vesting
+  u64,                          // nonce
+);
+
+impl Entry for AccountState {
+  type Value = Brc20v2AccountValue;
+
+  fn load((balance, locked_balance, vesting, nonce): Self::Value) -> Self {
+    Self {
+      balance,
+      locked_balance,
+      nonce,
+      vesting: vesting.map(
+        |(start_height, cliff_blocks, duration_blocks, interval, total_locked)| VestingSchedule {
+          start_height,
+          cliff_blocks,
+          duration_blocks,
+          curve: match interval {
+            0 => VestingCurve::Linear,
+            interval => VestingCurve::Step { interval },
+          },
+          total_locked,
+        },
+      ),
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    (
+      self.balance,
+      self.locked_balance,
+      self.vesting.map(
+        |VestingSchedule {
+           start_height,
+           cliff_blocks,
+           duration_blocks,
+           curve,
+           total_locked,
+         }| {
+          let interval = match curve {
+            VestingCurve::Linear => 0,
+            VestingCurve::Step { interval } => interval,
+          };
+
+          (
+            start_height,
+            cliff_blocks,
+            duration_blocks,
+            interval,
+            total_locked,
+          )
+        },
+      ),
+      self.nonce,
+    )
+  }
+}
+
+pub(super) type Brc20v2RejectionValue = (
+  u8,     // reason
+  String, // ticker, op, field or message
+  String, // message or amount
+  u8,     // decimals
+);
+
+impl Entry for Rejection {
+  type Value = Brc20v2RejectionValue;
+
+  fn load((reason, first, second, decimals): Self::Value) -> Self {
+    match reason {
+      0 => Self::InvalidPayload { message: first },
+      1 => Self::InvalidTicker { ticker: first },
+      2 => Self::UnknownOperation { op: first },
+      3 => Self::InvalidBody {
+        op: first,
+        message: second,
+      },
+      4 => Self::InvalidAmount {
+        field: first,
+        amount: second,
+      },
+      5 => Self::ExcessPrecision {
+        field: first,
+        amount: second,
+        decimals,
+      },
+      6 => Self::InvalidDecimals { decimals },
+      7 => Self::Rule { message: first },
+      reason => panic!("unknown BRC-20 v2 rejection reason {reason}"),
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    match self {
+      Self::InvalidPayload { message } => (0, message, String::new(), 0),
+      Self::InvalidTicker { ticker } => (1, ticker, String::new(), 0),
+      Self::UnknownOperation { op } => (2, op, String::new(), 0),
+      Self::InvalidBody { op, message } => (3, op, message, 0),
+      Self::InvalidAmount { field, amount } => (4, field, amount, 0),
+      Self::ExcessPrecision {
+        field,
+        amount,
+        decimals,
+      } => (5, field, amount, decimals),
+      Self::InvalidDecimals { decimals } => (6, String::new(), String::new(), decimals),
+      Self::Rule { message } => (7, message, String::new(), 0),
+    }
+  }
+}
+
+pub(super) type EventValue = (
+  u8,                         // type
+  u32,                        // block height
+  Option<InscriptionIdValue>, // inscription id
+  u32,                        // sequence number
+  Option<SatPointValue>,      // location or new location
+  Option<SatPointValue>,      // old location
+  u16,                        // charms
+  Vec<InscriptionIdValue>,    // parent inscription ids
+  Option<RuneIdValue>,        // rune id
+  Option<TxidValue>,          // txid
+  Option<OutPointValue>,      // outpoint
+  u128,                       // amount
+);
+
+impl Entry for Event {
+  type Value = EventValue;
+
+  fn load(
+    (
+      event_type,
+      block_height,
+      inscription_id,
+      sequence_number,
+      location,
+      old_location,
+      charms,
+      parent_inscription_ids,
+      rune_id,
+      txid,
+      outpoint,
+      amount,
+    ): Self::Value,
+  ) -> Self {
+    let inscription_id = || InscriptionId::load(inscription_id.unwrap());
+    let rune_id = || RuneId::load(rune_id.unwrap());
+    let txid = || Txid::load(txid.unwrap());
+
+    match event_type {
+      0 => Self::InscriptionCreated {
+        block_height,
+        charms,
+        inscription_id: inscription_id(),
+        location: location.map(SatPoint::load),
+        parent_inscription_ids: parent_inscription_ids
+          .into_iter()
+          .map(InscriptionId::load)
+          .collect(),
+        sequence_number,
+      },
+      1 => Self::InscriptionTransferred {
+        block_height,
+        inscription_id: inscription_id(),
+        new_location: SatPoint::load(location.unwrap()),
+        old_location: SatPoint::load(old_location.unwrap()),
+        sequence_number,
+      },
+      2 => Self::RuneBurned {
+        amount,
+        block_height,
+        rune_id: rune_id(),
+        txid: txid(),
+      },
+      3 => Self::RuneEtched {
+        block_height,
+        rune_id: rune_id(),
+        txid: txid(),
+      },
+      4 => Self::RuneMinted {
+        amount,
+        block_height,
+        rune_id: rune_id(),
+        txid: txid(),
+      },
+      5 => Self::RuneTransferred {
+        amount,
+        block_height,
+        outpoint: OutPoint::load(outpoint.unwrap()),
+        rune_id: rune_id(),
+        txid: txid(),
+      },
+      event_type => panic!("unknown event type {event_type}"),
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    match self {
+      Self::InscriptionCreated {
+        block_height,
+        charms,
+        inscription_id,
+        location,
+        parent_inscription_ids,
+        sequence_number,
+      } => (
+        0,
+        block_height,
+        Some(inscription_id.store()),
+        sequence_number,
+        location.map(SatPoint::store),
+        None,
+        charms,
+        parent_inscription_ids
+          .into_iter()
+          .map(InscriptionId::store)
+          .collect(),
+        None,
+        None,
+        None,
+        0,
+      ),
+      Self::InscriptionTransferred {
+        block_height,
+        inscription_id,
+        new_location,
+        old_location,
+        sequence_number,
+      } => (
+        1,
+        block_height,
+        Some(inscription_id.store()),
+        sequence_number,
+        Some(new_location.store()),
+        Some(old_location.store()),
+        0,
+        Vec::new(),
+        None,
+        None,
+        None,
+        0,
+      ),
+      Self::RuneBurned {
+        amount,
+        block_height,
+        rune_id,
+        txid,
+      } => (
+        2,
+        block_height,
+        None,
+        0,
+        None,
+        None,
+        0,
+        Vec::new(),
+        Some(rune_id.store()),
+        Some(txid.store()),
+        None,
+        amount,
+      ),
+      Self::RuneEtched {
+        block_height,
+        rune_id,
+        txid,
+      } => (
+        3,
+        block_height,
+        None,
+        0,
+        None,
+        None,
+        0,
+        Vec::new(),
+        Some(rune_id.store()),
+        Some(txid.store()),
+        None,
+        0,
+      ),
+      Self::RuneMinted {
+        amount,
+        block_height,
+        rune_id,
+        txid,
+      } => (
+        4,
+        block_height,
+        None,
+        0,
+        None,
+        None,
+        0,
+        Vec::new(),
+        Some(rune_id.store()),
+        Some(txid.store()),
+        None,
+        amount,
+      ),
+      Self::RuneTransferred {
+        amount,
+        block_height,
+        outpoint,
+        rune_id,
+        txid,
+      } => (
+        5,
+        block_height,
+        None,
+        0,
+        None,
+        None,
+        0,
+        Vec::new(),
+        Some(rune_id.store()),
+        Some(txid.store()),
+        Some(outpoint.store()),
+        amount,
+      ),
+    }
+  }
+}
+
+pub(super) type EventRecordValue = (
+  EventValue, // event
+  u32,        // number
+  u32,        // tx index
+);
+
+impl Entry for EventRecord {
+  type Value = EventRecordValue;
+
+  fn load((event, number, tx_index): Self::Value) -> Self {
+    Self {
+      event: Event::load(event),
+      number,
+      tx_index,
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    (self.event.store(), self.number, self.tx_index)
+  }
+}
+
+type RuneBalancesValue = Vec<(RuneIdValue, u128)>;
+
+pub(super) type AddressActivityValue = (
+  Vec<u32>,          // inscriptions received
+  Vec<u32>,          // inscriptions sent
+  u64,               // received
+  RuneBalancesValue, // runes received
+  RuneBalancesValue, // runes sent
+  u64,               // spent
+  TxidValue,         // txid
+);
+
+impl Entry for AddressActivity {
+  type Value = AddressActivityValue;
+
+  fn load(
+    (
+      inscriptions_received,
+      inscriptions_sent,
+      received,
+      runes_received,
+      runes_sent,
+      spent,
+      txid,
+    ): Self::Value,
+  ) -> Self {
+    let runes = |balances: RuneBalancesValue| {
+      balances
+        .into_iter()
+        .map(|(id, amount)| (RuneId::load(id), amount))
+        .collect()
+    };
+
+    Self {
+      inscriptions_received,
+      inscriptions_sent,
+      received,
+      runes_received: runes(runes_received),
+      runes_sent: runes(runes_sent),
+      spent,
+      txid: Txid::load(txid),
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    let runes = |balances: Vec<(RuneId, u128)>| {
+      balances
+        .into_iter()
+        .map(|(id, amount)| (id.store(), amount))
+        .collect()
+    };
+
+    (
+      self.inscriptions_received,
+      self.inscriptions_sent,
+      self.received,
+      runes(self.runes_received),
+      runes(self.runes_sent),
+      self.spent,
+      self.txid.store(),
+    )
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -516,6 +1064,37 @@ mod tests {
     assert_eq!(InscriptionEntry::load(value), entry);
   }
 
+  #[test]
+  fn inscription_transfer_entry() {
+    let transfer = InscriptionTransfer {
+      height: 1,
+      new_satpoint: SatPoint {
+        outpoint: OutPoint {
+          txid: txid(2),
+          vout: 3,
+        },
+        offset: 4,
+      },
+      old_satpoint: Some(SatPoint {
+        outpoint: OutPoint {
+          txid: txid(5),
+          vout: 6,
+        },
+        offset: 7,
+      }),
+      txid: txid(2),
+    };
+
+    assert_eq!(InscriptionTransfer::load(transfer.store()), transfer);
+
+    let created = InscriptionTransfer {
+      old_satpoint: None,
+      ..transfer
+    };
+
+    assert_eq!(InscriptionTransfer::load(created.store()), created);
+  }
+
   #[test]
   fn inscription_id_entry() {
     let inscription_id = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdefi0"
@@ -922,4 +1501,218 @@ mod tests {
       1001
     );
   }
+
+  #[test]
+  fn brc20v2_token_entry() {
+    let entry = Brc20v2TokenEntry {
+      admin: Some([1; 32]),
+      decimals: 8,
+      holders: 3,
+      max_supply: 21_000_000,
+      mint_limit: 1000,
+      require_identity: true,
+      soulbound: false,
+      total_supply: 5000,
+    };
+
+    let value = (Some([1; 32]), 8, 3, 21_000_000, 1000, true, false, 5000);
+
+    assert_eq!(entry.store(), value);
+    assert_eq!(Brc20v2TokenEntry::load(value), entry);
+  }
+
+  #[test]
+  fn brc20v2_account_entry() {
+    let account = AccountState {
+      balance: 100,
+      locked_balance: 50,
+      vesting: Some(VestingSchedule {
+        start_height: 1,
+        cliff_blocks: 2,
+        duration_blocks: 3,
+        curve: VestingCurve::Linear,
+        total_locked: 50,
+      }),
+      nonce: 7,
+    };
+
+    let value = (100, 50, Some((1, 2, 3, 0, 50)), 7);
+
+    assert_eq!(account.clone().store(), value);
+    assert_eq!(AccountState::load(value), account);
+
+    let account = AccountState {
+      vesting: Some(VestingSchedule {
+        curve: VestingCurve::Step { interval: 144 },
+        ..account.vesting.unwrap()
+      }),
+      ..account
+    };
+
+    let value = (100, 50, Some((1, 2, 3, 144, 50)), 7);
+
+    assert_eq!(account.clone().store(), value);
+    assert_eq!(AccountState::load(value), account);
+
+    assert_eq!(AccountState::default().store(), (0, 0, None, 0));
+  }
+
+  #[test]
+  fn brc20v2_rejection_entry() {
+    for (rejection, value) in [
+      (
+        Rejection::InvalidPayload {
+          message: "foo".into(),
+        },
+        (0, "foo".into(), String::new(), 0),
+      ),
+      (
+        Rejection::InvalidTicker { ticker: "x".into() },
+        (1, "x".into(), String::new(), 0),
+      ),
+      (
+        Rejection::UnknownOperation { op: "burn".into() },
+        (2, "burn".into(), String::new(), 0),
+      ),
+      (
+        Rejection::InvalidBody {
+          op: "mint".into(),
+          message: "foo".into(),
+        },
+        (3, "mint".into(), "foo".into(), 0),
+      ),
+      (
+        Rejection::InvalidAmount {
+          field: "amt".into(),
+          amount: "-1".into(),
+        },
+        (4, "amt".into(), "-1".into(), 0),
+      ),
+      (
+        Rejection::ExcessPrecision {
+          field: "amt".into(),
+          amount: "1.001".into(),
+          decimals: 2,
+        },
+        (5, "amt".into(), "1.001".into(), 2),
+      ),
+      (
+        Rejection::InvalidDecimals { decimals: 19 },
+        (6, String::new(), String::new(), 19),
+      ),
+      (
+        Rejection::Rule {
+          message: "foo".into(),
+        },
+        (7, "foo".into(), String::new(), 0),
+      ),
+    ] {
+      assert_eq!(rejection.clone().store(), value);
+      assert_eq!(Rejection::load(value), rejection);
+    }
+  }
+
+  #[test]
+  fn event_record_entry() {
+    let inscription_id = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdefi0"
+      .parse::<InscriptionId>()
+      .unwrap();
+    let outpoint = OutPoint {
+      txid: Txid::from_byte_array([1; 32]),
+      vout: 2,
+    };
+    let satpoint = SatPoint {
+      outpoint,
+      offset: 3,
+    };
+    let rune_id = RuneId { block: 4, tx: 5 };
+    let txid = Txid::from_byte_array([6; 32]);
+
+    for event in [
+      Event::InscriptionCreated {
+        block_height: 7,
+        charms: 8,
+        inscription_id,
+        location: Some(satpoint),
+        parent_inscription_ids: vec![inscription_id],
+        sequence_number: 9,
+      },
+      Event::InscriptionCreated {
+        block_height: 7,
+        charms: 0,
+        inscription_id,
+        location: None,
+        parent_inscription_ids: Vec::new(),
+        sequence_number: 9,
+      },
+      Event::InscriptionTransferred {
+        block_height: 7,
+        inscription_id,
+        new_location: satpoint,
+        old_location: SatPoint {
+          offset: 10,
+          ..satpoint
+        },
+        sequence_number: 9,
+      },
+      Event::RuneBurned {
+        amount: 11,
+        block_height: 7,
+        rune_id,
+        txid,
+      },
+      Event::RuneEtched {
+        block_height: 7,
+        rune_id,
+        txid,
+      },
+      Event::RuneMinted {
+        amount: 11,
+        block_height: 7,
+        rune_id,
+        txid,
+      },
+      Event::RuneTransferred {
+        amount: 11,
+        block_height: 7,
+        outpoint,
+        rune_id,
+        txid,
+      },
+    ] {
+      let record = EventRecord {
+        event,
+        number: 12,
+        tx_index: 13,
+      };
+
+      assert_eq!(EventRecord::load(record.clone().store()), record);
+    }
+  }
+
+  #[test]
+  fn address_activity_entry() {
+    let activity = AddressActivity {
+      inscriptions_received: vec![1, 2],
+      inscriptions_sent: vec![3],
+      received: 4,
+      runes_received: vec![(RuneId { block: 5, tx: 6 }, 7)],
+      runes_sent: vec![(RuneId { block: 8, tx: 9 }, 10)],
+      spent: 11,
+      txid: Txid::from_byte_array([12; 32]),
+    };
+
+    let value = (
+      vec![1, 2],
+      vec![3],
+      4,
+      vec![((5, 6), 7)],
+      vec![((8, 9), 10)],
+      11,
+      [12; 32],
+    );
+
+    assert_eq!(activity.clone().store(), value);
+    assert_eq!(AddressActivity::load(value), activity);
+  }
 }
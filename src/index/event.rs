@@ -1,6 +1,7 @@
 use super::*;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
 pub enum Event {
   InscriptionCreated {
     block_height: u32,
@@ -42,3 +43,138 @@ pub enum Event {
     txid: Txid,
   },
 }
+
+/// An event in the event log, with the index of the transaction it was
+/// emitted for and its position among the events of its block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventRecord {
+  pub event: Event,
+  pub number: u32,
+  pub tx_index: u32,
+}
+
+impl Event {
+  pub fn block_height(&self) -> u32 {
+    match self {
+      Self::InscriptionCreated { block_height, .. }
+      | Self::InscriptionTransferred { block_height, .. }
+      | Self::RuneBurned { block_height, .. }
+      | Self::RuneEtched { block_height, .. }
+      | Self::RuneMinted { block_height, .. }
+      | Self::RuneTransferred { block_height, .. } => *block_height,
+    }
+  }
+
+  pub fn event_type(&self) -> EventType {
+    match self {
+      Self::InscriptionCreated { .. } => EventType::InscriptionCreated,
+      Self::InscriptionTransferred { .. } => EventType::InscriptionTransferred,
+      Self::RuneBurned { .. } => EventType::RuneBurned,
+      Self::RuneEtched { .. } => EventType::RuneEtched,
+      Self::RuneMinted { .. } => EventType::RuneMinted,
+      Self::RuneTransferred { .. } => EventType::RuneTransferred,
+    }
+  }
+
+  pub fn inscription_id(&self) -> Option<InscriptionId> {
+    match self {
+      Self::InscriptionCreated { inscription_id, .. }
+      | Self::InscriptionTransferred { inscription_id, .. } => Some(*inscription_id),
+      _ => None,
+    }
+  }
+
+  pub fn rune_id(&self) -> Option<RuneId> {
+    match self {
+      Self::RuneBurned { rune_id, .. }
+      | Self::RuneEtched { rune_id, .. }
+      | Self::RuneMinted { rune_id, .. }
+      | Self::RuneTransferred { rune_id, .. } => Some(*rune_id),
+      _ => None,
+    }
+  }
+}
+
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, DeserializeFromStr, SerializeDisplay,
+)]
+pub enum EventType {
+  InscriptionCreated,
+  InscriptionTransferred,
+  RuneBurned,
+  RuneEtched,
+  RuneMinted,
+  RuneTransferred,
+}
+
+impl Display for EventType {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::InscriptionCreated => "inscription_created",
+        Self::InscriptionTransferred => "inscription_transferred",
+        Self::RuneBurned => "rune_burned",
+        Self::RuneEtched => "rune_etched",
+        Self::RuneMinted => "rune_minted",
+        Self::RuneTransferred => "rune_transferred",
+      }
+    )
+  }
+}
+
+impl FromStr for EventType {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    Ok(match s {
+      "inscription_created" => Self::InscriptionCreated,
+      "inscription_transferred" => Self::InscriptionTransferred,
+      "rune_burned" => Self::RuneBurned,
+      "rune_etched" => Self::RuneEtched,
+      "rune_minted" => Self::RuneMinted,
+      "rune_transferred" => Self::RuneTransferred,
+      _ => bail!("invalid event type: {s}"),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn event_type_round_trips() {
+    for event_type in [
+      EventType::InscriptionCreated,
+      EventType::InscriptionTransferred,
+      EventType::RuneBurned,
+      EventType::RuneEtched,
+      EventType::RuneMinted,
+      EventType::RuneTransferred,
+    ] {
+      assert_eq!(
+        event_type.to_string().parse::<EventType>().unwrap(),
+        event_type
+      );
+    }
+
+    assert!("foo".parse::<EventType>().is_err());
+  }
+
+  #[test]
+  fn events_are_tagged_with_their_type() {
+    let event = Event::RuneEtched {
+      block_height: 1,
+      rune_id: RuneId { block: 1, tx: 2 },
+      txid: txid(1),
+    };
+
+    let json = serde_json::to_value(&event).unwrap();
+
+    assert_eq!(json["type"], event.event_type().to_string());
+
+    assert_eq!(serde_json::from_value::<Event>(json).unwrap(), event);
+  }
+}
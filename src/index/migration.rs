@@ -0,0 +1,294 @@
+use {super::*, redb::ReadTransaction};
+
+/// Upgrades an index in place from schema version `from` to `from + 1`.
+/// Migrations that can't produce the same index that reindexing would
+/// produce fail, explaining why the index must be rebuilt instead.
+struct Migration {
+  from: u64,
+  description: &'static str,
+  migrate: fn(&Migrator) -> Result,
+}
+
+struct Migrator<'a> {
+  /// Read transaction opened before any migration was applied
+  rtx: &'a ReadTransaction,
+  wtx: &'a WriteTransaction,
+}
+
+impl Migrator<'_> {
+  /// Fails if the index was built with the optional index recorded by
+  /// `statistic`, whose `data` can't be recovered for blocks that have
+  /// already been indexed. Indices are enabled when the index is created and
+  /// can't be enabled afterwards, so an index that doesn't record one never
+  /// has it.
+  fn ensure_not_indexed(&self, statistic: Statistic, data: &str, flag: &str) -> Result {
+    ensure!(
+      !Index::is_statistic_set(&self.rtx.open_table(STATISTIC_TO_COUNT)?, statistic)?,
+      "{data} can't be recovered, the index must be rebuilt with `{flag}`",
+    );
+
+    Ok(())
+  }
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+  from: 31,
+  description: "add BRC-20 v2, event, transfer history, address history and search indices, \
+    and discard unvalidated offers",
+  migrate: |migrator| {
+    migrator.ensure_not_indexed(
+      Statistic::IndexBrc20v2,
+      "BRC-20 v2 state of indexed blocks",
+      "--index-brc20v2",
+    )?;
+    migrator.ensure_not_indexed(
+      Statistic::IndexEvents,
+      "events of indexed blocks",
+      "--index-events",
+    )?;
+    migrator.ensure_not_indexed(
+      Statistic::IndexTransferHistory,
+      "transfers of indexed inscriptions",
+      "--index-transfer-history",
+    )?;
+    migrator.ensure_not_indexed(
+      Statistic::IndexAddressHistory,
+      "transactions of indexed blocks",
+      "--index-address-history",
+    )?;
+    migrator.ensure_not_indexed(
+      Statistic::IndexSearch,
+      "search terms of indexed inscriptions",
+      "--index-search",
+    )?;
+
+    let wtx = migrator.wtx;
+
+    wtx.open_table(BRC20V2_ACCOUNT_TO_STATE)?;
+    wtx.open_table(BRC20V2_ALLOWANCE_TO_AMOUNT)?;
+    wtx.open_table(BRC20V2_BRANCH_TO_HASH)?;
+    wtx.open_table(BRC20V2_COMMITMENT_TO_IDENTITY)?;
+    wtx.open_table(BRC20V2_FROZEN_ACCOUNTS)?;
+    wtx.open_table(BRC20V2_INSCRIPTION_ID_TO_REJECTION)?;
+    wtx.open_table(BRC20V2_LEAF_TO_HASH)?;
+    wtx.open_table(BRC20V2_TICKER_TO_TOKEN)?;
+    wtx.open_table(HEIGHT_AND_NUMBER_TO_EVENT)?;
+    wtx.open_table(SCRIPT_PUBKEY_HEIGHT_AND_TX_INDEX_TO_ACTIVITY)?;
+    wtx.open_table(SEQUENCE_NUMBER_AND_INDEX_TO_TRANSFER)?;
+    wtx.open_multimap_table(SEARCH_TERM_TO_SEQUENCE_NUMBER)?;
+
+    // offers used to be accepted without checking their signatures
+    wtx.delete_table(NUMBER_TO_OFFER)?;
+    wtx.open_table(NUMBER_TO_OFFER)?;
+    wtx.open_multimap_table(EXPIRY_TO_OFFER_NUMBER)?;
+    wtx.open_multimap_table(OUTPOINT_TO_OFFER_NUMBER)?;
+
+    Ok(())
+  },
+}];
+
+impl Index {
+  /// Migrates the index at `settings.index()` to `SCHEMA_VERSION` in a single
+  /// write transaction, which is aborted instead of committed if `dry_run`.
+  /// Returns the schema version the index was at, and the descriptions of the
+  /// migrations that were applied.
+  pub(crate) fn migrate(settings: &Settings, dry_run: bool) -> Result<(u64, Vec<&'static str>)> {
+    let path = settings.index();
+
+    let database = Database::open(path)
+      .with_context(|| format!("failed to open index at `{}`", path.display()))?;
+
+    let rtx = database.begin_read()?;
+
+    let schema_version = rtx
+      .open_table(STATISTIC_TO_COUNT)?
+      .get(&Statistic::Schema.key())?
+      .map(|version| version.value())
+      .unwrap_or(0);
+
+    ensure!(
+      schema_version <= SCHEMA_VERSION,
+      "index schema {schema_version} is newer than ord schema {SCHEMA_VERSION}, consider updating ord",
+    );
+
+    let migrations = MIGRATIONS
+      .iter()
+      .filter(|migration| migration.from >= schema_version)
+      .collect::<Vec<&Migration>>();
+
+    if schema_version == SCHEMA_VERSION {
+      return Ok((schema_version, Vec::new()));
+    }
+
+    ensure!(
+      migrations
+        .first()
+        .is_some_and(|migration| migration.from == schema_version),
+      "index schema {schema_version} can't be migrated, the index must be rebuilt",
+    );
+
+    let progress_bar =
+      if cfg!(test) || log_enabled!(log::Level::Info) || settings.integration_test() {
+        None
+      } else {
+        let progress_bar = ProgressBar::new(migrations.len().try_into().unwrap());
+        progress_bar.set_style(
+          ProgressStyle::with_template("[migrating index] {wide_bar} {pos}/{len} {msg}").unwrap(),
+        );
+        Some(progress_bar)
+      };
+
+    let wtx = database.begin_write()?;
+
+    let migrator = Migrator {
+      rtx: &rtx,
+      wtx: &wtx,
+    };
+
+    for migration in &migrations {
+      log::info!(
+        "Migrating index from schema {} to {}: {}",
+        migration.from,
+        migration.from + 1,
+        migration.description,
+      );
+
+      if let Some(progress_bar) = &progress_bar {
+        progress_bar.set_message(migration.description);
+      }
+
+      (migration.migrate)(&migrator).with_context(|| {
+        format!(
+          "failed to migrate index from schema {} to {}",
+          migration.from,
+          migration.from + 1,
+        )
+      })?;
+
+      if let Some(progress_bar) = &progress_bar {
+        progress_bar.inc(1);
+      }
+    }
+
+    wtx
+      .open_table(STATISTIC_TO_COUNT)?
+      .insert(&Statistic::Schema.key(), &SCHEMA_VERSION)?;
+
+    if dry_run {
+      wtx.abort()?;
+    } else {
+      wtx.commit()?;
+    }
+
+    if let Some(progress_bar) = progress_bar {
+      progress_bar.finish_and_clear();
+    }
+
+    Ok((
+      schema_version,
+      migrations
+        .iter()
+        .map(|migration| migration.description)
+        .collect(),
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn migrations_are_contiguous_up_to_schema_version() {
+    for (migration, next) in MIGRATIONS.iter().zip(MIGRATIONS.iter().skip(1)) {
+      assert_eq!(migration.from + 1, next.from);
+    }
+
+    assert_eq!(MIGRATIONS.last().unwrap().from + 1, SCHEMA_VERSION);
+  }
+
+  fn downgrade(settings: &Settings, schema_version: u64, tables: &[&str]) {
+    let database = Database::open(settings.index()).unwrap();
+    let wtx = database.begin_write().unwrap();
+
+    for table in tables {
+      wtx
+        .delete_table(TableDefinition::<u64, u64>::new(table))
+        .unwrap();
+    }
+
+    wtx
+      .open_table(STATISTIC_TO_COUNT)
+      .unwrap()
+      .insert(&Statistic::Schema.key(), &schema_version)
+      .unwrap();
+
+    wtx.commit().unwrap();
+  }
+
+  #[test]
+  fn index_is_migrated_in_place() {
+    let context = testing::Context::builder().build();
+
+    context.mine_blocks(1);
+
+    let settings = context.index.settings.clone();
+
+    drop(context.index);
+
+    downgrade(
+      &settings,
+      31,
+      &[
+        BRC20V2_TICKER_TO_TOKEN.name(),
+        HEIGHT_AND_NUMBER_TO_EVENT.name(),
+        SEQUENCE_NUMBER_AND_INDEX_TO_TRANSFER.name(),
+      ],
+    );
+
+    let migrations = vec![
+      "add BRC-20 v2, event, transfer history, address history and search indices, \
+      and discard unvalidated offers",
+    ];
+
+    assert_eq!(
+      Index::migrate(&settings, true).unwrap(),
+      (31, migrations.clone()),
+    );
+
+    assert_eq!(Index::migrate(&settings, false).unwrap(), (31, migrations),);
+
+    assert_eq!(
+      Index::migrate(&settings, false).unwrap(),
+      (SCHEMA_VERSION, Vec::new())
+    );
+
+    let index = Index::open(&settings).unwrap();
+
+    assert!(index.get_events(0, u32::MAX).unwrap().is_empty());
+  }
+
+  #[test]
+  fn migrations_that_cannot_be_recovered_fail() {
+    let context = testing::Context::builder().arg("--index-events").build();
+
+    let settings = context.index.settings.clone();
+
+    drop(context.index);
+
+    downgrade(&settings, 31, &[HEIGHT_AND_NUMBER_TO_EVENT.name()]);
+
+    assert_eq!(
+      format!("{:#}", Index::migrate(&settings, false).unwrap_err()),
+      "failed to migrate index from schema 31 to 32: events of indexed blocks can't be recovered, \
+      the index must be rebuilt with `--index-events`",
+    );
+
+    downgrade(&settings, 30, &[]);
+
+    assert_eq!(
+      Index::migrate(&settings, false).unwrap_err().to_string(),
+      "index schema 30 can't be migrated, the index must be rebuilt",
+    );
+  }
+}
@@ -0,0 +1,786 @@
+use {
+  super::*,
+  bitcoin::{
+    EcdsaSighashType, PublicKey, TapSighashType, ecdsa,
+    psbt::{self, raw::ProprietaryKey},
+    secp256k1::{Message, Secp256k1, Verification, XOnlyPublicKey},
+    sighash::{Prevouts, SighashCache},
+    taproot,
+  },
+  redb::ReadTransaction,
+};
+
+/// Why an offer was rejected. Sell offers sign every input with
+/// `SIGHASH_SINGLE|ANYONECANPAY`, committing only to the output at the same
+/// index, so that a buyer can add inputs to pay for it and outputs to receive
+/// it. Buy offers leave the inputs they want to buy unsigned, for their owner
+/// to sign, and sign the inputs that pay for them with `SIGHASH_ALL`. Offers
+/// carry an expiry height in a global proprietary PSBT field with prefix `ord`
+/// and subtype 0, and can only be included in blocks below it, see
+/// `Index::is_offer_expired`.
+#[derive(Debug, PartialEq)]
+pub(crate) enum OfferError {
+  Expired {
+    expiry: u32,
+    height: u32,
+  },
+  InvalidExpiry,
+  InvalidSignature {
+    outpoint: OutPoint,
+  },
+  MissingExpiry,
+  MissingOutput {
+    outpoint: OutPoint,
+  },
+  NoAssets {
+    outpoint: OutPoint,
+  },
+  NoInputs,
+  SighashType {
+    outpoint: OutPoint,
+    required: EcdsaSighashType,
+    sighash_type: EcdsaSighashType,
+  },
+  UnrecognizedSignature {
+    outpoint: OutPoint,
+  },
+  Unsigned {
+    outpoint: OutPoint,
+  },
+}
+
+impl Display for OfferError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Expired { expiry, height } => {
+        write!(
+          f,
+          "offer expired at height {expiry}, next block is {height}"
+        )
+      }
+      Self::InvalidExpiry => write!(f, "offer expiry is not a little-endian u32"),
+      Self::InvalidSignature { outpoint } => write!(
+        f,
+        "offer input {outpoint} has a signature that is not valid for the output it spends"
+      ),
+      Self::MissingExpiry => write!(f, "offer has no expiry height"),
+      Self::MissingOutput { outpoint } => {
+        write!(f, "offer input {outpoint} has no output at the same index")
+      }
+      Self::NoAssets { outpoint } => {
+        write!(
+          f,
+          "offer input {outpoint} does not hold any inscriptions or runes"
+        )
+      }
+      Self::NoInputs => write!(f, "offer has no inputs"),
+      Self::SighashType {
+        outpoint,
+        required,
+        sighash_type,
+      } => write!(
+        f,
+        "offer input {outpoint} is signed with {sighash_type}, {} offers must be signed with \
+        {required}",
+        if *required == EcdsaSighashType::All {
+          "buy"
+        } else {
+          "sell"
+        },
+      ),
+      Self::UnrecognizedSignature { outpoint } => {
+        write!(
+          f,
+          "offer input {outpoint} has a signature that can't be parsed"
+        )
+      }
+      Self::Unsigned { outpoint } => write!(f, "offer input {outpoint} is not signed"),
+    }
+  }
+}
+
+impl std::error::Error for OfferError {}
+
+/// A signature on an offer input, which must spend a P2WPKH or P2TR output.
+enum Signature {
+  Ecdsa(PublicKey, ecdsa::Signature),
+  Taproot(taproot::Signature),
+}
+
+impl Signature {
+  /// Taproot `SIGHASH_DEFAULT` is reported as `SIGHASH_ALL`, which it is
+  /// equivalent to.
+  fn sighash_type(&self) -> EcdsaSighashType {
+    match self {
+      Self::Ecdsa(_, signature) => signature.sighash_type,
+      Self::Taproot(signature) => EcdsaSighashType::from_consensus(match signature.sighash_type {
+        TapSighashType::Default => EcdsaSighashType::All.to_u32(),
+        sighash_type => u32::from(sighash_type as u8),
+      }),
+    }
+  }
+
+  /// Whether this signs input `index` of `tx`, which spends `prevout`.
+  fn verify<C: Verification>(
+    &self,
+    secp: &Secp256k1<C>,
+    cache: &mut SighashCache<&Transaction>,
+    index: usize,
+    prevout: &TxOut,
+  ) -> bool {
+    match self {
+      Self::Ecdsa(public_key, signature) => {
+        let Ok(pubkey_hash) = public_key.wpubkey_hash() else {
+          return false;
+        };
+
+        if prevout.script_pubkey != ScriptBuf::new_p2wpkh(&pubkey_hash) {
+          return false;
+        }
+
+        let Ok(sighash) = cache.p2wpkh_signature_hash(
+          index,
+          &prevout.script_pubkey,
+          prevout.value,
+          signature.sighash_type,
+        ) else {
+          return false;
+        };
+
+        secp
+          .verify_ecdsa(
+            &Message::from(sighash),
+            &signature.signature,
+            &public_key.inner,
+          )
+          .is_ok()
+      }
+      Self::Taproot(signature) => {
+        if !prevout.script_pubkey.is_p2tr() {
+          return false;
+        }
+
+        let Ok(output_key) = XOnlyPublicKey::from_slice(&prevout.script_pubkey.as_bytes()[2..])
+        else {
+          return false;
+        };
+
+        let Ok(sighash) = cache.taproot_key_spend_signature_hash(
+          index,
+          &Prevouts::One(index, prevout),
+          signature.sighash_type,
+        ) else {
+          return false;
+        };
+
+        secp
+          .verify_schnorr(&signature.signature, &Message::from(sighash), &output_key)
+          .is_ok()
+      }
+    }
+  }
+}
+
+impl Index {
+  /// Key of the global proprietary PSBT field holding an offer's expiry
+  /// height.
+  pub(crate) fn offer_expiry_key() -> ProprietaryKey {
+    ProprietaryKey {
+      prefix: b"ord".to_vec(),
+      subtype: 0,
+      key: Vec::new(),
+    }
+  }
+
+  /// Height at which `offer` expires.
+  pub(crate) fn offer_expiry(offer: &Psbt) -> Result<u32, OfferError> {
+    let expiry = offer
+      .proprietary
+      .get(&Self::offer_expiry_key())
+      .ok_or(OfferError::MissingExpiry)?;
+
+    Ok(u32::from_le_bytes(
+      expiry
+        .as_slice()
+        .try_into()
+        .map_err(|_| OfferError::InvalidExpiry)?,
+    ))
+  }
+
+  /// Whether an offer that expires at `expiry` has expired when the next
+  /// block is at `next_height`. Offers can only be included in blocks below
+  /// their expiry height, so they are rejected when submitted, and removed
+  /// from the offer book, once the next block is at their expiry height.
+  pub(crate) fn is_offer_expired(expiry: u32, next_height: u32) -> bool {
+    expiry <= next_height
+  }
+
+  /// Validates `offer` and adds it to the offer book, returning its number.
+  /// Validation failures are returned as `OfferError`s. Offers are validated
+  /// in the write transaction that adds them, so that they can't be added
+  /// after a block that spends their inputs or expires them was indexed.
+  pub(crate) fn submit_offer(&self, offer: &Psbt) -> Result<u64> {
+    if offer.unsigned_tx.input.is_empty() {
+      return Err(OfferError::NoInputs.into());
+    }
+
+    let wtx = self.begin_write()?;
+
+    let number = match self.validate_offer(&wtx, offer) {
+      Ok(expiry) => {
+        let mut expiry_to_offer_number = wtx.open_multimap_table(EXPIRY_TO_OFFER_NUMBER)?;
+        let mut number_to_offer = wtx.open_table(NUMBER_TO_OFFER)?;
+        let mut outpoint_to_offer_number = wtx.open_multimap_table(OUTPOINT_TO_OFFER_NUMBER)?;
+
+        let number = number_to_offer
+          .last()?
+          .map(|(number, _offer)| number.value() + 1)
+          .unwrap_or(0);
+
+        number_to_offer.insert(number, offer.serialize().as_slice())?;
+
+        for txin in &offer.unsigned_tx.input {
+          outpoint_to_offer_number.insert(&txin.previous_output.store(), number)?;
+        }
+
+        expiry_to_offer_number.insert(expiry, number)?;
+
+        number
+      }
+      Err(err) => {
+        wtx.abort()?;
+        return Err(err);
+      }
+    };
+
+    wtx.commit()?;
+
+    Ok(number)
+  }
+
+  /// Checks that `offer` is a sell or buy offer for inscriptions or runes
+  /// that haven't been spent, returning its expiry height. The signatures of
+  /// buy offers can't be checked until the inputs they are for are signed,
+  /// and are checked by the seller when they accept it.
+  fn validate_offer(&self, wtx: &WriteTransaction, offer: &Psbt) -> Result<u32> {
+    let outpoint_to_rune_balances = wtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
+    let outpoint_to_utxo_entry = wtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+
+    let signatures = offer
+      .unsigned_tx
+      .input
+      .iter()
+      .zip(&offer.inputs)
+      .map(|(txin, input)| {
+        Self::signatures(input).ok_or(OfferError::UnrecognizedSignature {
+          outpoint: txin.previous_output,
+        })
+      })
+      .collect::<Result<Vec<Vec<Signature>>, OfferError>>()?;
+
+    // an offer must be signed by whoever is paying for it
+    if signatures.iter().all(Vec::is_empty) {
+      return Err(
+        OfferError::Unsigned {
+          outpoint: offer.unsigned_tx.input[0].previous_output,
+        }
+        .into(),
+      );
+    }
+
+    let buy = signatures.iter().any(Vec::is_empty);
+
+    let required = if buy {
+      EcdsaSighashType::All
+    } else {
+      EcdsaSighashType::SinglePlusAnyoneCanPay
+    };
+
+    let secp = Secp256k1::verification_only();
+    let mut cache = SighashCache::new(&offer.unsigned_tx);
+
+    for (i, (txin, signatures)) in offer.unsigned_tx.input.iter().zip(&signatures).enumerate() {
+      let outpoint = txin.previous_output;
+
+      for signature in signatures {
+        let sighash_type = signature.sighash_type();
+        if sighash_type != required {
+          return Err(
+            OfferError::SighashType {
+              outpoint,
+              required,
+              sighash_type,
+            }
+            .into(),
+          );
+        }
+      }
+
+      if buy && !signatures.is_empty() {
+        continue;
+      }
+
+      if !buy && i >= offer.unsigned_tx.output.len() {
+        return Err(OfferError::MissingOutput { outpoint }.into());
+      }
+
+      // both tables only hold unspent outputs, so spent inputs hold nothing
+      let inscriptions = self.index_inscriptions
+        && outpoint_to_utxo_entry
+          .get(&outpoint.store())?
+          .is_some_and(|entry| !entry.value().parse(self).inscriptions().is_empty());
+
+      let runes = self.index_runes && outpoint_to_rune_balances.get(&outpoint.store())?.is_some();
+
+      if !inscriptions && !runes {
+        return Err(OfferError::NoAssets { outpoint }.into());
+      }
+
+      if buy {
+        continue;
+      }
+
+      let prevout = self
+        .get_transaction(outpoint.txid)?
+        .and_then(|tx| tx.output.into_iter().nth(outpoint.vout.into_usize()))
+        .ok_or_else(|| anyhow!("output {outpoint} not found"))?;
+
+      for signature in signatures {
+        if !signature.verify(&secp, &mut cache, i, &prevout) {
+          return Err(OfferError::InvalidSignature { outpoint }.into());
+        }
+      }
+    }
+
+    let expiry = Self::offer_expiry(offer)?;
+
+    let height = wtx
+      .open_table(HEIGHT_TO_BLOCK_HEADER)?
+      .last()?
+      .map(|(height, _header)| height.value() + 1)
+      .unwrap_or(0);
+
+    if Self::is_offer_expired(expiry, height) {
+      return Err(OfferError::Expired { expiry, height }.into());
+    }
+
+    Ok(expiry)
+  }
+
+  /// Signatures on `input`, or `None` if it was finalized with a script
+  /// whose signatures can't be parsed, or signed with a script path, which
+  /// offers don't support.
+  fn signatures(input: &psbt::Input) -> Option<Vec<Signature>> {
+    if let Some(witness) = &input.final_script_witness {
+      return match witness.len() {
+        1 => taproot::Signature::from_slice(&witness[0])
+          .ok()
+          .map(|signature| vec![Signature::Taproot(signature)]),
+        2 => Some(vec![Signature::Ecdsa(
+          PublicKey::from_slice(&witness[1]).ok()?,
+          ecdsa::Signature::from_slice(&witness[0]).ok()?,
+        )]),
+        _ => None,
+      };
+    }
+
+    if input.final_script_sig.is_some() || !input.tap_script_sigs.is_empty() {
+      return None;
+    }
+
+    Some(
+      input
+        .partial_sigs
+        .iter()
+        .map(|(public_key, signature)| Signature::Ecdsa(*public_key, *signature))
+        .chain(input.tap_key_sig.map(Signature::Taproot))
+        .collect(),
+    )
+  }
+
+  fn get_offers_by_number(rtx: &ReadTransaction, numbers: BTreeSet<u64>) -> Result<Vec<Vec<u8>>> {
+    let number_to_offer = rtx.open_table(NUMBER_TO_OFFER)?;
+
+    numbers
+      .into_iter()
+      .map(|number| Ok(number_to_offer.get(number)?.unwrap().value().to_vec()))
+      .collect()
+  }
+
+  /// Offers for the output an inscription is in, or `None` if there is no
+  /// such inscription.
+  pub(crate) fn get_offers_for_inscription(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<Vec<Vec<u8>>>> {
+    let rtx = self.database.begin_read()?;
+
+    let Some(sequence_number) = rtx
+      .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+      .get(&inscription_id.store())?
+      .map(|sequence_number| sequence_number.value())
+    else {
+      return Ok(None);
+    };
+
+    let satpoint = SatPoint::load(
+      *rtx
+        .open_table(SEQUENCE_NUMBER_TO_SATPOINT)?
+        .get(sequence_number)?
+        .unwrap()
+        .value(),
+    );
+
+    let numbers = rtx
+      .open_multimap_table(OUTPOINT_TO_OFFER_NUMBER)?
+      .get(&satpoint.outpoint.store())?
+      .map(|number| number.map(|number| number.value()))
+      .collect::<Result<BTreeSet<u64>, StorageError>>()?;
+
+    Ok(Some(Self::get_offers_by_number(&rtx, numbers)?))
+  }
+
+  /// Offers for outputs holding `rune`, or `None` if there is no such rune.
+  /// Outputs holding a rune aren't indexed by rune, so this checks the
+  /// balances of every offered output.
+  pub(crate) fn get_offers_for_rune(&self, rune: Rune) -> Result<Option<Vec<Vec<u8>>>> {
+    let rtx = self.database.begin_read()?;
+
+    let Some(id) = rtx
+      .open_table(RUNE_TO_RUNE_ID)?
+      .get(rune.store())?
+      .map(|id| RuneId::load(id.value()))
+    else {
+      return Ok(None);
+    };
+
+    let outpoint_to_rune_balances = rtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
+
+    let mut numbers = BTreeSet::new();
+
+    for result in rtx.open_multimap_table(OUTPOINT_TO_OFFER_NUMBER)?.iter()? {
+      let (outpoint, offer_numbers) = result?;
+
+      let Some(balances) = outpoint_to_rune_balances.get(outpoint.value())? else {
+        continue;
+      };
+
+      let balances = balances.value();
+
+      let mut i = 0;
+      let mut holds_rune = false;
+      while i < balances.len() {
+        let ((balance_id, _balance), len) = Index::decode_rune_balance(&balances[i..])?;
+        i += len;
+        holds_rune |= balance_id == id;
+      }
+
+      if holds_rune {
+        for number in offer_numbers {
+          numbers.insert(number?.value());
+        }
+      }
+    }
+
+    Ok(Some(Self::get_offers_by_number(&rtx, numbers)?))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn offers_are_validated_and_pruned_when_spent() {
+    let context = testing::Context::builder().build();
+
+    context.mine_blocks(1);
+
+    let keypair = offer_keypair(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      recipient: Some(offer_address(&keypair)),
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let id = InscriptionId { txid, index: 0 };
+    let outpoint = OutPoint { txid, vout: 0 };
+    let prevout = context.core.tx_by_id(txid).output[0].clone();
+
+    let offer =
+      |sighash_type, expiry| sell_offer(outpoint, &prevout, &keypair, sighash_type, expiry);
+
+    assert_eq!(
+      context
+        .index
+        .submit_offer(&offer(TapSighashType::All, 100))
+        .unwrap_err()
+        .to_string(),
+      format!(
+        "offer input {outpoint} is signed with SIGHASH_ALL, \
+        sell offers must be signed with SIGHASH_SINGLE|SIGHASH_ANYONECANPAY"
+      ),
+    );
+
+    let empty = OutPoint {
+      txid: context.core.tx(2, 0).compute_txid(),
+      vout: 0,
+    };
+
+    assert_eq!(
+      context
+        .index
+        .submit_offer(&sell_offer(
+          empty,
+          &prevout,
+          &keypair,
+          TapSighashType::SinglePlusAnyoneCanPay,
+          100,
+        ))
+        .unwrap_err()
+        .to_string(),
+      format!("offer input {empty} does not hold any inscriptions or runes"),
+    );
+
+    let mut unexpiring = offer(TapSighashType::SinglePlusAnyoneCanPay, 100);
+
+    unexpiring.proprietary.clear();
+
+    assert_eq!(
+      context
+        .index
+        .submit_offer(&unexpiring)
+        .unwrap_err()
+        .to_string(),
+      "offer has no expiry height",
+    );
+
+    assert_eq!(
+      context
+        .index
+        .submit_offer(&offer(TapSighashType::SinglePlusAnyoneCanPay, 3))
+        .unwrap_err()
+        .to_string(),
+      "offer expired at height 3, next block is 3",
+    );
+
+    let offer = offer(TapSighashType::SinglePlusAnyoneCanPay, 100);
+
+    assert_eq!(context.index.submit_offer(&offer).unwrap(), 0);
+
+    assert_eq!(
+      context.index.get_offers_for_inscription(id).unwrap(),
+      Some(vec![offer.serialize()]),
+    );
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Default::default())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context.index.get_offers_for_inscription(id).unwrap(),
+      Some(Vec::new()),
+    );
+
+    assert!(context.index.get_offers().unwrap().is_empty());
+  }
+
+  #[test]
+  fn forged_signatures_are_rejected() {
+    let context = testing::Context::builder().build();
+
+    context.mine_blocks(1);
+
+    let keypair = offer_keypair(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      recipient: Some(offer_address(&keypair)),
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let outpoint = OutPoint { txid, vout: 0 };
+    let prevout = context.core.tx_by_id(txid).output[0].clone();
+
+    let error =
+      format!("offer input {outpoint} has a signature that is not valid for the output it spends");
+
+    let forged = sell_offer(
+      outpoint,
+      &prevout,
+      &offer_keypair(2),
+      TapSighashType::SinglePlusAnyoneCanPay,
+      100,
+    );
+
+    assert_eq!(
+      context.index.submit_offer(&forged).unwrap_err().to_string(),
+      error,
+    );
+
+    let wrong_value = sell_offer(
+      outpoint,
+      &TxOut {
+        value: prevout.value + Amount::from_sat(1),
+        script_pubkey: prevout.script_pubkey.clone(),
+      },
+      &keypair,
+      TapSighashType::SinglePlusAnyoneCanPay,
+      100,
+    );
+
+    assert_eq!(
+      context
+        .index
+        .submit_offer(&wrong_value)
+        .unwrap_err()
+        .to_string(),
+      error,
+    );
+
+    let mut tampered = sell_offer(
+      outpoint,
+      &prevout,
+      &keypair,
+      TapSighashType::SinglePlusAnyoneCanPay,
+      100,
+    );
+
+    tampered.unsigned_tx.output[0].value = Amount::from_sat(1);
+
+    assert_eq!(
+      context
+        .index
+        .submit_offer(&tampered)
+        .unwrap_err()
+        .to_string(),
+      error,
+    );
+
+    assert!(context.index.get_offers().unwrap().is_empty());
+  }
+
+  #[test]
+  fn offers_are_pruned_when_they_expire() {
+    let context = testing::Context::builder().build();
+
+    context.mine_blocks(1);
+
+    let keypair = offer_keypair(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      recipient: Some(offer_address(&keypair)),
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let outpoint = OutPoint { txid, vout: 0 };
+    let prevout = context.core.tx_by_id(txid).output[0].clone();
+
+    let offer = |expiry| {
+      sell_offer(
+        outpoint,
+        &prevout,
+        &keypair,
+        TapSighashType::SinglePlusAnyoneCanPay,
+        expiry,
+      )
+    };
+
+    context.index.submit_offer(&offer(4)).unwrap();
+    context.index.submit_offer(&offer(5)).unwrap();
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context.index.get_offers().unwrap(),
+      vec![offer(5).serialize()]
+    );
+
+    context.mine_blocks(1);
+
+    assert!(context.index.get_offers().unwrap().is_empty());
+  }
+
+  #[test]
+  fn buy_offers_are_validated() {
+    let context = testing::Context::builder().build();
+
+    context.mine_blocks(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let id = InscriptionId { txid, index: 0 };
+    let inscription = OutPoint { txid, vout: 0 };
+    let payment = OutPoint {
+      txid: context.core.tx(2, 0).compute_txid(),
+      vout: 0,
+    };
+    let prevout = context.core.tx(2, 0).output[0].clone();
+
+    let offer = |outpoint, sighash_type| {
+      let mut offer = sell_offer(payment, &prevout, &offer_keypair(1), sighash_type, 100);
+      offer.unsigned_tx.input.insert(0, tx_in(outpoint));
+      offer.inputs.insert(0, psbt::Input::default());
+      offer
+    };
+
+    assert_eq!(
+      context
+        .index
+        .submit_offer(&offer(inscription, TapSighashType::SinglePlusAnyoneCanPay))
+        .unwrap_err()
+        .to_string(),
+      format!(
+        "offer input {payment} is signed with SIGHASH_SINGLE|SIGHASH_ANYONECANPAY, \
+        buy offers must be signed with SIGHASH_ALL"
+      ),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .submit_offer(&offer(payment, TapSighashType::All))
+        .unwrap_err()
+        .to_string(),
+      format!("offer input {payment} does not hold any inscriptions or runes"),
+    );
+
+    let mut unsigned = offer(inscription, TapSighashType::All);
+
+    unsigned.inputs[1].tap_key_sig = None;
+
+    assert_eq!(
+      context
+        .index
+        .submit_offer(&unsigned)
+        .unwrap_err()
+        .to_string(),
+      format!("offer input {inscription} is not signed"),
+    );
+
+    let offer = offer(inscription, TapSighashType::All);
+
+    assert_eq!(context.index.submit_offer(&offer).unwrap(), 0);
+
+    assert_eq!(
+      context.index.get_offers_for_inscription(id).unwrap(),
+      Some(vec![offer.serialize()]),
+    );
+  }
+}
@@ -0,0 +1,456 @@
+use {super::*, std::iter};
+
+/// Longest term that is indexed, in bytes. Longer runs of alphanumeric
+/// characters, like hashes and base64, are unlikely to be searched for.
+const MAX_TERM_LEN: usize = 32;
+
+/// Filters that search results must match in addition to the query terms.
+/// Rarity is only known for inscriptions on sats, which are only tracked when
+/// the sat index is enabled.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Facets {
+  pub(crate) charm: Option<Charm>,
+  pub(crate) content_type: Option<String>,
+  pub(crate) max_height: Option<u32>,
+  pub(crate) min_height: Option<u32>,
+  pub(crate) parent: Option<InscriptionId>,
+  pub(crate) rarity: Option<Rarity>,
+}
+
+impl Facets {
+  pub(crate) fn is_empty(&self) -> bool {
+    *self == Self::default()
+  }
+
+  /// Whether searching for `query` with these facets could only be answered
+  /// by checking every inscription, because neither the query, content type
+  /// nor parent narrow down the candidates.
+  pub(crate) fn require_scan(&self, query: &str) -> bool {
+    !self.is_empty()
+      && self.content_type.is_none()
+      && self.parent.is_none()
+      && tokenize(query).next().is_none()
+      && !has_unindexed_term(query)
+  }
+
+  fn matches(&self, entry: &InscriptionEntry) -> bool {
+    self.charm.is_none_or(|charm| charm.is_set(entry.charms))
+      && self
+        .rarity
+        .is_none_or(|rarity| entry.sat.map(Sat::rarity) == Some(rarity))
+      && self
+        .min_height
+        .is_none_or(|min_height| entry.height >= min_height)
+      && self
+        .max_height
+        .is_none_or(|max_height| entry.height <= max_height)
+  }
+}
+
+/// Lowercased runs of alphanumeric characters in `text`, which is how both
+/// inscriptions and queries are split into terms.
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+  text
+    .split(|c: char| !c.is_alphanumeric())
+    .filter(|term| !term.is_empty() && term.len() <= MAX_TERM_LEN)
+    .map(str::to_lowercase)
+}
+
+/// Whether `query` has a term longer than `MAX_TERM_LEN`, which no
+/// inscription can be found by.
+fn has_unindexed_term(query: &str) -> bool {
+  query
+    .split(|c: char| !c.is_alphanumeric())
+    .any(|term| term.len() > MAX_TERM_LEN)
+}
+
+/// Content types are indexed as a single term, without parameters, with a
+/// prefix that tokenizing never produces, so that they are only matched by
+/// the content type facet.
+fn content_type_term(content_type: &str) -> String {
+  format!(
+    "content-type:{}",
+    content_type
+      .split(';')
+      .next()
+      .unwrap_or_default()
+      .trim()
+      .to_lowercase()
+  )
+}
+
+/// Terms an inscription can be found by: the words of its body, if it is
+/// uncompressed text, markdown or code, of its title and traits, and of its
+/// metaprotocol, as well as its content type.
+pub(super) fn inscription_terms(inscription: &Inscription) -> BTreeSet<String> {
+  let mut terms = BTreeSet::new();
+
+  if matches!(
+    inscription.media(),
+    Media::Code(_) | Media::Markdown | Media::Text
+  ) && inscription.content_encoding().is_none()
+    && let Some(body) = inscription.body()
+  {
+    terms.extend(tokenize(&String::from_utf8_lossy(body)));
+  }
+
+  let attributes = inscription.properties().attributes;
+
+  if let Some(title) = &attributes.title {
+    terms.extend(tokenize(title));
+  }
+
+  for (name, value) in &attributes.traits.items {
+    terms.extend(tokenize(name));
+    terms.extend(tokenize(&value.to_string()));
+  }
+
+  if let Some(metaprotocol) = inscription.metaprotocol() {
+    terms.extend(tokenize(metaprotocol));
+  }
+
+  if let Some(content_type) = inscription.content_type() {
+    terms.insert(content_type_term(content_type));
+  }
+
+  terms
+}
+
+/// Values in every one of `lists`, which must be in descending order, in
+/// descending order. Each list is only advanced past values greater than the
+/// current candidate, so lists are read no further than the last value
+/// taken.
+fn intersect<I: Iterator<Item = Result<u32>>>(
+  mut lists: Vec<I>,
+) -> impl Iterator<Item = Result<u32>> {
+  iter::from_fn(move || {
+    let n = lists.len();
+
+    let mut candidate = match lists.first_mut()?.next()? {
+      Ok(value) => value,
+      Err(err) => return Some(Err(err)),
+    };
+
+    let mut matched = 1;
+    let mut i = 1 % n;
+
+    while matched < n {
+      loop {
+        let value = match lists[i].next()? {
+          Ok(value) => value,
+          Err(err) => return Some(Err(err)),
+        };
+
+        if value == candidate {
+          matched += 1;
+          break;
+        }
+
+        if value < candidate {
+          candidate = value;
+          matched = 1;
+          break;
+        }
+      }
+
+      i = (i + 1) % n;
+    }
+
+    Some(Ok(candidate))
+  })
+}
+
+impl Index {
+  pub fn has_search_index(&self) -> bool {
+    self.index_inscriptions && self.index_search
+  }
+
+  /// Inscriptions that have every term of `query` and match `facets`, most
+  /// recent first. Candidates are found by walking the posting lists of the
+  /// indexed terms, content type and parent from the most recent inscription
+  /// down, before the remaining facets are checked against their entries.
+  /// Searches with facets that can't be narrowed down this way would check
+  /// every inscription, and fail. Queries with terms too long to be indexed
+  /// match nothing.
+  pub(crate) fn search_inscriptions_paginated(
+    &self,
+    query: &str,
+    facets: &Facets,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
+    if has_unindexed_term(query) {
+      return Ok((Vec::new(), false));
+    }
+
+    let rtx = self.database.begin_read()?;
+
+    let search_term_to_sequence_number = rtx.open_multimap_table(SEARCH_TERM_TO_SEQUENCE_NUMBER)?;
+    let sequence_number_to_entry = rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let mut terms = tokenize(query).collect::<BTreeSet<String>>();
+
+    if let Some(content_type) = &facets.content_type {
+      terms.insert(content_type_term(content_type));
+    }
+
+    let mut lists = Vec::new();
+
+    for term in &terms {
+      lists.push(search_term_to_sequence_number.get(term.as_str())?);
+    }
+
+    if let Some(parent) = facets.parent {
+      let Some(parent) = rtx
+        .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+        .get(&parent.store())?
+        .map(|sequence_number| sequence_number.value())
+      else {
+        return Ok((Vec::new(), false));
+      };
+
+      lists.push(
+        rtx
+          .open_multimap_table(SEQUENCE_NUMBER_TO_CHILDREN)?
+          .get(parent)?,
+      );
+    }
+
+    ensure!(
+      !facets.require_scan(query),
+      "searches with facets require a query, content type or parent",
+    );
+
+    let sequence_numbers: Box<dyn Iterator<Item = Result<u32>> + '_> = if lists.is_empty() {
+      Box::new(
+        sequence_number_to_entry
+          .iter()?
+          .rev()
+          .map(|result| Ok(result?.0.value())),
+      )
+    } else {
+      Box::new(intersect(
+        lists
+          .into_iter()
+          .map(|list| {
+            list
+              .rev()
+              .map(|sequence_number| Ok(sequence_number?.value()))
+          })
+          .collect(),
+      ))
+    };
+
+    let mut skip = page_index.saturating_mul(page_size);
+    let mut inscriptions = Vec::new();
+
+    for sequence_number in sequence_numbers {
+      let entry = InscriptionEntry::load(
+        sequence_number_to_entry
+          .get(sequence_number?)?
+          .unwrap()
+          .value(),
+      );
+
+      if !facets.matches(&entry) {
+        continue;
+      }
+
+      if skip > 0 {
+        skip -= 1;
+        continue;
+      }
+
+      inscriptions.push(entry.id);
+
+      if inscriptions.len() > page_size {
+        break;
+      }
+    }
+
+    let more = inscriptions.len() > page_size;
+
+    if more {
+      inscriptions.pop();
+    }
+
+    Ok((inscriptions, more))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn inscriptions_are_tokenized() {
+    assert_eq!(
+      inscription_terms(&Inscription {
+        content_type: Some("text/markdown;charset=utf-8".into()),
+        body: Some("# Hello, World!\n\nhello again".into()),
+        metaprotocol: Some("brc-20".into()),
+        properties: Properties {
+          attributes: Attributes {
+            title: Some("Greeting".into()),
+            traits: Traits {
+              items: vec![("mood".into(), Trait::String("Cheerful".into()))],
+            },
+          },
+          ..default()
+        }
+        .to_cbor(),
+        ..default()
+      }),
+      [
+        "20",
+        "again",
+        "brc",
+        "cheerful",
+        "content-type:text/markdown",
+        "greeting",
+        "hello",
+        "mood",
+        "world",
+      ]
+      .into_iter()
+      .map(str::to_string)
+      .collect(),
+    );
+
+    assert_eq!(
+      inscription_terms(&inscription("image/png", "hello")),
+      ["content-type:image/png".to_string()].into(),
+    );
+  }
+
+  #[test]
+  fn search_index_flag_is_read_from_the_index() {
+    let testing::Context { index, tempdir, .. } = testing::Context::builder().build();
+
+    drop(index);
+
+    let context = testing::Context::builder()
+      .arg("--index-search")
+      .tempdir(tempdir)
+      .build();
+
+    assert!(!context.index.has_search_index());
+  }
+
+  #[test]
+  fn inscriptions_are_searched_by_terms_and_facets() {
+    let context = testing::Context::builder().arg("--index-search").build();
+
+    context.mine_blocks(1);
+
+    let hello = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        inscription("text/plain", "Hello world").to_witness(),
+      )],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let goodbye = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        2,
+        0,
+        0,
+        inscription("text/markdown", "goodbye world").to_witness(),
+      )],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let hello = InscriptionId {
+      txid: hello,
+      index: 0,
+    };
+
+    let goodbye = InscriptionId {
+      txid: goodbye,
+      index: 0,
+    };
+
+    let search = |query: &str, facets: Facets| {
+      context
+        .index
+        .search_inscriptions_paginated(query, &facets, 100, 0)
+        .unwrap()
+        .0
+    };
+
+    assert_eq!(search("world", Facets::default()), [goodbye, hello]);
+    assert_eq!(search("HELLO, world", Facets::default()), [hello]);
+    assert!(search("farewell", Facets::default()).is_empty());
+    assert!(search(&"a".repeat(MAX_TERM_LEN + 1), Facets::default()).is_empty());
+    assert!(
+      search(
+        &format!("world {}", "a".repeat(MAX_TERM_LEN + 1)),
+        Facets {
+          max_height: Some(2),
+          ..default()
+        }
+      )
+      .is_empty()
+    );
+
+    assert_eq!(
+      search(
+        "world",
+        Facets {
+          content_type: Some("Text/Markdown".into()),
+          ..default()
+        }
+      ),
+      [goodbye],
+    );
+
+    assert!(
+      context
+        .index
+        .search_inscriptions_paginated(
+          "",
+          &Facets {
+            max_height: Some(2),
+            ..default()
+          },
+          100,
+          0,
+        )
+        .is_err()
+    );
+
+    assert!(
+      search(
+        "world",
+        Facets {
+          min_height: Some(4),
+          ..default()
+        }
+      )
+      .is_empty()
+    );
+
+    assert_eq!(
+      context
+        .index
+        .search_inscriptions_paginated("world", &Facets::default(), 1, 0)
+        .unwrap(),
+      (vec![goodbye], true),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .search_inscriptions_paginated("world", &Facets::default(), 1, 1)
+        .unwrap(),
+      (vec![hello], false),
+    );
+  }
+}
@@ -65,11 +65,6 @@ impl ContextBuilder {
     self.tempdir = Some(tempdir);
     self
   }
-
-  pub(crate) fn event_sender(mut self, sender: tokio::sync::mpsc::Sender<Event>) -> Self {
-    self.event_sender = Some(sender);
-    self
-  }
 }
 
 pub(crate) struct Context {
@@ -103,19 +98,6 @@ impl Context {
     blocks
   }
 
-  pub(crate) fn mine_blocks_with_subsidy(&self, n: u64, subsidy: u64) -> Vec<Block> {
-    let blocks = self.core.mine_blocks_with_subsidy(n, subsidy);
-    self.index.update().unwrap();
-    blocks
-  }
-
-  pub(crate) fn configurations() -> Vec<Context> {
-    vec![
-      Context::builder().build(),
-      Context::builder().arg("--index-sats").build(),
-    ]
-  }
-
   #[track_caller]
   pub(crate) fn assert_runes(
     &self,
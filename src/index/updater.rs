@@ -1,16 +1,25 @@
 use {
-  self::{inscription_updater::InscriptionUpdater, rune_updater::RuneUpdater},
-  super::{fetcher::Fetcher, *},
-  futures::future::try_join_all,
-  tokio::sync::{
-    broadcast::{self, error::TryRecvError},
-    mpsc::{self},
+  self::{
+    address_history::AddressHistory, brc20v2_updater::Brc20v2Updater, event_emitter::EventEmitter,
+    inscription_updater::InscriptionUpdater, metrics::Metrics, prefetcher::Prefetcher,
+    rune_updater::RuneUpdater,
   },
+  super::{block_files::BlockFiles, *},
+  crate::brc20v2::{IdentityVerifier, IdentityVerifiers},
+  tokio::sync::mpsc,
 };
 
+mod address_history;
+mod backfill;
+mod brc20v2_updater;
+mod event_emitter;
 mod inscription_updater;
+mod metrics;
+mod prefetcher;
 mod rune_updater;
 
+const BLOCK_FILES_MIN_BLOCKS: u64 = 1000;
+
 pub(crate) struct BlockData {
   pub(crate) header: Header,
   pub(crate) txdata: Vec<(Transaction, Txid)>,
@@ -71,21 +80,35 @@ impl Updater<'_> {
       Some(progress_bar)
     };
 
-    let rx = Self::fetch_blocks_from(self.index, self.height)?;
+    let metrics = Arc::new(Metrics::default());
+
+    let mut prefetcher = Prefetcher::new(
+      self.index,
+      Self::fetch_blocks_from(self.index, self.height, metrics.clone())?,
+      metrics.clone(),
+    )?;
 
-    let (mut output_sender, mut txout_receiver) = Self::spawn_fetcher(self.index)?;
+    let identity_verifier = if self.index.has_brc20v2_index() {
+      self.index.settings.brc20v2_identity_verifier()?
+    } else {
+      IdentityVerifiers::default()
+    };
 
     let mut uncommitted = 0;
     let mut utxo_cache = HashMap::new();
-    while let Ok(block) = rx.recv() {
+    while let Some(block) = prefetcher.next_block(&wtx, &utxo_cache)? {
+      let start = Instant::now();
+
       self.index_block(
-        &mut output_sender,
-        &mut txout_receiver,
+        &mut prefetcher,
         &mut wtx,
         block,
         &mut utxo_cache,
+        &identity_verifier,
       )?;
 
+      metrics.index.record(1, start.elapsed());
+
       if let Some(progress_bar) = &mut progress_bar {
         progress_bar.inc(1);
 
@@ -104,7 +127,8 @@ impl Updater<'_> {
         || (!self.index.settings.integration_test()
           && Reorg::is_savepoint_required(self.index, self.height)?)
       {
-        self.commit(wtx, utxo_cache)?;
+        prefetcher.prefetch_during_commit(&wtx, &utxo_cache)?;
+        self.commit(wtx, utxo_cache, &metrics)?;
         utxo_cache = HashMap::new();
         uncommitted = 0;
         wtx = self.index.begin_write()?;
@@ -143,19 +167,24 @@ impl Updater<'_> {
     }
 
     if uncommitted > 0 {
-      self.commit(wtx, utxo_cache)?;
+      self.commit(wtx, utxo_cache, &metrics)?;
+    } else {
+      // backfilling begins its own write transaction, which would wait for
+      // this one forever
+      wtx.abort()?;
     }
 
     if let Some(progress_bar) = &mut progress_bar {
       progress_bar.finish_and_clear();
     }
 
-    Ok(())
+    self.backfill()
   }
 
   fn fetch_blocks_from(
     index: &Index,
     mut height: u32,
+    metrics: Arc<Metrics>,
   ) -> Result<std::sync::mpsc::Receiver<BlockData>> {
     let (tx, rx) = std::sync::mpsc::sync_channel(32);
 
@@ -165,6 +194,19 @@ impl Updater<'_> {
 
     let client = index.settings.bitcoin_rpc_client(None)?;
 
+    // scanning the block files takes a while, so they are only read from
+    // when far enough behind the tip for it to pay off
+    let block_files = if index.settings.read_block_files()
+      && client.get_block_count()? >= u64::from(height) + BLOCK_FILES_MIN_BLOCKS
+    {
+      Some(BlockFiles::open(
+        &index.settings.bitcoin_blocks_dir(),
+        index.settings.chain().network(),
+      )?)
+    } else {
+      None
+    };
+
     thread::spawn(move || {
       loop {
         if let Some(height_limit) = height_limit
@@ -173,9 +215,20 @@ impl Updater<'_> {
           break;
         }
 
-        match Self::get_block_with_retries(&client, height, first_index_height) {
+        let start = Instant::now();
+
+        match Self::get_block_with_retries(
+          &client,
+          block_files.as_ref(),
+          height,
+          first_index_height,
+        ) {
           Ok(Some(block)) => {
-            if let Err(err) = tx.send(block.into()) {
+            let block = BlockData::from(block);
+
+            metrics.fetch.record(1, start.elapsed());
+
+            if let Err(err) = tx.send(block) {
               log::info!("Block receiver disconnected: {err}");
               break;
             }
@@ -195,6 +248,7 @@ impl Updater<'_> {
 
   fn get_block_with_retries(
     client: &Client,
+    block_files: Option<&BlockFiles>,
     height: u32,
     first_index_height: u32,
   ) -> Result<Option<Block>> {
@@ -207,7 +261,16 @@ impl Updater<'_> {
           option
             .map(|hash| {
               if height >= first_index_height {
-                Ok(client.get_block(&hash)?)
+                // blocks that have not been flushed to the block files yet
+                // are fetched over RPC
+                match block_files
+                  .map(|block_files| block_files.get(hash))
+                  .transpose()?
+                  .flatten()
+                {
+                  Some(block) => Ok(block),
+                  None => Ok(client.get_block(&hash)?),
+                }
               } else {
                 Ok(Block {
                   header: client.get_block_header(&hash)?,
@@ -238,86 +301,13 @@ impl Updater<'_> {
     }
   }
 
-  fn spawn_fetcher(index: &Index) -> Result<(mpsc::Sender<OutPoint>, broadcast::Receiver<TxOut>)> {
-    let fetcher = Fetcher::new(&index.settings)?;
-
-    // A block probably has no more than 20k inputs
-    const CHANNEL_BUFFER_SIZE: usize = 20_000;
-
-    // Batch 2048 missing inputs at a time, arbitrarily chosen size
-    const BATCH_SIZE: usize = 2048;
-
-    let (outpoint_sender, mut outpoint_receiver) = mpsc::channel::<OutPoint>(CHANNEL_BUFFER_SIZE);
-
-    let (txout_sender, txout_receiver) = broadcast::channel::<TxOut>(CHANNEL_BUFFER_SIZE);
-
-    // Default rpcworkqueue in bitcoind is 16, meaning more than 16 concurrent requests will be rejected.
-    // Since we are already requesting blocks on a separate thread, and we don't want to break if anything
-    // else runs a request, we keep this to 12.
-    let parallel_requests: usize = index.settings.bitcoin_rpc_limit().try_into().unwrap();
-
-    thread::spawn(move || {
-      let rt = tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .unwrap();
-      rt.block_on(async move {
-        loop {
-          let Some(outpoint) = outpoint_receiver.recv().await else {
-            log::debug!("Outpoint channel closed");
-            return;
-          };
-
-          // There's no try_iter on tokio::sync::mpsc::Receiver like std::sync::mpsc::Receiver.
-          // So we just loop until BATCH_SIZE doing try_recv until it returns None.
-          let mut outpoints = vec![outpoint];
-          for _ in 0..BATCH_SIZE - 1 {
-            let Ok(outpoint) = outpoint_receiver.try_recv() else {
-              break;
-            };
-            outpoints.push(outpoint);
-          }
-
-          // Break outputs into chunks for parallel requests
-          let chunk_size = (outpoints.len() / parallel_requests) + 1;
-          let mut futs = Vec::with_capacity(parallel_requests);
-          for chunk in outpoints.chunks(chunk_size) {
-            let txids = chunk.iter().map(|outpoint| outpoint.txid).collect();
-            let fut = fetcher.get_transactions(txids);
-            futs.push(fut);
-          }
-
-          let txs = match try_join_all(futs).await {
-            Ok(txs) => txs,
-            Err(e) => {
-              log::error!("Couldn't receive txs {e}");
-              return;
-            }
-          };
-
-          // Send all tx outputs back in order
-          for (i, tx) in txs.iter().flatten().enumerate() {
-            let Ok(_) =
-              txout_sender.send(tx.output[usize::try_from(outpoints[i].vout).unwrap()].clone())
-            else {
-              log::error!("Value channel closed unexpectedly");
-              return;
-            };
-          }
-        }
-      })
-    });
-
-    Ok((outpoint_sender, txout_receiver))
-  }
-
   fn index_block(
     &mut self,
-    output_sender: &mut mpsc::Sender<OutPoint>,
-    txout_receiver: &mut broadcast::Receiver<TxOut>,
+    prefetcher: &mut Prefetcher,
     wtx: &mut WriteTransaction,
     block: BlockData,
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
+    identity_verifier: &dyn IdentityVerifier,
   ) -> Result<()> {
     Reorg::detect_reorg(&block, self.height, self.index)?;
 
@@ -337,59 +327,106 @@ impl Updater<'_> {
       wtx.open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
     let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
 
+    let mut event_log = if self.index.has_event_index() {
+      Some(wtx.open_table(HEIGHT_AND_NUMBER_TO_EVENT)?)
+    } else {
+      None
+    };
+
+    let mut event_emitter = EventEmitter {
+      event_log: event_log.as_mut(),
+      event_sender: self.index.event_sender.as_ref(),
+      next_number: 0,
+      tx_index: 0,
+    };
+
+    let mut address_history = self
+      .index
+      .has_address_history_index()
+      .then(|| AddressHistory::new(self.index));
+
+    // BRC-20 v2 operations are performed by the address their inscription is
+    // revealed to
+    let mut reveal_script_pubkeys = self.index.has_brc20v2_index().then(HashMap::new);
+
     if self.index.index_inscriptions || self.index.index_addresses || self.index.index_sats {
       self.index_utxo_entries(
         &block,
-        txout_receiver,
-        output_sender,
+        prefetcher,
         utxo_cache,
         wtx,
         &mut inscription_id_to_sequence_number,
         &mut statistic_to_count,
+        &mut event_emitter,
+        address_history.as_mut(),
+        reveal_script_pubkeys.as_mut(),
         &mut sat_ranges_written,
         &mut outputs_in_block,
       )?;
     }
 
-    if self.index.index_runes && self.height >= self.index.settings.first_rune_height() {
-      let mut outpoint_to_rune_balances = wtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
-      let mut rune_id_to_rune_entry = wtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
-      let mut rune_to_rune_id = wtx.open_table(RUNE_TO_RUNE_ID)?;
-      let mut sequence_number_to_rune_id = wtx.open_table(SEQUENCE_NUMBER_TO_RUNE_ID)?;
-      let mut transaction_id_to_rune = wtx.open_table(TRANSACTION_ID_TO_RUNE)?;
-
-      let runes = statistic_to_count
-        .get(&Statistic::Runes.into())?
-        .map(|x| x.value())
-        .unwrap_or(0);
-
-      let mut rune_updater = RuneUpdater {
-        event_sender: self.index.event_sender.as_ref(),
-        block_time: block.header.time,
-        burned: HashMap::new(),
-        client: &self.index.client,
+    // while the rune index is being backfilled, the backfill indexes runes in
+    // new blocks once it reaches them
+    if self.index.index_runes
+      && self.height >= self.index.settings.first_rune_height()
+      && statistic_to_count
+        .get(&Statistic::RunesBackfillHeight.key())?
+        .is_none()
+    {
+      Self::index_block_runes(
+        self.index,
+        wtx,
+        self.height,
+        &block,
+        &inscription_id_to_sequence_number,
+        &mut statistic_to_count,
+        &mut event_emitter,
+        address_history.as_mut(),
+      )?;
+    }
+
+    if let Some(address_history) = address_history {
+      address_history.write(wtx, self.height)?;
+    }
+
+    if let Some(reveal_script_pubkeys) = reveal_script_pubkeys
+      && self.height >= self.index.settings.first_inscription_height()
+    {
+      let mut brc20v2_account_to_state = wtx.open_table(BRC20V2_ACCOUNT_TO_STATE)?;
+      let mut brc20v2_allowance_to_amount = wtx.open_table(BRC20V2_ALLOWANCE_TO_AMOUNT)?;
+      let mut brc20v2_branch_to_hash = wtx.open_table(BRC20V2_BRANCH_TO_HASH)?;
+      let mut brc20v2_commitment_to_identity = wtx.open_table(BRC20V2_COMMITMENT_TO_IDENTITY)?;
+      let mut brc20v2_frozen_accounts = wtx.open_table(BRC20V2_FROZEN_ACCOUNTS)?;
+      let mut brc20v2_inscription_id_to_rejection =
+        wtx.open_table(BRC20V2_INSCRIPTION_ID_TO_REJECTION)?;
+      let mut brc20v2_leaf_to_hash = wtx.open_table(BRC20V2_LEAF_TO_HASH)?;
+      let mut brc20v2_ticker_to_token = wtx.open_table(BRC20V2_TICKER_TO_TOKEN)?;
+
+      let mut brc20v2_updater = Brc20v2Updater {
+        account_to_state: &mut brc20v2_account_to_state,
+        allowance_to_amount: &mut brc20v2_allowance_to_amount,
+        branch_to_hash: &mut brc20v2_branch_to_hash,
+        chain: self.index.settings.chain(),
+        changes: BTreeMap::new(),
+        commitment_to_identity: &mut brc20v2_commitment_to_identity,
+        frozen_accounts: &mut brc20v2_frozen_accounts,
+        inscription_id_to_rejection: &mut brc20v2_inscription_id_to_rejection,
         height: self.height,
-        id_to_entry: &mut rune_id_to_rune_entry,
-        inscription_id_to_sequence_number: &mut inscription_id_to_sequence_number,
-        minimum: Rune::minimum_at_height(
-          self.index.settings.chain().network(),
-          Height(self.height),
-        ),
-        outpoint_to_balances: &mut outpoint_to_rune_balances,
-        rune_to_id: &mut rune_to_rune_id,
-        runes,
-        sequence_number_to_rune_id: &mut sequence_number_to_rune_id,
-        statistic_to_count: &mut statistic_to_count,
-        transaction_id_to_rune: &mut transaction_id_to_rune,
+        identity_verifier,
+        leaf_to_hash: &mut brc20v2_leaf_to_hash,
+        state_machine: Default::default(),
+        ticker_to_token: &mut brc20v2_ticker_to_token,
       };
 
-      for (i, (tx, txid)) in block.txdata.iter().enumerate() {
-        rune_updater.index_runes(u32::try_from(i).unwrap(), tx, *txid)?;
+      for (tx, txid) in &block.txdata {
+        brc20v2_updater.index_brc20v2(tx, *txid, &reveal_script_pubkeys)?;
       }
 
-      rune_updater.update()?;
+      brc20v2_updater.update()?;
     }
 
+    Self::prune_offers(wtx, &block, self.height)?;
+
     height_to_block_header.insert(&self.height, &block.header.store())?;
 
     self.height += 1;
@@ -403,15 +440,119 @@ impl Updater<'_> {
     Ok(())
   }
 
+  fn index_block_runes<'wtx>(
+    index: &Index,
+    wtx: &'wtx WriteTransaction,
+    height: u32,
+    block: &BlockData,
+    inscription_id_to_sequence_number: &Table<'wtx, (u128, u128, u32), u32>,
+    statistic_to_count: &mut Table<'wtx, u64, u64>,
+    event_emitter: &mut EventEmitter,
+    address_history: Option<&mut AddressHistory>,
+  ) -> Result {
+    let mut outpoint_to_rune_balances = wtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
+    let mut rune_id_to_rune_entry = wtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+    let mut rune_to_rune_id = wtx.open_table(RUNE_TO_RUNE_ID)?;
+    let mut sequence_number_to_rune_id = wtx.open_table(SEQUENCE_NUMBER_TO_RUNE_ID)?;
+    let mut transaction_id_to_rune = wtx.open_table(TRANSACTION_ID_TO_RUNE)?;
+
+    let runes = statistic_to_count
+      .get(&Statistic::Runes.into())?
+      .map(|x| x.value())
+      .unwrap_or(0);
+
+    let mut rune_updater = RuneUpdater {
+      address_history,
+      block_time: block.header.time,
+      burned: HashMap::new(),
+      client: &index.client,
+      height,
+      id_to_entry: &mut rune_id_to_rune_entry,
+      inscription_id_to_sequence_number,
+      minimum: Rune::minimum_at_height(index.settings.chain().network(), Height(height)),
+      outpoint_to_balances: &mut outpoint_to_rune_balances,
+      rune_to_id: &mut rune_to_rune_id,
+      runes,
+      sequence_number_to_rune_id: &mut sequence_number_to_rune_id,
+      statistic_to_count,
+      transaction_id_to_rune: &mut transaction_id_to_rune,
+    };
+
+    for (i, (tx, txid)) in block.txdata.iter().enumerate() {
+      let tx_index = u32::try_from(i).unwrap();
+      event_emitter.tx_index = tx_index;
+      rune_updater.index_runes(tx_index, tx, *txid, event_emitter)?;
+    }
+
+    rune_updater.update()
+  }
+
+  /// Removes offers with inputs spent by `block`, which is at `height`, and
+  /// offers that have expired now that the next block is at `height + 1`,
+  /// neither of which can be accepted any longer. Offers are cancelled by
+  /// spending their inputs.
+  fn prune_offers(wtx: &WriteTransaction, block: &BlockData, height: u32) -> Result {
+    let mut outpoint_to_offer_number = wtx.open_multimap_table(OUTPOINT_TO_OFFER_NUMBER)?;
+
+    if outpoint_to_offer_number.is_empty()? {
+      return Ok(());
+    }
+
+    let mut expiry_to_offer_number = wtx.open_multimap_table(EXPIRY_TO_OFFER_NUMBER)?;
+    let mut number_to_offer = wtx.open_table(NUMBER_TO_OFFER)?;
+
+    let mut numbers = Vec::new();
+
+    for (tx, _txid) in &block.txdata {
+      for input in &tx.input {
+        for number in outpoint_to_offer_number.get(&input.previous_output.store())? {
+          numbers.push(number?.value());
+        }
+      }
+    }
+
+    for result in expiry_to_offer_number.iter()? {
+      let (expiry, expiring) = result?;
+
+      if !Index::is_offer_expired(expiry.value(), height.saturating_add(1)) {
+        break;
+      }
+
+      for number in expiring {
+        numbers.push(number?.value());
+      }
+    }
+
+    for number in numbers {
+      let Some(offer) = number_to_offer.remove(number)? else {
+        continue;
+      };
+
+      let offer = Psbt::deserialize(offer.value())?;
+
+      for txin in &offer.unsigned_tx.input {
+        outpoint_to_offer_number.remove(&txin.previous_output.store(), number)?;
+      }
+
+      if let Ok(expiry) = Index::offer_expiry(&offer) {
+        expiry_to_offer_number.remove(expiry, number)?;
+      }
+    }
+
+    Ok(())
+  }
+
   fn index_utxo_entries<'wtx>(
     &mut self,
     block: &BlockData,
-    txout_receiver: &mut broadcast::Receiver<TxOut>,
-    output_sender: &mut mpsc::Sender<OutPoint>,
+    prefetcher: &mut Prefetcher,
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
     wtx: &'wtx WriteTransaction,
     inscription_id_to_sequence_number: &mut Table<'wtx, (u128, u128, u32), u32>,
     statistic_to_count: &mut Table<'wtx, u64, u64>,
+    event_emitter: &mut EventEmitter,
+    mut address_history: Option<&mut AddressHistory>,
+    reveal_script_pubkeys: Option<&mut HashMap<InscriptionId, ScriptBuf>>,
     sat_ranges_written: &mut u64,
     outputs_in_block: &mut u64,
   ) -> Result {
@@ -428,51 +569,20 @@ impl Updater<'_> {
       wtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
     let mut transaction_id_to_transaction = wtx.open_table(TRANSACTION_ID_TO_TRANSACTION)?;
 
-    let index_inscriptions = self.height >= self.index.settings.first_inscription_height()
-      && self.index.index_inscriptions;
+    let mut sequence_number_and_index_to_transfer = if self.index.has_transfer_history_index() {
+      Some(wtx.open_table(SEQUENCE_NUMBER_AND_INDEX_TO_TRANSFER)?)
+    } else {
+      None
+    };
 
-    // If the receiver still has inputs something went wrong in the last
-    // block and we shouldn't recover from this and commit the last block
-    if index_inscriptions {
-      assert!(
-        matches!(txout_receiver.try_recv(), Err(TryRecvError::Empty)),
-        "Previous block did not consume all inputs"
-      );
-    }
+    let mut search_term_to_sequence_number = if self.index.has_search_index() {
+      Some(wtx.open_multimap_table(SEARCH_TERM_TO_SEQUENCE_NUMBER)?)
+    } else {
+      None
+    };
 
-    if !self.index.have_full_utxo_index() {
-      // Send all missing input outpoints to be fetched
-      let txids = block
-        .txdata
-        .iter()
-        .map(|(_, txid)| txid)
-        .collect::<HashSet<_>>();
-
-      for (tx, _) in &block.txdata {
-        for input in &tx.input {
-          let prev_output = input.previous_output;
-          // We don't need coinbase inputs
-          if prev_output.is_null() {
-            continue;
-          }
-          // We don't need inputs from txs earlier in the block, since
-          // they'll be added to cache when the tx is indexed
-          if txids.contains(&prev_output.txid) {
-            continue;
-          }
-          // We don't need inputs we already have in our cache from earlier blocks
-          if utxo_cache.contains_key(&prev_output) {
-            continue;
-          }
-          // We don't need inputs we already have in our database
-          if outpoint_to_utxo_entry.get(&prev_output.store())?.is_some() {
-            continue;
-          }
-          // Send this outpoint to background thread to be fetched
-          output_sender.blocking_send(prev_output)?;
-        }
-      }
-    }
+    let index_inscriptions = self.height >= self.index.settings.first_inscription_height()
+      && self.index.index_inscriptions;
 
     let mut lost_sats = statistic_to_count
       .get(&Statistic::LostSats.key())?
@@ -514,8 +624,11 @@ impl Updater<'_> {
       inscription_number_to_sequence_number: &mut inscription_number_to_sequence_number,
       lost_sats,
       next_sequence_number,
+      reveal_script_pubkeys,
       reward: Height(self.height).subsidy(),
       sat_to_sequence_number: &mut sat_to_sequence_number,
+      search_term_to_sequence_number: search_term_to_sequence_number.as_mut(),
+      sequence_number_and_index_to_transfer: sequence_number_and_index_to_transfer.as_mut(),
       sequence_number_to_children: &mut sequence_number_to_children,
       sequence_number_to_entry: &mut sequence_number_to_inscription_entry,
       timestamp: block.header.time,
@@ -567,12 +680,7 @@ impl Updater<'_> {
               entry.value().to_buf()
             } else {
               assert!(!self.index.have_full_utxo_index());
-              let txout = txout_receiver.blocking_recv().map_err(|err| {
-                anyhow!(
-                  "failed to get transaction for {}: {err}",
-                  input.previous_output
-                )
-              })?;
+              let txout = prefetcher.txout(input.previous_output)?;
 
               let mut entry = UtxoEntryBuf::new();
               entry.push_value(txout.value.to_sat(), self.index);
@@ -639,6 +747,8 @@ impl Updater<'_> {
       }
 
       if index_inscriptions {
+        event_emitter.tx_index = u32::try_from(tx_offset).unwrap();
+
         inscription_updater.index_inscriptions(
           tx,
           *txid,
@@ -646,10 +756,25 @@ impl Updater<'_> {
           &mut output_utxo_entries,
           utxo_cache,
           self.index,
+          event_emitter,
           input_sat_ranges.as_ref(),
         )?;
       }
 
+      if let Some(address_history) = &mut address_history {
+        let tx_index = u32::try_from(tx_offset).unwrap();
+
+        for (input, entry) in tx.input.iter().zip(&input_utxo_entries) {
+          address_history.spend(tx_index, *txid, input.previous_output, entry);
+        }
+
+        for (txout, entry) in tx.output.iter().zip(&output_utxo_entries) {
+          if !txout.script_pubkey.is_op_return() {
+            address_history.receive(tx_index, *txid, &entry.parse(self.index));
+          }
+        }
+      }
+
       for (vout, output_utxo_entry) in output_utxo_entries.into_iter().enumerate() {
         let vout = u32::try_from(vout).unwrap();
         utxo_cache.insert(OutPoint { txid: *txid, vout }, output_utxo_entry);
@@ -818,11 +943,19 @@ impl Updater<'_> {
     Ok(())
   }
 
+  /// Writes are not pipelined with indexing: redb allows only one write
+  /// transaction at a time, and the next block must be indexed in a write
+  /// transaction that sees this one's writes, so indexing waits for the
+  /// commit. Only fetching overlaps with it, see
+  /// `Prefetcher::prefetch_during_commit`.
   fn commit(
     &mut self,
     wtx: WriteTransaction,
     utxo_cache: HashMap<OutPoint, UtxoEntryBuf>,
+    metrics: &Metrics,
   ) -> Result {
+    let start = Instant::now();
+
     log::info!(
       "Committing at block height {}, {} outputs traversed, {} in map, {} cached",
       self.height,
@@ -873,6 +1006,9 @@ impl Updater<'_> {
 
     Reorg::update_savepoints(self.index, self.height)?;
 
+    metrics.commit.record(1, start.elapsed());
+    metrics.log();
+
     Ok(())
   }
 }
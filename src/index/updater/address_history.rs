@@ -0,0 +1,127 @@
+use super::*;
+
+/// Collects the activity of each script pubkey in a block as the block is
+/// indexed, which is written to the address history once the block's
+/// inscriptions and runes have been indexed.
+pub(super) struct AddressHistory {
+  activity: BTreeMap<(Vec<u8>, u32), AddressActivity>,
+  index_inscriptions: bool,
+  spent_script_pubkeys: HashMap<OutPoint, Vec<u8>>,
+}
+
+impl AddressHistory {
+  pub(super) fn new(index: &Index) -> Self {
+    Self {
+      activity: BTreeMap::new(),
+      index_inscriptions: index.index_inscriptions,
+      spent_script_pubkeys: HashMap::new(),
+    }
+  }
+
+  /// Sequence numbers of the inscriptions in `entry`, which only has
+  /// inscriptions if they are indexed.
+  fn inscriptions(&self, entry: &ParsedUtxoEntry) -> Vec<u32> {
+    if !self.index_inscriptions {
+      return Vec::new();
+    }
+
+    entry
+      .parse_inscriptions()
+      .into_iter()
+      .map(|(sequence_number, _offset)| sequence_number)
+      .collect()
+  }
+
+  fn activity(&mut self, script_pubkey: &[u8], tx_index: u32, txid: Txid) -> &mut AddressActivity {
+    self
+      .activity
+      .entry((script_pubkey.to_vec(), tx_index))
+      .or_insert_with(|| AddressActivity::new(txid))
+  }
+
+  pub(super) fn spend(
+    &mut self,
+    tx_index: u32,
+    txid: Txid,
+    outpoint: OutPoint,
+    entry: &ParsedUtxoEntry,
+  ) {
+    let script_pubkey = entry.script_pubkey();
+    let inscriptions = self.inscriptions(entry);
+
+    let activity = self.activity(script_pubkey, tx_index, txid);
+    activity.spent += entry.total_value();
+    activity.inscriptions_sent.extend(inscriptions);
+
+    self
+      .spent_script_pubkeys
+      .insert(outpoint, script_pubkey.to_vec());
+  }
+
+  pub(super) fn receive(&mut self, tx_index: u32, txid: Txid, entry: &ParsedUtxoEntry) {
+    let inscriptions = self.inscriptions(entry);
+
+    let activity = self.activity(entry.script_pubkey(), tx_index, txid);
+    activity.received += entry.total_value();
+    activity.inscriptions_received.extend(inscriptions);
+  }
+
+  /// Records runes spent from `outpoint`, which must have been spent by a
+  /// transaction in the block.
+  pub(super) fn spend_runes(
+    &mut self,
+    tx_index: u32,
+    txid: Txid,
+    outpoint: OutPoint,
+    id: RuneId,
+    amount: u128,
+  ) {
+    let script_pubkey = self.spent_script_pubkeys[&outpoint].clone();
+    Self::add_rune(
+      &mut self.activity(&script_pubkey, tx_index, txid).runes_sent,
+      id,
+      amount,
+    );
+  }
+
+  pub(super) fn receive_runes(
+    &mut self,
+    tx_index: u32,
+    txid: Txid,
+    script_pubkey: &Script,
+    id: RuneId,
+    amount: u128,
+  ) {
+    Self::add_rune(
+      &mut self
+        .activity(script_pubkey.as_bytes(), tx_index, txid)
+        .runes_received,
+      id,
+      amount,
+    );
+  }
+
+  fn add_rune(balances: &mut Vec<(RuneId, u128)>, id: RuneId, amount: u128) {
+    match balances
+      .iter_mut()
+      .find(|(balance_id, _)| *balance_id == id)
+    {
+      Some((_, balance)) => *balance += amount,
+      None => balances.push((id, amount)),
+    }
+  }
+
+  pub(super) fn write(self, wtx: &WriteTransaction, height: u32) -> Result {
+    let mut script_pubkey_height_and_tx_index_to_activity =
+      wtx.open_table(SCRIPT_PUBKEY_HEIGHT_AND_TX_INDEX_TO_ACTIVITY)?;
+
+    for ((script_pubkey, tx_index), activity) in self.activity {
+      script_pubkey_height_and_tx_index_to_activity.insert(
+        (script_pubkey.as_slice(), height, tx_index),
+        activity.store(),
+      )?;
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,161 @@
+use {super::*, crate::index::backfill::Feature};
+
+impl Updater<'_> {
+  /// Rescans blocks that were indexed before an index was enabled with `ord
+  /// index enable`, committing every `commit_interval` blocks. Yields when new
+  /// blocks arrive, so that backfilling doesn't hold up indexing the tip.
+  pub(super) fn backfill(&self) -> Result {
+    for feature in Feature::BACKFILLABLE {
+      loop {
+        if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+          return Ok(());
+        }
+
+        let Some(tip) = self.backfill_blocks(feature)? else {
+          break;
+        };
+
+        if u32::try_from(self.index.client.get_block_count()?).unwrap() >= tip
+          && self
+            .index
+            .height_limit
+            .is_none_or(|height_limit| tip < height_limit)
+        {
+          return Ok(());
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Backfills up to `commit_interval` blocks for `feature`. Returns the height
+  /// of the index tip if there are blocks left to backfill, or `None` if the
+  /// backfill has caught up or was paused by a reorg.
+  fn backfill_blocks(&self, feature: Feature) -> Result<Option<u32>> {
+    let statistic = feature.backfill_statistic();
+
+    let wtx = self.index.begin_write()?;
+
+    let Some(mut height) = wtx
+      .open_table(STATISTIC_TO_COUNT)?
+      .get(&statistic.key())?
+      .map(|height| u32::try_from(height.value()).unwrap())
+    else {
+      return Ok(None);
+    };
+
+    let tip = wtx
+      .open_table(HEIGHT_TO_BLOCK_HEADER)?
+      .last()?
+      .map(|(height, _header)| height.value() + 1)
+      .unwrap_or(0);
+
+    let commit_interval = u32::try_from(self.index.settings.commit_interval()).unwrap_or(u32::MAX);
+
+    let end = tip.min(height.saturating_add(commit_interval));
+
+    let blocks = if height < end {
+      Some(Self::fetch_blocks_from(
+        self.index,
+        height,
+        Arc::new(Metrics::default()),
+      )?)
+    } else {
+      None
+    };
+
+    let mut reorged = false;
+
+    while height < end {
+      let block = blocks
+        .as_ref()
+        .unwrap()
+        .recv()
+        .with_context(|| format!("failed to fetch block {height} to backfill {feature}"))?;
+
+      let indexed = wtx
+        .open_table(HEIGHT_TO_BLOCK_HEADER)?
+        .get(&height)?
+        .map(|header| Header::load(*header.value()).block_hash());
+
+      if indexed != Some(block.header.block_hash()) {
+        // the block was reorged after being indexed, so backfilling stops
+        // until the updater has handled the reorg
+        log::info!("Block {height} was reorged, pausing {feature} backfill");
+        reorged = true;
+        break;
+      }
+
+      log::info!("Backfilling {feature} for block {height}…");
+
+      match feature {
+        Feature::Runes => {
+          if height >= self.index.settings.first_rune_height() {
+            let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
+
+            Self::index_block_runes(
+              self.index,
+              &wtx,
+              height,
+              &block,
+              &wtx.open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?,
+              &mut statistic_to_count,
+              &mut EventEmitter {
+                event_log: None,
+                event_sender: None,
+                next_number: 0,
+                tx_index: 0,
+              },
+              None,
+            )?;
+          }
+        }
+        Feature::Transactions => {
+          let inscription_id_to_sequence_number =
+            wtx.open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
+          let mut transaction_id_to_transaction = wtx.open_table(TRANSACTION_ID_TO_TRANSACTION)?;
+
+          let mut buffer = Vec::new();
+
+          for (tx, txid) in &block.txdata {
+            // transactions are only stored if they create inscriptions, the
+            // first of which always has index zero
+            let inscription_id = InscriptionId {
+              txid: *txid,
+              index: 0,
+            };
+
+            if inscription_id_to_sequence_number
+              .get(inscription_id.store())?
+              .is_some()
+            {
+              tx.consensus_encode(&mut buffer)
+                .expect("in-memory writers don't error");
+              transaction_id_to_transaction.insert(&txid.store(), buffer.as_slice())?;
+              buffer.clear();
+            }
+          }
+        }
+      }
+
+      height += 1;
+    }
+
+    {
+      let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
+
+      if height >= tip {
+        log::info!("Finished backfilling {feature}");
+        statistic_to_count.remove(&statistic.key())?;
+      } else {
+        statistic_to_count.insert(&statistic.key(), &u64::from(height))?;
+      }
+    }
+
+    Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
+    wtx.commit()?;
+
+    Ok((!reorged && height < tip).then_some(tip))
+  }
+}
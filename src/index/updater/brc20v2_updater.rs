@@ -0,0 +1,344 @@
+use {
+  super::*,
+  crate::brc20v2::{
+    Brc20Inscription, Brc20StateMachine, IdentityVerifier, Operation, Rejection,
+    brc20v2::Change,
+    merkle::{self, PartialTree, Tree},
+    zk::PlaceholderZk,
+  },
+};
+
+pub(super) struct Brc20v2Updater<'a, 'tx> {
+  pub(super) account_to_state:
+    &'a mut Table<'tx, (&'static str, &'static [u8; 32]), Brc20v2AccountValue>,
+  pub(super) allowance_to_amount:
+    &'a mut Table<'tx, (&'static str, &'static [u8; 32], &'static [u8; 32]), u128>,
+  pub(super) branch_to_hash:
+    &'a mut Table<'tx, (&'static str, u16, &'static [u8; 32]), &'static [u8; 32]>,
+  pub(super) chain: Chain,
+  /// Changes made to each token that operations were applied to.
+  pub(super) changes: BTreeMap<String, Vec<Change>>,
+  pub(super) commitment_to_identity: &'a mut Table<'tx, &'static [u8; 32], &'static str>,
+  pub(super) frozen_accounts: &'a mut Table<'tx, (&'static str, &'static [u8; 32]), ()>,
+  pub(super) height: u32,
+  pub(super) identity_verifier: &'a dyn IdentityVerifier,
+  pub(super) inscription_id_to_rejection:
+    &'a mut Table<'tx, InscriptionIdValue, Brc20v2RejectionValue>,
+  pub(super) leaf_to_hash: &'a mut Table<'tx, (&'static str, &'static [u8; 32]), &'static [u8; 32]>,
+  pub(super) state_machine: Brc20StateMachine,
+  pub(super) ticker_to_token: &'a mut Table<'tx, &'static str, Brc20v2TokenEntryValue>,
+}
+
+impl<'tx> Brc20v2Updater<'_, 'tx> {
+  /// Apply the operations inscribed in `tx`. Each is performed by the
+  /// address its inscription was revealed to, according to
+  /// `reveal_script_pubkeys`, and has no owner if it was unbound, lost, or
+  /// revealed to a script without an address.
+  pub(super) fn index_brc20v2(
+    &mut self,
+    tx: &Transaction,
+    txid: Txid,
+    reveal_script_pubkeys: &HashMap<InscriptionId, ScriptBuf>,
+  ) -> Result {
+    for (index, envelope) in ParsedEnvelope::from_transaction(tx).into_iter().enumerate() {
+      let Some(payload) = envelope.payload.body().and_then(Brc20Inscription::parse) else {
+        continue;
+      };
+
+      let id = InscriptionId {
+        txid,
+        index: u32::try_from(index).unwrap(),
+      };
+
+      let owner = reveal_script_pubkeys
+        .get(&id)
+        .and_then(|script_pubkey| self.chain.address_from_script(script_pubkey).ok())
+        .map(|address| IdentityCommitment::new(address.to_string()));
+
+      let result = match payload {
+        Ok(payload) => {
+          self.load(&payload.tick)?;
+
+          let decimals = self
+            .state_machine
+            .tokens
+            .get(&payload.tick)
+            .map(|token| token.definition.decimals);
+
+          match payload.into_operation(decimals) {
+            Ok(operation) => {
+              self.load_accounts(&operation)?;
+              self.apply(operation, owner.as_ref())
+            }
+            Err(rejection) => Err(rejection),
+          }
+        }
+        Err(rejection) => Err(rejection),
+      };
+
+      if let Err(rejection) = result {
+        log::debug!(
+          "rejected BRC-20 v2 operation in {id} at height {}: {rejection}",
+          self.height
+        );
+
+        self
+          .inscription_id_to_rejection
+          .insert(&id.store(), rejection.store())?;
+      }
+    }
+
+    Ok(())
+  }
+
+  fn apply(
+    &mut self,
+    operation: Operation,
+    owner: Option<&IdentityCommitment>,
+  ) -> Result<(), Rejection> {
+    let ticker = operation.ticker();
+
+    self.state_machine.apply_operation(
+      operation,
+      &PlaceholderZk,
+      self.identity_verifier,
+      owner,
+      self.height,
+    )?;
+
+    let changes = self
+      .state_machine
+      .tokens
+      .get_mut(&ticker)
+      .unwrap()
+      .take_changes();
+
+    self.changes.entry(ticker).or_default().extend(changes);
+
+    Ok(())
+  }
+
+  /// Load `ticker`, without its accounts, unless it is already loaded.
+  fn load(&mut self, ticker: &str) -> Result {
+    if self.state_machine.tokens.contains_key(ticker) {
+      return Ok(());
+    }
+
+    let Some(entry) = self.ticker_to_token.get(ticker)? else {
+      return Ok(());
+    };
+
+    let entry = Brc20v2TokenEntry::load(entry.value());
+
+    let tree = PartialTree::new(&self.tree(ticker), merkle::DEPTH)?;
+
+    let token = Index::load_brc20v2_roles(
+      TokenState::with_tree(entry.definition(ticker.into()), entry.total_supply, tree),
+      entry.admin,
+      self.allowance_to_amount,
+      self.commitment_to_identity,
+      self.frozen_accounts,
+    )?;
+
+    self.state_machine.tokens.insert(ticker.into(), token);
+
+    Ok(())
+  }
+
+  /// Load the accounts that `operation` may change, along with the paths to
+  /// their leaves.
+  fn load_accounts(&mut self, operation: &Operation) -> Result {
+    let ticker = operation.ticker();
+
+    let Some(token) = self.state_machine.tokens.get_mut(&ticker) else {
+      return Ok(());
+    };
+
+    let tree = Brc20v2Tree {
+      branch_to_hash: &*self.branch_to_hash,
+      leaf_to_hash: &*self.leaf_to_hash,
+      namespace: &ticker,
+    };
+
+    for identity in operation.accounts() {
+      if token.is_loaded(identity) {
+        continue;
+      }
+
+      let account = self
+        .account_to_state
+        .get((ticker.as_str(), &identity.commitment))?
+        .map(|account| AccountState::load(account.value()));
+
+      token.load_account(identity, account, &tree)?;
+    }
+
+    Ok(())
+  }
+
+  fn tree<'a>(
+    &'a self,
+    namespace: &'a str,
+  ) -> Brc20v2Tree<
+    'a,
+    Table<'tx, (&'static str, u16, &'static [u8; 32]), &'static [u8; 32]>,
+    Table<'tx, (&'static str, &'static [u8; 32]), &'static [u8; 32]>,
+  > {
+    Brc20v2Tree {
+      branch_to_hash: &*self.branch_to_hash,
+      leaf_to_hash: &*self.leaf_to_hash,
+      namespace,
+    }
+  }
+
+  /// Write the leaves of `tree` at `keys`, and the branches above them, under
+  /// `namespace`.
+  fn write_tree(&mut self, namespace: &str, tree: &Tree, keys: &BTreeSet<[u8; 32]>) -> Result {
+    for key in keys {
+      let leaf = tree.leaf(key);
+
+      if leaf == merkle::EMPTY_LEAF {
+        self.leaf_to_hash.remove((namespace, key))?;
+      } else {
+        self.leaf_to_hash.insert((namespace, key), &leaf)?;
+      }
+    }
+
+    for ((height, prefix), hash) in tree.branches(&self.tree(namespace), keys)? {
+      match hash {
+        Some(hash) => self
+          .branch_to_hash
+          .insert((namespace, height, &prefix), &hash)?,
+        None => self.branch_to_hash.remove((namespace, height, &prefix))?,
+      };
+    }
+
+    Ok(())
+  }
+
+  fn insert_identity(&mut self, identity: &IdentityCommitment) -> Result {
+    if self
+      .commitment_to_identity
+      .get(&identity.commitment)?
+      .is_none()
+    {
+      self
+        .commitment_to_identity
+        .insert(&identity.commitment, identity.id.as_str())?;
+    }
+
+    Ok(())
+  }
+
+  /// Write the tokens that operations were applied to, and the changes made
+  /// to them, along with their trees and the tree of their state hashes.
+  pub(super) fn update(mut self) -> Result {
+    let mut states = PartialTree::new(&self.tree(""), merkle::DEPTH)?;
+    let mut state_keys = BTreeSet::new();
+
+    for (ticker, changes) in mem::take(&mut self.changes) {
+      let token = &self.state_machine.tokens[&ticker];
+      let ticker = ticker.as_str();
+
+      let mut holders = self
+        .ticker_to_token
+        .get(ticker)?
+        .map(|entry| Brc20v2TokenEntry::load(entry.value()).holders)
+        .unwrap_or_default();
+
+      // whether each changed account held a balance before the first change
+      let mut held = BTreeMap::new();
+
+      let mut keys = BTreeSet::new();
+      let mut identities = token
+        .admin
+        .iter()
+        .cloned()
+        .collect::<BTreeSet<IdentityCommitment>>();
+
+      for change in changes {
+        match change {
+          Change::Account { identity, previous } => {
+            held
+              .entry(identity.clone())
+              .or_insert(previous.is_some_and(|account| account.balance > 0));
+
+            match token.accounts.get(&identity) {
+              Some(account) => self
+                .account_to_state
+                .insert((ticker, &identity.commitment), account.clone().store())?,
+              None => self
+                .account_to_state
+                .remove((ticker, &identity.commitment))?,
+            };
+
+            keys.insert(identity.commitment);
+            identities.insert(identity);
+          }
+          Change::Allowance { owner, spender, .. } => {
+            let amount = token.allowance(&owner, &spender);
+            let key = (ticker, &owner.commitment, &spender.commitment);
+
+            if amount == 0 {
+              self.allowance_to_amount.remove(key)?;
+            } else {
+              self.allowance_to_amount.insert(key, amount)?;
+            }
+
+            identities.insert(owner);
+            identities.insert(spender);
+          }
+          Change::Frozen { identity, .. } => {
+            if token.frozen.contains(&identity) {
+              self
+                .frozen_accounts
+                .insert((ticker, &identity.commitment), ())?;
+            } else {
+              self
+                .frozen_accounts
+                .remove((ticker, &identity.commitment))?;
+            }
+
+            identities.insert(identity);
+          }
+          Change::Admin(_) | Change::Soulbound(_) | Change::TotalSupply(_) => {}
+        }
+      }
+
+      for (identity, held) in held {
+        let holds = token
+          .accounts
+          .get(&identity)
+          .is_some_and(|account| account.balance > 0);
+
+        match (held, holds) {
+          (false, true) => holders += 1,
+          (true, false) => holders -= 1,
+          _ => {}
+        }
+      }
+
+      self
+        .ticker_to_token
+        .insert(ticker, Brc20v2TokenEntry::new(token, holders).store())?;
+
+      for identity in &identities {
+        self.insert_identity(identity)?;
+      }
+
+      let token = self.state_machine.tokens.remove(ticker).unwrap();
+
+      self.write_tree(ticker, token.tree(), &keys)?;
+
+      let key = brc20v2::state_key(ticker);
+
+      states.load(&self.tree(""), &key)?;
+      states.insert(&key, token.state_hash());
+      state_keys.insert(key);
+    }
+
+    self.write_tree("", &Tree::Partial(states), &state_keys)?;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,32 @@
+use {super::*, crate::index::event::EventRecord};
+
+/// Sends events to the index's event sender and, if the index records them,
+/// appends them to the event log, numbered in the order they were emitted.
+pub(super) struct EventEmitter<'a, 'tx> {
+  pub(super) event_log: Option<&'a mut Table<'tx, (u32, u32), EventRecordValue>>,
+  pub(super) event_sender: Option<&'a mpsc::Sender<Event>>,
+  pub(super) next_number: u32,
+  pub(super) tx_index: u32,
+}
+
+impl EventEmitter<'_, '_> {
+  pub(super) fn emit(&mut self, event: Event) -> Result {
+    if let Some(event_log) = &mut self.event_log {
+      let record = EventRecord {
+        event: event.clone(),
+        number: self.next_number,
+        tx_index: self.tx_index,
+      };
+
+      event_log.insert((record.event.block_height(), record.number), record.store())?;
+
+      self.next_number += 1;
+    }
+
+    if let Some(sender) = self.event_sender {
+      sender.blocking_send(event)?;
+    }
+
+    Ok(())
+  }
+}
@@ -28,6 +28,7 @@ enum Origin {
     hidden: bool,
     parents: Vec<InscriptionId>,
     reinscription: bool,
+    search_terms: BTreeSet<String>,
     unbound: bool,
     vindicated: bool,
   },
@@ -48,10 +49,14 @@ pub(super) struct InscriptionUpdater<'a, 'tx> {
   pub(super) inscription_number_to_sequence_number: &'a mut Table<'tx, i32, u32>,
   pub(super) lost_sats: u64,
   pub(super) next_sequence_number: u32,
+  pub(super) reveal_script_pubkeys: Option<&'a mut HashMap<InscriptionId, ScriptBuf>>,
   pub(super) reward: u64,
   pub(super) transaction_buffer: Vec<u8>,
   pub(super) transaction_id_to_transaction: &'a mut Table<'tx, &'static TxidValue, &'static [u8]>,
   pub(super) sat_to_sequence_number: &'a mut MultimapTable<'tx, u64, u32>,
+  pub(super) search_term_to_sequence_number: Option<&'a mut MultimapTable<'tx, &'static str, u32>>,
+  pub(super) sequence_number_and_index_to_transfer:
+    Option<&'a mut Table<'tx, (u32, u32), InscriptionTransferValue>>,
   pub(super) sequence_number_to_children: &'a mut MultimapTable<'tx, u32, u32>,
   pub(super) sequence_number_to_entry: &'a mut Table<'tx, u32, InscriptionEntryValue>,
   pub(super) timestamp: u32,
@@ -67,6 +72,7 @@ impl InscriptionUpdater<'_, '_> {
     output_utxo_entries: &mut [UtxoEntryBuf],
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
     index: &Index,
+    event_emitter: &mut EventEmitter,
     input_sat_ranges: Option<&Vec<&[u8]>>,
   ) -> Result {
     let mut floating_inscriptions = Vec::new();
@@ -201,6 +207,11 @@ impl InscriptionUpdater<'_, '_> {
             hidden: inscription.payload.hidden(),
             parents: inscription.payload.parents(),
             reinscription: inscribed_offsets.contains_key(&offset),
+            search_terms: if self.search_term_to_sequence_number.is_some() {
+              search::inscription_terms(&inscription.payload)
+            } else {
+              BTreeSet::new()
+            },
             unbound: input_value == 0
               || curse == Some(Curse::UnrecognizedEvenField)
               || inscription.payload.unrecognized_even_field,
@@ -302,10 +313,22 @@ impl InscriptionUpdater<'_, '_> {
     }
 
     for (new_satpoint, flotsam, op_return) in new_locations.into_iter() {
+      if let Some(reveal_script_pubkeys) = &mut self.reveal_script_pubkeys
+        && let Origin::New { unbound: false, .. } = flotsam.origin
+      {
+        reveal_script_pubkeys.insert(
+          flotsam.inscription_id,
+          tx.output[usize::try_from(new_satpoint.outpoint.vout).unwrap()]
+            .script_pubkey
+            .clone(),
+        );
+      }
+
       let output_utxo_entry =
         &mut output_utxo_entries[usize::try_from(new_satpoint.outpoint.vout).unwrap()];
 
       self.update_inscription_location(
+        txid,
         input_sat_ranges,
         flotsam,
         new_satpoint,
@@ -313,6 +336,7 @@ impl InscriptionUpdater<'_, '_> {
         Some(output_utxo_entry),
         utxo_cache,
         index,
+        event_emitter,
       )?;
     }
 
@@ -323,6 +347,7 @@ impl InscriptionUpdater<'_, '_> {
           offset: self.lost_sats + flotsam.offset - output_value,
         };
         self.update_inscription_location(
+          txid,
           input_sat_ranges,
           flotsam,
           new_satpoint,
@@ -330,6 +355,7 @@ impl InscriptionUpdater<'_, '_> {
           None,
           utxo_cache,
           index,
+          event_emitter,
         )?;
       }
       self.lost_sats += self.reward - output_value;
@@ -366,6 +392,7 @@ impl InscriptionUpdater<'_, '_> {
 
   fn update_inscription_location(
     &mut self,
+    txid: Txid,
     input_sat_ranges: Option<&Vec<&[u8]>>,
     flotsam: Flotsam,
     new_satpoint: SatPoint,
@@ -373,8 +400,15 @@ impl InscriptionUpdater<'_, '_> {
     mut normal_output_utxo_entry: Option<&mut UtxoEntryBuf>,
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
     index: &Index,
+    event_emitter: &mut EventEmitter,
   ) -> Result {
     let inscription_id = flotsam.inscription_id;
+
+    let old_satpoint = match flotsam.origin {
+      Origin::Old { old_satpoint, .. } => Some(old_satpoint),
+      Origin::New { .. } => None,
+    };
+
     let (unbound, sequence_number) = match flotsam.origin {
       Origin::Old {
         sequence_number,
@@ -398,15 +432,13 @@ impl InscriptionUpdater<'_, '_> {
           )?;
         }
 
-        if let Some(ref sender) = index.event_sender {
-          sender.blocking_send(Event::InscriptionTransferred {
-            block_height: self.height,
-            inscription_id,
-            new_location: new_satpoint,
-            old_location: old_satpoint,
-            sequence_number,
-          })?;
-        }
+        event_emitter.emit(Event::InscriptionTransferred {
+          block_height: self.height,
+          inscription_id,
+          new_location: new_satpoint,
+          old_location: old_satpoint,
+          sequence_number,
+        })?;
 
         (false, sequence_number)
       }
@@ -416,6 +448,7 @@ impl InscriptionUpdater<'_, '_> {
         hidden,
         parents,
         reinscription,
+        search_terms,
         unbound,
         vindicated,
       } => {
@@ -493,16 +526,14 @@ impl InscriptionUpdater<'_, '_> {
           })
           .collect::<Result<Vec<u32>>>()?;
 
-        if let Some(ref sender) = index.event_sender {
-          sender.blocking_send(Event::InscriptionCreated {
-            block_height: self.height,
-            charms,
-            inscription_id,
-            location: (!unbound).then_some(new_satpoint),
-            parent_inscription_ids: parents,
-            sequence_number,
-          })?;
-        }
+        event_emitter.emit(Event::InscriptionCreated {
+          block_height: self.height,
+          charms,
+          inscription_id,
+          location: (!unbound).then_some(new_satpoint),
+          parent_inscription_ids: parents,
+          sequence_number,
+        })?;
 
         self.sequence_number_to_entry.insert(
           sequence_number,
@@ -524,6 +555,12 @@ impl InscriptionUpdater<'_, '_> {
           .id_to_sequence_number
           .insert(&inscription_id.store(), sequence_number)?;
 
+        if let Some(search_term_to_sequence_number) = &mut self.search_term_to_sequence_number {
+          for term in &search_terms {
+            search_term_to_sequence_number.insert(term.as_str(), sequence_number)?;
+          }
+        }
+
         if !hidden {
           self
             .home_inscriptions
@@ -565,6 +602,26 @@ impl InscriptionUpdater<'_, '_> {
 
     output_utxo_entry.push_inscription(sequence_number, satpoint.offset, index);
 
+    if let Some(transfers) = &mut self.sequence_number_and_index_to_transfer {
+      let number = transfers
+        .range((sequence_number, 0)..=(sequence_number, u32::MAX))?
+        .next_back()
+        .transpose()?
+        .map(|(key, _)| key.value().1 + 1)
+        .unwrap_or(0);
+
+      transfers.insert(
+        (sequence_number, number),
+        InscriptionTransfer {
+          height: self.height,
+          new_satpoint: satpoint,
+          old_satpoint,
+          txid,
+        }
+        .store(),
+      )?;
+    }
+
     Ok(())
   }
 }
@@ -0,0 +1,69 @@
+use {super::*, std::sync::atomic::AtomicU64};
+
+/// Time spent in each stage of the indexing pipeline, and the number of
+/// items each stage processed, since they were last logged.
+#[derive(Default)]
+pub(super) struct Metrics {
+  pub(super) commit: Stage,
+  pub(super) fetch: Stage,
+  pub(super) index: Stage,
+  pub(super) input_wait: Stage,
+  pub(super) prefetch: Stage,
+}
+
+#[derive(Default)]
+pub(super) struct Stage {
+  count: AtomicU64,
+  nanos: AtomicU64,
+}
+
+impl Stage {
+  pub(super) fn record(&self, count: u64, elapsed: Duration) {
+    self.count.fetch_add(count, atomic::Ordering::Relaxed);
+    self.nanos.fetch_add(
+      u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX),
+      atomic::Ordering::Relaxed,
+    );
+  }
+
+  fn take(&self) -> (u64, u128) {
+    (
+      self.count.swap(0, atomic::Ordering::Relaxed),
+      Duration::from_nanos(self.nanos.swap(0, atomic::Ordering::Relaxed)).as_millis(),
+    )
+  }
+}
+
+impl Metrics {
+  pub(super) fn log(&self) {
+    let (blocks_fetched, fetch_ms) = self.fetch.take();
+    let (outputs_prefetched, prefetch_ms) = self.prefetch.take();
+    let (inputs_waited_for, input_wait_ms) = self.input_wait.take();
+    let (blocks_indexed, index_ms) = self.index.take();
+    let (commits, commit_ms) = self.commit.take();
+
+    log::info!(
+      "Fetched {blocks_fetched} blocks in {fetch_ms} ms, \
+      requested {outputs_prefetched} outputs in {prefetch_ms} ms, \
+      waited {input_wait_ms} ms for {inputs_waited_for} outputs, \
+      indexed {blocks_indexed} blocks in {index_ms} ms, \
+      committed {commits} times in {commit_ms} ms",
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn stages_are_reset_when_taken() {
+    let stage = Stage::default();
+
+    stage.record(1, Duration::from_millis(2));
+    stage.record(3, Duration::from_millis(4));
+
+    assert_eq!(stage.take(), (4, 6));
+    assert_eq!(stage.take(), (0, 0));
+  }
+}
@@ -0,0 +1,250 @@
+use {
+  super::*, crate::index::fetcher::Fetcher, futures::future::try_join_all,
+  std::collections::VecDeque,
+};
+
+/// Number of blocks whose spent outputs are fetched ahead of indexing.
+const LOOKAHEAD_BLOCKS: usize = 8;
+
+/// Number of blocks whose spent outputs are fetched ahead of indexing while
+/// a commit is in progress, which is enough to cover the blocks fetched in the
+/// meantime.
+const COMMIT_LOOKAHEAD_BLOCKS: usize = 32;
+
+/// Buffers upcoming blocks and requests the outputs they spend that are not
+/// in the index, so that those outputs are fetched while earlier blocks are
+/// being indexed and committed, rather than one block at a time.
+///
+/// Outputs are requested in the order in which `index_utxo_entries` consumes
+/// them, which is block order, transaction order, and then input order, so
+/// that they can be received in order without being matched to outpoints.
+pub(super) struct Prefetcher {
+  block_receiver: std::sync::mpsc::Receiver<BlockData>,
+  blocks: VecDeque<BlockData>,
+  enabled: bool,
+  metrics: Arc<Metrics>,
+  outpoint_sender: mpsc::UnboundedSender<OutPoint>,
+  txids: HashSet<Txid>,
+  txout_receiver: mpsc::UnboundedReceiver<TxOut>,
+}
+
+impl Prefetcher {
+  pub(super) fn new(
+    index: &Index,
+    block_receiver: std::sync::mpsc::Receiver<BlockData>,
+    metrics: Arc<Metrics>,
+  ) -> Result<Self> {
+    let (outpoint_sender, txout_receiver) = Self::spawn_fetcher(index)?;
+
+    Ok(Self {
+      block_receiver,
+      blocks: VecDeque::new(),
+      enabled: !index.have_full_utxo_index()
+        && (index.index_inscriptions || index.index_addresses || index.index_sats),
+      metrics,
+      outpoint_sender,
+      txids: HashSet::new(),
+      txout_receiver,
+    })
+  }
+
+  /// Returns the next block to index, after requesting the outputs spent by
+  /// the blocks that have arrived since the last call.
+  pub(super) fn next_block(
+    &mut self,
+    wtx: &WriteTransaction,
+    utxo_cache: &HashMap<OutPoint, UtxoEntryBuf>,
+  ) -> Result<Option<BlockData>> {
+    self.buffer(wtx, utxo_cache, LOOKAHEAD_BLOCKS, true)?;
+
+    let Some(block) = self.blocks.pop_front() else {
+      return Ok(None);
+    };
+
+    for (_, txid) in &block.txdata {
+      self.txids.remove(txid);
+    }
+
+    Ok(Some(block))
+  }
+
+  /// Requests the outputs spent by the blocks that have already been
+  /// fetched, before `wtx` is committed, so that they are fetched while the
+  /// commit is in progress instead of after it. Outputs are requested with
+  /// the state that is about to be committed, which is the state the blocks
+  /// will be indexed on top of.
+  pub(super) fn prefetch_during_commit(
+    &mut self,
+    wtx: &WriteTransaction,
+    utxo_cache: &HashMap<OutPoint, UtxoEntryBuf>,
+  ) -> Result {
+    self.buffer(wtx, utxo_cache, COMMIT_LOOKAHEAD_BLOCKS, false)
+  }
+
+  fn buffer(
+    &mut self,
+    wtx: &WriteTransaction,
+    utxo_cache: &HashMap<OutPoint, UtxoEntryBuf>,
+    limit: usize,
+    wait: bool,
+  ) -> Result {
+    while self.blocks.len() < limit {
+      let block = if wait && self.blocks.is_empty() {
+        self.block_receiver.recv().ok()
+      } else {
+        self.block_receiver.try_recv().ok()
+      };
+
+      let Some(block) = block else {
+        break;
+      };
+
+      if self.enabled {
+        self.prefetch(wtx, utxo_cache, &block)?;
+      }
+
+      self.blocks.push_back(block);
+    }
+
+    Ok(())
+  }
+
+  /// Receives the next requested output, waiting for it to be fetched if
+  /// necessary.
+  pub(super) fn txout(&mut self, outpoint: OutPoint) -> Result<TxOut> {
+    let start = Instant::now();
+
+    let txout = self
+      .txout_receiver
+      .blocking_recv()
+      .ok_or_else(|| anyhow!("failed to get transaction for {outpoint}"))?;
+
+    self.metrics.input_wait.record(1, start.elapsed());
+
+    Ok(txout)
+  }
+
+  fn prefetch(
+    &mut self,
+    wtx: &WriteTransaction,
+    utxo_cache: &HashMap<OutPoint, UtxoEntryBuf>,
+    block: &BlockData,
+  ) -> Result {
+    let start = Instant::now();
+
+    let outpoint_to_utxo_entry = wtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+
+    self
+      .txids
+      .extend(block.txdata.iter().map(|(_, txid)| *txid));
+
+    let mut requested = 0;
+
+    for (tx, _) in &block.txdata {
+      for input in &tx.input {
+        let prev_output = input.previous_output;
+        // We don't need coinbase inputs
+        if prev_output.is_null() {
+          continue;
+        }
+        // We don't need inputs from txs earlier in this block or in buffered
+        // blocks, since they'll be added to cache when the tx is indexed
+        if self.txids.contains(&prev_output.txid) {
+          continue;
+        }
+        // We don't need inputs we already have in our cache from earlier blocks
+        if utxo_cache.contains_key(&prev_output) {
+          continue;
+        }
+        // We don't need inputs we already have in our database
+        if outpoint_to_utxo_entry.get(&prev_output.store())?.is_some() {
+          continue;
+        }
+        // Send this outpoint to background thread to be fetched
+        self.outpoint_sender.send(prev_output)?;
+        requested += 1;
+      }
+    }
+
+    self.metrics.prefetch.record(requested, start.elapsed());
+
+    Ok(())
+  }
+
+  fn spawn_fetcher(
+    index: &Index,
+  ) -> Result<(
+    mpsc::UnboundedSender<OutPoint>,
+    mpsc::UnboundedReceiver<TxOut>,
+  )> {
+    let fetcher = Fetcher::new(&index.settings)?;
+
+    // Batch 2048 missing inputs at a time, arbitrarily chosen size
+    const BATCH_SIZE: usize = 2048;
+
+    // Both channels are unbounded, since the number of outputs in flight is
+    // limited by the number of blocks buffered
+    let (outpoint_sender, mut outpoint_receiver) = mpsc::unbounded_channel::<OutPoint>();
+
+    let (txout_sender, txout_receiver) = mpsc::unbounded_channel::<TxOut>();
+
+    // Default rpcworkqueue in bitcoind is 16, meaning more than 16 concurrent requests will be rejected.
+    // Since we are already requesting blocks on a separate thread, and we don't want to break if anything
+    // else runs a request, we keep this to 12.
+    let parallel_requests: usize = index.settings.bitcoin_rpc_limit().try_into().unwrap();
+
+    thread::spawn(move || {
+      let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+      rt.block_on(async move {
+        loop {
+          let Some(outpoint) = outpoint_receiver.recv().await else {
+            log::debug!("Outpoint channel closed");
+            return;
+          };
+
+          // There's no try_iter on tokio::sync::mpsc::Receiver like std::sync::mpsc::Receiver.
+          // So we just loop until BATCH_SIZE doing try_recv until it returns None.
+          let mut outpoints = vec![outpoint];
+          for _ in 0..BATCH_SIZE - 1 {
+            let Ok(outpoint) = outpoint_receiver.try_recv() else {
+              break;
+            };
+            outpoints.push(outpoint);
+          }
+
+          // Break outputs into chunks for parallel requests
+          let chunk_size = (outpoints.len() / parallel_requests) + 1;
+          let mut futs = Vec::with_capacity(parallel_requests);
+          for chunk in outpoints.chunks(chunk_size) {
+            let txids = chunk.iter().map(|outpoint| outpoint.txid).collect();
+            let fut = fetcher.get_transactions(txids);
+            futs.push(fut);
+          }
+
+          let txs = match try_join_all(futs).await {
+            Ok(txs) => txs,
+            Err(e) => {
+              log::error!("Couldn't receive txs {e}");
+              return;
+            }
+          };
+
+          // Send all tx outputs back in order
+          for (i, tx) in txs.iter().flatten().enumerate() {
+            let Ok(_) =
+              txout_sender.send(tx.output[usize::try_from(outpoints[i].vout).unwrap()].clone())
+            else {
+              log::error!("Value channel closed unexpectedly");
+              return;
+            };
+          }
+        }
+      })
+    });
+
+    Ok((outpoint_sender, txout_receiver))
+  }
+}
@@ -1,10 +1,10 @@
 use super::*;
 
 pub(super) struct RuneUpdater<'a, 'tx, 'client> {
+  pub(super) address_history: Option<&'a mut AddressHistory>,
   pub(super) block_time: u32,
   pub(super) burned: HashMap<RuneId, Lot>,
   pub(super) client: &'client Client,
-  pub(super) event_sender: Option<&'a mpsc::Sender<Event>>,
   pub(super) height: u32,
   pub(super) id_to_entry: &'a mut Table<'tx, RuneIdValue, RuneEntryValue>,
   pub(super) inscription_id_to_sequence_number: &'a Table<'tx, InscriptionIdValue, u32>,
@@ -18,10 +18,16 @@ pub(super) struct RuneUpdater<'a, 'tx, 'client> {
 }
 
 impl RuneUpdater<'_, '_, '_> {
-  pub(super) fn index_runes(&mut self, tx_index: u32, tx: &Transaction, txid: Txid) -> Result<()> {
+  pub(super) fn index_runes(
+    &mut self,
+    tx_index: u32,
+    tx: &Transaction,
+    txid: Txid,
+    event_emitter: &mut EventEmitter,
+  ) -> Result<()> {
     let artifact = Runestone::decipher(tx);
 
-    let mut unallocated = self.unallocated(tx)?;
+    let mut unallocated = self.unallocated(tx_index, tx, txid)?;
 
     let mut allocated: Vec<HashMap<RuneId, Lot>> = vec![HashMap::new(); tx.output.len()];
 
@@ -31,14 +37,12 @@ impl RuneUpdater<'_, '_, '_> {
       {
         *unallocated.entry(id).or_default() += amount;
 
-        if let Some(sender) = self.event_sender {
-          sender.blocking_send(Event::RuneMinted {
-            block_height: self.height,
-            txid,
-            rune_id: id,
-            amount: amount.n(),
-          })?;
-        }
+        event_emitter.emit(Event::RuneMinted {
+          block_height: self.height,
+          txid,
+          rune_id: id,
+          amount: amount.n(),
+        })?;
       }
 
       let etched = self.etched(tx_index, tx, artifact)?;
@@ -123,7 +127,7 @@ impl RuneUpdater<'_, '_, '_> {
       }
 
       if let Some((id, rune)) = etched {
-        self.create_rune_entry(txid, artifact, id, rune)?;
+        self.create_rune_entry(txid, artifact, id, rune, event_emitter)?;
       }
     }
 
@@ -198,15 +202,23 @@ impl RuneUpdater<'_, '_, '_> {
       for (id, balance) in balances {
         Index::encode_rune_balance(id, balance.n(), &mut buffer);
 
-        if let Some(sender) = self.event_sender {
-          sender.blocking_send(Event::RuneTransferred {
-            outpoint,
-            block_height: self.height,
+        if let Some(address_history) = &mut self.address_history {
+          address_history.receive_runes(
+            tx_index,
             txid,
-            rune_id: id,
-            amount: balance.0,
-          })?;
+            &tx.output[vout].script_pubkey,
+            id,
+            balance.n(),
+          );
         }
+
+        event_emitter.emit(Event::RuneTransferred {
+          outpoint,
+          block_height: self.height,
+          txid,
+          rune_id: id,
+          amount: balance.0,
+        })?;
       }
 
       self
@@ -218,14 +230,12 @@ impl RuneUpdater<'_, '_, '_> {
     for (id, amount) in burned {
       *self.burned.entry(id).or_default() += amount;
 
-      if let Some(sender) = self.event_sender {
-        sender.blocking_send(Event::RuneBurned {
-          block_height: self.height,
-          txid,
-          rune_id: id,
-          amount: amount.n(),
-        })?;
-      }
+      event_emitter.emit(Event::RuneBurned {
+        block_height: self.height,
+        txid,
+        rune_id: id,
+        amount: amount.n(),
+      })?;
     }
 
     Ok(())
@@ -247,6 +257,7 @@ impl RuneUpdater<'_, '_, '_> {
     artifact: &Artifact,
     id: RuneId,
     rune: Rune,
+    event_emitter: &mut EventEmitter,
   ) -> Result {
     self.rune_to_id.insert(rune.store(), id.store())?;
     self
@@ -308,13 +319,11 @@ impl RuneUpdater<'_, '_, '_> {
 
     self.id_to_entry.insert(id.store(), entry.store())?;
 
-    if let Some(sender) = self.event_sender {
-      sender.blocking_send(Event::RuneEtched {
-        block_height: self.height,
-        txid,
-        rune_id: id,
-      })?;
-    }
+    event_emitter.emit(Event::RuneEtched {
+      block_height: self.height,
+      txid,
+      rune_id: id,
+    })?;
 
     let inscription_id = InscriptionId { txid, index: 0 };
 
@@ -466,7 +475,12 @@ impl RuneUpdater<'_, '_, '_> {
     Ok(false)
   }
 
-  fn unallocated(&mut self, tx: &Transaction) -> Result<HashMap<RuneId, Lot>> {
+  fn unallocated(
+    &mut self,
+    tx_index: u32,
+    tx: &Transaction,
+    txid: Txid,
+  ) -> Result<HashMap<RuneId, Lot>> {
     // map of rune ID to un-allocated balance of that rune
     let mut unallocated: HashMap<RuneId, Lot> = HashMap::new();
 
@@ -482,6 +496,10 @@ impl RuneUpdater<'_, '_, '_> {
           let ((id, balance), len) = Index::decode_rune_balance(&buffer[i..]).unwrap();
           i += len;
           *unallocated.entry(id).or_default() += balance;
+
+          if let Some(address_history) = &mut self.address_history {
+            address_history.spend_runes(tx_index, txid, input.previous_output, id, balance);
+          }
         }
       }
     }
@@ -21,6 +21,11 @@ pub struct Options {
     help = "Authenticate to Bitcoin Core RPC as <BITCOIN_RPC_USERNAME>."
   )]
   pub(crate) bitcoin_rpc_username: Option<String>,
+  #[arg(
+    long,
+    help = "Accept BRC-20 v2 identities attested in <BRC20V2_ATTESTATIONS> file."
+  )]
+  pub(crate) brc20v2_attestations: Option<PathBuf>,
   #[arg(long, help = "Max <N> requests in flight. [default: 12]")]
   pub(crate) bitcoin_rpc_limit: Option<u32>,
   #[arg(long = "chain", value_enum, help = "Use <CHAIN>. [default: mainnet]")]
@@ -49,19 +54,38 @@ pub struct Options {
   pub(crate) height_limit: Option<u32>,
   #[arg(long, help = "Use index at <INDEX>.")]
   pub(crate) index: Option<PathBuf>,
+  #[arg(
+    long,
+    help = "Track transactions of each address. Requires `--index-addresses`."
+  )]
+  pub(crate) index_address_history: bool,
   #[arg(long, help = "Track unspent output addresses.")]
   pub(crate) index_addresses: bool,
+  #[arg(
+    long,
+    help = "Track BRC-20 v2 token state. Requires inscriptions to be indexed."
+  )]
+  pub(crate) index_brc20v2: bool,
   #[arg(
     long,
     help = "Set index cache size to <INDEX_CACHE_SIZE> bytes. [default: 1/4 available RAM]"
   )]
   pub(crate) index_cache_size: Option<usize>,
+  #[arg(long, help = "Record a replayable log of index events.")]
+  pub(crate) index_events: bool,
   #[arg(long, help = "Track location of runes.")]
   pub(crate) index_runes: bool,
   #[arg(long, help = "Track location of all satoshis.")]
   pub(crate) index_sats: bool,
+  #[arg(
+    long,
+    help = "Index text, titles, traits and metaprotocols of inscriptions for search."
+  )]
+  pub(crate) index_search: bool,
   #[arg(long, help = "Store transactions in index.")]
   pub(crate) index_transactions: bool,
+  #[arg(long, help = "Track every location of each inscription.")]
+  pub(crate) index_transfer_history: bool,
   #[arg(long, help = "Run in integration test mode.")]
   pub(crate) integration_test: bool,
   #[clap(long, short, long, help = "Specify output format. [default: json]")]
@@ -73,6 +97,11 @@ pub struct Options {
     help = "Do not index inscriptions."
   )]
   pub(crate) no_index_inscriptions: bool,
+  #[arg(
+    long,
+    help = "Read blocks from Bitcoin Core's blk*.dat files instead of over RPC during initial sync."
+  )]
+  pub(crate) read_block_files: bool,
   #[arg(
     long,
     help = "Require basic HTTP authentication with <SERVER_PASSWORD>. Credentials are sent in cleartext. Consider using authentication in conjunction with HTTPS."
@@ -1,4 +1,11 @@
-use {super::*, bitcoincore_rpc::Auth};
+use {
+  super::*,
+  crate::brc20v2::{
+    IdentityVerifier, IdentityVerifiers,
+    identity::{AttestationVerifier, Attestations, Bip322Verifier},
+  },
+  bitcoincore_rpc::Auth,
+};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default, deny_unknown_fields)]
@@ -8,6 +15,7 @@ pub struct Settings {
   bitcoin_rpc_password: Option<String>,
   bitcoin_rpc_url: Option<String>,
   bitcoin_rpc_username: Option<String>,
+  brc20v2_attestations: Option<PathBuf>,
   chain: Option<Chain>,
   commit_interval: Option<usize>,
   config: Option<PathBuf>,
@@ -18,14 +26,20 @@ pub struct Settings {
   hidden: Option<HashSet<InscriptionId>>,
   http_port: Option<u16>,
   index: Option<PathBuf>,
+  index_address_history: bool,
   index_addresses: bool,
+  index_brc20v2: bool,
   index_cache_size: Option<usize>,
+  index_events: bool,
   index_runes: bool,
   index_sats: bool,
+  index_search: bool,
   index_transactions: bool,
+  index_transfer_history: bool,
   integration_test: bool,
   max_savepoints: Option<usize>,
   no_index_inscriptions: bool,
+  read_block_files: bool,
   savepoint_interval: Option<usize>,
   server_password: Option<String>,
   server_url: Option<String>,
@@ -115,6 +129,7 @@ impl Settings {
       bitcoin_rpc_password: self.bitcoin_rpc_password.or(source.bitcoin_rpc_password),
       bitcoin_rpc_url: self.bitcoin_rpc_url.or(source.bitcoin_rpc_url),
       bitcoin_rpc_username: self.bitcoin_rpc_username.or(source.bitcoin_rpc_username),
+      brc20v2_attestations: self.brc20v2_attestations.or(source.brc20v2_attestations),
       chain: self.chain.or(source.chain),
       commit_interval: self.commit_interval.or(source.commit_interval),
       config: self.config.or(source.config),
@@ -133,14 +148,20 @@ impl Settings {
       ),
       http_port: self.http_port.or(source.http_port),
       index: self.index.or(source.index),
+      index_address_history: self.index_address_history || source.index_address_history,
       index_addresses: self.index_addresses || source.index_addresses,
+      index_brc20v2: self.index_brc20v2 || source.index_brc20v2,
       index_cache_size: self.index_cache_size.or(source.index_cache_size),
+      index_events: self.index_events || source.index_events,
       index_runes: self.index_runes || source.index_runes,
       index_sats: self.index_sats || source.index_sats,
+      index_search: self.index_search || source.index_search,
       index_transactions: self.index_transactions || source.index_transactions,
+      index_transfer_history: self.index_transfer_history || source.index_transfer_history,
       integration_test: self.integration_test || source.integration_test,
       max_savepoints: self.max_savepoints.or(source.max_savepoints),
       no_index_inscriptions: self.no_index_inscriptions || source.no_index_inscriptions,
+      read_block_files: self.read_block_files || source.read_block_files,
       savepoint_interval: self.savepoint_interval.or(source.savepoint_interval),
       server_password: self.server_password.or(source.server_password),
       server_url: self.server_url.or(source.server_url),
@@ -155,6 +176,7 @@ impl Settings {
       bitcoin_rpc_password: options.bitcoin_rpc_password,
       bitcoin_rpc_url: options.bitcoin_rpc_url,
       bitcoin_rpc_username: options.bitcoin_rpc_username,
+      brc20v2_attestations: options.brc20v2_attestations,
       chain: options
         .signet
         .then_some(Chain::Signet)
@@ -171,14 +193,20 @@ impl Settings {
       hidden: None,
       http_port: None,
       index: options.index,
+      index_address_history: options.index_address_history,
       index_addresses: options.index_addresses,
+      index_brc20v2: options.index_brc20v2,
       index_cache_size: options.index_cache_size,
+      index_events: options.index_events,
       index_runes: options.index_runes,
       index_sats: options.index_sats,
+      index_search: options.index_search,
       index_transactions: options.index_transactions,
+      index_transfer_history: options.index_transfer_history,
       integration_test: options.integration_test,
       max_savepoints: options.max_savepoints,
       no_index_inscriptions: options.no_index_inscriptions,
+      read_block_files: options.read_block_files,
       savepoint_interval: options.savepoint_interval,
       server_password: options.server_password,
       server_url: None,
@@ -251,6 +279,7 @@ impl Settings {
       bitcoin_rpc_password: get_string("BITCOIN_RPC_PASSWORD"),
       bitcoin_rpc_url: get_string("BITCOIN_RPC_URL"),
       bitcoin_rpc_username: get_string("BITCOIN_RPC_USERNAME"),
+      brc20v2_attestations: get_path("BRC20V2_ATTESTATIONS"),
       chain: get_chain("CHAIN")?,
       commit_interval: get_usize("COMMIT_INTERVAL")?,
       config: get_path("CONFIG"),
@@ -261,14 +290,20 @@ impl Settings {
       hidden: inscriptions("HIDDEN")?,
       http_port: get_u16("HTTP_PORT")?,
       index: get_path("INDEX"),
+      index_address_history: get_bool("INDEX_ADDRESS_HISTORY"),
       index_addresses: get_bool("INDEX_ADDRESSES"),
+      index_brc20v2: get_bool("INDEX_BRC20V2"),
       index_cache_size: get_usize("INDEX_CACHE_SIZE")?,
+      index_events: get_bool("INDEX_EVENTS"),
       index_runes: get_bool("INDEX_RUNES"),
       index_sats: get_bool("INDEX_SATS"),
+      index_search: get_bool("INDEX_SEARCH"),
       index_transactions: get_bool("INDEX_TRANSACTIONS"),
+      index_transfer_history: get_bool("INDEX_TRANSFER_HISTORY"),
       integration_test: get_bool("INTEGRATION_TEST"),
       max_savepoints: get_usize("MAX_SAVEPOINTS")?,
       no_index_inscriptions: get_bool("NO_INDEX_INSCRIPTIONS"),
+      read_block_files: get_bool("READ_BLOCK_FILES"),
       savepoint_interval: get_usize("SAVEPOINT_INTERVAL")?,
       server_password: get_string("SERVER_PASSWORD"),
       server_url: get_string("SERVER_URL"),
@@ -283,6 +318,7 @@ impl Settings {
       bitcoin_rpc_password: None,
       bitcoin_rpc_url: Some(rpc_url.into()),
       bitcoin_rpc_username: None,
+      brc20v2_attestations: None,
       chain: Some(Chain::Regtest),
       commit_interval: None,
       config: None,
@@ -293,14 +329,20 @@ impl Settings {
       hidden: None,
       http_port: None,
       index: None,
+      index_address_history: false,
       index_addresses: true,
+      index_brc20v2: false,
       index_cache_size: None,
+      index_events: false,
       index_runes: true,
       index_sats: true,
+      index_search: false,
       index_transactions: false,
+      index_transfer_history: false,
       integration_test: false,
       max_savepoints: None,
       no_index_inscriptions: false,
+      read_block_files: false,
       savepoint_interval: None,
       server_password: None,
       server_url: Some(server_url.into()),
@@ -352,6 +394,7 @@ impl Settings {
           .unwrap_or_else(|| format!("127.0.0.1:{}", chain.default_rpc_port())),
       ),
       bitcoin_rpc_username: self.bitcoin_rpc_username,
+      brc20v2_attestations: self.brc20v2_attestations,
       chain: Some(chain),
       commit_interval: Some(self.commit_interval.unwrap_or(5000)),
       config: None,
@@ -362,7 +405,9 @@ impl Settings {
       hidden: self.hidden,
       http_port: self.http_port,
       index: Some(index),
+      index_address_history: self.index_address_history,
       index_addresses: self.index_addresses,
+      index_brc20v2: self.index_brc20v2,
       index_cache_size: Some(match self.index_cache_size {
         Some(index_cache_size) => index_cache_size,
         None => {
@@ -371,12 +416,16 @@ impl Settings {
           usize::try_from(sys.total_memory() / 4)?
         }
       }),
+      index_events: self.index_events,
       index_runes: self.index_runes,
       index_sats: self.index_sats,
+      index_search: self.index_search,
       index_transactions: self.index_transactions,
+      index_transfer_history: self.index_transfer_history,
       integration_test: self.integration_test,
       max_savepoints: Some(self.max_savepoints.unwrap_or(2)),
       no_index_inscriptions: self.no_index_inscriptions,
+      read_block_files: self.read_block_files,
       savepoint_interval: Some(self.savepoint_interval.unwrap_or(10)),
       server_password: self.server_password,
       server_url: self.server_url,
@@ -548,14 +597,76 @@ impl Settings {
     self.index.as_ref().unwrap()
   }
 
+  pub fn index_address_history_raw(&self) -> bool {
+    self.index_address_history
+  }
+
   pub fn index_addresses_raw(&self) -> bool {
     self.index_addresses
   }
 
+  pub fn index_brc20v2_raw(&self) -> bool {
+    self.index_brc20v2
+  }
+
+  pub fn index_events_raw(&self) -> bool {
+    self.index_events
+  }
+
+  pub fn brc20v2_identity_verifier(&self) -> Result<IdentityVerifiers> {
+    let mut verifiers: Vec<Box<dyn IdentityVerifier>> = vec![Box::new(Bip322Verifier {
+      network: self.chain().network(),
+    })];
+
+    if let Some(verifier) = self.brc20v2_attestation_verifier()? {
+      verifiers.push(Box::new(verifier));
+    }
+
+    Ok(IdentityVerifiers(verifiers))
+  }
+
+  /// Fingerprint of the identities attested in the `--brc20v2-attestations`
+  /// file, or zero if there is none. BRC-20 v2 state depends on which
+  /// identities are attested, so an index records the fingerprint it was
+  /// built with.
+  pub fn brc20v2_attestations_fingerprint(&self) -> Result<u64> {
+    Ok(
+      self
+        .brc20v2_attestation_verifier()?
+        .map(|verifier| u64::from_le_bytes(verifier.digest()[..8].try_into().unwrap()))
+        .unwrap_or_default(),
+    )
+  }
+
+  fn brc20v2_attestation_verifier(&self) -> Result<Option<AttestationVerifier>> {
+    let Some(path) = &self.brc20v2_attestations else {
+      return Ok(None);
+    };
+
+    let attestations = serde_json::from_slice::<Attestations>(
+      &fs::read(path)
+        .with_context(|| format!("failed to read attestations from `{}`", path.display()))?,
+    )
+    .with_context(|| format!("failed to parse attestations from `{}`", path.display()))?;
+
+    Ok(Some(AttestationVerifier::new(&attestations)?))
+  }
+
   pub fn index_inscriptions_raw(&self) -> bool {
     !self.no_index_inscriptions
   }
 
+  pub fn read_block_files(&self) -> bool {
+    self.read_block_files
+  }
+
+  pub fn bitcoin_blocks_dir(&self) -> PathBuf {
+    self
+      .chain()
+      .join_with_data_dir(self.bitcoin_data_dir.as_ref().unwrap())
+      .join("blocks")
+  }
+
   pub fn index_runes_raw(&self) -> bool {
     self.index_runes
   }
@@ -568,10 +679,18 @@ impl Settings {
     self.index_sats
   }
 
+  pub fn index_search_raw(&self) -> bool {
+    self.index_search
+  }
+
   pub fn index_transactions_raw(&self) -> bool {
     self.index_transactions
   }
 
+  pub fn index_transfer_history_raw(&self) -> bool {
+    self.index_transfer_history
+  }
+
   pub fn integration_test(&self) -> bool {
     self.integration_test
   }
@@ -816,6 +935,14 @@ mod tests {
     }));
   }
 
+  #[test]
+  fn bitcoin_blocks_dir_is_in_bitcoin_data_dir() {
+    assert_eq!(
+      parse(&["--bitcoin-data-dir=foo", "--chain=signet"]).bitcoin_blocks_dir(),
+      Path::new("foo").join("signet").join("blocks"),
+    );
+  }
+
   #[test]
   fn mainnet_data_dir() {
     let data_dir = parse(&[]).data_dir().display().to_string();
@@ -977,6 +1104,64 @@ mod tests {
     assert!(!parse(&[]).index_runes_raw());
   }
 
+  #[test]
+  fn brc20v2_identity_verifier() {
+    use crate::brc20v2::{
+      IdentityCommitment,
+      identity::{AttestationList, Attestations},
+    };
+
+    let keypair = secp256k1::Keypair::from_secret_key(
+      &Secp256k1::new(),
+      &secp256k1::SecretKey::from_slice(&[1; 32]).unwrap(),
+    );
+
+    let tempdir = TempDir::new().unwrap();
+
+    let path = tempdir.path().join("attestations.json");
+
+    fs::write(
+      &path,
+      serde_json::to_string(&Attestations {
+        issuer: keypair.x_only_public_key().0.to_string(),
+        lists: vec![AttestationList::sign(vec!["alice".into()], &keypair)],
+      })
+      .unwrap(),
+    )
+    .unwrap();
+
+    let verifier = Settings {
+      brc20v2_attestations: Some(path.clone()),
+      chain: Some(Chain::Mainnet),
+      ..default()
+    }
+    .brc20v2_identity_verifier()
+    .unwrap();
+
+    assert!(
+      verifier
+        .verify(&IdentityCommitment::new("alice"), "", None)
+        .is_ok()
+    );
+    assert!(
+      verifier
+        .verify(&IdentityCommitment::new("bob"), "", None)
+        .is_err()
+    );
+
+    fs::write(&path, "{}").unwrap();
+
+    assert!(
+      Settings {
+        brc20v2_attestations: Some(path),
+        chain: Some(Chain::Mainnet),
+        ..default()
+      }
+      .brc20v2_identity_verifier()
+      .is_err()
+    );
+  }
+
   #[test]
   fn bitcoin_rpc_and_pass_setting() {
     let config = Settings {
@@ -1067,6 +1252,7 @@ mod tests {
       ("BITCOIN_RPC_PASSWORD", "bitcoin password"),
       ("BITCOIN_RPC_URL", "url"),
       ("BITCOIN_RPC_USERNAME", "bitcoin username"),
+      ("BRC20V2_ATTESTATIONS", "attestations.json"),
       ("CHAIN", "signet"),
       ("COMMIT_INTERVAL", "1"),
       ("CONFIG", "config"),
@@ -1077,14 +1263,20 @@ mod tests {
       ("HIDDEN", "6fb976ab49dcec017f1e201e84395983204ae1a7c2abf7ced0a85d692e442799i0 703e5f7c49d82aab99e605af306b9a30e991e57d42f982908a962a81ac439832i0"),
       ("HTTP_PORT", "8080"),
       ("INDEX", "index"),
+      ("INDEX_ADDRESS_HISTORY", "1"),
       ("INDEX_ADDRESSES", "1"),
+      ("INDEX_BRC20V2", "1"),
       ("INDEX_CACHE_SIZE", "4"),
+      ("INDEX_EVENTS", "1"),
       ("INDEX_RUNES", "1"),
       ("INDEX_SATS", "1"),
+      ("INDEX_SEARCH", "1"),
       ("INDEX_TRANSACTIONS", "1"),
+      ("INDEX_TRANSFER_HISTORY", "1"),
       ("INTEGRATION_TEST", "1"),
       ("MAX_SAVEPOINTS", "2"),
       ("NO_INDEX_INSCRIPTIONS", "1"),
+      ("READ_BLOCK_FILES", "1"),
       ("SAVEPOINT_INTERVAL", "10"),
       ("SERVER_PASSWORD", "server password"),
       ("SERVER_URL", "server url"),
@@ -1102,6 +1294,7 @@ mod tests {
         bitcoin_rpc_password: Some("bitcoin password".into()),
         bitcoin_rpc_url: Some("url".into()),
         bitcoin_rpc_username: Some("bitcoin username".into()),
+        brc20v2_attestations: Some("attestations.json".into()),
         chain: Some(Chain::Signet),
         commit_interval: Some(1),
         savepoint_interval: Some(10),
@@ -1125,13 +1318,19 @@ mod tests {
         ),
         http_port: Some(8080),
         index: Some("index".into()),
+        index_address_history: true,
         index_addresses: true,
+        index_brc20v2: true,
         index_cache_size: Some(4),
+        index_events: true,
         index_runes: true,
         index_sats: true,
+        index_search: true,
         index_transactions: true,
+        index_transfer_history: true,
         integration_test: true,
         no_index_inscriptions: true,
+        read_block_files: true,
         server_password: Some("server password".into()),
         server_url: Some("server url".into()),
         server_username: Some("server username".into()),
@@ -1150,6 +1349,7 @@ mod tests {
           "--bitcoin-rpc-password=bitcoin password",
           "--bitcoin-rpc-url=url",
           "--bitcoin-rpc-username=bitcoin username",
+          "--brc20v2-attestations=attestations.json",
           "--chain=signet",
           "--commit-interval=1",
           "--savepoint-interval=10",
@@ -1159,14 +1359,20 @@ mod tests {
           "--cookie-file=cookie file",
           "--datadir=/data/dir",
           "--height-limit=3",
+          "--index-address-history",
           "--index-addresses",
+          "--index-brc20v2",
           "--index-cache-size=4",
+          "--index-events",
           "--index-runes",
           "--index-sats",
+          "--index-search",
           "--index-transactions",
+          "--index-transfer-history",
           "--index=index",
           "--integration-test",
           "--no-index-inscriptions",
+          "--read-block-files",
           "--server-password=server password",
           "--server-username=server username",
         ])
@@ -1178,6 +1384,7 @@ mod tests {
         bitcoin_rpc_password: Some("bitcoin password".into()),
         bitcoin_rpc_url: Some("url".into()),
         bitcoin_rpc_username: Some("bitcoin username".into()),
+        brc20v2_attestations: Some("attestations.json".into()),
         chain: Some(Chain::Signet),
         commit_interval: Some(1),
         savepoint_interval: Some(10),
@@ -1190,13 +1397,19 @@ mod tests {
         hidden: None,
         http_port: None,
         index: Some("index".into()),
+        index_address_history: true,
         index_addresses: true,
+        index_brc20v2: true,
         index_cache_size: Some(4),
+        index_events: true,
         index_runes: true,
         index_sats: true,
+        index_search: true,
         index_transactions: true,
+        index_transfer_history: true,
         integration_test: true,
         no_index_inscriptions: true,
+        read_block_files: true,
         server_password: Some("server password".into()),
         server_url: None,
         server_username: Some("server username".into()),
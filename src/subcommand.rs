@@ -8,6 +8,7 @@ pub mod find;
 pub mod index;
 pub mod list;
 pub mod parse;
+pub mod relay;
 pub mod runes;
 pub mod server;
 mod settings;
@@ -37,6 +38,8 @@ pub(crate) enum Subcommand {
   List(list::List),
   #[command(about = "Parse a satoshi from ordinal notation")]
   Parse(parse::Parse),
+  #[command(about = "Relay BRC-20 v2 state roots to Ethereum")]
+  Relay(relay::Relay),
   #[command(about = "List all runes")]
   Runes,
   #[command(about = "Run the explorer server")]
@@ -70,12 +73,17 @@ impl Subcommand {
       Self::Index(index) => index.run(settings),
       Self::List(list) => list.run(settings),
       Self::Parse(parse) => parse.run(),
+      Self::Relay(relay) => relay.run(settings),
       Self::Runes => runes::run(settings),
       Self::Server(server) => {
-        let index = Arc::new(Index::open(&settings)?);
+        let (event_sender, event_receiver) = server::Events::channel();
+        let index = Arc::new(Index::open_with_event_sender(
+          &settings,
+          Some(event_sender),
+        )?);
         let handle = axum_server::Handle::new();
         LISTENERS.lock().unwrap().push(handle.clone());
-        server.run(settings, index, handle)
+        server.run(settings, index, handle, event_receiver)
       }
       Self::Settings => settings::run(settings),
       Self::Subsidy(subsidy) => subsidy.run(),
@@ -1,15 +1,27 @@
 use super::*;
 
-mod export;
+pub mod enable;
+pub mod events;
+pub mod export;
 pub mod info;
+pub mod migrate;
+pub mod snapshot;
 mod update;
 
 #[derive(Debug, Parser)]
 pub(crate) enum IndexSubcommand {
-  #[command(about = "Write inscription numbers and ids to a tab-separated file")]
+  #[command(about = "Enable an optional index without reindexing")]
+  Enable(enable::Enable),
+  #[command(about = "List logged index events")]
+  Events(events::Events),
+  #[command(about = "Export index data as TSV, CSV, JSON Lines or Parquet")]
   Export(export::Export),
   #[command(about = "Print index statistics")]
   Info(info::Info),
+  #[command(about = "Migrate the index to the current schema version without reindexing")]
+  Migrate(migrate::Migrate),
+  #[command(subcommand, about = "Create and restore index snapshots")]
+  Snapshot(snapshot::Snapshot),
   #[command(about = "Update the index", alias = "run")]
   Update,
 }
@@ -17,8 +29,12 @@ pub(crate) enum IndexSubcommand {
 impl IndexSubcommand {
   pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
     match self {
+      Self::Enable(enable) => enable.run(settings),
+      Self::Events(events) => events.run(settings),
       Self::Export(export) => export.run(settings),
       Self::Info(info) => info.run(settings),
+      Self::Migrate(migrate) => migrate.run(settings),
+      Self::Snapshot(snapshot) => snapshot.run(settings),
       Self::Update => update::run(settings),
     }
   }
@@ -0,0 +1,24 @@
+use {super::*, crate::index::backfill::Feature};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Enable {
+  #[arg(help = "Enable <FEATURE> index.")]
+  feature: Feature,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub backfill_height: Option<u32>,
+  pub feature: Feature,
+}
+
+impl Enable {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let backfill_height = Index::enable(&settings, self.feature)?;
+
+    Ok(Some(Box::new(Output {
+      backfill_height,
+      feature: self.feature,
+    })))
+  }
+}
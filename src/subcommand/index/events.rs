@@ -0,0 +1,31 @@
+use {super::*, crate::index::event::EventRecord};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Events {
+  #[arg(long, default_value_t = 0, help = "List events from <FROM_HEIGHT>.")]
+  from_height: u32,
+  #[arg(long, help = "List events up to and including <TO_HEIGHT>.")]
+  to_height: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub events: Vec<EventRecord>,
+}
+
+impl Events {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let index = Index::open(&settings)?;
+
+    ensure!(
+      index.has_event_index(),
+      "listing events requires index created with `--index-events` flag",
+    );
+
+    index.update()?;
+
+    Ok(Some(Box::new(Output {
+      events: index.get_events(self.from_height, self.to_height.unwrap_or(u32::MAX))?,
+    })))
+  }
+}
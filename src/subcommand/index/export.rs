@@ -1,20 +1,149 @@
-use super::*;
+use {
+  super::*,
+  crate::index::dataset::{ColumnType, Dataset, Field},
+  clap::ValueEnum,
+};
+
+mod writer;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum Format {
+  Csv,
+  Jsonl,
+  Parquet,
+}
+
+impl Format {
+  fn extension(self) -> &'static str {
+    match self {
+      Self::Csv => "csv",
+      Self::Jsonl => "jsonl",
+      Self::Parquet => "parquet",
+    }
+  }
+}
 
 #[derive(Debug, Parser)]
 pub(crate) struct Export {
-  #[arg(long, help = "Include addresses in export")]
+  #[arg(
+    long,
+    help = "Update the index to block <AT_HEIGHT> and export the state as of that block. Blocks \
+    can't be unindexed, so export fails if the index is already past <AT_HEIGHT>."
+  )]
+  at_height: Option<u32>,
+  #[arg(
+    long,
+    value_delimiter = ',',
+    requires = "output",
+    help = "Export <DATASET>s, separated by commas."
+  )]
+  dataset: Vec<Dataset>,
+  #[arg(long, value_enum, default_value_t = Format::Jsonl, help = "Export in <FORMAT>.")]
+  format: Format,
+  #[arg(long, requires = "tsv", help = "Include addresses in export")]
   include_addresses: bool,
-  #[arg(long, help = "Write export to <TSV>")]
-  tsv: String,
+  #[arg(
+    long,
+    requires = "dataset",
+    help = "Write each dataset to <OUTPUT>/<DATASET>.<FORMAT>."
+  )]
+  output: Option<PathBuf>,
+  #[arg(
+    long,
+    conflicts_with = "output",
+    required_unless_present = "output",
+    help = "Write export to <TSV>"
+  )]
+  tsv: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub block_hash: BlockHash,
+  pub height: u32,
+  pub files: BTreeMap<String, FileOutput>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct FileOutput {
+  pub path: PathBuf,
+  pub rows: u64,
 }
 
 impl Export {
   pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let settings = match self.at_height {
+      Some(at_height) => Settings::from_options(Options {
+        height_limit: Some(
+          at_height
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("--at-height {at_height} is out of range"))?,
+        ),
+        ..default()
+      })
+      .or(settings),
+      None => settings,
+    };
+
     let index = Index::open(&settings)?;
 
     index.update()?;
-    index.export(&self.tsv, self.include_addresses)?;
 
-    Ok(None)
+    if let Some(at_height) = self.at_height {
+      let height = index.block_height()?.map(|height| height.n());
+
+      ensure!(
+        height == Some(at_height),
+        "index is at height {}, not {at_height}",
+        height.map_or_else(|| "none".into(), |height| height.to_string()),
+      );
+    }
+
+    if let Some(tsv) = self.tsv {
+      index.export(&tsv, self.include_addresses)?;
+      return Ok(None);
+    }
+
+    let output = self.output.unwrap();
+
+    fs::create_dir_all(&output)?;
+
+    let mut datasets = Vec::new();
+    let mut writers = Vec::new();
+    let mut files = BTreeMap::new();
+
+    for dataset in self.dataset {
+      if datasets.contains(&dataset) {
+        continue;
+      }
+
+      let path = output.join(format!("{}.{}", dataset.name(), self.format.extension()));
+      writers.push((dataset, self.format.writer(&path, dataset)?));
+      files.insert(dataset.name().into(), FileOutput { path, rows: 0 });
+      datasets.push(dataset);
+    }
+
+    let (height, block_hash) = index.export_datasets(&datasets, |dataset, row| {
+      let (_, writer) = writers
+        .iter_mut()
+        .find(|(writer_dataset, _)| *writer_dataset == dataset)
+        .unwrap();
+
+      writer.write(row)?;
+
+      files.get_mut(dataset.name()).unwrap().rows += 1;
+
+      Ok(())
+    })?;
+
+    for (_, writer) in writers {
+      writer.finish()?;
+    }
+
+    Ok(Some(Box::new(Output {
+      block_hash,
+      height,
+      files,
+    })))
   }
 }
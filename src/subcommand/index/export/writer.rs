@@ -0,0 +1,324 @@
+use {
+  super::*,
+  ::parquet::{
+    basic::{LogicalType, Repetition, Type as PhysicalType},
+    data_type::{BoolType, ByteArray, ByteArrayType, Int64Type},
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::types::Type,
+  },
+  std::{
+    borrow::Cow,
+    io::{BufWriter, Write},
+  },
+};
+
+const ROW_GROUP_SIZE: usize = 64 * 1024;
+
+pub(super) trait Writer {
+  fn write(&mut self, row: Vec<Field>) -> Result;
+
+  fn finish(self: Box<Self>) -> Result;
+}
+
+impl Format {
+  pub(super) fn writer(self, path: &Path, dataset: Dataset) -> Result<Box<dyn Writer>> {
+    let file = BufWriter::new(
+      File::create(path).with_context(|| format!("failed to create `{}`", path.display()))?,
+    );
+
+    Ok(match self {
+      Self::Csv => Box::new(Csv::new(file, dataset)?),
+      Self::Jsonl => Box::new(Jsonl { dataset, file }),
+      Self::Parquet => Box::new(Parquet::new(file, dataset)?),
+    })
+  }
+}
+
+struct Csv {
+  file: BufWriter<File>,
+}
+
+impl Csv {
+  fn new(mut file: BufWriter<File>, dataset: Dataset) -> Result<Self> {
+    let header = dataset
+      .columns()
+      .iter()
+      .map(|(name, _)| *name)
+      .collect::<Vec<&str>>();
+
+    writeln!(file, "{}", header.join(","))?;
+
+    Ok(Self { file })
+  }
+
+  fn escape(value: &str) -> Cow<str> {
+    if value.contains([',', '"', '\n', '\r']) {
+      format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+      value.into()
+    }
+  }
+}
+
+impl Writer for Csv {
+  fn write(&mut self, row: Vec<Field>) -> Result {
+    let row = row
+      .into_iter()
+      .map(|field| match field {
+        Field::Boolean(value) => value.to_string(),
+        Field::Integer(value) => value.to_string(),
+        Field::Null => String::new(),
+        Field::Text(value) => Self::escape(&value).into_owned(),
+        Field::Unsigned(value) => value.to_string(),
+      })
+      .collect::<Vec<String>>();
+
+    writeln!(self.file, "{}", row.join(","))?;
+
+    Ok(())
+  }
+
+  fn finish(mut self: Box<Self>) -> Result {
+    self.file.flush()?;
+    Ok(())
+  }
+}
+
+struct Jsonl {
+  dataset: Dataset,
+  file: BufWriter<File>,
+}
+
+impl Writer for Jsonl {
+  fn write(&mut self, row: Vec<Field>) -> Result {
+    let object = self
+      .dataset
+      .columns()
+      .iter()
+      .zip(row)
+      .map(|((name, _), field)| {
+        let value = match field {
+          Field::Boolean(value) => value.into(),
+          Field::Integer(value) => value.into(),
+          Field::Null => serde_json::Value::Null,
+          Field::Text(value) => value.into(),
+          Field::Unsigned(value) => value.into(),
+        };
+
+        ((*name).into(), value)
+      })
+      .collect::<serde_json::Map<String, serde_json::Value>>();
+
+    serde_json::to_writer(&mut self.file, &object)?;
+    writeln!(self.file)?;
+
+    Ok(())
+  }
+
+  fn finish(mut self: Box<Self>) -> Result {
+    self.file.flush()?;
+    Ok(())
+  }
+}
+
+/// Writes rows in row groups of `ROW_GROUP_SIZE` rows, with every column
+/// optional, so that nulls are stored as undefined values.
+struct Parquet {
+  dataset: Dataset,
+  rows: Vec<Vec<Field>>,
+  writer: SerializedFileWriter<BufWriter<File>>,
+}
+
+impl Parquet {
+  fn new(file: BufWriter<File>, dataset: Dataset) -> Result<Self> {
+    let fields = dataset
+      .columns()
+      .iter()
+      .map(|(name, ty)| {
+        let builder = match ty {
+          ColumnType::Boolean => Type::primitive_type_builder(name, PhysicalType::BOOLEAN),
+          ColumnType::Integer => Type::primitive_type_builder(name, PhysicalType::INT64),
+          ColumnType::Text => Type::primitive_type_builder(name, PhysicalType::BYTE_ARRAY)
+            .with_logical_type(Some(LogicalType::String)),
+          ColumnType::Unsigned => Type::primitive_type_builder(name, PhysicalType::INT64)
+            .with_logical_type(Some(LogicalType::Integer {
+              bit_width: 64,
+              is_signed: false,
+            })),
+        };
+
+        Ok(Arc::new(
+          builder.with_repetition(Repetition::OPTIONAL).build()?,
+        ))
+      })
+      .collect::<Result<Vec<Arc<Type>>>>()?;
+
+    let schema = Type::group_type_builder("schema")
+      .with_fields(fields)
+      .build()?;
+
+    Ok(Self {
+      dataset,
+      rows: Vec::new(),
+      writer: SerializedFileWriter::new(
+        file,
+        Arc::new(schema),
+        Arc::new(WriterProperties::builder().build()),
+      )?,
+    })
+  }
+
+  fn flush(&mut self) -> Result {
+    if self.rows.is_empty() {
+      return Ok(());
+    }
+
+    let mut row_group = self.writer.next_row_group()?;
+
+    for (i, (_, ty)) in self.dataset.columns().iter().enumerate() {
+      let mut column = row_group
+        .next_column()?
+        .ok_or_else(|| anyhow!("parquet schema is missing column {i}"))?;
+
+      let definition_levels = self
+        .rows
+        .iter()
+        .map(|row| i16::from(row[i] != Field::Null))
+        .collect::<Vec<i16>>();
+
+      let fields = self.rows.iter().map(|row| &row[i]);
+
+      match ty {
+        ColumnType::Boolean => {
+          let values = fields
+            .filter_map(|field| match field {
+              Field::Boolean(value) => Some(*value),
+              _ => None,
+            })
+            .collect::<Vec<bool>>();
+
+          column
+            .typed::<BoolType>()
+            .write_batch(&values, Some(&definition_levels), None)?;
+        }
+        ColumnType::Integer => {
+          let values = fields
+            .filter_map(|field| match field {
+              Field::Integer(value) => Some(*value),
+              _ => None,
+            })
+            .collect::<Vec<i64>>();
+
+          column
+            .typed::<Int64Type>()
+            .write_batch(&values, Some(&definition_levels), None)?;
+        }
+        ColumnType::Text => {
+          let values = fields
+            .filter_map(|field| match field {
+              Field::Text(value) => Some(ByteArray::from(value.as_bytes().to_vec())),
+              _ => None,
+            })
+            .collect::<Vec<ByteArray>>();
+
+          column
+            .typed::<ByteArrayType>()
+            .write_batch(&values, Some(&definition_levels), None)?;
+        }
+        ColumnType::Unsigned => {
+          // parquet stores unsigned integers in the bits of a signed integer
+          let values = fields
+            .filter_map(|field| match field {
+              Field::Unsigned(value) => Some(value.cast_signed()),
+              _ => None,
+            })
+            .collect::<Vec<i64>>();
+
+          column
+            .typed::<Int64Type>()
+            .write_batch(&values, Some(&definition_levels), None)?;
+        }
+      }
+
+      column.close()?;
+    }
+
+    row_group.close()?;
+
+    self.rows.clear();
+
+    Ok(())
+  }
+}
+
+impl Writer for Parquet {
+  fn write(&mut self, row: Vec<Field>) -> Result {
+    self.rows.push(row);
+
+    if self.rows.len() == ROW_GROUP_SIZE {
+      self.flush()?;
+    }
+
+    Ok(())
+  }
+
+  fn finish(mut self: Box<Self>) -> Result {
+    self.flush()?;
+    self.writer.close()?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn csv_fields_are_escaped() {
+    assert_eq!(Csv::escape("foo"), "foo");
+    assert_eq!(Csv::escape("foo,bar"), "\"foo,bar\"");
+    assert_eq!(Csv::escape("foo\"bar"), "\"foo\"\"bar\"");
+    assert_eq!(Csv::escape("foo\nbar"), "\"foo\nbar\"");
+  }
+
+  #[test]
+  fn unsigned_fields_beyond_i64_are_written() {
+    let tempdir = TempDir::new().unwrap();
+
+    for format in [Format::Csv, Format::Jsonl, Format::Parquet] {
+      let path = tempdir
+        .path()
+        .join(format!("sat-ranges.{}", format.extension()));
+
+      let mut writer = format.writer(&path, Dataset::SatRanges).unwrap();
+
+      writer
+        .write(vec![
+          Field::Text("outpoint".into()),
+          Field::from(u64::MAX - 1),
+          Field::from(u64::MAX),
+        ])
+        .unwrap();
+
+      writer.finish().unwrap();
+    }
+
+    assert_eq!(
+      fs::read_to_string(tempdir.path().join("sat-ranges.csv")).unwrap(),
+      format!(
+        "outpoint,start,end\noutpoint,{},{}\n",
+        u64::MAX - 1,
+        u64::MAX
+      ),
+    );
+
+    assert_eq!(
+      fs::read_to_string(tempdir.path().join("sat-ranges.jsonl")).unwrap(),
+      format!(
+        "{{\"outpoint\":\"outpoint\",\"start\":{},\"end\":{}}}\n",
+        u64::MAX - 1,
+        u64::MAX,
+      ),
+    );
+  }
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Migrate {
+  #[arg(
+    long,
+    help = "Check that the index can be migrated, without migrating it."
+  )]
+  dry_run: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub dry_run: bool,
+  pub from: u64,
+  pub migrations: Vec<String>,
+  pub to: u64,
+}
+
+impl Migrate {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let (from, migrations) = Index::migrate(&settings, self.dry_run)?;
+
+    Ok(Some(Box::new(Output {
+      dry_run: self.dry_run,
+      from,
+      migrations: migrations.into_iter().map(str::to_string).collect(),
+      to: crate::index::SCHEMA_VERSION,
+    })))
+  }
+}
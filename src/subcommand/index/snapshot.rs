@@ -0,0 +1,98 @@
+use {super::*, crate::index::SCHEMA_VERSION, bitcoin::hashes::sha256};
+
+pub mod create;
+pub mod restore;
+
+const MANIFEST: &str = "manifest.json";
+
+#[derive(Debug, Parser)]
+pub(crate) enum Snapshot {
+  #[command(about = "Write a compressed snapshot of the index to a directory")]
+  Create(create::Create),
+  #[command(about = "Verify a snapshot and restore the index from it")]
+  Restore(restore::Restore),
+}
+
+impl Snapshot {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    match self {
+      Self::Create(create) => create.run(settings),
+      Self::Restore(restore) => restore.run(settings),
+    }
+  }
+}
+
+/// Describes a snapshot, which is the index file split into chunks, each of
+/// which is compressed with brotli and written to `chunk-<N>.br`.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+  pub block_hash: BlockHash,
+  pub chain: Chain,
+  pub chunks: Vec<Chunk>,
+  pub flags: Flags,
+  pub height: u32,
+  pub schema_version: u64,
+  pub size: u64,
+}
+
+/// The uncompressed size and SHA-256 hash of a chunk.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Chunk {
+  pub sha256: sha256::Hash,
+  pub size: u64,
+}
+
+/// The indices that were enabled when the snapshot was created, which must
+/// also be enabled when it is restored.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Flags {
+  pub address_history: bool,
+  pub addresses: bool,
+  pub brc20v2: bool,
+  pub events: bool,
+  pub inscriptions: bool,
+  pub runes: bool,
+  pub sats: bool,
+  pub search: bool,
+  pub transactions: bool,
+  pub transfer_history: bool,
+}
+
+impl Flags {
+  fn from_index(index: &Index) -> Self {
+    Self {
+      address_history: index.has_address_history_index(),
+      addresses: index.has_address_index(),
+      brc20v2: index.has_brc20v2_index(),
+      events: index.has_event_index(),
+      inscriptions: index.has_inscription_index(),
+      runes: index.has_rune_index(),
+      sats: index.has_sat_index(),
+      search: index.has_search_index(),
+      transactions: index.has_transaction_index(),
+      transfer_history: index.has_transfer_history_index(),
+    }
+  }
+
+  fn from_settings(settings: &Settings) -> Self {
+    Self {
+      address_history: settings.index_address_history_raw(),
+      addresses: settings.index_addresses_raw(),
+      brc20v2: settings.index_brc20v2_raw(),
+      events: settings.index_events_raw(),
+      inscriptions: settings.index_inscriptions_raw(),
+      runes: settings.index_runes_raw(),
+      sats: settings.index_sats_raw(),
+      search: settings.index_search_raw(),
+      transactions: settings.index_transactions_raw(),
+      transfer_history: settings.index_transfer_history_raw(),
+    }
+  }
+}
+
+fn chunk_path(dir: &Path, chunk: usize) -> PathBuf {
+  dir.join(format!("chunk-{chunk:05}.br"))
+}
@@ -0,0 +1,111 @@
+use {
+  super::*,
+  brotli::enc::writer::CompressorWriter,
+  std::io::{BufWriter, Write},
+};
+
+const COMPRESSION_QUALITY: u32 = 6;
+const COMPRESSION_WINDOW: u32 = 22;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Create {
+  #[arg(
+    long,
+    default_value_t = 256 * 1024 * 1024,
+    help = "Split the index into chunks of <CHUNK_SIZE> bytes."
+  )]
+  chunk_size: u64,
+  #[arg(long, help = "Write snapshot to <OUTPUT> directory.")]
+  output: PathBuf,
+}
+
+impl Create {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    ensure!(self.chunk_size > 0, "chunk size must be greater than zero");
+
+    let index = Index::open(&settings)?;
+
+    index.update()?;
+
+    let height = index
+      .block_height()?
+      .ok_or_else(|| anyhow!("index has no blocks"))?
+      .n();
+
+    let block_hash = index
+      .block_hash(Some(height))?
+      .ok_or_else(|| anyhow!("index has no block at height {height}"))?;
+
+    let flags = Flags::from_index(&index);
+
+    // the index is closed before it is read, so that it is read in the state
+    // it is left in when closed cleanly, and can be opened without repair
+    drop(index);
+
+    fs::create_dir_all(&self.output)?;
+
+    ensure!(
+      !self.output.join(MANIFEST).exists(),
+      "`{}` already contains a snapshot",
+      self.output.display(),
+    );
+
+    let mut reader = BufReader::new(
+      File::open(settings.index())
+        .with_context(|| format!("failed to open `{}`", settings.index().display()))?,
+    );
+
+    let mut chunks = Vec::new();
+    let mut size = 0;
+    let mut buffer = Vec::new();
+
+    loop {
+      buffer.clear();
+
+      (&mut reader)
+        .take(self.chunk_size)
+        .read_to_end(&mut buffer)?;
+
+      if buffer.is_empty() {
+        break;
+      }
+
+      let mut writer = CompressorWriter::new(
+        BufWriter::new(File::create(chunk_path(&self.output, chunks.len()))?),
+        4096,
+        COMPRESSION_QUALITY,
+        COMPRESSION_WINDOW,
+      );
+
+      writer.write_all(&buffer)?;
+      writer.into_inner().flush()?;
+
+      let chunk_size = u64::try_from(buffer.len()).unwrap();
+
+      chunks.push(Chunk {
+        sha256: sha256::Hash::hash(&buffer),
+        size: chunk_size,
+      });
+
+      size += chunk_size;
+    }
+
+    let manifest = Manifest {
+      block_hash,
+      chain: settings.chain(),
+      chunks,
+      flags,
+      height,
+      schema_version: SCHEMA_VERSION,
+      size,
+    };
+
+    // the manifest is written last, so that an interrupted snapshot has none
+    fs::write(
+      self.output.join(MANIFEST),
+      serde_json::to_vec_pretty(&manifest)?,
+    )?;
+
+    Ok(Some(Box::new(manifest)))
+  }
+}
@@ -0,0 +1,146 @@
+use {super::*, brotli::Decompressor, std::io::Write};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Restore {
+  #[arg(long, help = "Restore snapshot from <INPUT> directory.")]
+  input: PathBuf,
+}
+
+impl Restore {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let manifest_path = self.input.join(MANIFEST);
+
+    let manifest = serde_json::from_slice::<Manifest>(
+      &fs::read(&manifest_path)
+        .with_context(|| format!("failed to read `{}`", manifest_path.display()))?,
+    )
+    .with_context(|| format!("failed to parse `{}`", manifest_path.display()))?;
+
+    ensure!(
+      manifest.chain == settings.chain(),
+      "snapshot is of {} index, not {}",
+      manifest.chain,
+      settings.chain(),
+    );
+
+    ensure!(
+      manifest.schema_version == SCHEMA_VERSION,
+      "snapshot has schema version {}, but this version of ord expects schema version {}",
+      manifest.schema_version,
+      SCHEMA_VERSION,
+    );
+
+    let flags = Flags::from_settings(&settings);
+
+    ensure!(
+      manifest.flags == flags,
+      "snapshot was created with index flags {:?}, but settings have {:?}",
+      manifest.flags,
+      flags,
+    );
+
+    ensure!(
+      manifest.chunks.iter().map(|chunk| chunk.size).sum::<u64>() == manifest.size,
+      "snapshot size does not match sum of chunk sizes",
+    );
+
+    let index_path = settings.index().to_owned();
+
+    ensure!(
+      !index_path.exists(),
+      "`{}` already exists, remove it before restoring a snapshot",
+      index_path.display(),
+    );
+
+    let tip = settings
+      .bitcoin_rpc_client(None)?
+      .get_block_hash(manifest.height.into())
+      .with_context(|| format!("failed to get hash of block {}", manifest.height))?;
+
+    ensure!(
+      tip == manifest.block_hash,
+      "snapshot tip {} at height {} is not in the active chain, which has {tip}",
+      manifest.block_hash,
+      manifest.height,
+    );
+
+    let mut partial_path = index_path.clone().into_os_string();
+    partial_path.push(".partial");
+    let partial_path = PathBuf::from(partial_path);
+
+    if let Some(parent) = index_path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+
+    let result = Self::restore(&self.input, &manifest, &partial_path);
+
+    if let Err(err) = result {
+      fs::remove_file(&partial_path).ok();
+      return Err(err);
+    }
+
+    fs::rename(&partial_path, &index_path)?;
+
+    let index = Index::open(&settings)?;
+
+    if index.block_hash(None)? != Some(manifest.block_hash) {
+      drop(index);
+      fs::remove_file(&index_path)?;
+      bail!("restored index tip does not match snapshot manifest");
+    }
+
+    Ok(Some(Box::new(manifest)))
+  }
+
+  fn restore(input: &Path, manifest: &Manifest, path: &Path) -> Result {
+    let mut file = File::create(path)?;
+
+    let mut buffer = Vec::new();
+
+    for (i, chunk) in manifest.chunks.iter().enumerate() {
+      let chunk_path = chunk_path(input, i);
+
+      buffer.clear();
+
+      // one more byte than expected is read, so that chunks that are too
+      // large are detected without decompressing them entirely
+      Decompressor::new(
+        File::open(&chunk_path)
+          .with_context(|| format!("failed to open `{}`", chunk_path.display()))?,
+        4096,
+      )
+      .take(chunk.size.saturating_add(1))
+      .read_to_end(&mut buffer)
+      .with_context(|| format!("failed to decompress `{}`", chunk_path.display()))?;
+
+      ensure!(
+        u64::try_from(buffer.len()).unwrap() == chunk.size,
+        "`{}` has size {}, but manifest has {}",
+        chunk_path.display(),
+        buffer.len(),
+        chunk.size,
+      );
+
+      let sha256 = sha256::Hash::hash(&buffer);
+
+      ensure!(
+        sha256 == chunk.sha256,
+        "`{}` has hash {sha256}, but manifest has {}",
+        chunk_path.display(),
+        chunk.sha256,
+      );
+
+      file.write_all(&buffer)?;
+    }
+
+    ensure!(
+      !chunk_path(input, manifest.chunks.len()).exists(),
+      "`{}` contains chunks not listed in manifest",
+      input.display(),
+    );
+
+    file.sync_all()?;
+
+    Ok(())
+  }
+}
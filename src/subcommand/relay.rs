@@ -0,0 +1,354 @@
+use {
+  super::*,
+  crate::brc20v2::relay::{
+    checkpoint::{RelayCheckpoint, RelayEnvelope},
+    ethereum::{EthereumAddress, EthereumRelay},
+  },
+  secp256k1::SecretKey,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Relay {
+  #[arg(long, help = "Submit state roots to relay contract at <CONTRACT>.")]
+  pub(crate) contract: EthereumAddress,
+  #[arg(
+    long,
+    help = "Persist relay progress to <CHECKPOINT>. [default: <DATA_DIR>/brc20v2-relay.json]"
+  )]
+  pub(crate) checkpoint: Option<PathBuf>,
+  #[arg(
+    long,
+    help = "Submit transactions to Ethereum JSON-RPC endpoint at <ETHEREUM_RPC_URL>."
+  )]
+  pub(crate) ethereum_rpc_url: String,
+  #[arg(
+    long,
+    help = "Sign transactions with hex-encoded secret key in <KEY_FILE>."
+  )]
+  pub(crate) key_file: PathBuf,
+  #[arg(long, help = "Relay the current state root if needed and exit.")]
+  pub(crate) once: bool,
+  #[arg(
+    long,
+    default_value = "10s",
+    help = "Check for new state roots every <POLLING_INTERVAL>."
+  )]
+  pub(crate) polling_interval: humantime::Duration,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub checkpoint: Option<RelayCheckpoint>,
+}
+
+impl Relay {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let index = Index::open(&settings)?;
+
+    ensure!(
+      index.has_brc20v2_index(),
+      "relaying requires index created with `--index-brc20v2` flag",
+    );
+
+    let secret_key = fs::read_to_string(&self.key_file)
+      .with_context(|| format!("failed to read key file `{}`", self.key_file.display()))?
+      .trim()
+      .trim_start_matches("0x")
+      .parse::<SecretKey>()
+      .with_context(|| format!("invalid secret key in `{}`", self.key_file.display()))?;
+
+    let relay = EthereumRelay::new(self.ethereum_rpc_url, self.contract, secret_key);
+
+    let path = self
+      .checkpoint
+      .unwrap_or_else(|| settings.data_dir().join("brc20v2-relay.json"));
+
+    let mut checkpoint = RelayCheckpoint::load(&path)?;
+
+    loop {
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        break;
+      }
+
+      match index
+        .update()
+        .and_then(|()| Self::relay(&index, &relay, &path, checkpoint.as_ref()))
+      {
+        Ok(Some(relayed)) => {
+          log::info!(
+            "Relayed BRC-20 v2 state root {} at height {} in transaction 0x{}",
+            hex::encode(relayed.envelope.state_hash),
+            relayed.envelope.height,
+            hex::encode(relayed.receipt.transaction_hash),
+          );
+
+          checkpoint = Some(relayed);
+        }
+        Ok(None) => {}
+        Err(err) => {
+          if self.once {
+            return Err(err);
+          }
+
+          log::warn!("Relaying BRC-20 v2 state root: {err}");
+        }
+      }
+
+      if self.once {
+        break;
+      }
+
+      thread::sleep(self.polling_interval.into());
+    }
+
+    Ok(Some(Box::new(Output { checkpoint })))
+  }
+
+  /// Relay the current state root, unless it was the last root relayed and
+  /// the block it was relayed at is still in the best chain. After a reorg
+  /// the root is relayed again, chained to the last relayed envelope, since
+  /// the relayed root may no longer reflect the best chain.
+  fn relay(
+    index: &Index,
+    relay: &EthereumRelay,
+    path: &Path,
+    checkpoint: Option<&RelayCheckpoint>,
+  ) -> Result<Option<RelayCheckpoint>> {
+    let Some((height, block_hash, state_hash)) = index.get_brc20v2_state_root()? else {
+      return Ok(None);
+    };
+
+    let previous = checkpoint.map(|checkpoint| &checkpoint.envelope);
+
+    let current = match previous {
+      Some(previous) => {
+        previous.state_hash == state_hash
+          && index.block_hash(Some(previous.height))? == Some(previous.block_hash)
+      }
+      None => state_hash == brc20v2::state_root([]),
+    };
+
+    if current {
+      return Ok(None);
+    }
+
+    let envelope = RelayEnvelope::next(
+      previous,
+      relay.chain_id()?,
+      relay.contract,
+      height,
+      block_hash,
+      state_hash,
+    );
+
+    let relayed = RelayCheckpoint::relay(checkpoint, relay, envelope)?;
+
+    relayed.save(path)?;
+
+    Ok(Some(relayed))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::{
+      brc20v2::{Brc20Inscription, IdentityCommitment, Operation, TokenDefinition},
+      index::testing::Context,
+    },
+  };
+
+  fn witness(operation: Operation) -> Witness {
+    inscription(
+      "application/json",
+      Brc20Inscription::from_operation(&operation, 8, [0; 32]).body,
+    )
+    .to_witness()
+  }
+
+  fn deploy() -> Witness {
+    witness(Operation::Deploy {
+      definition: TokenDefinition {
+        ticker: "ordi".into(),
+        max_supply: 21_000,
+        mint_limit: 1000,
+        decimals: 8,
+        soulbound: false,
+        transfer_rules: default(),
+      },
+    })
+  }
+
+  fn mint() -> Witness {
+    witness(Operation::Mint {
+      ticker: "ordi".into(),
+      to: IdentityCommitment::new("alice"),
+      amount: 1000,
+    })
+  }
+
+  fn relay(ethereum: &mockethereum::Handle) -> EthereumRelay {
+    EthereumRelay::new(
+      ethereum.url(),
+      EthereumAddress([0xcc; 20]),
+      SecretKey::from_slice(&[0x46; 32]).unwrap(),
+    )
+  }
+
+  #[test]
+  fn new_roots_are_relayed_once() {
+    let context = Context::builder().arg("--index-brc20v2").build();
+    let ethereum = mockethereum::spawn();
+    let relay = relay(&ethereum);
+    let path = context.tempdir.path().join("relay.json");
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      Relay::relay(&context.index, &relay, &path, None).unwrap(),
+      None
+    );
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, deploy())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let first = Relay::relay(&context.index, &relay, &path, None)
+      .unwrap()
+      .unwrap();
+
+    let (height, block_hash, state_hash) = context.index.get_brc20v2_state_root().unwrap().unwrap();
+
+    assert_eq!(first.envelope.nonce, 0);
+    assert_eq!(first.envelope.prev_state_hash, [0; 32]);
+    assert_eq!(first.envelope.state_hash, state_hash);
+    assert_eq!(first.envelope.height, height);
+    assert_eq!(first.envelope.block_hash, block_hash);
+    assert_eq!(first.envelope.chain_id, 1337);
+
+    assert_eq!(
+      Relay::relay(&context.index, &relay, &path, Some(&first)).unwrap(),
+      None
+    );
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      Relay::relay(&context.index, &relay, &path, Some(&first)).unwrap(),
+      None
+    );
+
+    assert_eq!(ethereum.state().transactions.len(), 1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, mint())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let second = Relay::relay(&context.index, &relay, &path, Some(&first))
+      .unwrap()
+      .unwrap();
+
+    assert_eq!(second.envelope.nonce, 1);
+    assert_eq!(second.envelope.prev_state_hash, first.envelope.state_hash);
+    assert_ne!(second.envelope.state_hash, first.envelope.state_hash);
+    assert_eq!(ethereum.state().transactions.len(), 2);
+  }
+
+  #[test]
+  fn progress_is_persisted() {
+    let context = Context::builder().arg("--index-brc20v2").build();
+    let ethereum = mockethereum::spawn();
+    let path = context.tempdir.path().join("relay.json");
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, deploy())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let first = Relay::relay(&context.index, &relay(&ethereum), &path, None)
+      .unwrap()
+      .unwrap();
+
+    let checkpoint = RelayCheckpoint::load(&path).unwrap();
+
+    assert_eq!(checkpoint.as_ref(), Some(&first));
+
+    assert_eq!(
+      Relay::relay(
+        &context.index,
+        &relay(&ethereum),
+        &path,
+        checkpoint.as_ref()
+      )
+      .unwrap(),
+      None,
+    );
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, mint())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let second = Relay::relay(
+      &context.index,
+      &relay(&ethereum),
+      &path,
+      checkpoint.as_ref(),
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(second.envelope.nonce, 1);
+    assert_eq!(second.receipt.nonce, 1);
+    assert_eq!(RelayCheckpoint::load(&path).unwrap(), Some(second));
+  }
+
+  #[test]
+  fn roots_are_relayed_again_after_reorg() {
+    let context = Context::builder().arg("--index-brc20v2").build();
+    let ethereum = mockethereum::spawn();
+    let relay = relay(&ethereum);
+    let path = context.tempdir.path().join("relay.json");
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, deploy())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let mut first = Relay::relay(&context.index, &relay, &path, None)
+      .unwrap()
+      .unwrap();
+
+    // the block the root was relayed at is no longer in the best chain
+    first.envelope.block_hash = BlockHash::all_zeros();
+
+    let second = Relay::relay(&context.index, &relay, &path, Some(&first))
+      .unwrap()
+      .unwrap();
+
+    assert_eq!(second.envelope.nonce, 1);
+    assert_eq!(second.envelope.prev_state_hash, first.envelope.state_hash);
+    assert_eq!(second.envelope.state_hash, first.envelope.state_hash);
+    assert_eq!(
+      second.envelope.block_hash,
+      context.index.block_hash(None).unwrap().unwrap()
+    );
+  }
+}
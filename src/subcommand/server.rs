@@ -3,15 +3,20 @@ use {
     accept_encoding::AcceptEncoding,
     accept_json::AcceptJson,
     error::{OptionExt, ServerError, ServerResult},
+    events::EventsQuery,
   },
   super::*,
-  crate::templates::{
-    AddressHtml, BlockHtml, BlocksHtml, ChildrenHtml, ClockSvg, CollectionsHtml, HomeHtml,
-    InputHtml, InscriptionHtml, InscriptionsBlockHtml, InscriptionsHtml, ItemHtml, OutputHtml,
-    PageContent, PageHtml, ParentsHtml, PreviewAudioHtml, PreviewCodeHtml, PreviewFontHtml,
-    PreviewImageHtml, PreviewMarkdownHtml, PreviewModelHtml, PreviewPdfHtml, PreviewTextHtml,
-    PreviewUnknownHtml, PreviewVideoHtml, RareTxt, RuneHtml, RuneNotFoundHtml, RunesHtml, SatHtml,
-    SatscardHtml, TransactionHtml,
+  crate::{
+    index::{event::Event, offer::OfferError, search::Facets},
+    templates::{
+      AddressHtml, AddressTransactionsHtml, BackfillStatusHtml, BlockHtml, BlocksHtml,
+      Brc20v2AccountHtml, Brc20v2HoldersHtml, Brc20v2TokensHtml, ChildrenHtml, ClockSvg,
+      CollectionsHtml, HomeHtml, InputHtml, InscriptionHtml, InscriptionsBlockHtml,
+      InscriptionsHtml, ItemHtml, OutputHtml, PageContent, PageHtml, ParentsHtml, PreviewAudioHtml,
+      PreviewCodeHtml, PreviewFontHtml, PreviewImageHtml, PreviewMarkdownHtml, PreviewModelHtml,
+      PreviewPdfHtml, PreviewTextHtml, PreviewUnknownHtml, PreviewVideoHtml, RareTxt, RuneHtml,
+      RuneNotFoundHtml, RunesHtml, SatHtml, SatscardHtml, SearchResultsHtml, TransactionHtml,
+    },
   },
   axum::{
     Router,
@@ -38,11 +43,13 @@ use {
   },
 };
 
+pub(crate) use events::Events;
 pub use server_config::ServerConfig;
 
 mod accept_encoding;
 mod accept_json;
 mod error;
+mod events;
 pub mod query;
 mod r;
 mod server_config;
@@ -73,7 +80,15 @@ pub(crate) enum OutputType {
 
 #[derive(Deserialize)]
 struct Search {
+  #[serde(default)]
   query: String,
+  charm: Option<Charm>,
+  content_type: Option<String>,
+  max_height: Option<u32>,
+  min_height: Option<u32>,
+  page: Option<usize>,
+  parent: Option<InscriptionId>,
+  rarity: Option<Rarity>,
 }
 
 #[derive(RustEmbed)]
@@ -146,8 +161,18 @@ pub struct Server {
 }
 
 impl Server {
-  pub fn run(self, settings: Settings, index: Arc<Index>, handle: Handle) -> SubcommandResult {
+  pub fn run(
+    self,
+    settings: Settings,
+    index: Arc<Index>,
+    handle: Handle,
+    event_receiver: tokio::sync::mpsc::Receiver<Event>,
+  ) -> SubcommandResult {
     Runtime::new()?.block_on(async {
+      let events = Arc::new(Events::new(index.block_count()?));
+
+      tokio::spawn(events.clone().forward(event_receiver));
+
       let index_clone = index.clone();
       let integration_test = settings.integration_test();
 
@@ -197,10 +222,33 @@ impl Server {
       let router = Router::new()
         .route("/", get(Self::home))
         .route("/address/{address}", get(Self::address))
+        .route(
+          "/address/{address}/transactions",
+          get(Self::address_transactions),
+        )
+        .route(
+          "/address/{address}/transactions/{page}",
+          get(Self::address_transactions_paginated),
+        )
         .route("/block/{query}", get(Self::block))
         .route("/blockcount", get(Self::block_count))
         .route("/blocks", get(Self::blocks))
         .route("/bounties", get(Self::bounties))
+        .route("/brc20v2/account/{identity}", get(Self::brc20v2_account))
+        .route("/brc20v2/token/{ticker}", get(Self::brc20v2_token))
+        .route(
+          "/brc20v2/token/{ticker}/holders",
+          get(Self::brc20v2_holders),
+        )
+        .route(
+          "/brc20v2/token/{ticker}/holders/{page}",
+          get(Self::brc20v2_holders_paginated),
+        )
+        .route("/brc20v2/tokens", get(Self::brc20v2_tokens))
+        .route(
+          "/brc20v2/tokens/{page}",
+          get(Self::brc20v2_tokens_paginated),
+        )
         .route("/children/{inscription_id}", get(Self::children))
         .route(
           "/children/{inscription_id}/{page}",
@@ -210,6 +258,12 @@ impl Server {
         .route("/collections", get(Self::collections))
         .route("/collections/{page}", get(Self::collections_paginated))
         .route("/decode/{txid}", get(Self::decode))
+        .route("/events", get(Self::events))
+        .route("/events/{height}", get(Self::events_in_block))
+        .route(
+          "/events/{height}/{page}",
+          get(Self::events_in_block_paginated),
+        )
         .route("/faq", get(Self::faq))
         .route("/favicon.ico", get(Self::favicon))
         .route("/feed.xml", get(Self::feed))
@@ -220,6 +274,10 @@ impl Server {
           "/inscription/{inscription_query}/{child}",
           get(Self::inscription_child),
         )
+        .route(
+          "/inscription/{inscription_query}/history",
+          get(r::inscription_history),
+        )
         .route("/inscriptions", get(Self::inscriptions))
         .route(
           "/inscriptions",
@@ -237,6 +295,11 @@ impl Server {
         .route("/install.sh", get(Self::install_script))
         .route("/offer", post(Self::offer))
         .route("/offers", get(Self::offers))
+        .route(
+          "/offers/inscription/{inscription_id}",
+          get(Self::offers_for_inscription),
+        )
+        .route("/offers/rune/{rune}", get(Self::offers_for_rune))
         .route("/ordinal/{sat}", get(Self::ordinal))
         .route("/output/{output}", get(Self::output))
         .route("/outputs", post(Self::outputs).layer(body_limit))
@@ -272,6 +335,19 @@ impl Server {
         .route("/r/blockheight", get(r::blockheight_string))
         .route("/r/blockinfo/{query}", get(r::blockinfo))
         .route("/r/blocktime", get(r::blocktime_string))
+        .route("/r/brc20v2/account/{identity}", get(r::brc20v2_account))
+        .route("/r/brc20v2/token/{ticker}", get(r::brc20v2_token))
+        .route("/r/brc20v2/token/{ticker}/holders", get(r::brc20v2_holders))
+        .route(
+          "/r/brc20v2/token/{ticker}/holders/{page}",
+          get(r::brc20v2_holders_paginated),
+        )
+        .route(
+          "/r/brc20v2/token/{ticker}/proof/{identity}",
+          get(r::brc20v2_inclusion_proof),
+        )
+        .route("/r/brc20v2/tokens", get(r::brc20v2_tokens))
+        .route("/r/brc20v2/tokens/{page}", get(r::brc20v2_tokens_paginated))
         .route(
           "/r/children/{inscription_id}/inscriptions",
           get(r::children_inscriptions),
@@ -310,6 +386,10 @@ impl Server {
           get(r::children_paginated),
         )
         .route("/r/inscription/{inscription_id}", get(r::inscription))
+        .route(
+          "/r/inscription/{inscription_id}/history",
+          get(r::inscription_history),
+        )
         .route("/r/metadata/{inscription_id}", get(r::metadata))
         .route("/r/sat/{sat_number}/at/{index}", get(r::sat_at_index))
         .route(
@@ -322,6 +402,7 @@ impl Server {
 
       let router = router
         .fallback(Self::fallback)
+        .layer(Extension(events))
         .layer(Extension(index))
         .layer(Extension(server_config.clone()))
         .layer(Extension(settings.clone()))
@@ -1041,6 +1122,155 @@ impl Server {
     })
   }
 
+  async fn brc20v2_account(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(identity): Path<String>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_brc20v2_index() {
+        return Err(ServerError::NotFound(
+          "this server has no BRC-20 v2 index".to_string(),
+        ));
+      }
+
+      let identity = crate::brc20v2::IdentityCommitment::new(identity);
+
+      let account = Brc20v2AccountHtml {
+        balances: index.get_brc20v2_balances(&identity)?,
+        commitment: identity.commitment,
+        identity: identity.id,
+      };
+
+      Ok(if accept_json {
+        Json(account).into_response()
+      } else {
+        account.page(server_config).into_response()
+      })
+    })
+  }
+
+  async fn brc20v2_token(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(ticker): Path<String>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_brc20v2_index() {
+        return Err(ServerError::NotFound(
+          "this server has no BRC-20 v2 index".to_string(),
+        ));
+      }
+
+      let token = index
+        .get_brc20v2_token_info(&ticker)?
+        .ok_or_not_found(|| format!("token {ticker}"))?;
+
+      Ok(if accept_json {
+        Json(token).into_response()
+      } else {
+        token.page(server_config).into_response()
+      })
+    })
+  }
+
+  async fn brc20v2_holders(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(ticker): Path<String>,
+    accept_json: AcceptJson,
+  ) -> ServerResult {
+    Self::brc20v2_holders_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path((ticker, 0)),
+      accept_json,
+    )
+    .await
+  }
+
+  async fn brc20v2_holders_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path((ticker, page_index)): Path<(String, usize)>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_brc20v2_index() {
+        return Err(ServerError::NotFound(
+          "this server has no BRC-20 v2 index".to_string(),
+        ));
+      }
+
+      let token = index
+        .get_brc20v2_token_info(&ticker)?
+        .ok_or_not_found(|| format!("token {ticker}"))?;
+
+      let (holders, more) = index.get_brc20v2_holders_paginated(&ticker, 100, page_index)?;
+
+      let holders = Brc20v2HoldersHtml {
+        decimals: token.definition.decimals,
+        holders,
+        more,
+        prev: page_index.checked_sub(1),
+        next: more.then_some(page_index + 1),
+        ticker: token.definition.ticker,
+      };
+
+      Ok(if accept_json {
+        Json(holders).into_response()
+      } else {
+        holders.page(server_config).into_response()
+      })
+    })
+  }
+
+  async fn brc20v2_tokens(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    accept_json: AcceptJson,
+  ) -> ServerResult {
+    Self::brc20v2_tokens_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path(0),
+      accept_json,
+    )
+    .await
+  }
+
+  async fn brc20v2_tokens_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(page_index): Path<usize>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_brc20v2_index() {
+        return Err(ServerError::NotFound(
+          "this server has no BRC-20 v2 index".to_string(),
+        ));
+      }
+
+      let (entries, more) = index.get_brc20v2_tokens_paginated(50, page_index)?;
+
+      let tokens = Brc20v2TokensHtml {
+        entries,
+        more,
+        prev: page_index.checked_sub(1),
+        next: more.then_some(page_index + 1),
+      };
+
+      Ok(if accept_json {
+        Json(tokens).into_response()
+      } else {
+        tokens.page(server_config).into_response()
+      })
+    })
+  }
+
   async fn home(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -1102,7 +1332,12 @@ impl Server {
       let offer = Psbt::deserialize(&offer)
         .map_err(|err| ServerError::BadRequest(format!("invalid offer PSBT: {err}")))?;
 
-      index.insert_offer(offer).map_err(ServerError::Internal)?;
+      index
+        .submit_offer(&offer)
+        .map_err(|err| match err.downcast::<OfferError>() {
+          Ok(err) => ServerError::BadRequest(format!("invalid offer: {err}")),
+          Err(err) => ServerError::Internal(err),
+        })?;
 
       Ok("".into_response())
     })
@@ -1130,6 +1365,58 @@ impl Server {
     })
   }
 
+  async fn offers_for_inscription(
+    Extension(index): Extension<Arc<Index>>,
+    Path(inscription_id): Path<InscriptionId>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    if !accept_json {
+      return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    task::block_in_place(|| {
+      let offers = index
+        .get_offers_for_inscription(inscription_id)?
+        .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+      Ok(
+        Json(api::Offers {
+          offers: offers
+            .into_iter()
+            .map(|offer| base64_encode(&offer))
+            .collect(),
+        })
+        .into_response(),
+      )
+    })
+  }
+
+  async fn offers_for_rune(
+    Extension(index): Extension<Arc<Index>>,
+    Path(rune): Path<SpacedRune>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    if !accept_json {
+      return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    task::block_in_place(|| {
+      let offers = index
+        .get_offers_for_rune(rune.rune)?
+        .ok_or_not_found(|| format!("rune {rune}"))?;
+
+      Ok(
+        Json(api::Offers {
+          offers: offers
+            .into_iter()
+            .map(|offer| base64_encode(&offer))
+            .collect(),
+        })
+        .into_response(),
+      )
+    })
+  }
+
   async fn address(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -1171,6 +1458,61 @@ impl Server {
     })
   }
 
+  async fn address_transactions(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    Self::address_transactions_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path((address, 0)),
+      AcceptJson(accept_json),
+    )
+    .await
+  }
+
+  async fn address_transactions_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path((address, page)): Path<(Address<NetworkUnchecked>, usize)>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let address = address
+        .require_network(server_config.chain.network())
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+      if !index.has_address_history_index() {
+        return Err(ServerError::NotFound(
+          "this server has no address history index".to_string(),
+        ));
+      }
+
+      let (transactions, more) =
+        index.get_address_transactions_paginated(&address.script_pubkey(), 100, page)?;
+
+      Ok(if accept_json {
+        Json(api::AddressTransactions {
+          transactions,
+          more,
+          page,
+        })
+        .into_response()
+      } else {
+        AddressTransactionsHtml {
+          address,
+          transactions,
+          prev_page: page.checked_sub(1),
+          next_page: more.then_some(page + 1),
+        }
+        .page(server_config)
+        .into_response()
+      })
+    })
+  }
+
   fn address_info(index: &Index, address: &Address) -> ServerResult<Option<api::AddressInfo>> {
     if !index.has_address_index() {
       return Ok(None);
@@ -1311,6 +1653,45 @@ impl Server {
     })
   }
 
+  async fn events(
+    Extension(events): Extension<Arc<Events>>,
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<EventsQuery>,
+  ) -> ServerResult {
+    task::block_in_place(|| events.stream(&index, query))
+  }
+
+  async fn events_in_block(
+    Extension(index): Extension<Arc<Index>>,
+    Path(height): Path<u32>,
+  ) -> ServerResult {
+    Self::events_in_block_paginated(Extension(index), Path((height, 0))).await
+  }
+
+  async fn events_in_block_paginated(
+    Extension(index): Extension<Arc<Index>>,
+    Path((height, page_index)): Path<(u32, u32)>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_event_index() {
+        return Err(ServerError::NotFound(
+          "this server has no event index".to_string(),
+        ));
+      }
+
+      let (events, more) = index.get_events_paginated(height, 100, page_index.into_usize())?;
+
+      Ok(
+        Json(api::Events {
+          events,
+          more,
+          page_index,
+        })
+        .into_response(),
+      )
+    })
+  }
+
   async fn update(
     Extension(settings): Extension<Arc<Settings>>,
     Extension(index): Extension<Arc<Index>>,
@@ -1331,72 +1712,152 @@ impl Server {
     AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
+      let status = BackfillStatusHtml {
+        backfilling: index.backfilling()?,
+        status: index.status(server_config.json_api_enabled)?,
+      };
+
       Ok(if accept_json {
-        Json(index.status(server_config.json_api_enabled)?).into_response()
+        Json(status).into_response()
       } else {
-        index
-          .status(server_config.json_api_enabled)?
-          .page(server_config)
-          .into_response()
+        status.page(server_config).into_response()
       })
     })
   }
 
   async fn search_by_query(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
     Query(search): Query<Search>,
-  ) -> ServerResult<Redirect> {
-    Self::search(index, search.query).await
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    Self::search(server_config, index, search, accept_json).await
   }
 
   async fn search_by_path(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
     Path(search): Path<Search>,
-  ) -> ServerResult<Redirect> {
-    Self::search(index, search.query).await
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    Self::search(server_config, index, search, accept_json).await
   }
 
-  async fn search(index: Arc<Index>, query: String) -> ServerResult<Redirect> {
+  /// Redirects queries that are exactly a block, transaction, output,
+  /// inscription, rune, address, satpoint or sat to its page, unless the
+  /// query is a sat name that inscriptions can be found by. Other queries,
+  /// and searches with facets or a page, are answered with inscription search
+  /// results if there is a search index, and otherwise redirected to the sat
+  /// page.
+  async fn search(
+    server_config: Arc<ServerConfig>,
+    index: Arc<Index>,
+    search: Search,
+    accept_json: bool,
+  ) -> ServerResult {
     task::block_in_place(|| {
-      let query = query.trim();
+      let query = search.query.trim().to_string();
+
+      let facets = Facets {
+        charm: search.charm,
+        content_type: search.content_type,
+        max_height: search.max_height,
+        min_height: search.min_height,
+        parent: search.parent,
+        rarity: search.rarity,
+      };
 
-      if re::HASH.is_match(query) {
-        if index.block_header(query.parse().unwrap())?.is_some() {
-          Ok(Redirect::to(&format!("/block/{query}")))
-        } else {
-          Ok(Redirect::to(&format!("/tx/{query}")))
+      if facets.is_empty() && search.page.is_none() {
+        if let Some(redirect) = Self::search_redirect(&index, &query)? {
+          return Ok(redirect.into_response());
+        }
+
+        // sat names are also words, so they are searched for first
+        let sat = query.parse::<Sat>().is_ok()
+          && !(query.chars().any(|c| c.is_ascii_lowercase())
+            && index.has_search_index()
+            && index
+              .search_inscriptions_paginated(&query, &facets, 0, 0)?
+              .1);
+
+        if sat || !index.has_search_index() {
+          return Ok(Redirect::to(&format!("/sat/{query}")).into_response());
         }
-      } else if re::OUTPOINT.is_match(query) {
-        Ok(Redirect::to(&format!("/output/{query}")))
-      } else if re::INSCRIPTION_ID.is_match(query) || re::INSCRIPTION_NUMBER.is_match(query) {
-        Ok(Redirect::to(&format!("/inscription/{query}")))
-      } else if let Some(captures) = re::COINKITE_SATSCARD_URL.captures(query) {
-        Ok(Redirect::to(&format!(
-          "/satscard?{}",
-          &captures["parameters"]
-        )))
-      } else if let Some(captures) = re::ORDINALS_SATSCARD_URL.captures(query) {
-        Ok(Redirect::to(&format!("/satscard?{}", &captures["query"])))
-      } else if re::SPACED_RUNE.is_match(query) {
-        Ok(Redirect::to(&format!("/rune/{query}")))
-      } else if re::RUNE_ID.is_match(query) {
-        let id = query
-          .parse::<RuneId>()
-          .map_err(|err| ServerError::BadRequest(err.to_string()))?;
-
-        let rune = index.get_rune_by_id(id)?.ok_or_not_found(|| "rune ID")?;
-
-        Ok(Redirect::to(&format!("/rune/{rune}")))
-      } else if re::ADDRESS.is_match(query) {
-        Ok(Redirect::to(&format!("/address/{query}")))
-      } else if re::SATPOINT.is_match(query) {
-        Ok(Redirect::to(&format!("/satpoint/{query}")))
-      } else {
-        Ok(Redirect::to(&format!("/sat/{query}")))
       }
+
+      if !index.has_search_index() {
+        return Err(ServerError::NotFound(
+          "this server has no search index".to_string(),
+        ));
+      }
+
+      if facets.require_scan(&query) {
+        return Err(ServerError::BadRequest(
+          "searches with facets require a query, content type or parent".into(),
+        ));
+      }
+
+      let page = search.page.unwrap_or_default();
+
+      let (inscriptions, more) = index.search_inscriptions_paginated(&query, &facets, 100, page)?;
+
+      Ok(if accept_json {
+        Json(api::SearchResults {
+          inscriptions,
+          more,
+          page,
+        })
+        .into_response()
+      } else {
+        SearchResultsHtml {
+          query,
+          facets,
+          inscriptions,
+          prev_page: page.checked_sub(1),
+          next_page: more.then_some(page + 1),
+        }
+        .page(server_config)
+        .into_response()
+      })
     })
   }
 
+  fn search_redirect(index: &Index, query: &str) -> ServerResult<Option<Redirect>> {
+    let path = if re::HASH.is_match(query) {
+      if index.block_header(query.parse().unwrap())?.is_some() {
+        format!("/block/{query}")
+      } else {
+        format!("/tx/{query}")
+      }
+    } else if re::OUTPOINT.is_match(query) {
+      format!("/output/{query}")
+    } else if re::INSCRIPTION_ID.is_match(query) || re::INSCRIPTION_NUMBER.is_match(query) {
+      format!("/inscription/{query}")
+    } else if let Some(captures) = re::COINKITE_SATSCARD_URL.captures(query) {
+      format!("/satscard?{}", &captures["parameters"])
+    } else if let Some(captures) = re::ORDINALS_SATSCARD_URL.captures(query) {
+      format!("/satscard?{}", &captures["query"])
+    } else if re::SPACED_RUNE.is_match(query) {
+      format!("/rune/{query}")
+    } else if re::RUNE_ID.is_match(query) {
+      let id = query
+        .parse::<RuneId>()
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+      let rune = index.get_rune_by_id(id)?.ok_or_not_found(|| "rune ID")?;
+
+      format!("/rune/{rune}")
+    } else if re::ADDRESS.is_match(query) {
+      format!("/address/{query}")
+    } else if re::SATPOINT.is_match(query) {
+      format!("/satpoint/{query}")
+    } else {
+      return Ok(None);
+    };
+
+    Ok(Some(Redirect::to(&path)))
+  }
+
   async fn favicon() -> ServerResult {
     Ok(
       Self::static_asset(Path("/favicon.png".to_string()))
@@ -1728,7 +2189,20 @@ impl Server {
 
         let properties = inscription.properties();
 
+        let brc20v2_rejection = if index.has_brc20v2_index() {
+          index.get_brc20v2_rejection(info.id)?
+        } else {
+          None
+        };
+
+        let transfers = if index.has_transfer_history_index() {
+          index.get_inscription_transfers(info.id)?
+        } else {
+          None
+        };
+
         InscriptionHtml {
+          brc20v2_rejection,
           chain: server_config.chain,
           charms: Charm::Vindicated.unset(info.charms.iter().fold(0, |mut acc, charm| {
             charm.set(&mut acc);
@@ -1750,6 +2224,7 @@ impl Server {
           sat: info.sat,
           satpoint: info.satpoint,
           timestamp: Utc.timestamp_opt(info.timestamp, 0).unwrap(),
+          transfers,
         }
         .page(server_config)
         .into_response()
@@ -2065,7 +2540,7 @@ mod tests {
       header::{self, HeaderMap},
     },
     serde::de::DeserializeOwned,
-    std::net::TcpListener,
+    std::{io::BufRead, net::TcpListener},
     tempfile::TempDir,
   };
 
@@ -2198,13 +2673,19 @@ mod tests {
         .or_defaults()
         .unwrap();
 
-      let index = Arc::new(Index::open(&settings).unwrap());
+      let (event_sender, event_receiver) = Events::channel();
+
+      let index = Arc::new(Index::open_with_event_sender(&settings, Some(event_sender)).unwrap());
       let ord_server_handle = Handle::new();
 
       {
         let index = index.clone();
         let ord_server_handle = ord_server_handle.clone();
-        thread::spawn(|| server.run(settings, index, ord_server_handle).unwrap());
+        thread::spawn(|| {
+          server
+            .run(settings, index, ord_server_handle, event_receiver)
+            .unwrap()
+        });
       }
 
       while index.statistic(crate::index::Statistic::Commits) == 0 {
@@ -2246,6 +2727,14 @@ mod tests {
       self.ord_flag("--index-addresses")
     }
 
+    fn index_brc20v2(self) -> Self {
+      self.ord_flag("--index-brc20v2")
+    }
+
+    fn index_events(self) -> Self {
+      self.ord_flag("--index-events")
+    }
+
     fn index_runes(self) -> Self {
       self.ord_flag("--index-runes")
     }
@@ -2254,6 +2743,10 @@ mod tests {
       self.ord_flag("--index-sats")
     }
 
+    fn index_transfer_history(self) -> Self {
+      self.ord_flag("--index-transfer-history")
+    }
+
     fn redirect_http_to_https(self) -> Self {
       self.server_flag("--redirect-http-to-https")
     }
@@ -2706,6 +3199,101 @@ mod tests {
     TestServer::new().assert_redirect("/faq", "https://docs.ordinals.com/faq");
   }
 
+  #[test]
+  fn events_are_streamed_from_height() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let response = server.get("/events?from_height=2&type=inscription_created");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_TYPE).unwrap(),
+      "text/event-stream"
+    );
+
+    let mut lines = BufReader::new(response).lines();
+
+    assert_eq!(lines.next().unwrap().unwrap(), "event: inscription_created");
+
+    let event = serde_json::from_str::<Event>(
+      lines
+        .next()
+        .unwrap()
+        .unwrap()
+        .strip_prefix("data: ")
+        .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(event.block_height(), 2);
+    assert_eq!(
+      event.inscription_id(),
+      Some(InscriptionId { txid, index: 0 })
+    );
+  }
+
+  #[test]
+  fn events_are_listed_by_block() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_events()
+      .build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let events = server.get_json::<api::Events>("/events/2");
+
+    assert_eq!(events.events.len(), 1);
+    assert_eq!(events.events[0].tx_index, 1);
+    assert_eq!(
+      events.events[0].event.inscription_id(),
+      Some(InscriptionId { txid, index: 0 })
+    );
+    assert!(!events.more);
+    assert_eq!(events.page_index, 0);
+
+    assert!(
+      server
+        .get_json::<api::Events>("/events/2/1")
+        .events
+        .is_empty()
+    );
+  }
+
+  #[test]
+  fn events_by_block_require_event_index() {
+    TestServer::new().assert_response(
+      "/events/0",
+      StatusCode::NOT_FOUND,
+      "this server has no event index",
+    );
+  }
+
+  #[test]
+  fn events_with_invalid_type_are_rejected() {
+    TestServer::new().assert_response(
+      "/events?type=inscription_created,foo",
+      StatusCode::BAD_REQUEST,
+      "invalid event type: foo",
+    );
+  }
+
   #[test]
   fn search_by_query_returns_rune() {
     TestServer::new().assert_redirect("/search?query=ABCD", "/rune/ABCD");
@@ -2863,7 +3451,95 @@ mod tests {
       &format!("/sat/{}", 50 * COIN_VALUE),
     );
 
-    server.assert_response_regex("/search/1:2:3", StatusCode::BAD_REQUEST, ".*");
+    server.assert_response_regex("/search/1:2:3", StatusCode::BAD_REQUEST, ".*");
+  }
+
+  #[test]
+  fn search_returns_inscriptions_matching_query_and_facets() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-search")
+      .build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        inscription("text/plain", "Hello, world!").to_witness(),
+      )],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let id = InscriptionId { txid, index: 0 };
+
+    assert_eq!(
+      server.get_json::<api::SearchResults>("/search?query=hello"),
+      api::SearchResults {
+        inscriptions: vec![id],
+        more: false,
+        page: 0,
+      },
+    );
+
+    assert_eq!(
+      server
+        .get_json::<api::SearchResults>("/search?query=world&content_type=text/plain&min_height=2")
+        .inscriptions,
+      [id],
+    );
+
+    for facet in ["content_type=image/png", "charm=cursed", "max_height=1"] {
+      assert!(
+        server
+          .get_json::<api::SearchResults>(format!("/search?query=world&{facet}"))
+          .inscriptions
+          .is_empty()
+      );
+    }
+
+    server.assert_response_regex(
+      "/search/hello%20world",
+      StatusCode::OK,
+      format!(".*<title>Search Results for hello world</title>.*<a href=/inscription/{id}>.*"),
+    );
+
+    server.assert_redirect("/search/0", "/inscription/0");
+
+    server.assert_response(
+      "/search?query=&min_height=1",
+      StatusCode::BAD_REQUEST,
+      "searches with facets require a query, content type or parent",
+    );
+  }
+
+  #[test]
+  fn search_with_search_index_redirects_sats() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-search")
+      .build();
+
+    server.assert_redirect("/search?query=nvtdijuwxlp", "/sat/nvtdijuwxlp");
+    server.assert_redirect("/search/nvtdijuwxlp", "/sat/nvtdijuwxlp");
+    server.assert_redirect("/search?query=1.1", "/sat/1.1");
+  }
+
+  #[test]
+  fn search_facets_require_search_index() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.assert_response(
+      "/search?query=hello&min_height=1",
+      StatusCode::NOT_FOUND,
+      "this server has no search index",
+    );
+
+    server.assert_redirect("/search/hello", "/sat/hello");
   }
 
   #[test]
@@ -4877,6 +5553,141 @@ mod tests {
     );
   }
 
+  #[test]
+  fn inscription_history() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_transfer_history()
+      .build();
+
+    server.mine_blocks(1);
+
+    let create = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/foo", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let transfer = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Default::default())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let id = InscriptionId {
+      txid: create,
+      index: 0,
+    };
+
+    let history =
+      server.get_json::<api::InscriptionHistory>(format!("/r/inscription/{id}/history"));
+
+    assert_eq!(history.id, id);
+    assert_eq!(history.transfers.len(), 2);
+    assert_eq!(history.transfers[0].height, 2);
+    assert_eq!(history.transfers[0].old_satpoint, None);
+    assert_eq!(history.transfers[1].height, 3);
+    assert_eq!(history.transfers[1].txid, transfer);
+    assert_eq!(
+      history.transfers[1].old_satpoint,
+      Some(history.transfers[0].new_satpoint)
+    );
+
+    assert_eq!(
+      server.get_json::<api::InscriptionHistory>(format!("/inscription/{id}/history")),
+      history,
+    );
+
+    server.assert_response_regex(
+      format!("/inscription/{id}"),
+      StatusCode::OK,
+      format!(
+        ".*<h2>History</h2>.*<a class=collapse href=/tx/{create}>.*<a class=collapse href=/tx/{transfer}>.*"
+      ),
+    );
+  }
+
+  #[test]
+  fn inscription_history_requires_transfer_history_index() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/foo", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let id = InscriptionId { txid, index: 0 };
+
+    server.assert_response(
+      format!("/r/inscription/{id}/history"),
+      StatusCode::NOT_FOUND,
+      "this server has no transfer history index",
+    );
+  }
+
+  #[test]
+  fn address_transactions() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_addresses()
+      .ord_flag("--index-address-history")
+      .build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/foo", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let address = default_address(Chain::Regtest);
+
+    let transactions =
+      server.get_json::<api::AddressTransactions>(format!("/address/{address}/transactions"));
+
+    assert!(!transactions.more);
+    assert_eq!(transactions.page, 0);
+
+    let transaction = &transactions.transactions[0];
+    assert_eq!(transaction.height, 2);
+    assert_eq!(transaction.txid, txid);
+    assert_eq!(transaction.received, 50 * COIN_VALUE);
+    assert_eq!(transaction.spent, 0);
+    assert_eq!(
+      transaction.inscriptions_received,
+      [InscriptionId { txid, index: 0 }],
+    );
+    assert!(transaction.inscriptions_sent.is_empty());
+
+    server.assert_response_regex(
+      format!("/address/{address}/transactions"),
+      StatusCode::OK,
+      format!(".*<h1><a href=/address/{address}>Address {address}</a> Transactions</h1>.*<a class=collapse href=/tx/{txid}>.*"),
+    );
+  }
+
+  #[test]
+  fn address_transactions_require_address_history_index() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_addresses()
+      .build();
+
+    server.assert_response(
+      format!("/address/{}/transactions", default_address(Chain::Regtest)),
+      StatusCode::NOT_FOUND,
+      "this server has no address history index",
+    );
+  }
+
   #[test]
   fn inscription_page_has_sat_when_sats_are_tracked() {
     let server = TestServer::builder()
@@ -6301,81 +7112,390 @@ next
       )]
     );
 
-    server.mine_blocks(1);
+    server.mine_blocks(1);
+
+    // merge rune with two inscriptions
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[
+        (6, 0, 0, inscription("text/plain", "foo").to_witness()),
+        (7, 0, 0, inscription("text/plain", "bar").to_witness()),
+        (7, 1, 0, Witness::new()),
+      ],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+    let second_inscription_id = InscriptionId { txid, index: 1 };
+    let outpoint: OutPoint = OutPoint { txid, vout: 0 };
+
+    let utxo_recursive = server.get_json::<api::UtxoRecursive>(format!("/r/utxo/{outpoint}"));
+
+    pretty_assert_eq!(
+      utxo_recursive,
+      api::UtxoRecursive {
+        inscriptions: Some(vec![inscription_id, second_inscription_id]),
+        runes: Some(
+          [(
+            SpacedRune { rune, spacers: 0 },
+            Pile {
+              amount: u128::MAX,
+              divisibility: 1,
+              symbol: None
+            }
+          )]
+          .into_iter()
+          .collect()
+        ),
+        sat_ranges: Some(vec![
+          (6 * 50 * COIN_VALUE, 7 * 50 * COIN_VALUE),
+          (7 * 50 * COIN_VALUE, 8 * 50 * COIN_VALUE),
+          (50 * COIN_VALUE, 2 * 50 * COIN_VALUE)
+        ]),
+        value: 150 * COIN_VALUE,
+      }
+    );
+  }
+
+  #[test]
+  fn utxo_recursive_endpoint_only_inscriptions() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+    let outpoint: OutPoint = OutPoint { txid, vout: 0 };
+
+    let utxo_recursive = server.get_json::<api::UtxoRecursive>(format!("/r/utxo/{outpoint}"));
+
+    pretty_assert_eq!(
+      utxo_recursive,
+      api::UtxoRecursive {
+        inscriptions: Some(vec![inscription_id]),
+        runes: None,
+        sat_ranges: None,
+        value: 50 * COIN_VALUE,
+      }
+    );
+  }
+
+  fn brc20v2_operations() -> Vec<crate::brc20v2::Operation> {
+    use crate::brc20v2::{IdentityCommitment, Operation, TokenDefinition};
+
+    vec![
+      Operation::Deploy {
+        definition: TokenDefinition {
+          ticker: "ordi".into(),
+          max_supply: 1000,
+          mint_limit: 1000,
+          decimals: 0,
+          soulbound: false,
+          transfer_rules: Default::default(),
+        },
+      },
+      Operation::Mint {
+        ticker: "ordi".into(),
+        to: IdentityCommitment::new("alice"),
+        amount: 100,
+      },
+      Operation::Mint {
+        ticker: "ordi".into(),
+        to: IdentityCommitment::new("bob"),
+        amount: 50,
+      },
+    ]
+  }
+
+  fn brc20v2_server(operations: &[crate::brc20v2::Operation]) -> TestServer {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_brc20v2()
+      .build();
+
+    server.mine_blocks(operations.len().try_into().unwrap());
+
+    let mut decimals = BTreeMap::new();
+
+    for (i, operation) in operations.iter().enumerate() {
+      if let crate::brc20v2::Operation::Deploy { definition } = operation {
+        decimals.insert(definition.ticker.clone(), definition.decimals);
+      }
+
+      server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          i + 1,
+          0,
+          0,
+          inscription(
+            "application/json",
+            crate::brc20v2::Brc20Inscription::from_operation(
+              operation,
+              decimals[&operation.ticker()],
+              [0; 32],
+            )
+            .body,
+          )
+          .to_witness(),
+        )],
+        ..default()
+      });
+
+      server.mine_blocks(1);
+    }
+
+    server
+  }
+
+  #[test]
+  fn brc20v2_inclusion_proof_recursive_endpoint() {
+    let server = brc20v2_server(&brc20v2_operations());
+
+    let proof = server.get_json::<api::Brc20v2InclusionProof>("/r/brc20v2/token/ordi/proof/alice");
+
+    let root = server
+      .index
+      .get_brc20v2_token("ordi")
+      .unwrap()
+      .unwrap()
+      .merkle_root();
+
+    assert_eq!(proof.leaf.unwrap().balance, 100);
+    assert_eq!(proof.identity, "alice");
+    assert_eq!(proof.siblings.len(), 1);
+    assert_eq!(proof.root, root);
+    assert!(proof.proof().verify(root));
+
+    let proof = server.get_json::<api::Brc20v2InclusionProof>("/r/brc20v2/token/ordi/proof/carol");
+
+    assert_eq!(proof.leaf, None);
+    assert_eq!(proof.root, root);
+    assert!(proof.proof().verify(root));
+
+    server.assert_response(
+      "/r/brc20v2/token/pepe/proof/alice",
+      StatusCode::NOT_FOUND,
+      "token pepe not found",
+    );
+  }
+
+  #[test]
+  fn brc20v2_tokens() {
+    let mut operations = brc20v2_operations();
+
+    operations.push(crate::brc20v2::Operation::Deploy {
+      definition: crate::brc20v2::TokenDefinition {
+        ticker: "pepe".into(),
+        max_supply: 100,
+        mint_limit: 10,
+        decimals: 2,
+        soulbound: true,
+        transfer_rules: Default::default(),
+      },
+    });
+
+    let server = brc20v2_server(&operations);
+
+    server.assert_response_regex(
+      "/brc20v2/tokens",
+      StatusCode::OK,
+      ".*<title>BRC-20 v2 Tokens</title>.*
+<ul>
+  <li><a href=/brc20v2/token/ordi>ordi</a></li>
+  <li><a href=/brc20v2/token/pepe>pepe</a></li>
+</ul>.*",
+    );
+
+    let tokens = server.get_json::<Brc20v2TokensHtml>("/brc20v2/tokens");
+
+    assert_eq!(
+      tokens
+        .entries
+        .iter()
+        .map(|(ticker, _)| ticker.as_str())
+        .collect::<Vec<&str>>(),
+      ["ordi", "pepe"],
+    );
+    assert!(!tokens.more);
+
+    assert_eq!(
+      server.get_json::<api::Brc20v2Tickers>("/r/brc20v2/tokens"),
+      api::Brc20v2Tickers {
+        tickers: vec!["ordi".into(), "pepe".into()],
+        more: false,
+        page: 0,
+      },
+    );
+
+    assert_eq!(
+      server.get_json::<api::Brc20v2Tickers>("/r/brc20v2/tokens/1"),
+      api::Brc20v2Tickers {
+        tickers: Vec::new(),
+        more: false,
+        page: 1,
+      },
+    );
+  }
+
+  #[test]
+  fn brc20v2_token() {
+    let server = brc20v2_server(&brc20v2_operations());
+
+    server.assert_response_regex(
+      "/brc20v2/token/ordi",
+      StatusCode::OK,
+      ".*<title>BRC-20 v2 Token ordi</title>.*
+<h1>ordi</h1>
+<dl>
+  <dt>supply</dt>
+  <dd>150</dd>
+.*
+  <dt>holders</dt>
+  <dd><a href=/brc20v2/token/ordi/holders>2</a></dd>
+.*",
+    );
+
+    let token = server.get_json::<api::Brc20v2Token>("/brc20v2/token/ordi");
+
+    assert_eq!(token.holders, 2);
+    assert_eq!(token.total_supply, 150);
+    assert_eq!(token.definition.max_supply, 1000);
+    assert_eq!(
+      token.merkle_root,
+      server
+        .index
+        .get_brc20v2_token("ordi")
+        .unwrap()
+        .unwrap()
+        .merkle_root()
+    );
+
+    assert_eq!(
+      server.get_json::<api::Brc20v2Token>("/r/brc20v2/token/ordi"),
+      token,
+    );
 
-    // merge rune with two inscriptions
-    let txid = server.core.broadcast_tx(TransactionTemplate {
-      inputs: &[
-        (6, 0, 0, inscription("text/plain", "foo").to_witness()),
-        (7, 0, 0, inscription("text/plain", "bar").to_witness()),
-        (7, 1, 0, Witness::new()),
-      ],
-      ..default()
-    });
+    server.assert_response(
+      "/brc20v2/token/pepe",
+      StatusCode::NOT_FOUND,
+      "token pepe not found",
+    );
 
-    server.mine_blocks(1);
+    server.assert_response(
+      "/r/brc20v2/token/pepe",
+      StatusCode::NOT_FOUND,
+      "token pepe not found",
+    );
+  }
 
-    let inscription_id = InscriptionId { txid, index: 0 };
-    let second_inscription_id = InscriptionId { txid, index: 1 };
-    let outpoint: OutPoint = OutPoint { txid, vout: 0 };
+  #[test]
+  fn brc20v2_holders() {
+    let server = brc20v2_server(&brc20v2_operations());
 
-    let utxo_recursive = server.get_json::<api::UtxoRecursive>(format!("/r/utxo/{outpoint}"));
+    server.assert_response_regex(
+      "/brc20v2/token/ordi/holders",
+      StatusCode::OK,
+      ".*<h1><a href=/brc20v2/token/ordi>ordi</a> Holders</h1>
+<dl>
+  <dt><a class=collapse href=/brc20v2/account/alice>alice</a></dt>
+  <dd>100</dd>
+  <dt><a class=collapse href=/brc20v2/account/bob>bob</a></dt>
+  <dd>50</dd>
+</dl>.*",
+    );
 
-    pretty_assert_eq!(
-      utxo_recursive,
-      api::UtxoRecursive {
-        inscriptions: Some(vec![inscription_id, second_inscription_id]),
-        runes: Some(
-          [(
-            SpacedRune { rune, spacers: 0 },
-            Pile {
-              amount: u128::MAX,
-              divisibility: 1,
-              symbol: None
-            }
-          )]
-          .into_iter()
-          .collect()
-        ),
-        sat_ranges: Some(vec![
-          (6 * 50 * COIN_VALUE, 7 * 50 * COIN_VALUE),
-          (7 * 50 * COIN_VALUE, 8 * 50 * COIN_VALUE),
-          (50 * COIN_VALUE, 2 * 50 * COIN_VALUE)
-        ]),
-        value: 150 * COIN_VALUE,
-      }
+    let holders = server.get_json::<api::Brc20v2Holders>("/r/brc20v2/token/ordi/holders");
+
+    assert_eq!(
+      holders
+        .holders
+        .iter()
+        .map(|holder| (holder.identity.as_str(), holder.balance))
+        .collect::<Vec<(&str, u128)>>(),
+      [("alice", 100), ("bob", 50)],
+    );
+    assert_eq!(
+      holders.holders[0].commitment,
+      crate::brc20v2::IdentityCommitment::new("alice").commitment,
+    );
+    assert!(!holders.more);
+
+    assert_eq!(
+      server.get_json::<Brc20v2HoldersHtml>("/brc20v2/token/ordi/holders/1"),
+      Brc20v2HoldersHtml {
+        decimals: 0,
+        holders: Vec::new(),
+        more: false,
+        prev: Some(0),
+        next: None,
+        ticker: "ordi".into(),
+      },
     );
   }
 
   #[test]
-  fn utxo_recursive_endpoint_only_inscriptions() {
-    let server = TestServer::builder().chain(Chain::Regtest).build();
-
-    server.mine_blocks(1);
+  fn brc20v2_account() {
+    let server = brc20v2_server(&brc20v2_operations());
 
-    let txid = server.core.broadcast_tx(TransactionTemplate {
-      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
-      ..default()
-    });
+    server.assert_response_regex(
+      "/brc20v2/account/alice",
+      StatusCode::OK,
+      ".*<title>BRC-20 v2 Account alice</title>.*
+  <dt>balances</dt>
+  <dd><a class=monospace href=/brc20v2/token/ordi>ordi</a>: 100</dd>
+</dl>.*",
+    );
 
-    server.mine_blocks(1);
+    let account = server.get_json::<api::Brc20v2Account>("/brc20v2/account/alice");
 
-    let inscription_id = InscriptionId { txid, index: 0 };
-    let outpoint: OutPoint = OutPoint { txid, vout: 0 };
+    assert_eq!(account.identity, "alice");
+    assert_eq!(account.balances.len(), 1);
+    assert_eq!(account.balances[0].0.ticker, "ordi");
+    assert_eq!(account.balances[0].1.balance, 100);
 
-    let utxo_recursive = server.get_json::<api::UtxoRecursive>(format!("/r/utxo/{outpoint}"));
+    assert_eq!(
+      server.get_json::<api::Brc20v2Account>("/r/brc20v2/account/alice"),
+      account,
+    );
 
-    pretty_assert_eq!(
-      utxo_recursive,
-      api::UtxoRecursive {
-        inscriptions: Some(vec![inscription_id]),
-        runes: None,
-        sat_ranges: None,
-        value: 50 * COIN_VALUE,
-      }
+    assert!(
+      server
+        .get_json::<api::Brc20v2Account>("/r/brc20v2/account/carol")
+        .balances
+        .is_empty()
     );
   }
 
+  #[test]
+  fn brc20v2_endpoints_require_index() {
+    let server = TestServer::new();
+
+    for path in [
+      "/brc20v2/account/alice",
+      "/brc20v2/token/ordi",
+      "/brc20v2/token/ordi/holders",
+      "/brc20v2/tokens",
+      "/r/brc20v2/account/alice",
+      "/r/brc20v2/token/ordi",
+      "/r/brc20v2/token/ordi/holders",
+      "/r/brc20v2/token/ordi/proof/alice",
+      "/r/brc20v2/tokens",
+    ] {
+      server.assert_response(
+        path,
+        StatusCode::NOT_FOUND,
+        "this server has no BRC-20 v2 index",
+      );
+    }
+  }
+
   #[test]
   fn sat_recursive_endpoints() {
     let server = TestServer::builder()
@@ -8047,45 +9167,160 @@ next
     );
   }
 
+  fn signed_offer(server: &TestServer, outpoint: OutPoint) -> String {
+    base64_encode(
+      &sell_offer(
+        outpoint,
+        &server.core.tx_by_id(outpoint.txid).output[outpoint.vout.into_usize()],
+        &offer_keypair(1),
+        bitcoin::TapSighashType::SinglePlusAnyoneCanPay,
+        100,
+      )
+      .serialize(),
+    )
+  }
+
   #[test]
   fn offers_are_accepted() {
-    let server = TestServer::builder().server_flag("--accept-offers").build();
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_flag("--accept-offers")
+      .build();
 
-    let psbt0 = base64_encode(
-      &Psbt {
-        unsigned_tx: Transaction {
-          version: Version(0),
-          lock_time: LockTime::ZERO,
-          input: Vec::new(),
-          output: Vec::new(),
-        },
-        version: 0,
-        xpub: BTreeMap::new(),
-        proprietary: BTreeMap::new(),
-        unknown: BTreeMap::new(),
-        inputs: Vec::new(),
-        outputs: Vec::new(),
-      }
-      .serialize(),
-    );
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/foo", "hello").to_witness())],
+      recipient: Some(offer_address(&offer_keypair(1))),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let id = InscriptionId { txid, index: 0 };
+
+    let offer = signed_offer(&server, OutPoint { txid, vout: 0 });
 
-    let response = server.post("offer", &psbt0, StatusCode::OK);
+    let response = server.post("offer", &offer, StatusCode::OK);
 
     assert_eq!(response.text().unwrap(), "");
 
-    let offers = server.get_json::<api::Offers>("/offers");
+    assert_eq!(
+      server.get_json::<api::Offers>("/offers"),
+      api::Offers {
+        offers: vec![offer.clone()],
+      },
+    );
+
+    assert_eq!(
+      server.get_json::<api::Offers>(format!("/offers/inscription/{id}")),
+      api::Offers {
+        offers: vec![offer],
+      },
+    );
+
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Default::default())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    assert_eq!(
+      server.get_json::<api::Offers>("/offers"),
+      api::Offers { offers: Vec::new() },
+    );
+
+    assert_eq!(
+      server.get_json::<api::Offers>(format!("/offers/inscription/{id}")),
+      api::Offers { offers: Vec::new() },
+    );
+  }
+
+  #[test]
+  fn rune_offers_can_be_queried() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .server_flag("--accept-offers")
+      .build();
+
+    server.mine_blocks(1);
+
+    let (txid, _id) = server.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: u128::MAX,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(u128::MAX),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    let (block, tx) = server.core.tx_index(txid);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(block, tx, 0, Default::default())],
+      recipient: Some(offer_address(&offer_keypair(1))),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let offer = signed_offer(&server, OutPoint { txid, vout: 0 });
+
+    server.post("offer", &offer, StatusCode::OK);
 
     assert_eq!(
-      offers,
+      server.get_json::<api::Offers>("/offers/rune/AAAAAAAAAAAAA"),
       api::Offers {
-        offers: vec![psbt0.clone()],
+        offers: vec![offer],
       },
     );
+  }
+
+  #[test]
+  fn offers_must_hold_inscriptions_or_runes() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_flag("--accept-offers")
+      .build();
+
+    server.mine_blocks(1);
+
+    let outpoint = OutPoint {
+      txid: server.core.tx(1, 0).compute_txid(),
+      vout: 0,
+    };
+
+    let response = server.post(
+      "offer",
+      &signed_offer(&server, outpoint),
+      StatusCode::BAD_REQUEST,
+    );
+
+    assert_eq!(
+      response.text().unwrap(),
+      format!("invalid offer: offer input {outpoint} does not hold any inscriptions or runes"),
+    );
+  }
+
+  #[test]
+  fn offers_without_inputs_are_rejected() {
+    let server = TestServer::builder().server_flag("--accept-offers").build();
 
-    let psbt1 = base64_encode(
+    let psbt = base64_encode(
       &Psbt {
         unsigned_tx: Transaction {
-          version: Version(1),
+          version: Version(0),
           lock_time: LockTime::ZERO,
           input: Vec::new(),
           output: Vec::new(),
@@ -8100,17 +9335,11 @@ next
       .serialize(),
     );
 
-    let response = server.post("offer", &psbt1, StatusCode::OK);
-
-    assert_eq!(response.text().unwrap(), "");
-
-    let offers = server.get_json::<api::Offers>("/offers");
+    let response = server.post("offer", &psbt, StatusCode::BAD_REQUEST);
 
     assert_eq!(
-      offers,
-      api::Offers {
-        offers: vec![psbt0, psbt1],
-      },
+      response.text().unwrap(),
+      "invalid offer: offer has no inputs"
     );
   }
 
@@ -8148,29 +9377,26 @@ next
   #[test]
   fn offer_acceptance_does_not_require_json_api() {
     let server = TestServer::builder()
+      .chain(Chain::Regtest)
       .server_flag("--disable-json-api")
       .server_flag("--accept-offers")
       .build();
 
-    let psbt = base64_encode(
-      &Psbt {
-        unsigned_tx: Transaction {
-          version: Version(0),
-          lock_time: LockTime::ZERO,
-          input: Vec::new(),
-          output: Vec::new(),
-        },
-        version: 0,
-        xpub: BTreeMap::new(),
-        proprietary: BTreeMap::new(),
-        unknown: BTreeMap::new(),
-        inputs: Vec::new(),
-        outputs: Vec::new(),
-      }
-      .serialize(),
-    );
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/foo", "hello").to_witness())],
+      recipient: Some(offer_address(&offer_keypair(1))),
+      ..default()
+    });
 
-    server.post("offer", &psbt, StatusCode::OK);
+    server.mine_blocks(1);
+
+    server.post(
+      "offer",
+      &signed_offer(&server, OutPoint { txid, vout: 0 }),
+      StatusCode::OK,
+    );
   }
 
   #[test]
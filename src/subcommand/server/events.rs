@@ -0,0 +1,351 @@
+use {
+  super::*,
+  crate::index::event::{Event, EventType},
+  axum::response::sse::{self, KeepAlive, Sse},
+  std::collections::VecDeque,
+  tokio::sync::{broadcast, mpsc},
+};
+
+const BROADCAST_CAPACITY: usize = 4096;
+const CHANNEL_CAPACITY: usize = 1024;
+const HISTORY_BLOCKS: u32 = 10;
+
+#[derive(Deserialize)]
+pub(super) struct EventsQuery {
+  from_height: Option<u32>,
+  inscription: Option<InscriptionId>,
+  rune: Option<RuneId>,
+  #[serde(rename = "type")]
+  ty: Option<String>,
+}
+
+struct Filter {
+  inscription: Option<InscriptionId>,
+  rune: Option<RuneId>,
+  types: Option<BTreeSet<EventType>>,
+}
+
+impl Filter {
+  fn matches(&self, event: &Event) -> bool {
+    if let Some(inscription) = self.inscription
+      && event.inscription_id() != Some(inscription)
+    {
+      return false;
+    }
+
+    if let Some(rune) = self.rune
+      && event.rune_id() != Some(rune)
+    {
+      return false;
+    }
+
+    if let Some(types) = &self.types
+      && !types.contains(&event.event_type())
+    {
+      return false;
+    }
+
+    true
+  }
+}
+
+struct History {
+  events: VecDeque<Event>,
+  first_height: u32,
+}
+
+/// Fans index events out to `/events` subscribers, keeping the events of
+/// the last `HISTORY_BLOCKS` blocks so that subscribers can resume.
+pub(crate) struct Events {
+  history: Mutex<History>,
+  sender: broadcast::Sender<Event>,
+}
+
+impl Events {
+  /// `first_height` is the height of the first block whose events will be
+  /// received, which is the block count of the index when it is opened.
+  pub(crate) fn new(first_height: u32) -> Self {
+    Self {
+      history: Mutex::new(History {
+        events: VecDeque::new(),
+        first_height,
+      }),
+      sender: broadcast::channel(BROADCAST_CAPACITY).0,
+    }
+  }
+
+  pub(crate) fn channel() -> (mpsc::Sender<Event>, mpsc::Receiver<Event>) {
+    mpsc::channel(CHANNEL_CAPACITY)
+  }
+
+  pub(crate) async fn forward(self: Arc<Self>, mut receiver: mpsc::Receiver<Event>) {
+    while let Some(event) = receiver.recv().await {
+      self.push(event);
+    }
+  }
+
+  fn push(&self, event: Event) {
+    let mut history = self.history.lock().unwrap();
+
+    let height = event.block_height();
+
+    // events arrive in block order, so an earlier height means that blocks
+    // from that height on were reorged out and are being indexed again
+    if history
+      .events
+      .back()
+      .is_some_and(|last| last.block_height() > height)
+    {
+      while history
+        .events
+        .back()
+        .is_some_and(|last| last.block_height() >= height)
+      {
+        history.events.pop_back();
+      }
+    }
+
+    while let Some(first) = history.events.front().map(Event::block_height)
+      && first + HISTORY_BLOCKS <= height
+    {
+      history.first_height = first + 1;
+      history.events.pop_front();
+    }
+
+    history.events.push_back(event.clone());
+
+    self.sender.send(event).ok();
+  }
+
+  /// Subscribe to events, first replaying the events of blocks from
+  /// `from_height` on. If the index records events, committed blocks are
+  /// replayed from the event log, and only later blocks from the history, so
+  /// that any height can be resumed from. Returns the replayed events, the
+  /// height below which live events were already replayed from the log, and
+  /// the receiver of live events.
+  fn subscribe(
+    &self,
+    index: &Index,
+    from_height: Option<u32>,
+  ) -> ServerResult<(Vec<Event>, u32, broadcast::Receiver<Event>)> {
+    let history = self.history.lock().unwrap();
+
+    let Some(from_height) = from_height else {
+      return Ok((Vec::new(), 0, self.sender.subscribe()));
+    };
+
+    let (mut replay, logged) = if index.has_event_index() {
+      let (records, block_count) = index.get_events_since(from_height)?;
+
+      (
+        records.into_iter().map(|record| record.event).collect(),
+        block_count,
+      )
+    } else {
+      (Vec::new(), 0)
+    };
+
+    let from_height = from_height.max(logged);
+
+    if from_height < history.first_height {
+      return Err(ServerError::BadRequest(format!(
+        "events before height {} are not available{}",
+        history.first_height,
+        if index.has_event_index() {
+          ""
+        } else {
+          ", the index must record events with `--index-events` to resume from earlier heights"
+        },
+      )));
+    }
+
+    replay.extend(
+      history
+        .events
+        .iter()
+        .filter(|event| event.block_height() >= from_height)
+        .cloned(),
+    );
+
+    Ok((replay, logged, self.sender.subscribe()))
+  }
+
+  pub(super) fn stream(&self, index: &Index, query: EventsQuery) -> ServerResult {
+    let filter = Filter {
+      inscription: query.inscription,
+      rune: query.rune,
+      types: query
+        .ty
+        .map(|types| {
+          types
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<BTreeSet<EventType>>>()
+        })
+        .transpose()
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?,
+    };
+
+    let (replay, logged, receiver) = self.subscribe(index, query.from_height)?;
+
+    // events of logged blocks that were committed before they were received
+    // are skipped, up to the first event of a block that wasn't logged. a
+    // subscriber that lags behind is disconnected, and may resume from the
+    // height of the last event it received.
+    let live = futures::stream::unfold(
+      (receiver, logged),
+      |(mut receiver, mut logged)| async move {
+        loop {
+          let event = receiver.recv().await.ok()?;
+
+          if event.block_height() >= logged {
+            logged = 0;
+            return Some((event, (receiver, logged)));
+          }
+        }
+      },
+    );
+
+    let stream = tokio_stream::iter(replay)
+      .chain(live)
+      .filter(move |event| filter.matches(event))
+      .map(|event| {
+        sse::Event::default()
+          .event(event.event_type().to_string())
+          .json_data(event)
+      });
+
+    Ok(
+      Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::index::testing::Context};
+
+  fn event(block_height: u32) -> Event {
+    Event::RuneEtched {
+      block_height,
+      rune_id: RuneId {
+        block: block_height.into(),
+        tx: 0,
+      },
+      txid: txid(block_height),
+    }
+  }
+
+  fn heights(events: &Events, index: &Index, from_height: u32) -> Vec<u32> {
+    events
+      .subscribe(index, Some(from_height))
+      .unwrap()
+      .0
+      .iter()
+      .map(Event::block_height)
+      .collect()
+  }
+
+  #[test]
+  fn history_is_replayed_from_height() {
+    let context = Context::builder().build();
+
+    let events = Events::new(1);
+
+    for height in 1..4 {
+      events.push(event(height));
+    }
+
+    assert_eq!(heights(&events, &context.index, 1), [1, 2, 3]);
+    assert_eq!(heights(&events, &context.index, 3), [3]);
+    assert!(heights(&events, &context.index, 4).is_empty());
+    assert!(events.subscribe(&context.index, Some(0)).is_err());
+  }
+
+  #[test]
+  fn history_is_limited_to_recent_blocks() {
+    let context = Context::builder().build();
+
+    let events = Events::new(0);
+
+    for height in 0..HISTORY_BLOCKS + 2 {
+      events.push(event(height));
+    }
+
+    assert!(events.subscribe(&context.index, Some(1)).is_err());
+    assert_eq!(
+      heights(&events, &context.index, 2).len(),
+      usize::try_from(HISTORY_BLOCKS).unwrap()
+    );
+  }
+
+  #[test]
+  fn reorged_events_are_dropped_from_history() {
+    let context = Context::builder().build();
+
+    let events = Events::new(0);
+
+    for height in [0, 1, 2, 2, 3] {
+      events.push(event(height));
+    }
+
+    events.push(event(2));
+
+    assert_eq!(heights(&events, &context.index, 0), [0, 1, 2]);
+  }
+
+  #[test]
+  fn earlier_heights_are_rejected_without_event_log() {
+    let context = Context::builder().build();
+
+    let events = Events::new(5);
+
+    let Err(ServerError::BadRequest(message)) = events.subscribe(&context.index, Some(4)) else {
+      panic!("resuming from before the history succeeded");
+    };
+
+    assert_eq!(
+      message,
+      "events before height 5 are not available, the index must record events with \
+      `--index-events` to resume from earlier heights",
+    );
+  }
+
+  #[test]
+  fn logged_events_are_replayed_from_event_log() {
+    let context = Context::builder().arg("--index-events").build();
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Witness::new())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let block_count = context.index.block_count().unwrap();
+
+    let events = Events::new(block_count);
+
+    assert_eq!(heights(&events, &context.index, 0), [2, 3]);
+    assert_eq!(heights(&events, &context.index, 3), [3]);
+
+    events.push(event(block_count));
+
+    assert_eq!(heights(&events, &context.index, 0), [2, 3, block_count],);
+    assert_eq!(
+      events.subscribe(&context.index, Some(0)).unwrap().1,
+      block_count,
+    );
+  }
+}
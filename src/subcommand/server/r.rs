@@ -146,6 +146,129 @@ pub(super) async fn blocktime_string(
   })
 }
 
+pub(super) async fn brc20v2_account(
+  Extension(index): Extension<Arc<Index>>,
+  Path(identity): Path<String>,
+) -> ServerResult<Json<api::Brc20v2Account>> {
+  task::block_in_place(|| {
+    if !index.has_brc20v2_index() {
+      return Err(ServerError::NotFound(
+        "this server has no BRC-20 v2 index".to_string(),
+      ));
+    }
+
+    let identity = crate::brc20v2::IdentityCommitment::new(identity);
+
+    Ok(Json(api::Brc20v2Account {
+      balances: index.get_brc20v2_balances(&identity)?,
+      commitment: identity.commitment,
+      identity: identity.id,
+    }))
+  })
+}
+
+pub(super) async fn brc20v2_holders(
+  Extension(index): Extension<Arc<Index>>,
+  Path(ticker): Path<String>,
+) -> ServerResult<Json<api::Brc20v2Holders>> {
+  brc20v2_holders_paginated(Extension(index), Path((ticker, 0))).await
+}
+
+pub(super) async fn brc20v2_holders_paginated(
+  Extension(index): Extension<Arc<Index>>,
+  Path((ticker, page)): Path<(String, usize)>,
+) -> ServerResult<Json<api::Brc20v2Holders>> {
+  task::block_in_place(|| {
+    if !index.has_brc20v2_index() {
+      return Err(ServerError::NotFound(
+        "this server has no BRC-20 v2 index".to_string(),
+      ));
+    }
+
+    index
+      .get_brc20v2_token_info(&ticker)?
+      .ok_or_not_found(|| format!("token {ticker}"))?;
+
+    let (holders, more) = index.get_brc20v2_holders_paginated(&ticker, 100, page)?;
+
+    Ok(Json(api::Brc20v2Holders {
+      holders,
+      more,
+      page,
+    }))
+  })
+}
+
+pub(super) async fn brc20v2_inclusion_proof(
+  Extension(index): Extension<Arc<Index>>,
+  Path((ticker, identity)): Path<(String, String)>,
+) -> ServerResult<Json<api::Brc20v2InclusionProof>> {
+  task::block_in_place(|| {
+    if !index.has_brc20v2_index() {
+      return Err(ServerError::NotFound(
+        "this server has no BRC-20 v2 index".to_string(),
+      ));
+    }
+
+    let (proof, root) = index
+      .get_brc20v2_inclusion_proof(
+        &ticker,
+        &crate::brc20v2::IdentityCommitment::new(identity.as_str()),
+      )?
+      .ok_or_not_found(|| format!("token {ticker}"))?;
+
+    Ok(Json(api::Brc20v2InclusionProof::new(
+      ticker, identity, proof, root,
+    )))
+  })
+}
+
+pub(super) async fn brc20v2_token(
+  Extension(index): Extension<Arc<Index>>,
+  Path(ticker): Path<String>,
+) -> ServerResult<Json<api::Brc20v2Token>> {
+  task::block_in_place(|| {
+    if !index.has_brc20v2_index() {
+      return Err(ServerError::NotFound(
+        "this server has no BRC-20 v2 index".to_string(),
+      ));
+    }
+
+    Ok(Json(
+      index
+        .get_brc20v2_token_info(&ticker)?
+        .ok_or_not_found(|| format!("token {ticker}"))?,
+    ))
+  })
+}
+
+pub(super) async fn brc20v2_tokens(
+  Extension(index): Extension<Arc<Index>>,
+) -> ServerResult<Json<api::Brc20v2Tickers>> {
+  brc20v2_tokens_paginated(Extension(index), Path(0)).await
+}
+
+pub(super) async fn brc20v2_tokens_paginated(
+  Extension(index): Extension<Arc<Index>>,
+  Path(page): Path<usize>,
+) -> ServerResult<Json<api::Brc20v2Tickers>> {
+  task::block_in_place(|| {
+    if !index.has_brc20v2_index() {
+      return Err(ServerError::NotFound(
+        "this server has no BRC-20 v2 index".to_string(),
+      ));
+    }
+
+    let (entries, more) = index.get_brc20v2_tokens_paginated(100, page)?;
+
+    Ok(Json(api::Brc20v2Tickers {
+      tickers: entries.into_iter().map(|(ticker, _)| ticker).collect(),
+      more,
+      page,
+    }))
+  })
+}
+
 pub(super) async fn children(
   Extension(index): Extension<Arc<Index>>,
   Path(inscription_id): Path<InscriptionId>,
@@ -404,6 +527,28 @@ pub(super) async fn inscription(
   })
 }
 
+pub(super) async fn inscription_history(
+  Extension(index): Extension<Arc<Index>>,
+  Path(inscription_id): Path<InscriptionId>,
+) -> ServerResult<Json<api::InscriptionHistory>> {
+  task::block_in_place(|| {
+    if !index.has_transfer_history_index() {
+      return Err(ServerError::NotFound(
+        "this server has no transfer history index".to_string(),
+      ));
+    }
+
+    let transfers = index
+      .get_inscription_transfers(inscription_id)?
+      .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+    Ok(Json(api::InscriptionHistory {
+      id: inscription_id,
+      transfers,
+    }))
+  })
+}
+
 pub(super) async fn metadata(
   Extension(index): Extension<Arc<Index>>,
   Path(inscription_id): Path<InscriptionId>,
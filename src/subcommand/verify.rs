@@ -38,9 +38,7 @@ impl Verify {
     };
 
     if let Some(witness) = self.witness {
-      let mut cursor = bitcoin::io::Cursor::new(general_purpose::STANDARD.decode(witness)?);
-      let witness = Witness::consensus_decode_from_finite_reader(&mut cursor)?;
-      bip322::verify_simple(&self.address.assume_checked(), message, witness)?;
+      verify_witness(&self.address.assume_checked(), message, &witness)?;
     } else if let Some(transaction) = self.transaction {
       let mut cursor = bitcoin::io::Cursor::new(general_purpose::STANDARD.decode(transaction)?);
       let transaction = Transaction::consensus_decode_from_finite_reader(&mut cursor)?;
@@ -52,3 +50,12 @@ impl Verify {
     Ok(None)
   }
 }
+
+/// Verify a base64-encoded BIP-322 simple signature, as output by
+/// `ord wallet sign`, over `message` by `address`.
+pub(crate) fn verify_witness(address: &Address, message: &[u8], witness: &str) -> Result {
+  let mut cursor = bitcoin::io::Cursor::new(general_purpose::STANDARD.decode(witness)?);
+  let witness = Witness::consensus_decode_from_finite_reader(&mut cursor)?;
+  bip322::verify_simple(address, message, witness)?;
+  Ok(())
+}
@@ -7,6 +7,7 @@ use {
 pub mod addresses;
 pub mod balance;
 mod batch_command;
+pub mod brc20v2;
 pub mod burn;
 pub mod cardinals;
 pub mod create;
@@ -54,6 +55,8 @@ pub(crate) enum Subcommand {
   Balance,
   #[command(about = "Create inscriptions and runes")]
   Batch(batch_command::Batch),
+  #[command(subcommand, about = "BRC-20 v2 commands")]
+  Brc20v2(brc20v2::Brc20v2),
   #[command(about = "Burn an inscription")]
   Burn(burn::Burn),
   #[command(about = "List unspent cardinal outputs in wallet")]
@@ -124,6 +127,7 @@ impl WalletCommand {
       Subcommand::Addresses => addresses::run(wallet),
       Subcommand::Balance => balance::run(wallet),
       Subcommand::Batch(batch) => batch.run(wallet),
+      Subcommand::Brc20v2(brc20v2) => brc20v2.run(wallet),
       Subcommand::Burn(burn) => burn.run(wallet),
       Subcommand::Cardinals => cardinals::run(wallet),
       Subcommand::Create(_) | Subcommand::Restore(_) => unreachable!(),
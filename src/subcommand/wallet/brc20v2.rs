@@ -0,0 +1,91 @@
+use {
+  super::*,
+  crate::brc20v2::{Brc20Inscription, IdentityCommitment, Operation},
+};
+
+pub mod balance;
+pub mod deploy;
+pub mod mint;
+pub mod transfer;
+
+#[derive(Debug, Parser)]
+pub(crate) enum Brc20v2 {
+  #[command(about = "Show BRC-20 v2 balances of wallet addresses")]
+  Balance(balance::Balance),
+  #[command(about = "Deploy a BRC-20 v2 token")]
+  Deploy(deploy::Deploy),
+  #[command(about = "Mint BRC-20 v2 tokens")]
+  Mint(mint::Mint),
+  #[command(about = "Transfer BRC-20 v2 tokens")]
+  Transfer(transfer::Transfer),
+}
+
+impl Brc20v2 {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    match self {
+      Self::Balance(balance) => balance.run(wallet),
+      Self::Deploy(deploy) => deploy.run(wallet),
+      Self::Mint(mint) => mint.run(wallet),
+      Self::Transfer(transfer) => transfer.run(wallet),
+    }
+  }
+
+  /// Inscribe `operation` on a token with `decimals` in the first output of
+  /// the reveal transaction, sent to `destination`, which makes `destination`
+  /// the operation's owner.
+  fn inscribe(
+    wallet: &Wallet,
+    shared: &SharedArgs,
+    operation: &Operation,
+    decimals: u8,
+    destination: Address,
+    postage: Amount,
+  ) -> Result<batch::Output> {
+    ensure!(
+      !shared.compress,
+      "BRC-20 v2 inscriptions cannot be compressed"
+    );
+
+    let Brc20Inscription { content_type, body } =
+      Brc20Inscription::from_operation(operation, decimals, [0; 32]);
+
+    if let Some(Err(rejection)) =
+      Brc20Inscription::parse(&body).map(|payload| payload?.into_operation(Some(decimals)))
+    {
+      bail!("invalid BRC-20 v2 operation: {rejection}");
+    }
+
+    batch::Plan {
+      commit_fee_rate: shared.commit_fee_rate.unwrap_or(shared.fee_rate),
+      destinations: vec![destination],
+      dry_run: shared.dry_run,
+      inscriptions: vec![Inscription {
+        body: Some(body),
+        content_type: Some(content_type.into_bytes()),
+        ..default()
+      }],
+      mode: batch::Mode::SeparateOutputs,
+      no_backup: shared.no_backup,
+      no_limit: shared.no_limit,
+      postages: vec![postage],
+      reveal_fee_rate: shared.fee_rate,
+      ..default()
+    }
+    .inscribe_output(
+      &wallet.locked_utxos().clone().into_keys().collect(),
+      wallet.get_runic_outputs()?.unwrap_or_default(),
+      wallet.utxos(),
+      wallet,
+    )
+  }
+
+  fn token(wallet: &Wallet, ticker: &str) -> Result<api::Brc20v2Token> {
+    wallet
+      .get_brc20v2_token(ticker)?
+      .ok_or_else(|| anyhow!("BRC-20 v2 token {ticker} has not been deployed"))
+  }
+
+  fn identity(address: &Address) -> IdentityCommitment {
+    IdentityCommitment::new(address.to_string())
+  }
+}
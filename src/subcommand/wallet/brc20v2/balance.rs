@@ -0,0 +1,60 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Balance {
+  #[arg(
+    long,
+    help = "Show balances of <IDENTITY> instead of wallet addresses."
+  )]
+  identity: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub balances: BTreeMap<String, BTreeMap<String, Decimal>>,
+}
+
+impl Balance {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let identities = match self.identity {
+      Some(identity) => [identity].into(),
+      None => wallet
+        .utxos()
+        .values()
+        .filter_map(|txout| {
+          wallet
+            .chain()
+            .address_from_script(&txout.script_pubkey)
+            .ok()
+        })
+        .map(|address| address.to_string())
+        .collect::<BTreeSet<String>>(),
+    };
+
+    let mut balances = BTreeMap::new();
+
+    for identity in identities {
+      let tokens = wallet
+        .get_brc20v2_account(&identity)?
+        .balances
+        .into_iter()
+        .filter(|(_, account)| account.balance > 0)
+        .map(|(definition, account)| {
+          (
+            definition.ticker,
+            Decimal {
+              value: account.balance,
+              scale: definition.decimals,
+            },
+          )
+        })
+        .collect::<BTreeMap<String, Decimal>>();
+
+      if !tokens.is_empty() {
+        balances.insert(identity, tokens);
+      }
+    }
+
+    Ok(Some(Box::new(Output { balances })))
+  }
+}
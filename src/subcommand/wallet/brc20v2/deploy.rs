@@ -0,0 +1,79 @@
+use {
+  super::*,
+  crate::brc20v2::{TokenDefinition, TransferRules, inscription::MAX_DECIMALS},
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Deploy {
+  #[command(flatten)]
+  shared: SharedArgs,
+  #[arg(long, help = "Deploy token with ticker <TICKER>.")]
+  ticker: String,
+  #[arg(long, help = "Allow at most <MAX_SUPPLY> tokens to be minted.")]
+  max_supply: Decimal,
+  #[arg(long, help = "Allow at most <MINT_LIMIT> tokens to be minted at once.")]
+  mint_limit: Decimal,
+  #[arg(
+    long,
+    default_value_t = 0,
+    help = "Give token <DECIMALS> decimal places."
+  )]
+  decimals: u8,
+  #[arg(long, help = "Make token non-transferable.")]
+  soulbound: bool,
+  #[arg(long, help = "Require senders to prove their identity.")]
+  require_identity: bool,
+  #[arg(
+    long,
+    help = "Make <DESTINATION> the token admin. Defaults to a new wallet address."
+  )]
+  destination: Option<Address<NetworkUnchecked>>,
+  #[arg(
+    long,
+    help = "Include <AMOUNT> postage with inscription. [default: 10000sat]",
+    value_name = "AMOUNT"
+  )]
+  postage: Option<Amount>,
+}
+
+impl Deploy {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    ensure!(
+      self.decimals <= MAX_DECIMALS,
+      "decimals must be at most {MAX_DECIMALS}"
+    );
+
+    ensure!(
+      wallet.get_brc20v2_token(&self.ticker)?.is_none(),
+      "BRC-20 v2 token {} has already been deployed",
+      self.ticker,
+    );
+
+    let destination = match self.destination {
+      Some(destination) => destination.require_network(wallet.chain().network())?,
+      None => wallet.get_change_address()?,
+    };
+
+    let operation = Operation::Deploy {
+      definition: TokenDefinition {
+        max_supply: self.max_supply.to_integer(self.decimals)?,
+        mint_limit: self.mint_limit.to_integer(self.decimals)?,
+        decimals: self.decimals,
+        soulbound: self.soulbound,
+        transfer_rules: TransferRules {
+          require_identity: self.require_identity,
+        },
+        ticker: self.ticker,
+      },
+    };
+
+    Ok(Some(Box::new(Brc20v2::inscribe(
+      &wallet,
+      &self.shared,
+      &operation,
+      self.decimals,
+      destination,
+      self.postage.unwrap_or(TARGET_POSTAGE),
+    )?)))
+  }
+}
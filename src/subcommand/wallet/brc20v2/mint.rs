@@ -0,0 +1,48 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Mint {
+  #[command(flatten)]
+  shared: SharedArgs,
+  #[arg(long, help = "Mint <TICKER>.")]
+  ticker: String,
+  #[arg(long, help = "Mint <AMOUNT> tokens.")]
+  amount: Decimal,
+  #[arg(
+    long,
+    help = "Mint tokens to <DESTINATION>. Defaults to a new wallet address."
+  )]
+  destination: Option<Address<NetworkUnchecked>>,
+  #[arg(
+    long,
+    help = "Include <AMOUNT> postage with inscription. [default: 10000sat]",
+    value_name = "AMOUNT"
+  )]
+  postage: Option<Amount>,
+}
+
+impl Mint {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let token = Brc20v2::token(&wallet, &self.ticker)?;
+
+    let destination = match self.destination {
+      Some(destination) => destination.require_network(wallet.chain().network())?,
+      None => wallet.get_change_address()?,
+    };
+
+    let operation = Operation::Mint {
+      to: Brc20v2::identity(&destination),
+      amount: self.amount.to_integer(token.definition.decimals)?,
+      ticker: self.ticker,
+    };
+
+    Ok(Some(Box::new(Brc20v2::inscribe(
+      &wallet,
+      &self.shared,
+      &operation,
+      token.definition.decimals,
+      destination,
+      self.postage.unwrap_or(TARGET_POSTAGE),
+    )?)))
+  }
+}
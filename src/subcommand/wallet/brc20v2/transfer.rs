@@ -0,0 +1,166 @@
+use {
+  super::*, bitcoin::secp256k1::constants::SCHNORR_SIGNATURE_SIZE,
+  bitcoincore_rpc::bitcoincore_rpc_json::SignRawTransactionInput,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Transfer {
+  #[command(flatten)]
+  shared: SharedArgs,
+  #[arg(long, help = "Transfer <TICKER>.")]
+  ticker: String,
+  #[arg(long, help = "Transfer <AMOUNT> tokens.")]
+  amount: Decimal,
+  #[arg(long, help = "Transfer tokens held by wallet address <FROM>.")]
+  from: Address<NetworkUnchecked>,
+  #[arg(long, help = "Transfer tokens to <TO>.")]
+  to: Address<NetworkUnchecked>,
+  #[arg(
+    long,
+    help = "Include <AMOUNT> postage with inscription. [default: 10000sat]",
+    value_name = "AMOUNT"
+  )]
+  postage: Option<Amount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub inscribe: batch::Output,
+  pub send: Option<Txid>,
+}
+
+impl Transfer {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let network = wallet.chain().network();
+
+    let from = self.from.require_network(network)?;
+    let to = self.to.require_network(network)?;
+
+    ensure!(
+      wallet
+        .bitcoin_client()
+        .get_address_info(&from)?
+        .is_mine
+        .unwrap_or_default(),
+      "address {from} not in wallet"
+    );
+
+    let token = Brc20v2::token(&wallet, &self.ticker)?;
+
+    let amount = self.amount.to_integer(token.definition.decimals)?;
+
+    let balance = wallet
+      .get_brc20v2_account(&from.to_string())?
+      .balances
+      .into_iter()
+      .find(|(definition, _)| definition.ticker == self.ticker)
+      .map(|(_, account)| account.balance)
+      .unwrap_or_default();
+
+    ensure!(
+      balance >= amount,
+      "insufficient {} balance in {from}: {} available, {} required",
+      self.ticker,
+      Decimal {
+        value: balance,
+        scale: token.definition.decimals,
+      },
+      self.amount,
+    );
+
+    let operation = Operation::Transfer {
+      ticker: self.ticker,
+      from: Brc20v2::identity(&from),
+      to: Brc20v2::identity(&to),
+      amount,
+      proof: None,
+    };
+
+    let postage = self.postage.unwrap_or(TARGET_POSTAGE);
+
+    // the transfer is applied when it is revealed to `from`, which owns it,
+    // and the inscription is then sent on to `to` as a receipt
+    let inscribe = Brc20v2::inscribe(
+      &wallet,
+      &self.shared,
+      &operation,
+      token.definition.decimals,
+      from.clone(),
+      postage,
+    )?;
+
+    let send = if inscribe.reveal_broadcast {
+      Some(Self::send(
+        &wallet,
+        &inscribe,
+        &from,
+        &to,
+        postage,
+        self.shared.fee_rate,
+      )?)
+    } else {
+      None
+    };
+
+    Ok(Some(Box::new(Output { inscribe, send })))
+  }
+
+  /// Send the transfer inscription on to the recipient in a transaction that
+  /// spends the unconfirmed reveal output and pays its fee out of the postage.
+  fn send(
+    wallet: &Wallet,
+    inscribe: &batch::Output,
+    from: &Address,
+    to: &Address,
+    postage: Amount,
+    fee_rate: FeeRate,
+  ) -> Result<Txid> {
+    let location = inscribe.inscriptions[0].location;
+
+    let mut transaction = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: location.outpoint,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::from_slice(&[&[0; SCHNORR_SIGNATURE_SIZE]]),
+      }],
+      output: vec![TxOut {
+        script_pubkey: to.script_pubkey(),
+        value: postage,
+      }],
+    };
+
+    let fee = fee_rate.fee(transaction.vsize());
+
+    transaction.input[0].witness = Witness::new();
+
+    transaction.output[0].value = postage
+      .checked_sub(fee)
+      .filter(|value| *value >= to.script_pubkey().minimal_non_dust())
+      .ok_or_else(|| {
+        anyhow!("postage of {postage} cannot pay fee of {fee} to send transfer inscription")
+      })?;
+
+    let result = wallet.bitcoin_client().sign_raw_transaction_with_wallet(
+      &transaction,
+      Some(&[SignRawTransactionInput {
+        txid: location.outpoint.txid,
+        vout: location.outpoint.vout,
+        script_pub_key: from.script_pubkey(),
+        redeem_script: None,
+        amount: Some(postage),
+      }]),
+      None,
+    )?;
+
+    ensure!(
+      result.complete,
+      "failed to sign transfer inscription send transaction: {:?}",
+      result.errors
+    );
+
+    Ok(wallet.bitcoin_client().send_raw_transaction(&result.hex)?)
+  }
+}
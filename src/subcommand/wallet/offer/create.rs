@@ -15,12 +15,23 @@ pub(crate) struct Create {
   amount: Amount,
   #[arg(long, help = "<FEE_RATE> for finalized transaction.")]
   fee_rate: FeeRate,
+  #[arg(
+    long,
+    help = "Offer expires at block <HEIGHT>. [default: 1008 blocks after the next block]",
+    value_name = "HEIGHT"
+  )]
+  expiry: Option<u32>,
   #[arg(long, help = "Submit offer to <URL>.", value_name = "URL")]
   submit: Option<String>,
 }
 
 impl Create {
   pub(crate) fn run(&self, wallet: Wallet) -> SubcommandResult {
+    let expiry = match self.expiry {
+      Some(expiry) => expiry,
+      None => u32::try_from(wallet.bitcoin_client().get_block_count()?)?.saturating_add(1 + 1008),
+    };
+
     ensure!(
       !wallet.inscription_info().contains_key(&self.inscription),
       "inscription {} already in wallet",
@@ -91,10 +102,18 @@ impl Create {
       "PSBT unexpectedly complete after processing with wallet",
     }
 
+    let mut psbt = Psbt::deserialize(&base64_decode(&result.psbt)?)?;
+
+    psbt
+      .proprietary
+      .insert(Index::offer_expiry_key(), expiry.to_le_bytes().to_vec());
+
+    let psbt = base64_encode(&psbt.serialize());
+
     if let Some(url) = &self.submit {
       let response = reqwest::blocking::Client::new()
         .post(url)
-        .body(result.psbt.as_bytes().to_vec())
+        .body(psbt.as_bytes().to_vec())
         .send()
         .context("failed to submit PSBT")?;
 
@@ -111,7 +130,7 @@ impl Create {
     }
 
     Ok(Some(Box::new(Output {
-      psbt: result.psbt,
+      psbt,
       inscription: self.inscription,
       seller_address: seller_address.into_unchecked(),
     })))
@@ -3,6 +3,7 @@ use {super::*, boilerplate::Boilerplate};
 pub(crate) use {
   crate::subcommand::server::ServerConfig,
   address::AddressHtml,
+  address_transactions::AddressTransactionsHtml,
   attributes::AttributesHtml,
   block::BlockHtml,
   children::ChildrenHtml,
@@ -24,17 +25,26 @@ pub(crate) use {
   rune_not_found::RuneNotFoundHtml,
   sat::SatHtml,
   satscard::SatscardHtml,
+  search_results::SearchResultsHtml,
+  status::BackfillStatusHtml,
 };
 
 pub use {
-  blocks::BlocksHtml, inscription::InscriptionHtml, item::ItemHtml, rune::RuneHtml,
-  runes::RunesHtml, status::StatusHtml, transaction::TransactionHtml,
+  blocks::BlocksHtml, brc20v2_account::Brc20v2AccountHtml, brc20v2_holders::Brc20v2HoldersHtml,
+  brc20v2_token::Brc20v2TokenHtml, brc20v2_tokens::Brc20v2TokensHtml, inscription::InscriptionHtml,
+  item::ItemHtml, rune::RuneHtml, runes::RunesHtml, status::StatusHtml,
+  transaction::TransactionHtml,
 };
 
 pub mod address;
+mod address_transactions;
 mod attributes;
 pub mod block;
 pub mod blocks;
+pub mod brc20v2_account;
+pub mod brc20v2_holders;
+pub mod brc20v2_token;
+pub mod brc20v2_tokens;
 mod children;
 mod clock;
 pub mod collections;
@@ -55,6 +65,7 @@ pub mod rune_not_found;
 pub mod runes;
 pub mod sat;
 mod satscard;
+mod search_results;
 pub mod status;
 pub mod transaction;
 
@@ -0,0 +1,95 @@
+use {super::*, crate::index::address_history::AddressTransaction};
+
+#[derive(Boilerplate)]
+pub(crate) struct AddressTransactionsHtml {
+  pub(crate) address: Address,
+  pub(crate) transactions: Vec<AddressTransaction>,
+  pub(crate) prev_page: Option<usize>,
+  pub(crate) next_page: Option<usize>,
+}
+
+impl PageContent for AddressTransactionsHtml {
+  fn title(&self) -> String {
+    format!("Address {} Transactions", self.address)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn address() -> Address {
+    Address::from_str("bc1phuq0vkls6w926zdaem6x9n02z2gg7j2xfudgwddyey7uyquarlgsh40ev8")
+      .unwrap()
+      .require_network(Network::Bitcoin)
+      .unwrap()
+  }
+
+  #[test]
+  fn without_transactions() {
+    assert_regex_match!(
+      AddressTransactionsHtml {
+        address: address(),
+        transactions: Vec::new(),
+        prev_page: None,
+        next_page: None,
+      },
+      "
+        <h1><a href=/address/bc1p.*>Address bc1p.*</a> Transactions</h1>
+        <h3>No transactions</h3>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_transactions() {
+    assert_regex_match!(
+      AddressTransactionsHtml {
+        address: address(),
+        transactions: vec![AddressTransaction {
+          height: 2,
+          inscriptions_received: vec![inscription_id(1)],
+          inscriptions_sent: Vec::new(),
+          received: 10_000,
+          runes_received: vec![(
+            SpacedRune {
+              rune: Rune(26),
+              spacers: 0,
+            },
+            Decimal { value: 5, scale: 0 },
+            Some('%'),
+          )],
+          runes_sent: Vec::new(),
+          spent: 0,
+          txid: txid(1),
+        }],
+        prev_page: Some(1),
+        next_page: Some(3),
+      },
+      "
+        <h1>.*</h1>
+        <h2><a class=collapse href=/tx/1{64}>1{64}</a></h2>
+        <dl>
+          <dt>height</dt>
+          <dd><a href=/block/2>2</a></dd>
+          <dt>received</dt>
+          <dd>10000</dd>
+          <dt>spent</dt>
+          <dd>0</dd>
+          <dt>inscriptions received</dt>
+          <dd class=thumbnails>
+            <a href=/inscription/1{64}i1>.*</a>
+          </dd>
+          <dt>runes received</dt>
+          <dd><a class=monospace href=/rune/AA>AA</a>: 5%</dd>
+        </dl>
+        <div class=center>
+          <a class=prev href=/address/bc1p.*/transactions/1>prev</a>
+          <a class=next href=/address/bc1p.*/transactions/3>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
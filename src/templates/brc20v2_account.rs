@@ -0,0 +1,97 @@
+use {
+  super::*,
+  crate::brc20v2::{AccountState, TokenDefinition},
+  serde_hex::{SerHex, Strict},
+};
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Brc20v2AccountHtml {
+  pub balances: Vec<(TokenDefinition, AccountState)>,
+  #[serde(with = "SerHex::<Strict>")]
+  pub commitment: [u8; 32],
+  pub identity: String,
+}
+
+impl Brc20v2AccountHtml {
+  fn amount(definition: &TokenDefinition, value: u128) -> Decimal {
+    Decimal {
+      value,
+      scale: definition.decimals,
+    }
+  }
+}
+
+impl PageContent for Brc20v2AccountHtml {
+  fn title(&self) -> String {
+    format!("BRC-20 v2 Account {}", self.identity)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn definition(ticker: &str) -> TokenDefinition {
+    TokenDefinition {
+      ticker: ticker.into(),
+      max_supply: 1000,
+      mint_limit: 1000,
+      decimals: 1,
+      soulbound: false,
+      transfer_rules: Default::default(),
+    }
+  }
+
+  #[test]
+  fn display() {
+    assert_regex_match!(
+      Brc20v2AccountHtml {
+        balances: vec![
+          (
+            definition("ordi"),
+            AccountState {
+              balance: 100,
+              ..default()
+            },
+          ),
+          (
+            definition("pepe"),
+            AccountState {
+              balance: 25,
+              locked_balance: 5,
+              ..default()
+            },
+          ),
+        ],
+        commitment: [0xab; 32],
+        identity: "alice".into(),
+      },
+      "<h1>BRC-20 v2 Account alice</h1>
+<dl>
+  <dt>commitment</dt>
+  <dd class=collapse>(ab){32}</dd>
+  <dt>balances</dt>
+  <dd><a class=monospace href=/brc20v2/token/ordi>ordi</a>: 10</dd>
+  <dd><a class=monospace href=/brc20v2/token/pepe>pepe</a>: 2.5 \\(0.5 locked\\)</dd>
+</dl>
+"
+    );
+  }
+
+  #[test]
+  fn display_without_balances() {
+    assert_regex_match!(
+      Brc20v2AccountHtml {
+        balances: Vec::new(),
+        commitment: [0; 32],
+        identity: "carol".into(),
+      },
+      "<h1>BRC-20 v2 Account carol</h1>
+<dl>
+  <dt>commitment</dt>
+  <dd class=collapse>0{64}</dd>
+</dl>
+"
+    );
+  }
+}
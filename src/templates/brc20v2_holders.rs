@@ -0,0 +1,60 @@
+use super::*;
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Brc20v2HoldersHtml {
+  pub decimals: u8,
+  pub holders: Vec<api::Brc20v2Holder>,
+  pub more: bool,
+  pub prev: Option<usize>,
+  pub next: Option<usize>,
+  pub ticker: String,
+}
+
+impl Brc20v2HoldersHtml {
+  fn amount(&self, value: u128) -> Decimal {
+    Decimal {
+      value,
+      scale: self.decimals,
+    }
+  }
+}
+
+impl PageContent for Brc20v2HoldersHtml {
+  fn title(&self) -> String {
+    format!("BRC-20 v2 Token {} Holders", self.ticker)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn display() {
+    assert_regex_match!(
+      Brc20v2HoldersHtml {
+        decimals: 1,
+        holders: vec![api::Brc20v2Holder {
+          balance: 15,
+          commitment: [0; 32],
+          identity: "alice".into(),
+          locked_balance: 0,
+        }],
+        more: true,
+        prev: None,
+        next: Some(1),
+        ticker: "ordi".into(),
+      },
+      "<h1><a href=/brc20v2/token/ordi>ordi</a> Holders</h1>
+<dl>
+  <dt><a class=collapse href=/brc20v2/account/alice>alice</a></dt>
+  <dd>1.5</dd>
+</dl>
+<div class=center>
+    prev
+      <a class=next href=/brc20v2/token/ordi/holders/1>next</a>
+  </div>
+"
+    );
+  }
+}
@@ -0,0 +1,73 @@
+use {
+  super::*,
+  crate::brc20v2::TokenDefinition,
+  serde_hex::{SerHex, Strict},
+};
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Brc20v2TokenHtml {
+  pub definition: TokenDefinition,
+  pub holders: usize,
+  #[serde(with = "SerHex::<Strict>")]
+  pub merkle_root: [u8; 32],
+  pub total_supply: u128,
+}
+
+impl Brc20v2TokenHtml {
+  fn amount(&self, value: u128) -> Decimal {
+    Decimal {
+      value,
+      scale: self.definition.decimals,
+    }
+  }
+}
+
+impl PageContent for Brc20v2TokenHtml {
+  fn title(&self) -> String {
+    format!("BRC-20 v2 Token {}", self.definition.ticker)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn display() {
+    assert_regex_match!(
+      Brc20v2TokenHtml {
+        definition: TokenDefinition {
+          ticker: "ordi".into(),
+          max_supply: 2_100_000,
+          mint_limit: 1000,
+          decimals: 2,
+          soulbound: false,
+          transfer_rules: Default::default(),
+        },
+        holders: 3,
+        merkle_root: [0xab; 32],
+        total_supply: 12345,
+      },
+      "<h1>ordi</h1>
+<dl>
+  <dt>supply</dt>
+  <dd>123.45</dd>
+  <dt>max supply</dt>
+  <dd>21000</dd>
+  <dt>mint limit</dt>
+  <dd>10</dd>
+  <dt>decimals</dt>
+  <dd>2</dd>
+  <dt>soulbound</dt>
+  <dd>false</dd>
+  <dt>identity required</dt>
+  <dd>false</dd>
+  <dt>holders</dt>
+  <dd><a href=/brc20v2/token/ordi/holders>3</a></dd>
+  <dt>state root</dt>
+  <dd class=collapse>(ab){32}</dd>
+</dl>
+"
+    );
+  }
+}
@@ -0,0 +1,77 @@
+use {super::*, crate::index::Brc20v2TokenEntry};
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Brc20v2TokensHtml {
+  pub entries: Vec<(String, Brc20v2TokenEntry)>,
+  pub more: bool,
+  pub prev: Option<usize>,
+  pub next: Option<usize>,
+}
+
+impl PageContent for Brc20v2TokensHtml {
+  fn title(&self) -> String {
+    "BRC-20 v2 Tokens".to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entry() -> Brc20v2TokenEntry {
+    Brc20v2TokenEntry {
+      admin: None,
+      decimals: 0,
+      holders: 0,
+      max_supply: 1000,
+      mint_limit: 10,
+      require_identity: false,
+      soulbound: false,
+      total_supply: 0,
+    }
+  }
+
+  #[test]
+  fn display() {
+    assert_eq!(
+      Brc20v2TokensHtml {
+        entries: vec![("ordi".into(), entry())],
+        more: false,
+        prev: None,
+        next: None,
+      }
+      .to_string(),
+      "<h1>BRC-20 v2 Tokens</h1>
+<ul>
+  <li><a href=/brc20v2/token/ordi>ordi</a></li>
+</ul>
+<div class=center>
+    prev
+      next
+  </div>
+"
+    );
+  }
+
+  #[test]
+  fn with_prev_and_next() {
+    assert_regex_match!(
+      Brc20v2TokensHtml {
+        entries: vec![("ordi".into(), entry()), ("pepe".into(), entry())],
+        more: true,
+        prev: Some(1),
+        next: Some(2),
+      },
+      "<h1>BRC-20 v2 Tokens</h1>
+<ul>
+  <li><a href=/brc20v2/token/ordi>ordi</a></li>
+  <li><a href=/brc20v2/token/pepe>pepe</a></li>
+</ul>
+<div class=center>
+    <a class=prev href=/brc20v2/tokens/1>prev</a>
+      <a class=next href=/brc20v2/tokens/2>next</a>
+  </div>
+"
+    );
+  }
+}
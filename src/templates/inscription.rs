@@ -1,7 +1,11 @@
-use super::*;
+use {
+  super::*,
+  crate::{brc20v2::Rejection, index::InscriptionTransfer},
+};
 
 #[derive(Boilerplate, Default)]
 pub struct InscriptionHtml {
+  pub brc20v2_rejection: Option<Rejection>,
   pub chain: Chain,
   pub charms: u16,
   pub child_count: u64,
@@ -20,6 +24,7 @@ pub struct InscriptionHtml {
   pub sat: Option<Sat>,
   pub satpoint: SatPoint,
   pub timestamp: DateTime<Utc>,
+  pub transfers: Option<Vec<InscriptionTransfer>>,
 }
 
 impl PageContent for InscriptionHtml {
@@ -464,6 +469,86 @@ mod tests {
     );
   }
 
+  #[test]
+  fn with_brc20v2_rejection() {
+    assert_regex_match!(
+      InscriptionHtml {
+        brc20v2_rejection: Some(Rejection::InvalidTicker {
+          ticker: "ORDI".into(),
+        }),
+        fee: 1,
+        inscription: inscription("application/json", "{}"),
+        id: inscription_id(1),
+        number: 1,
+        satpoint: satpoint(1, 0),
+        ..default()
+      },
+      "
+        <h1>Inscription 1</h1>
+        .*
+        <dl>
+          .*
+          <dt>brc-20 v2 rejection</dt>
+          <dd>invalid ticker `ORDI`</dd>
+          .*
+        </dl>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_transfer_history() {
+    assert_regex_match!(
+      InscriptionHtml {
+        fee: 1,
+        inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+        id: inscription_id(1),
+        number: 1,
+        satpoint: satpoint(2, 0),
+        transfers: Some(vec![
+          InscriptionTransfer {
+            height: 1,
+            new_satpoint: satpoint(1, 0),
+            old_satpoint: None,
+            txid: txid(1),
+          },
+          InscriptionTransfer {
+            height: 2,
+            new_satpoint: satpoint(2, 0),
+            old_satpoint: Some(satpoint(1, 0)),
+            txid: txid(2),
+          },
+        ]),
+        ..default()
+      },
+      "
+        <h1>Inscription 1</h1>
+        .*
+        </dl>
+        <h2>History</h2>
+        <table>
+          <tr>
+            <th>height</th>
+            <th>transaction</th>
+            <th>location</th>
+          </tr>
+          <tr>
+            <td><a href=/block/1>1</a></td>
+            <td><a class=collapse href=/tx/1{64}>1{64}</a></td>
+            <td><a class=collapse href=/satpoint/1{64}:1:0>1{64}:1:0</a></td>
+          </tr>
+          <tr>
+            <td><a href=/block/2>2</a></td>
+            <td><a class=collapse href=/tx/2{64}>2{64}</a></td>
+            <td><a class=collapse href=/satpoint/2{64}:2:0>2{64}:2:0</a></td>
+          </tr>
+        </table>
+      "
+      .unindent()
+    );
+  }
+
   #[test]
   fn with_burn_metadata() {
     let script_pubkey = script::Builder::new()
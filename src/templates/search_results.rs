@@ -0,0 +1,129 @@
+use {super::*, crate::index::search::Facets};
+
+#[derive(Boilerplate)]
+pub(crate) struct SearchResultsHtml {
+  pub(crate) query: String,
+  pub(crate) facets: Facets,
+  pub(crate) inscriptions: Vec<InscriptionId>,
+  pub(crate) prev_page: Option<usize>,
+  pub(crate) next_page: Option<usize>,
+}
+
+impl SearchResultsHtml {
+  /// Link to `page` of the same search.
+  fn href(&self, page: usize) -> String {
+    let mut parameters = vec![("query", self.query.clone())];
+
+    if let Some(charm) = self.facets.charm {
+      parameters.push(("charm", charm.to_string()));
+    }
+
+    if let Some(content_type) = &self.facets.content_type {
+      parameters.push(("content_type", content_type.clone()));
+    }
+
+    if let Some(max_height) = self.facets.max_height {
+      parameters.push(("max_height", max_height.to_string()));
+    }
+
+    if let Some(min_height) = self.facets.min_height {
+      parameters.push(("min_height", min_height.to_string()));
+    }
+
+    if let Some(parent) = self.facets.parent {
+      parameters.push(("parent", parent.to_string()));
+    }
+
+    if let Some(rarity) = self.facets.rarity {
+      parameters.push(("rarity", rarity.to_string()));
+    }
+
+    parameters.push(("page", page.to_string()));
+
+    format!(
+      "/search?{}",
+      parameters
+        .iter()
+        .map(|(key, value)| format!("{key}={}", urlencoding::encode(value)))
+        .collect::<Vec<String>>()
+        .join("&")
+    )
+  }
+}
+
+impl PageContent for SearchResultsHtml {
+  fn title(&self) -> String {
+    if self.query.is_empty() {
+      "Search Results".into()
+    } else {
+      format!("Search Results for {}", self.query)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn without_results() {
+    assert_regex_match!(
+      SearchResultsHtml {
+        query: "hello".into(),
+        facets: Facets::default(),
+        inscriptions: Vec::new(),
+        prev_page: None,
+        next_page: None,
+      },
+      "
+        <h1>Search Results</h1>
+        <dl>
+          <dt>query</dt>
+          <dd>hello</dd>
+        </dl>
+        <h3>No inscriptions</h3>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_results_and_facets() {
+    assert_regex_match!(
+      SearchResultsHtml {
+        query: "hello world".into(),
+        facets: Facets {
+          content_type: Some("text/plain".into()),
+          min_height: Some(2),
+          rarity: Some(Rarity::Uncommon),
+          ..default()
+        },
+        inscriptions: vec![inscription_id(1), inscription_id(2)],
+        prev_page: Some(1),
+        next_page: Some(3),
+      },
+      "
+        <h1>Search Results</h1>
+        <dl>
+          <dt>query</dt>
+          <dd>hello world</dd>
+          <dt>content type</dt>
+          <dd>text/plain</dd>
+          <dt>rarity</dt>
+          <dd><span class=uncommon>uncommon</span></dd>
+          <dt>min height</dt>
+          <dd><a href=/block/2>2</a></dd>
+        </dl>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1>.*</a>
+          <a href=/inscription/2{64}i2>.*</a>
+        </div>
+        <div class=center>
+          <a class=prev href=\"/search\\?query=hello%20world&amp;content_type=text%2Fplain&amp;min_height=2&amp;rarity=uncommon&amp;page=1\">prev</a>
+          <a class=next href=\"/search\\?query=hello%20world&amp;content_type=text%2Fplain&amp;min_height=2&amp;rarity=uncommon&amp;page=3\">next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
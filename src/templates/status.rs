@@ -1,4 +1,4 @@
-use super::*;
+use {super::*, crate::index::backfill::Feature};
 
 #[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
 pub struct StatusHtml {
@@ -27,3 +27,18 @@ impl PageContent for StatusHtml {
     "Status".into()
   }
 }
+
+/// The status page, along with the indices that are being backfilled after
+/// being enabled with `ord index enable`.
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BackfillStatusHtml {
+  pub backfilling: BTreeMap<Feature, u32>,
+  #[serde(flatten)]
+  pub status: StatusHtml,
+}
+
+impl PageContent for BackfillStatusHtml {
+  fn title(&self) -> String {
+    self.status.title()
+  }
+}
@@ -3,6 +3,7 @@ pub(crate) use {
   bitcoin::{
     WPubkeyHash,
     blockdata::script::{PushBytes, PushBytesBuf},
+    key::TapTweak,
     opcodes,
   },
   mockcore::TransactionTemplate,
@@ -157,3 +158,64 @@ pub(crate) fn default_address(chain: Chain) -> Address {
   )
   .unwrap()
 }
+
+pub(crate) fn offer_keypair(n: u8) -> secp256k1::Keypair {
+  secp256k1::Keypair::from_secret_key(
+    &Secp256k1::new(),
+    &secp256k1::SecretKey::from_slice(&[n; 32]).unwrap(),
+  )
+}
+
+pub(crate) fn offer_address(keypair: &secp256k1::Keypair) -> Address {
+  Address::p2tr(
+    &Secp256k1::new(),
+    keypair.x_only_public_key().0,
+    None,
+    Network::Regtest,
+  )
+}
+
+/// An offer to sell `outpoint`, which holds `prevout`, that expires at
+/// `expiry`, with a key path signature by `keypair` of `sighash_type`.
+pub(crate) fn sell_offer(
+  outpoint: OutPoint,
+  prevout: &TxOut,
+  keypair: &secp256k1::Keypair,
+  sighash_type: bitcoin::TapSighashType,
+  expiry: u32,
+) -> Psbt {
+  let mut psbt = Psbt::from_unsigned_tx(Transaction {
+    version: Version(2),
+    lock_time: LockTime::ZERO,
+    input: vec![tx_in(outpoint)],
+    output: vec![TxOut {
+      value: Amount::from_sat(1000),
+      script_pubkey: ScriptBuf::new(),
+    }],
+  })
+  .unwrap();
+
+  psbt
+    .proprietary
+    .insert(Index::offer_expiry_key(), expiry.to_le_bytes().to_vec());
+
+  let sighash = bitcoin::sighash::SighashCache::new(&psbt.unsigned_tx)
+    .taproot_key_spend_signature_hash(
+      0,
+      &bitcoin::sighash::Prevouts::All(&[prevout]),
+      sighash_type,
+    )
+    .unwrap();
+
+  let secp = Secp256k1::new();
+
+  psbt.inputs[0].tap_key_sig = Some(bitcoin::taproot::Signature {
+    signature: secp.sign_schnorr_no_aux_rand(
+      &secp256k1::Message::from(sighash),
+      &keypair.tap_tweak(&secp, None).to_keypair(),
+    ),
+    sighash_type,
+  });
+
+  psbt
+}
@@ -332,6 +332,40 @@ impl Wallet {
     Ok(Some((rune_json.id, rune_json.entry, rune_json.parent)))
   }
 
+  pub(crate) fn get_brc20v2_token(&self, ticker: &str) -> Result<Option<api::Brc20v2Token>> {
+    let response = self
+      .ord_client
+      .get(
+        self
+          .rpc_url
+          .join(&format!("/brc20v2/token/{ticker}"))
+          .unwrap(),
+      )
+      .send()?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+      return Ok(None);
+    }
+
+    Ok(Some(response.error_for_status()?.json()?))
+  }
+
+  pub(crate) fn get_brc20v2_account(&self, identity: &str) -> Result<api::Brc20v2Account> {
+    Ok(
+      self
+        .ord_client
+        .get(
+          self
+            .rpc_url
+            .join(&format!("/brc20v2/account/{identity}"))
+            .unwrap(),
+        )
+        .send()?
+        .error_for_status()?
+        .json()?,
+    )
+  }
+
   pub(crate) fn get_change_address(&self) -> Result<Address> {
     Ok(
       self
@@ -438,11 +472,9 @@ impl Wallet {
           progress.finish_with_message("Rune matured, submitting...");
           break;
         }
-        Maturity::ConfirmationsPending(remaining) => {
-          if remaining < pending_confirmations {
-            pending_confirmations = remaining;
-            progress.inc(1);
-          }
+        Maturity::ConfirmationsPending(remaining) if remaining < pending_confirmations => {
+          pending_confirmations = remaining;
+          progress.inc(1);
         }
         Maturity::CommitSpent(txid) => {
           self.clear_etching(rune)?;
@@ -90,3 +90,307 @@ fn export_inscription_number_to_id_tsv() {
     &ord::Object::InscriptionId(inscription),
   );
 }
+
+#[test]
+fn events_are_listed_by_height() {
+  let core = mockcore::spawn();
+
+  core.mine_blocks(1);
+
+  let txid = core.broadcast_tx(TransactionTemplate {
+    inputs: &[(
+      1,
+      0,
+      0,
+      envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"foo"]),
+    )],
+    ..default()
+  });
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("--index-events index events --from-height 2 --to-height 2")
+    .core(&core)
+    .run_and_deserialize_output::<ord::subcommand::index::events::Output>();
+
+  assert_eq!(output.events.len(), 1);
+  assert_eq!(output.events[0].tx_index, 1);
+  assert_eq!(
+    output.events[0].event.inscription_id(),
+    Some(InscriptionId { txid, index: 0 })
+  );
+
+  let output = CommandBuilder::new("--index-events index events --from-height 3")
+    .core(&core)
+    .run_and_deserialize_output::<ord::subcommand::index::events::Output>();
+
+  assert!(output.events.is_empty());
+}
+
+#[test]
+fn listing_events_requires_event_index() {
+  let core = mockcore::spawn();
+
+  CommandBuilder::new("index events")
+    .core(&core)
+    .expected_stderr("error: listing events requires index created with `--index-events` flag\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn snapshot_can_be_restored() {
+  let core = mockcore::spawn();
+  core.mine_blocks(2);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let created = CommandBuilder::new("--index foo.redb index snapshot create --output snapshot")
+    .core(&core)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<ord::subcommand::index::snapshot::Manifest>();
+
+  assert_eq!(created.height, 2);
+  assert_eq!(created.block_hash, core.state().hashes[2]);
+  assert!(!created.chunks.is_empty());
+
+  let restored = CommandBuilder::new("--index bar.redb index snapshot restore --input snapshot")
+    .core(&core)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<ord::subcommand::index::snapshot::Manifest>();
+
+  assert_eq!(restored, created);
+
+  assert!(tempdir.path().join("bar.redb").is_file());
+
+  CommandBuilder::new("--index bar.redb --index-sats index snapshot restore --input snapshot")
+    .core(&core)
+    .temp_dir(tempdir)
+    .stderr_regex("error: snapshot was created with index flags .*, but settings have .*\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn snapshot_with_reorged_tip_is_not_restored() {
+  let core = mockcore::spawn();
+  core.mine_blocks(2);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let manifest = CommandBuilder::new("--index foo.redb index snapshot create --output snapshot")
+    .core(&core)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<ord::subcommand::index::snapshot::Manifest>();
+
+  core.invalidate_tip();
+  core.mine_blocks(1);
+
+  CommandBuilder::new("--index bar.redb index snapshot restore --input snapshot")
+    .core(&core)
+    .temp_dir(tempdir.clone())
+    .expected_stderr(format!(
+      "error: snapshot tip {} at height 2 is not in the active chain, which has {}\n",
+      manifest.block_hash,
+      core.state().hashes[2],
+    ))
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  assert!(!tempdir.path().join("bar.redb").exists());
+}
+
+#[test]
+fn snapshot_with_corrupt_chunk_is_not_restored() {
+  let core = mockcore::spawn();
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  CommandBuilder::new("--index foo.redb index snapshot create --output snapshot --chunk-size 4096")
+    .core(&core)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<ord::subcommand::index::snapshot::Manifest>();
+
+  fs::copy(
+    tempdir.path().join("snapshot/chunk-00000.br"),
+    tempdir.path().join("snapshot/chunk-00001.br"),
+  )
+  .unwrap();
+
+  CommandBuilder::new("--index bar.redb index snapshot restore --input snapshot")
+    .core(&core)
+    .temp_dir(tempdir.clone())
+    .stderr_regex(
+      "error: `.*chunk-00001.br` has hash [[:xdigit:]]{64}, but manifest has [[:xdigit:]]{64}\n",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  assert!(!tempdir.path().join("bar.redb").exists());
+  assert!(!tempdir.path().join("bar.redb.partial").exists());
+}
+
+#[test]
+fn export_datasets() {
+  let core = mockcore::spawn();
+
+  core.mine_blocks(1);
+
+  let txid = core.broadcast_tx(TransactionTemplate {
+    inputs: &[(
+      1,
+      0,
+      0,
+      envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"foo"]),
+    )],
+    ..default()
+  });
+
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let output = CommandBuilder::new(
+    "--index-sats index export --dataset inscriptions,sat-ranges --format csv --output out",
+  )
+  .core(&core)
+  .temp_dir(tempdir.clone())
+  .run_and_deserialize_output::<ord::subcommand::index::export::Output>();
+
+  assert_eq!(output.height, 2);
+  assert_eq!(output.block_hash, core.state().hashes[2]);
+  assert_eq!(output.files["inscriptions"].rows, 1);
+  assert!(output.files["sat-ranges"].rows > 0);
+
+  let inscriptions = fs::read_to_string(tempdir.path().join("out/inscriptions.csv")).unwrap();
+
+  let mut lines = inscriptions.lines();
+
+  assert_eq!(
+    lines.next().unwrap(),
+    "sequence_number,number,id,height,timestamp,fee,sat,satpoint",
+  );
+
+  assert!(
+    lines
+      .next()
+      .unwrap()
+      .starts_with(&format!("0,0,{txid}i0,2,"))
+  );
+
+  let output = CommandBuilder::new(
+    "--index-sats index export --dataset inscriptions --format jsonl --output out --at-height 2",
+  )
+  .core(&core)
+  .temp_dir(tempdir.clone())
+  .run_and_deserialize_output::<ord::subcommand::index::export::Output>();
+
+  assert_eq!(output.height, 2);
+
+  let inscription: serde_json::Value = serde_json::from_str(
+    &fs::read_to_string(tempdir.path().join("out/inscriptions.jsonl")).unwrap(),
+  )
+  .unwrap();
+
+  assert_eq!(inscription["id"], format!("{txid}i0"));
+  assert_eq!(inscription["height"], 2);
+}
+
+#[test]
+fn export_at_height_fails_if_index_is_past_height() {
+  let core = mockcore::spawn();
+
+  core.mine_blocks(2);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  CommandBuilder::new("index update")
+    .core(&core)
+    .temp_dir(tempdir.clone())
+    .run_and_extract_stdout();
+
+  CommandBuilder::new("index export --dataset inscriptions --output out --at-height 1")
+    .core(&core)
+    .temp_dir(tempdir)
+    .expected_stderr("error: index is at height 2, not 1\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn export_at_height_fails_if_height_is_out_of_range() {
+  let core = mockcore::spawn();
+
+  CommandBuilder::new("index export --dataset inscriptions --output out --at-height 4294967295")
+    .core(&core)
+    .expected_stderr("error: --at-height 4294967295 is out of range\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn exporting_dataset_requires_index() {
+  let core = mockcore::spawn();
+
+  CommandBuilder::new("index export --dataset rune-entries --output out")
+    .core(&core)
+    .expected_stderr(
+      "error: exporting rune-entries requires index created with `--index-runes` flag\n",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn migrating_current_index_does_nothing() {
+  let core = mockcore::spawn();
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  CommandBuilder::new("index update")
+    .core(&core)
+    .temp_dir(tempdir.clone())
+    .run_and_extract_stdout();
+
+  let output = CommandBuilder::new("index migrate --dry-run")
+    .core(&core)
+    .temp_dir(tempdir)
+    .run_and_deserialize_output::<ord::subcommand::index::migrate::Output>();
+
+  assert_eq!(output.from, output.to);
+  assert!(output.migrations.is_empty());
+  assert!(output.dry_run);
+}
+
+#[test]
+fn enabling_index_starts_backfill() {
+  let core = mockcore::spawn();
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  CommandBuilder::new("index update")
+    .core(&core)
+    .temp_dir(tempdir.clone())
+    .run_and_extract_stdout();
+
+  assert_eq!(
+    CommandBuilder::new("index enable runes")
+      .core(&core)
+      .temp_dir(tempdir.clone())
+      .run_and_deserialize_output::<ord::subcommand::index::enable::Output>(),
+    ord::subcommand::index::enable::Output {
+      backfill_height: Some(0),
+      feature: ord::index::backfill::Feature::Runes,
+    },
+  );
+
+  CommandBuilder::new("index enable runes")
+    .core(&core)
+    .temp_dir(tempdir)
+    .expected_stderr("error: runes are already indexed\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
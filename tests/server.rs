@@ -250,6 +250,7 @@ fn inscription_page() {
     format!("/inscription/{inscription}"),
     Chain::Mainnet,
     InscriptionHtml {
+      brc20v2_rejection: None,
       chain: Chain::Mainnet,
       charms: 0,
       child_count: 0,
@@ -283,6 +284,7 @@ fn inscription_page() {
       timestamp: "1970-01-01 00:00:02+00:00"
         .parse::<DateTime<Utc>>()
         .unwrap(),
+      transfers: None,
     },
   );
 }
@@ -11,6 +11,7 @@ fn default() {
   "bitcoin_rpc_password": null,
   "bitcoin_rpc_url": "127.0.0.1:8332",
   "bitcoin_rpc_username": null,
+  "brc20v2_attestations": null,
   "chain": "mainnet",
   "commit_interval": 5000,
   "config": null,
@@ -21,14 +22,20 @@ fn default() {
   "hidden": \[\],
   "http_port": null,
   "index": ".*index\.redb",
+  "index_address_history": false,
   "index_addresses": false,
+  "index_brc20v2": false,
   "index_cache_size": \d+,
+  "index_events": false,
   "index_runes": false,
   "index_sats": false,
+  "index_search": false,
   "index_transactions": false,
+  "index_transfer_history": false,
   "integration_test": false,
   "max_savepoints": 2,
   "no_index_inscriptions": false,
+  "read_block_files": false,
   "savepoint_interval": 10,
   "server_password": null,
   "server_url": null,
@@ -4,6 +4,7 @@ mod addresses;
 mod authentication;
 mod balance;
 mod batch_command;
+mod brc20v2;
 mod burn;
 mod cardinals;
 mod create;
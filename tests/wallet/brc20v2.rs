@@ -0,0 +1,160 @@
+use {
+  super::*,
+  ord::{
+    decimal::Decimal,
+    subcommand::wallet::brc20v2::{balance, transfer},
+  },
+};
+
+#[test]
+fn tokens_can_be_deployed_minted_and_transferred() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-brc20v2", "--regtest"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(
+    "--regtest wallet brc20v2 deploy --fee-rate 1 --ticker ordi --max-supply 1000 --mint-limit 100 --decimals 2",
+  )
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<batch::Output>();
+
+  core.mine_blocks(1);
+
+  let mint =
+    CommandBuilder::new("--regtest wallet brc20v2 mint --fee-rate 1 --ticker ordi --amount 50.5")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<batch::Output>();
+
+  core.mine_blocks(1);
+
+  let holder = mint.inscriptions[0]
+    .destination
+    .clone()
+    .assume_checked()
+    .to_string();
+
+  pretty_assert_eq!(
+    CommandBuilder::new(format!(
+      "--regtest wallet brc20v2 balance --identity {holder}"
+    ))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<balance::Output>(),
+    balance::Output {
+      balances: [(
+        holder.clone(),
+        [("ordi".into(), "50.5".parse::<Decimal>().unwrap())].into(),
+      )]
+      .into(),
+    }
+  );
+
+  let recipient = "bcrt1pyrmadgg78e38ewfv0an8c6eppk2fttv5vnuvz04yza60qau5va0saknu8k";
+
+  CommandBuilder::new(format!(
+    "--regtest wallet brc20v2 transfer --fee-rate 1 --ticker ordi --amount 100 --from {holder} --to {recipient}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_stderr(format!(
+    "error: insufficient ordi balance in {holder}: 50.5 available, 100 required\n"
+  ))
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+
+  let output = CommandBuilder::new(format!(
+    "--regtest wallet brc20v2 transfer --fee-rate 1 --ticker ordi --amount 20.25 --from {holder} --to {recipient}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<transfer::Output>();
+
+  assert_eq!(
+    output.inscribe.inscriptions[0]
+      .destination
+      .clone()
+      .assume_checked()
+      .to_string(),
+    holder,
+  );
+
+  core.mine_blocks(1);
+
+  let transaction = core.tx_by_id(output.send.unwrap());
+
+  assert_eq!(
+    transaction.input[0].previous_output,
+    output.inscribe.inscriptions[0].location.outpoint
+  );
+
+  assert_eq!(
+    transaction.output[0].script_pubkey,
+    recipient
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap()
+      .assume_checked()
+      .script_pubkey()
+  );
+
+  pretty_assert_eq!(
+    CommandBuilder::new(format!(
+      "--regtest wallet brc20v2 balance --identity {recipient}"
+    ))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<balance::Output>(),
+    balance::Output {
+      balances: [(
+        recipient.into(),
+        [("ordi".into(), "20.25".parse::<Decimal>().unwrap())].into(),
+      )]
+      .into(),
+    }
+  );
+}
+
+#[test]
+fn transferring_from_address_not_in_wallet_fails() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-brc20v2", "--regtest"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let from = "bcrt1pyrmadgg78e38ewfv0an8c6eppk2fttv5vnuvz04yza60qau5va0saknu8k";
+
+  CommandBuilder::new(format!(
+    "--regtest wallet brc20v2 transfer --fee-rate 1 --ticker ordi --amount 1 --from {from} --to {from}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_stderr(format!("error: address {from} not in wallet\n"))
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn minting_undeployed_token_fails() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-brc20v2", "--regtest"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("--regtest wallet brc20v2 mint --fee-rate 1 --ticker ordi --amount 1")
+    .core(&core)
+    .ord(&ord)
+    .expected_stderr("error: BRC-20 v2 token ordi has not been deployed\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
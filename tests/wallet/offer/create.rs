@@ -229,3 +229,50 @@ fn offers_can_be_submitted() {
     },
   );
 }
+
+#[test]
+fn offers_have_expiry() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _) = inscribe_with_options(&core, &ord, Some(9000), 0);
+
+  let address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+    .parse::<Address<NetworkUnchecked>>()
+    .unwrap()
+    .require_network(Network::Bitcoin)
+    .unwrap();
+
+  CommandBuilder::new(format!("wallet send --fee-rate 0 {address} {inscription}"))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  let expiry = |args: &str| {
+    let create = CommandBuilder::new(format!(
+      "wallet offer create --inscription {inscription} --amount 1btc --fee-rate 1{args}",
+    ))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Create>();
+
+    let psbt = Psbt::deserialize(&base64_decode(&create.psbt).unwrap()).unwrap();
+
+    let key = psbt
+      .proprietary
+      .keys()
+      .find(|key| key.prefix == b"ord" && key.subtype == 0)
+      .unwrap();
+
+    u32::from_le_bytes(psbt.proprietary[key].as_slice().try_into().unwrap())
+  };
+
+  assert_eq!(expiry(""), core.height() as u32 + 1 + 1008);
+
+  assert_eq!(expiry(" --expiry 500"), 500);
+}